@@ -0,0 +1,65 @@
+//! Cookieless visitor counting: bot filtering, Do-Not-Track and daily salted visitor hashes.
+
+use sha2::{Digest, Sha256};
+
+/// User agent fragments of crawlers, link previewers, monitoring and scripted clients
+const BOT_USER_AGENTS: &[&str] = &[
+    "bot", "crawl", "spider", "slurp", "archiver", "facebookexternalhit", "embedly", "preview",
+    "headless", "lighthouse", "pingdom", "uptime", "monitor", "curl", "wget", "python-requests",
+    "python-urllib", "go-http-client", "java/", "okhttp", "axios", "node-fetch", "httpclient",
+];
+
+/// Whether a user agent is missing or belongs to an automated client
+pub fn is_bot(user_agent: Option<&str>) -> bool {
+    let Some(user_agent) = user_agent.map(str::trim).filter(|ua| !ua.is_empty()) else {
+        return true;
+    };
+
+    let user_agent = user_agent.to_lowercase();
+    BOT_USER_AGENTS.iter().any(|fragment| user_agent.contains(fragment))
+}
+
+/// Whether the visitor asked not to be tracked, via `DNT: 1` or `Sec-GPC: 1`
+pub fn do_not_track(dnt: Option<&str>, global_privacy_control: Option<&str>) -> bool {
+    [dnt, global_privacy_control]
+        .into_iter()
+        .flatten()
+        .any(|value| value.trim() == "1")
+}
+
+/// Random salt for one day of visitor hashes
+pub fn new_salt() -> String {
+    format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple())
+}
+
+/// Anonymous visitor id: the same visitor gets the same hash all day, and a new one the
+/// next day once the salt has rotated. The IP address itself is never stored.
+pub fn visitor_hash(salt: &str, ip: &str, user_agent: &str) -> String {
+    let mut hasher = Sha256::new();
+    for part in [salt, ip, user_agent] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hex::encode(&hasher.finalize()[..16])
+}
+
+/// Host of an external referrer; referrals from the site itself and unparsable values are dropped
+pub fn referrer_host(referrer: Option<&str>, own_host: Option<&str>) -> Option<String> {
+    let url = url::Url::parse(referrer?.trim()).ok()?;
+    let host = url.host_str()?.trim_start_matches("www.").to_lowercase();
+
+    let own_host = own_host.map(|own| own.split(':').next().unwrap_or(own).trim_start_matches("www.").to_lowercase());
+    (own_host.as_deref() != Some(host.as_str())).then_some(host)
+}
+
+/// Rough device class of a user agent: `mobile`, `tablet` or `desktop`
+pub fn device_class(user_agent: &str) -> &'static str {
+    let user_agent = user_agent.to_lowercase();
+    if user_agent.contains("ipad") || user_agent.contains("tablet") || (user_agent.contains("android") && !user_agent.contains("mobile")) {
+        "tablet"
+    } else if user_agent.contains("mobi") || user_agent.contains("iphone") || user_agent.contains("android") {
+        "mobile"
+    } else {
+        "desktop"
+    }
+}
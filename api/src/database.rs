@@ -1,6 +1,29 @@
+use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection};
 use sqlx::{Pool, Row, Sqlite, SqlitePool};
 use std::path::Path;
-use client::{Post, User, UserRole};
+use std::str::FromStr;
+use std::collections::HashMap;
+use client::{normalize_redirect_path, AnalyticsEvent, ColorScheme, ContentType, DailyStats, HourlyStats, HtmlPolicy, HtmlSettings, Language, Metric, MetricSummary, MediaFile, Page, Post, Redirect, ScheduleStatus, ScheduledAction, ScheduledContent, SeoSettings, TextDirection, Theme, Translation, TranslationImportPreview, User, UserRole};
+
+/// Columns selected whenever a full `Post` is loaded
+const POST_COLUMNS: &str = "id, slug, title, content, author, created_at, updated_at, published, template, \
+    meta_description, meta_keywords, cover_image, canonical_url, target_date, publish_at, expire_at, language, translation_group";
+const THEME_COLUMNS: &str = "id, name, description, css_content, active, version, variables, templates";
+const PAGE_COLUMNS: &str = "id, slug, title, content, author, created_at, updated_at, published, template, \
+    meta_description, meta_keywords, cover_image, publish_at, expire_at, language, translation_group";
+const REDIRECT_COLUMNS: &str = "id, source, target, status, hits, created_at";
+const SCHEDULED_JOB_COLUMNS: &str = "id, content_type, content_id, action, payload, scheduled_at, status, error, \
+    created_at, created_by, completed_at";
+
+/// Condition for posts and pages readers can see: published, past any embargo and not
+/// expired. Checked when querying, so it holds even while the scheduler isn't running.
+/// `param` is the number of the SQL parameter bound to the current time.
+fn live_filter(param: usize) -> String {
+    format!(
+        "published = 1 AND (publish_at IS NULL OR publish_at <= ?{0}) AND (expire_at IS NULL OR expire_at > ?{0})",
+        param
+    )
+}
 
 /// Database manager for the CMS
 pub struct Database {
@@ -15,7 +38,8 @@ impl Database {
             std::fs::create_dir_all(parent)?;
         }
         
-        let pool = SqlitePool::connect(database_url).await?;
+        let options = SqliteConnectOptions::from_str(database_url)?.create_if_missing(true);
+        let pool = SqlitePool::connect_with(options).await?;
         
         let database = Self { pool };
         database.create_tables().await?;
@@ -39,7 +63,10 @@ impl Database {
                 published BOOLEAN NOT NULL DEFAULT 0,
                 scheduled_at TEXT,
                 meta_description TEXT,
-                meta_keywords TEXT
+                meta_keywords TEXT,
+                template TEXT,
+                cover_image TEXT,
+                canonical_url TEXT
             )
             "#
         )
@@ -92,7 +119,25 @@ impl Database {
                 name TEXT UNIQUE NOT NULL,
                 description TEXT,
                 css_content TEXT NOT NULL,
-                active BOOLEAN NOT NULL DEFAULT 0
+                active BOOLEAN NOT NULL DEFAULT 0,
+                version TEXT NOT NULL DEFAULT '1.0.0',
+                variables TEXT NOT NULL DEFAULT '[]',
+                templates TEXT NOT NULL DEFAULT '[]'
+            )
+            "#
+        )
+        .execute(&self.pool)
+        .await?;
+        
+        // Login sessions, keyed by the token in the session cookie
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS sessions (
+                token TEXT PRIMARY KEY,
+                user_id INTEGER NOT NULL,
+                created_at TEXT NOT NULL,
+                expires_at TEXT NOT NULL,
+                FOREIGN KEY (user_id) REFERENCES users(id)
             )
             "#
         )
@@ -112,9 +157,216 @@ impl Database {
         .execute(&self.pool)
         .await?;
         
+        // Static pages, served at /page/:slug
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS pages (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                slug TEXT UNIQUE NOT NULL,
+                title TEXT NOT NULL,
+                content TEXT NOT NULL,
+                author TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                published BOOLEAN NOT NULL DEFAULT 0,
+                template TEXT NOT NULL DEFAULT 'default',
+                meta_description TEXT,
+                meta_keywords TEXT,
+                cover_image TEXT
+            )
+            "#
+        )
+        .execute(&self.pool)
+        .await?;
+        
+        // Publishing, unpublishing, updates and deletions waiting to be run by the scheduler.
+        // `claimed_by` records which server instance took a job so no two run it.
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS scheduled_jobs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                content_type TEXT NOT NULL,
+                content_id INTEGER NOT NULL,
+                action TEXT NOT NULL,
+                payload TEXT,
+                scheduled_at TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                error TEXT,
+                created_at TEXT NOT NULL,
+                created_by INTEGER NOT NULL DEFAULT 0,
+                claimed_by TEXT,
+                claimed_at TEXT,
+                completed_at TEXT
+            )
+            "#
+        )
+        .execute(&self.pool)
+        .await?;
+        
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_scheduled_jobs_due ON scheduled_jobs (status, scheduled_at)")
+            .execute(&self.pool)
+            .await?;
+        
+        // Redirects from old URLs, matched on the exact request path
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS redirects (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source TEXT UNIQUE NOT NULL,
+                target TEXT NOT NULL DEFAULT '',
+                status INTEGER NOT NULL DEFAULT 301,
+                hits INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL
+            )
+            "#
+        )
+        .execute(&self.pool)
+        .await?;
+        
+        // First-party analytics; visitors are identified only by a daily salted hash
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS analytics_events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                event_type TEXT NOT NULL,
+                path TEXT NOT NULL,
+                visitor_hash TEXT NOT NULL,
+                referrer TEXT,
+                timestamp TEXT NOT NULL
+            )
+            "#
+        )
+        .execute(&self.pool)
+        .await?;
+        
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_analytics_events_timestamp ON analytics_events (timestamp)")
+            .execute(&self.pool)
+            .await?;
+        
+        // Performance samples: browser Web Vitals and server render times
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS metrics (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                value REAL NOT NULL,
+                route TEXT NOT NULL,
+                timestamp TEXT NOT NULL
+            )
+            "#
+        )
+        .execute(&self.pool)
+        .await?;
+        
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_metrics_timestamp ON metrics (timestamp)")
+            .execute(&self.pool)
+            .await?;
+        
+        // Aggregates of the raw events, kept after the raw rows are pruned
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS analytics_daily (
+                date TEXT PRIMARY KEY,
+                views INTEGER NOT NULL,
+                unique_visitors INTEGER NOT NULL,
+                avg_session_duration REAL NOT NULL,
+                bounce_rate REAL NOT NULL,
+                top_pages TEXT NOT NULL DEFAULT '[]',
+                top_referrers TEXT NOT NULL DEFAULT '[]',
+                devices TEXT NOT NULL DEFAULT '[]'
+            )
+            "#
+        )
+        .execute(&self.pool)
+        .await?;
+        
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS analytics_hourly (
+                hour TEXT PRIMARY KEY,
+                views INTEGER NOT NULL,
+                unique_visitors INTEGER NOT NULL
+            )
+            "#
+        )
+        .execute(&self.pool)
+        .await?;
+        
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS languages (
+                code TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                native_name TEXT NOT NULL,
+                direction TEXT NOT NULL DEFAULT 'ltr',
+                active BOOLEAN NOT NULL DEFAULT 1
+            )
+            "#
+        )
+        .execute(&self.pool)
+        .await?;
+        
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS translations (
+                namespace TEXT NOT NULL,
+                key TEXT NOT NULL,
+                language_code TEXT NOT NULL,
+                value TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                PRIMARY KEY (namespace, key, language_code)
+            )
+            "#
+        )
+        .execute(&self.pool)
+        .await?;
+        
         // Migrate existing users table if needed
         self.migrate_users_table().await?;
         
+        // Columns added after the first release
+        let added_to_posts = self.ensure_columns("posts", &[
+            ("template", "TEXT"),
+            ("meta_description", "TEXT"),
+            ("meta_keywords", "TEXT"),
+            ("cover_image", "TEXT"),
+            ("canonical_url", "TEXT"),
+            ("target_date", "TEXT"),
+            ("publish_at", "TEXT"),
+            ("expire_at", "TEXT"),
+            ("language", "TEXT NOT NULL DEFAULT ''"),
+            ("translation_group", "TEXT"),
+        ]).await?;
+        let added_to_pages = self.ensure_columns("pages", &[
+            ("publish_at", "TEXT"),
+            ("expire_at", "TEXT"),
+            ("language", "TEXT NOT NULL DEFAULT ''"),
+            ("translation_group", "TEXT"),
+        ]).await?;
+        // Content from before languages were tracked is in the site's default language; this
+        // only runs once, when the column is added
+        for (table, added) in [("posts", added_to_posts), ("pages", added_to_pages)] {
+            if added.contains(&"language") {
+                let default_language = self.get_default_language().await?;
+                sqlx::query(&format!("UPDATE {} SET language = ? WHERE language = ''", table))
+                    .bind(default_language)
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+        self.ensure_columns("users", &[("color_scheme", "TEXT"), ("language", "TEXT")]).await?;
+        self.ensure_columns("analytics_events", &[("device", "TEXT")]).await?;
+        self.ensure_columns("themes", &[
+            ("version", "TEXT NOT NULL DEFAULT '1.0.0'"),
+            ("variables", "TEXT NOT NULL DEFAULT '[]'"),
+            ("templates", "TEXT NOT NULL DEFAULT '[]'"),
+        ]).await?;
+        
+        // Make sure there is always at least one theme to render with
+        self.seed_default_themes().await?;
+        self.seed_default_pages().await?;
+        self.seed_default_translations().await?;
+        
         Ok(())
     }
     
@@ -143,86 +395,272 @@ impl Database {
         Ok(())
     }
     
-    /// Get all published posts
+    /// Add any of `columns` (name, SQL definition) missing from `table`; returns the names added
+    async fn ensure_columns<'a>(&self, table: &str, columns: &[(&'a str, &str)]) -> Result<Vec<&'a str>, Box<dyn std::error::Error>> {
+        let existing: Vec<String> = sqlx::query(&format!("PRAGMA table_info({})", table))
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|row| row.get::<String, _>("name"))
+            .collect();
+        
+        let mut added = Vec::new();
+        for (name, definition) in columns {
+            if !existing.iter().any(|column| column == name) {
+                sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, name, definition))
+                    .execute(&self.pool)
+                    .await?;
+                added.push(*name);
+            }
+        }
+        
+        Ok(added)
+    }
+    
+    /// Insert the shipped themes if the themes table is empty
+    async fn seed_default_themes(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let theme_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM themes")
+            .fetch_one(&self.pool)
+            .await?;
+        
+        if theme_count > 0 {
+            return Ok(());
+        }
+        
+        // The first shipped theme starts out active
+        for (index, theme) in crate::theme_package::builtin_themes().into_iter().enumerate() {
+            let id = self.save_theme(&theme).await?;
+            if index == 0 {
+                self.activate_theme(id).await?;
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Insert English, Spanish and French if no languages exist, and add any shipped messages
+    /// missing from the translations table. Edited messages are kept.
+    async fn seed_default_translations(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let language_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM languages")
+            .fetch_one(&self.pool)
+            .await?;
+        
+        if language_count == 0 {
+            for (code, name, native_name) in [("en", "English", "English"), ("es", "Spanish", "Español"), ("fr", "French", "Français")] {
+                self.save_language(&Language {
+                    code: code.to_string(),
+                    name: name.to_string(),
+                    native_name: native_name.to_string(),
+                    direction: TextDirection::LeftToRight,
+                    active: true,
+                }).await?;
+            }
+        }
+        
+        let now = client::time::now_iso8601();
+        for (namespace, key, language_code, value) in client::DEFAULT_TRANSLATIONS {
+            sqlx::query(
+                "INSERT OR IGNORE INTO translations (namespace, key, language_code, value, updated_at) VALUES (?, ?, ?, ?, ?)"
+            )
+            .bind(namespace)
+            .bind(key)
+            .bind(language_code)
+            .bind(value)
+            .bind(&now)
+            .execute(&self.pool)
+            .await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Insert the About and Contact pages if the pages table is empty
+    async fn seed_default_pages(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let page_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pages")
+            .fetch_one(&self.pool)
+            .await?;
+        
+        if page_count > 0 {
+            return Ok(());
+        }
+        
+        let about_page = Page {
+            id: 0,
+            slug: "about".to_string(),
+            title: "About BananaBit CMS".to_string(),
+            content: r#"# About BananaBit CMS
+
+BananaBit CMS is a modern, extension-based content management system built with Rust and Dioxus.
+
+## Mission
+
+Our mission is to provide a fast, secure, and highly customizable CMS that developers love to work with.
+
+## Features
+
+- **Extension-based Architecture**: Everything is a plugin, making the system highly modular
+- **Performance**: Built with Rust for maximum performance and safety
+- **Modern UI**: Uses Dioxus for a reactive, component-based frontend
+- **Security**: Type-safe code and secure defaults
+- **Flexibility**: Easy to extend and customize
+
+## Technology Stack
+
+- **Backend**: Rust with modern web frameworks
+- **Frontend**: Dioxus (React-like for Rust)
+- **Database**: Pluggable storage backends
+- **Authentication**: Built-in secure auth system
+
+## Getting Started
+
+Visit our documentation to learn how to set up and customize your BananaBit CMS installation.
+
+---
+
+*Built with ❤️ by the BananaBit team*"#.to_string(),
+            author: "Admin".to_string(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+            published: true,
+            template: "default".to_string(),
+            meta_description: Some("BananaBit CMS is a modern, extension-based content management system built with Rust and Dioxus.".to_string()),
+            meta_keywords: Some("bananabit, cms, rust, dioxus, about".to_string()),
+            ..Default::default()
+        };
+        self.save_page(&about_page).await?;
+        
+        let contact_page = Page {
+            id: 0,
+            slug: "contact".to_string(),
+            title: "Contact Us".to_string(),
+            content: r#"# Contact Us
+
+Get in touch with the BananaBit CMS team.
+
+## Ways to Reach Us
+
+### Email
+- General inquiries: hello@bananabit.cms
+- Support: support@bananabit.cms
+- Security issues: security@bananabit.cms
+
+### Social Media
+- Twitter: [@BananaBitCMS](https://twitter.com/bananabitcms)
+- GitHub: [bananabit-dev](https://github.com/bananabit-dev)
+
+### Community
+- Discord: Join our community server
+- Forums: Community discussion boards
+
+## Business Hours
+
+We're available Monday through Friday, 9 AM to 5 PM UTC.
+
+---
+
+We'd love to hear from you!"#.to_string(),
+            author: "Admin".to_string(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+            published: true,
+            template: "default".to_string(),
+            meta_description: Some("Get in touch with the BananaBit CMS team by email, social media or our community channels.".to_string()),
+            ..Default::default()
+        };
+        self.save_page(&contact_page).await?;
+        
+        Ok(())
+    }
+    
+    /// Get all posts readers can see now
     pub async fn get_published_posts(&self) -> Result<Vec<Post>, Box<dyn std::error::Error>> {
-        let rows = sqlx::query(
-            "SELECT id, slug, title, content, author, created_at, updated_at, published 
-             FROM posts WHERE published = 1 ORDER BY id DESC"
-        )
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM posts WHERE {} ORDER BY id DESC", POST_COLUMNS, live_filter(1)
+        ))
+        .bind(client::time::now_iso8601())
         .fetch_all(&self.pool)
         .await?;
         
-        let posts = rows.into_iter().map(|row| Post {
-            id: row.get::<i64, _>("id") as u32,
-            slug: row.get("slug"),
-            title: row.get("title"),
-            content: row.get("content"),
-            author: row.get("author"),
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
-            published: row.get("published"),
-        }).collect();
+        Ok(rows.into_iter().map(|row| Self::post_from_row(&row)).collect())
+    }
+    
+    /// Get every post, drafts included, newest first
+    pub async fn get_all_posts(&self) -> Result<Vec<Post>, Box<dyn std::error::Error>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM posts ORDER BY id DESC", POST_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
         
-        Ok(posts)
+        Ok(rows.into_iter().map(|row| Self::post_from_row(&row)).collect())
     }
     
     /// Get post by ID
     pub async fn get_post_by_id(&self, id: u32) -> Result<Option<Post>, Box<dyn std::error::Error>> {
-        let row = sqlx::query(
-            "SELECT id, slug, title, content, author, created_at, updated_at, published 
-             FROM posts WHERE id = ?"
-        )
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM posts WHERE id = ?", POST_COLUMNS
+        ))
         .bind(id as i64)
         .fetch_optional(&self.pool)
         .await?;
         
-        match row {
-            Some(row) => Ok(Some(Post {
-                id: row.get::<i64, _>("id") as u32,
-                slug: row.get("slug"),
-                title: row.get("title"),
-                content: row.get("content"),
-                author: row.get("author"),
-                created_at: row.get("created_at"),
-                updated_at: row.get("updated_at"),
-                published: row.get("published"),
-            })),
-            None => Ok(None),
-        }
+        Ok(row.map(|row| Self::post_from_row(&row)))
+    }
+    
+    /// Get a post readers can see now by id
+    pub async fn get_live_post_by_id(&self, id: u32) -> Result<Option<Post>, Box<dyn std::error::Error>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM posts WHERE id = ?1 AND {}", POST_COLUMNS, live_filter(2)
+        ))
+        .bind(id as i64)
+        .bind(client::time::now_iso8601())
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(row.map(|row| Self::post_from_row(&row)))
     }
     
-    /// Get post by slug
+    /// Get a post readers can see now by slug
     pub async fn get_post_by_slug(&self, slug: &str) -> Result<Option<Post>, Box<dyn std::error::Error>> {
-        let row = sqlx::query(
-            "SELECT id, slug, title, content, author, created_at, updated_at, published 
-             FROM posts WHERE slug = ?"
-        )
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM posts WHERE slug = ?1 AND {}", POST_COLUMNS, live_filter(2)
+        ))
         .bind(slug)
+        .bind(client::time::now_iso8601())
         .fetch_optional(&self.pool)
         .await?;
         
-        match row {
-            Some(row) => Ok(Some(Post {
-                id: row.get::<i64, _>("id") as u32,
-                slug: row.get("slug"),
-                title: row.get("title"),
-                content: row.get("content"),
-                author: row.get("author"),
-                created_at: row.get("created_at"),
-                updated_at: row.get("updated_at"),
-                published: row.get("published"),
-            })),
-            None => Ok(None),
-        }
+        Ok(row.map(|row| Self::post_from_row(&row)))
+    }
+    
+    /// Get post by slug whether or not readers can see it, for editors' previews
+    pub async fn get_post_by_slug_for_preview(&self, slug: &str) -> Result<Option<Post>, Box<dyn std::error::Error>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM posts WHERE slug = ?", POST_COLUMNS
+        ))
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(row.map(|row| Self::post_from_row(&row)))
     }
     
     /// Create or update a post
     pub async fn save_post(&self, post: &Post) -> Result<u32, Box<dyn std::error::Error>> {
+        let language = self.content_language(&post.language).await?;
+        let default_language = self.get_default_language().await?;
+        // Where a post with this slug and language lives, e.g. `/es/post/hola`
+        let path = |slug: &str, language: &str| Post { slug: slug.to_string(), language: language.to_string(), ..Post::default() }.path(&default_language);
+        // The content and the redirects for its old URL change together
+        let mut tx = self.pool.begin().await?;
+        
         if post.id == 0 {
             // Insert new post
             let result = sqlx::query(
-                "INSERT INTO posts (slug, title, content, author, created_at, updated_at, published)
-                 VALUES (?, ?, ?, ?, ?, ?, ?)"
+                "INSERT INTO posts (slug, title, content, author, created_at, updated_at, published, template,
+                                    meta_description, meta_keywords, cover_image, canonical_url, target_date, publish_at, expire_at,
+                                    language, translation_group)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
             )
             .bind(&post.slug)
             .bind(&post.title)
@@ -231,14 +669,34 @@ impl Database {
             .bind(&post.created_at)
             .bind(&post.updated_at)
             .bind(post.published)
-            .execute(&self.pool)
+            .bind(&post.template)
+            .bind(&post.meta_description)
+            .bind(&post.meta_keywords)
+            .bind(&post.cover_image)
+            .bind(&post.canonical_url)
+            .bind(&post.target_date)
+            .bind(&post.publish_at)
+            .bind(&post.expire_at)
+            .bind(&language)
+            .bind(&post.translation_group)
+            .execute(&mut *tx)
             .await?;
             
+            Self::clear_redirect(&mut tx, &path(&post.slug, &language)).await?;
+            tx.commit().await?;
+            
             Ok(result.last_insert_rowid() as u32)
         } else {
+            let previous: Option<(String, String)> = sqlx::query_as("SELECT slug, language FROM posts WHERE id = ?")
+                .bind(post.id as i64)
+                .fetch_optional(&mut *tx)
+                .await?;
+            
             // Update existing post
             sqlx::query(
-                "UPDATE posts SET slug=?, title=?, content=?, author=?, updated_at=?, published=?
+                "UPDATE posts SET slug=?, title=?, content=?, author=?, updated_at=?, published=?, template=?,
+                                  meta_description=?, meta_keywords=?, cover_image=?, canonical_url=?, target_date=?,
+                                  publish_at=?, expire_at=?, language=?, translation_group=?
                  WHERE id=?"
             )
             .bind(&post.slug)
@@ -247,33 +705,413 @@ impl Database {
             .bind(&post.author)
             .bind(&post.updated_at)
             .bind(post.published)
+            .bind(&post.template)
+            .bind(&post.meta_description)
+            .bind(&post.meta_keywords)
+            .bind(&post.cover_image)
+            .bind(&post.canonical_url)
+            .bind(&post.target_date)
+            .bind(&post.publish_at)
+            .bind(&post.expire_at)
+            .bind(&language)
+            .bind(&post.translation_group)
             .bind(post.id as i64)
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await?;
             
+            // Renaming or moving to another language both change the URL
+            let new_path = path(&post.slug, &language);
+            match previous.map(|(slug, language)| path(&slug, &language)) {
+                Some(old_path) if old_path != new_path => Self::redirect_renamed_path(&mut tx, &old_path, &new_path).await?,
+                _ => Self::clear_redirect(&mut tx, &new_path).await?,
+            }
+            tx.commit().await?;
+            
             Ok(post.id)
         }
     }
     
-    /// Get user by username
-    pub async fn get_user_by_username(&self, username: &str) -> Result<Option<User>, Box<dyn std::error::Error>> {
-        let row = sqlx::query(
-            "SELECT id, username, email, password_hash, role, created_at, active, email_verified, verification_token
-             FROM users WHERE username = ?"
-        )
-        .bind(username)
-        .fetch_optional(&self.pool)
+    /// Publish or unpublish a post; returns whether it exists
+    pub async fn set_post_published(&self, id: u32, published: bool) -> Result<bool, Box<dyn std::error::Error>> {
+        let result = sqlx::query("UPDATE posts SET published = ?, updated_at = ? WHERE id = ?")
+            .bind(published)
+            .bind(client::time::now_iso8601())
+            .bind(id as i64)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(result.rows_affected() > 0)
+    }
+    
+    /// Move the day a draft is planned for; `None` clears it. Returns whether the post exists.
+    pub async fn set_post_target_date(&self, id: u32, target_date: Option<&str>) -> Result<bool, Box<dyn std::error::Error>> {
+        let result = sqlx::query("UPDATE posts SET target_date = ? WHERE id = ?")
+            .bind(target_date)
+            .bind(id as i64)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(result.rows_affected() > 0)
+    }
+    
+    /// Delete a post; returns whether it existed
+    pub async fn delete_post(&self, id: u32) -> Result<bool, Box<dyn std::error::Error>> {
+        let result = sqlx::query("DELETE FROM posts WHERE id = ?")
+            .bind(id as i64)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(result.rows_affected() > 0)
+    }
+    
+    /// Posts in a translation group that readers can see now, by language
+    pub async fn get_post_translations(&self, group: &str) -> Result<Vec<Post>, Box<dyn std::error::Error>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM posts WHERE translation_group = ?1 AND {} ORDER BY language", POST_COLUMNS, live_filter(2)
+        ))
+        .bind(group)
+        .bind(client::time::now_iso8601())
+        .fetch_all(&self.pool)
         .await?;
         
-        match row {
-            Some(row) => {
-                let role_str: String = row.get("role");
-                let role = match role_str.as_str() {
-                    "Admin" => UserRole::Admin,
-                    "Editor" => UserRole::Editor,
-                    "Author" => UserRole::Author,
-                    "Subscriber" => UserRole::Subscriber,
-                    _ => UserRole::Subscriber,
+        Ok(rows.iter().map(Self::post_from_row).collect())
+    }
+    
+    /// Start a translation of post `id` into `language`: an unpublished copy in the same
+    /// translation group, with the slug suffixed by the language. Returns the new post's id,
+    /// or `None` if the source post doesn't exist.
+    pub async fn create_post_translation(&self, id: u32, language: &str) -> Result<Option<u32>, Box<dyn std::error::Error>> {
+        let Some(source) = self.get_post_by_id(id).await? else {
+            return Ok(None);
+        };
+        if source.language == language {
+            return Err(format!("The post is already in {}", language).into());
+        }
+        let group = self.join_translation_group("posts", id, source.translation_group.as_deref(), language).await?;
+        
+        let now = client::time::now_iso8601();
+        let translation = Post {
+            id: 0,
+            slug: format!("{}-{}", source.slug, language),
+            created_at: now.clone(),
+            updated_at: now,
+            published: false,
+            target_date: None,
+            publish_at: None,
+            expire_at: None,
+            language: language.to_string(),
+            translation_group: Some(group),
+            ..source
+        };
+        Ok(Some(self.save_post(&translation).await?))
+    }
+    
+    /// Published posts whose title or content contains `query`, newest first
+    pub async fn search_published_posts(&self, query: &str, limit: u32) -> Result<Vec<Post>, Box<dyn std::error::Error>> {
+        // Escape LIKE wildcards so the query is matched literally
+        let pattern = format!(
+            "%{}%",
+            query.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+        );
+        
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM posts WHERE {} AND (title LIKE ?1 ESCAPE '\\' OR content LIKE ?1 ESCAPE '\\')
+             ORDER BY id DESC LIMIT ?2",
+            POST_COLUMNS,
+            live_filter(3)
+        ))
+        .bind(pattern)
+        .bind(limit as i64)
+        .bind(client::time::now_iso8601())
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(rows.iter().map(Self::post_from_row).collect())
+    }
+    
+    fn post_from_row(row: &sqlx::sqlite::SqliteRow) -> Post {
+        Post {
+            id: row.get::<i64, _>("id") as u32,
+            slug: row.get("slug"),
+            title: row.get("title"),
+            content: row.get("content"),
+            author: row.get("author"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            published: row.get("published"),
+            template: row.get("template"),
+            meta_description: row.get("meta_description"),
+            meta_keywords: row.get("meta_keywords"),
+            cover_image: row.get("cover_image"),
+            canonical_url: row.get("canonical_url"),
+            target_date: row.get("target_date"),
+            publish_at: row.get("publish_at"),
+            expire_at: row.get("expire_at"),
+            language: row.get("language"),
+            translation_group: row.get("translation_group"),
+        }
+    }
+    
+    /// Get all pages readers can see now, sorted by title
+    pub async fn get_published_pages(&self) -> Result<Vec<Page>, Box<dyn std::error::Error>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM pages WHERE {} ORDER BY title", PAGE_COLUMNS, live_filter(1)
+        ))
+        .bind(client::time::now_iso8601())
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(rows.iter().map(Self::page_from_row).collect())
+    }
+    
+    /// Get every page, drafts included, sorted by title
+    pub async fn get_all_pages(&self) -> Result<Vec<Page>, Box<dyn std::error::Error>> {
+        let rows = sqlx::query(&format!("SELECT {} FROM pages ORDER BY title", PAGE_COLUMNS))
+            .fetch_all(&self.pool)
+            .await?;
+        
+        Ok(rows.iter().map(Self::page_from_row).collect())
+    }
+    
+    /// Get page by ID
+    pub async fn get_page_by_id(&self, id: u32) -> Result<Option<Page>, Box<dyn std::error::Error>> {
+        let row = sqlx::query(&format!("SELECT {} FROM pages WHERE id = ?", PAGE_COLUMNS))
+            .bind(id as i64)
+            .fetch_optional(&self.pool)
+            .await?;
+        
+        Ok(row.as_ref().map(Self::page_from_row))
+    }
+    
+    /// Get a page readers can see now by slug
+    pub async fn get_page_by_slug(&self, slug: &str) -> Result<Option<Page>, Box<dyn std::error::Error>> {
+        let row = sqlx::query(&format!("SELECT {} FROM pages WHERE slug = ?1 AND {}", PAGE_COLUMNS, live_filter(2)))
+            .bind(slug)
+            .bind(client::time::now_iso8601())
+            .fetch_optional(&self.pool)
+            .await?;
+        
+        Ok(row.as_ref().map(Self::page_from_row))
+    }
+    
+    /// Get page by slug whether or not readers can see it, for editors' previews
+    pub async fn get_page_by_slug_for_preview(&self, slug: &str) -> Result<Option<Page>, Box<dyn std::error::Error>> {
+        let row = sqlx::query(&format!("SELECT {} FROM pages WHERE slug = ?", PAGE_COLUMNS))
+            .bind(slug)
+            .fetch_optional(&self.pool)
+            .await?;
+        
+        Ok(row.as_ref().map(Self::page_from_row))
+    }
+    
+    /// Create or update a page
+    pub async fn save_page(&self, page: &Page) -> Result<u32, Box<dyn std::error::Error>> {
+        let language = self.content_language(&page.language).await?;
+        let default_language = self.get_default_language().await?;
+        // Where a page with this slug and language lives, e.g. `/es/page/hola`
+        let path = |slug: &str, language: &str| Page { slug: slug.to_string(), language: language.to_string(), ..Page::default() }.path(&default_language);
+        // The content and the redirects for its old URL change together
+        let mut tx = self.pool.begin().await?;
+        
+        if page.id == 0 {
+            let result = sqlx::query(
+                "INSERT INTO pages (slug, title, content, author, created_at, updated_at, published, template,
+                                    meta_description, meta_keywords, cover_image, publish_at, expire_at,
+                                    language, translation_group)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+            )
+            .bind(&page.slug)
+            .bind(&page.title)
+            .bind(&page.content)
+            .bind(&page.author)
+            .bind(&page.created_at)
+            .bind(&page.updated_at)
+            .bind(page.published)
+            .bind(&page.template)
+            .bind(&page.meta_description)
+            .bind(&page.meta_keywords)
+            .bind(&page.cover_image)
+            .bind(&page.publish_at)
+            .bind(&page.expire_at)
+            .bind(&language)
+            .bind(&page.translation_group)
+            .execute(&mut *tx)
+            .await?;
+            
+            Self::clear_redirect(&mut tx, &path(&page.slug, &language)).await?;
+            tx.commit().await?;
+            
+            Ok(result.last_insert_rowid() as u32)
+        } else {
+            let previous: Option<(String, String)> = sqlx::query_as("SELECT slug, language FROM pages WHERE id = ?")
+                .bind(page.id as i64)
+                .fetch_optional(&mut *tx)
+                .await?;
+            
+            sqlx::query(
+                "UPDATE pages SET slug=?, title=?, content=?, author=?, updated_at=?, published=?, template=?,
+                                  meta_description=?, meta_keywords=?, cover_image=?, publish_at=?, expire_at=?,
+                                  language=?, translation_group=?
+                 WHERE id=?"
+            )
+            .bind(&page.slug)
+            .bind(&page.title)
+            .bind(&page.content)
+            .bind(&page.author)
+            .bind(&page.updated_at)
+            .bind(page.published)
+            .bind(&page.template)
+            .bind(&page.meta_description)
+            .bind(&page.meta_keywords)
+            .bind(&page.cover_image)
+            .bind(&page.publish_at)
+            .bind(&page.expire_at)
+            .bind(&language)
+            .bind(&page.translation_group)
+            .bind(page.id as i64)
+            .execute(&mut *tx)
+            .await?;
+            
+            // Renaming or moving to another language both change the URL
+            let new_path = path(&page.slug, &language);
+            match previous.map(|(slug, language)| path(&slug, &language)) {
+                Some(old_path) if old_path != new_path => Self::redirect_renamed_path(&mut tx, &old_path, &new_path).await?,
+                _ => Self::clear_redirect(&mut tx, &new_path).await?,
+            }
+            tx.commit().await?;
+            
+            Ok(page.id)
+        }
+    }
+    
+    /// Publish or unpublish a page; returns whether it exists
+    pub async fn set_page_published(&self, id: u32, published: bool) -> Result<bool, Box<dyn std::error::Error>> {
+        let result = sqlx::query("UPDATE pages SET published = ?, updated_at = ? WHERE id = ?")
+            .bind(published)
+            .bind(client::time::now_iso8601())
+            .bind(id as i64)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(result.rows_affected() > 0)
+    }
+    
+    /// Delete a page; returns whether it existed
+    pub async fn delete_page(&self, id: u32) -> Result<bool, Box<dyn std::error::Error>> {
+        let result = sqlx::query("DELETE FROM pages WHERE id = ?")
+            .bind(id as i64)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(result.rows_affected() > 0)
+    }
+    
+    /// Pages in a translation group that readers can see now, by language
+    pub async fn get_page_translations(&self, group: &str) -> Result<Vec<Page>, Box<dyn std::error::Error>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM pages WHERE translation_group = ?1 AND {} ORDER BY language", PAGE_COLUMNS, live_filter(2)
+        ))
+        .bind(group)
+        .bind(client::time::now_iso8601())
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(rows.iter().map(Self::page_from_row).collect())
+    }
+    
+    /// Start a translation of page `id` into `language`, like [`Self::create_post_translation`]
+    pub async fn create_page_translation(&self, id: u32, language: &str) -> Result<Option<u32>, Box<dyn std::error::Error>> {
+        let Some(source) = self.get_page_by_id(id).await? else {
+            return Ok(None);
+        };
+        if source.language == language {
+            return Err(format!("The page is already in {}", language).into());
+        }
+        let group = self.join_translation_group("pages", id, source.translation_group.as_deref(), language).await?;
+        
+        let now = client::time::now_iso8601();
+        let translation = Page {
+            id: 0,
+            slug: format!("{}-{}", source.slug, language),
+            created_at: now.clone(),
+            updated_at: now,
+            published: false,
+            publish_at: None,
+            expire_at: None,
+            language: language.to_string(),
+            translation_group: Some(group),
+            ..source
+        };
+        Ok(Some(self.save_page(&translation).await?))
+    }
+    
+    /// Translation group of the post or page `id` in `table`, giving it a new group if it has
+    /// none. Fails if the group already has content in `language`.
+    async fn join_translation_group(&self, table: &str, id: u32, group: Option<&str>, language: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let Some(group) = group else {
+            let group = client::time::generate_id();
+            sqlx::query(&format!("UPDATE {} SET translation_group = ? WHERE id = ?", table))
+                .bind(&group)
+                .bind(id as i64)
+                .execute(&self.pool)
+                .await?;
+            return Ok(group);
+        };
+        
+        let existing: Option<i64> = sqlx::query_scalar(&format!(
+            "SELECT id FROM {} WHERE translation_group = ? AND language = ?", table
+        ))
+        .bind(group)
+        .bind(language)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        match existing {
+            Some(existing) => Err(format!("There is already a {} translation (id {})", language, existing).into()),
+            None => Ok(group.to_string()),
+        }
+    }
+    
+    fn page_from_row(row: &sqlx::sqlite::SqliteRow) -> Page {
+        Page {
+            id: row.get::<i64, _>("id") as u32,
+            slug: row.get("slug"),
+            title: row.get("title"),
+            content: row.get("content"),
+            author: row.get("author"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            published: row.get("published"),
+            template: row.get("template"),
+            meta_description: row.get("meta_description"),
+            meta_keywords: row.get("meta_keywords"),
+            cover_image: row.get("cover_image"),
+            publish_at: row.get("publish_at"),
+            expire_at: row.get("expire_at"),
+            language: row.get("language"),
+            translation_group: row.get("translation_group"),
+        }
+    }
+    
+    /// Get user by username
+    pub async fn get_user_by_username(&self, username: &str) -> Result<Option<User>, Box<dyn std::error::Error>> {
+        let row = sqlx::query(
+            "SELECT id, username, email, password_hash, role, created_at, active, email_verified, verification_token, color_scheme
+             FROM users WHERE username = ?"
+        )
+        .bind(username)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        match row {
+            Some(row) => {
+                let role_str: String = row.get("role");
+                let role = match role_str.as_str() {
+                    "Admin" => UserRole::Admin,
+                    "Editor" => UserRole::Editor,
+                    "Author" => UserRole::Author,
+                    "Subscriber" => UserRole::Subscriber,
+                    _ => UserRole::Subscriber,
                 };
                 
                 Ok(Some(User {
@@ -286,12 +1124,28 @@ impl Database {
                     active: row.get("active"),
                     email_verified: row.get::<bool, _>("email_verified"),
                     verification_token: row.get("verification_token"),
+                    color_scheme: row.get::<Option<String>, _>("color_scheme").as_deref().and_then(ColorScheme::parse),
                 }))
             },
             None => Ok(None),
         }
     }
     
+    /// Role of a user, if they exist
+    pub async fn get_user_role(&self, user_id: u32) -> Result<Option<UserRole>, Box<dyn std::error::Error>> {
+        let role: Option<String> = sqlx::query_scalar("SELECT role FROM users WHERE id = ? AND active = 1")
+            .bind(user_id as i64)
+            .fetch_optional(&self.pool)
+            .await?;
+        
+        Ok(role.map(|role| match role.as_str() {
+            "Admin" => UserRole::Admin,
+            "Editor" => UserRole::Editor,
+            "Author" => UserRole::Author,
+            _ => UserRole::Subscriber,
+        }))
+    }
+    
     /// Create a new user
     pub async fn create_user(&self, user: &User) -> Result<u32, Box<dyn std::error::Error>> {
         let role_str = match user.role {
@@ -319,6 +1173,1030 @@ impl Database {
         Ok(result.last_insert_rowid() as u32)
     }
     
+    /// Store a user's color scheme preference
+    pub async fn set_user_color_scheme(&self, user_id: u32, scheme: Option<ColorScheme>) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query("UPDATE users SET color_scheme = ? WHERE id = ?")
+            .bind(scheme.map(|scheme| scheme.as_str()))
+            .bind(user_id as i64)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(())
+    }
+    
+    /// Get a user's color scheme preference
+    pub async fn get_user_color_scheme(&self, user_id: u32) -> Result<Option<ColorScheme>, Box<dyn std::error::Error>> {
+        let scheme: Option<Option<String>> = sqlx::query_scalar("SELECT color_scheme FROM users WHERE id = ?")
+            .bind(user_id as i64)
+            .fetch_optional(&self.pool)
+            .await?;
+        
+        Ok(scheme.flatten().as_deref().and_then(ColorScheme::parse))
+    }
+    
+    /// Store the language a user picked
+    pub async fn set_user_language(&self, user_id: u32, language: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query("UPDATE users SET language = ? WHERE id = ?")
+            .bind(language)
+            .bind(user_id as i64)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(())
+    }
+    
+    /// Get the language a user picked
+    pub async fn get_user_language(&self, user_id: u32) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let language: Option<Option<String>> = sqlx::query_scalar("SELECT language FROM users WHERE id = ?")
+            .bind(user_id as i64)
+            .fetch_optional(&self.pool)
+            .await?;
+        
+        Ok(language.flatten())
+    }
+    
+    /// Start a login session that lasts `ttl_days`
+    pub async fn create_session(&self, token: &str, user_id: u32, ttl_days: i64) -> Result<(), Box<dyn std::error::Error>> {
+        let now = chrono::Utc::now();
+        let expires_at = now + chrono::Duration::days(ttl_days);
+        
+        sqlx::query("INSERT INTO sessions (token, user_id, created_at, expires_at) VALUES (?, ?, ?, ?)")
+            .bind(token)
+            .bind(user_id as i64)
+            .bind(now.to_rfc3339())
+            .bind(expires_at.to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(())
+    }
+    
+    /// Get the user a session token belongs to, if the session hasn't expired
+    pub async fn get_session_user_id(&self, token: &str) -> Result<Option<u32>, Box<dyn std::error::Error>> {
+        let user_id: Option<i64> = sqlx::query_scalar("SELECT user_id FROM sessions WHERE token = ? AND expires_at > ?")
+            .bind(token)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .fetch_optional(&self.pool)
+            .await?;
+        
+        Ok(user_id.map(|id| id as u32))
+    }
+    
+    /// Record an uploaded media file
+    pub async fn create_media(&self, media: &MediaFile) -> Result<u32, Box<dyn std::error::Error>> {
+        let result = sqlx::query(
+            "INSERT INTO media (filename, original_name, mime_type, file_size, uploaded_at, uploaded_by, alt_text)
+             VALUES (?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(&media.filename)
+        .bind(&media.original_name)
+        .bind(&media.mime_type)
+        .bind(media.file_size as i64)
+        .bind(&media.uploaded_at)
+        .bind(media.uploaded_by.map(|id| id as i64))
+        .bind(&media.alt_text)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(result.last_insert_rowid() as u32)
+    }
+    
+    /// Get all media files, newest first
+    pub async fn get_media_files(&self) -> Result<Vec<MediaFile>, Box<dyn std::error::Error>> {
+        let rows = sqlx::query(
+            "SELECT id, filename, original_name, mime_type, file_size, uploaded_at, uploaded_by, alt_text
+             FROM media ORDER BY id DESC"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(rows.into_iter().map(|row| Self::media_from_row(&row)).collect())
+    }
+    
+    /// Get media file by ID
+    pub async fn get_media_by_id(&self, id: u32) -> Result<Option<MediaFile>, Box<dyn std::error::Error>> {
+        let row = sqlx::query(
+            "SELECT id, filename, original_name, mime_type, file_size, uploaded_at, uploaded_by, alt_text
+             FROM media WHERE id = ?"
+        )
+        .bind(id as i64)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(row.map(|row| Self::media_from_row(&row)))
+    }
+    
+    /// Delete a media record
+    pub async fn delete_media(&self, id: u32) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query("DELETE FROM media WHERE id = ?")
+            .bind(id as i64)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(())
+    }
+    
+    fn media_from_row(row: &sqlx::sqlite::SqliteRow) -> MediaFile {
+        MediaFile {
+            id: row.get::<i64, _>("id") as u32,
+            filename: row.get("filename"),
+            original_name: row.get("original_name"),
+            mime_type: row.get("mime_type"),
+            file_size: row.get::<i64, _>("file_size") as u64,
+            uploaded_at: row.get("uploaded_at"),
+            uploaded_by: row.get::<Option<i64>, _>("uploaded_by").map(|id| id as u32),
+            alt_text: row.get("alt_text"),
+        }
+    }
+    
+    /// Get all themes
+    pub async fn get_themes(&self) -> Result<Vec<Theme>, Box<dyn std::error::Error>> {
+        let rows = sqlx::query(&format!("SELECT {} FROM themes ORDER BY id", THEME_COLUMNS))
+            .fetch_all(&self.pool)
+            .await?;
+        
+        Ok(rows.into_iter().map(|row| Self::theme_from_row(&row)).collect())
+    }
+    
+    /// Get theme by ID
+    pub async fn get_theme_by_id(&self, id: u32) -> Result<Option<Theme>, Box<dyn std::error::Error>> {
+        let row = sqlx::query(&format!("SELECT {} FROM themes WHERE id = ?", THEME_COLUMNS))
+            .bind(id as i64)
+            .fetch_optional(&self.pool)
+            .await?;
+        
+        Ok(row.map(|row| Self::theme_from_row(&row)))
+    }
+    
+    /// Get the currently active theme
+    pub async fn get_active_theme(&self) -> Result<Option<Theme>, Box<dyn std::error::Error>> {
+        let row = sqlx::query(&format!("SELECT {} FROM themes WHERE active = 1 LIMIT 1", THEME_COLUMNS))
+            .fetch_optional(&self.pool)
+            .await?;
+        
+        Ok(row.map(|row| Self::theme_from_row(&row)))
+    }
+    
+    /// Create or update a theme. Activation is handled separately by `activate_theme`.
+    pub async fn save_theme(&self, theme: &Theme) -> Result<u32, Box<dyn std::error::Error>> {
+        let variables = serde_json::to_string(&theme.variables)?;
+        let templates = serde_json::to_string(&theme.templates)?;
+        
+        if theme.id == 0 {
+            let result = sqlx::query("INSERT INTO themes (name, description, css_content, active, version, variables, templates) VALUES (?, ?, ?, 0, ?, ?, ?)")
+                .bind(&theme.name)
+                .bind(&theme.description)
+                .bind(&theme.css_content)
+                .bind(&theme.version)
+                .bind(&variables)
+                .bind(&templates)
+                .execute(&self.pool)
+                .await?;
+            
+            Ok(result.last_insert_rowid() as u32)
+        } else {
+            sqlx::query("UPDATE themes SET name=?, description=?, css_content=?, version=?, variables=?, templates=? WHERE id=?")
+                .bind(&theme.name)
+                .bind(&theme.description)
+                .bind(&theme.css_content)
+                .bind(&theme.version)
+                .bind(&variables)
+                .bind(&templates)
+                .bind(theme.id as i64)
+                .execute(&self.pool)
+                .await?;
+            
+            Ok(theme.id)
+        }
+    }
+    
+    /// Make a theme the only active one
+    pub async fn activate_theme(&self, id: u32) -> Result<(), Box<dyn std::error::Error>> {
+        let mut tx = self.pool.begin().await?;
+        
+        let exists: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM themes WHERE id = ?")
+            .bind(id as i64)
+            .fetch_one(&mut *tx)
+            .await?;
+        
+        if exists == 0 {
+            return Err(format!("Theme {} not found", id).into());
+        }
+        
+        sqlx::query("UPDATE themes SET active = (id = ?)")
+            .bind(id as i64)
+            .execute(&mut *tx)
+            .await?;
+        
+        tx.commit().await?;
+        Ok(())
+    }
+    
+    /// Delete a theme. The active theme can't be deleted.
+    pub async fn delete_theme(&self, id: u32) -> Result<(), Box<dyn std::error::Error>> {
+        let result = sqlx::query("DELETE FROM themes WHERE id = ? AND active = 0")
+            .bind(id as i64)
+            .execute(&self.pool)
+            .await?;
+        
+        if result.rows_affected() == 0 {
+            return Err("Theme not found or currently active".into());
+        }
+        
+        Ok(())
+    }
+    
+    /// Save an imported theme package, replacing the theme with the same name if there is one
+    pub async fn import_theme(&self, mut theme: Theme) -> Result<Theme, Box<dyn std::error::Error>> {
+        let existing: Option<(i64, bool)> = sqlx::query_as("SELECT id, active FROM themes WHERE name = ? LIMIT 1")
+            .bind(&theme.name)
+            .fetch_optional(&self.pool)
+            .await?;
+        
+        if let Some((id, active)) = existing {
+            theme.id = id as u32;
+            theme.active = active;
+        }
+        
+        theme.id = self.save_theme(&theme).await?;
+        Ok(theme)
+    }
+    
+    fn theme_from_row(row: &sqlx::sqlite::SqliteRow) -> Theme {
+        Theme {
+            id: row.get::<i64, _>("id") as u32,
+            name: row.get("name"),
+            description: row.get("description"),
+            css_content: row.get("css_content"),
+            active: row.get("active"),
+            version: row.get("version"),
+            variables: serde_json::from_str(row.get("variables")).unwrap_or_default(),
+            templates: serde_json::from_str(row.get("templates")).unwrap_or_default(),
+        }
+    }
+    
+    /// Get all redirects, sorted by source path
+    pub async fn get_redirects(&self) -> Result<Vec<Redirect>, Box<dyn std::error::Error>> {
+        let rows = sqlx::query(&format!("SELECT {} FROM redirects ORDER BY source", REDIRECT_COLUMNS))
+            .fetch_all(&self.pool)
+            .await?;
+        
+        Ok(rows.iter().map(Self::redirect_from_row).collect())
+    }
+    
+    /// Find the redirect for a request path
+    pub async fn find_redirect(&self, path: &str) -> Result<Option<Redirect>, Box<dyn std::error::Error>> {
+        let row = sqlx::query(&format!("SELECT {} FROM redirects WHERE source = ?", REDIRECT_COLUMNS))
+            .bind(normalize_redirect_path(path))
+            .fetch_optional(&self.pool)
+            .await?;
+        
+        Ok(row.as_ref().map(Self::redirect_from_row))
+    }
+    
+    /// Create or update a redirect. New redirects replace any existing one with the same source.
+    pub async fn save_redirect(&self, redirect: &Redirect) -> Result<u32, Box<dyn std::error::Error>> {
+        let source = normalize_redirect_path(&redirect.source);
+        
+        if redirect.id == 0 {
+            let id: i64 = sqlx::query_scalar(
+                "INSERT INTO redirects (source, target, status, created_at) VALUES (?, ?, ?, ?)
+                 ON CONFLICT(source) DO UPDATE SET target = excluded.target, status = excluded.status
+                 RETURNING id"
+            )
+            .bind(&source)
+            .bind(&redirect.target)
+            .bind(redirect.status as i64)
+            .bind(client::time::now_iso8601())
+            .fetch_one(&self.pool)
+            .await?;
+            
+            Ok(id as u32)
+        } else {
+            sqlx::query("UPDATE redirects SET source = ?, target = ?, status = ? WHERE id = ?")
+                .bind(&source)
+                .bind(&redirect.target)
+                .bind(redirect.status as i64)
+                .bind(redirect.id as i64)
+                .execute(&self.pool)
+                .await?;
+            
+            Ok(redirect.id)
+        }
+    }
+    
+    /// Delete a redirect
+    pub async fn delete_redirect(&self, id: u32) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query("DELETE FROM redirects WHERE id = ?")
+            .bind(id as i64)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(())
+    }
+    
+    /// Count a request served by a redirect
+    pub async fn record_redirect_hit(&self, id: u32) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query("UPDATE redirects SET hits = hits + 1 WHERE id = ?")
+            .bind(id as i64)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(())
+    }
+    
+    /// Permanently redirect `old_path` to `new_path` after content moves. Redirects that
+    /// pointed at the old path are repointed so they don't form a chain, and any redirect
+    /// away from the new path is removed so it doesn't shadow the content now living there.
+    async fn redirect_renamed_path(conn: &mut SqliteConnection, old_path: &str, new_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let old_path = normalize_redirect_path(old_path);
+        let new_path = normalize_redirect_path(new_path);
+        
+        Self::clear_redirect(conn, &new_path).await?;
+        
+        sqlx::query("UPDATE redirects SET target = ? WHERE target = ? AND status != 410")
+            .bind(&new_path)
+            .bind(&old_path)
+            .execute(&mut *conn)
+            .await?;
+        
+        let redirect = Redirect::permanent(&old_path, &new_path);
+        sqlx::query(
+            "INSERT INTO redirects (source, target, status, created_at) VALUES (?, ?, ?, ?)
+             ON CONFLICT(source) DO UPDATE SET target = excluded.target, status = excluded.status"
+        )
+        .bind(&redirect.source)
+        .bind(&redirect.target)
+        .bind(redirect.status as i64)
+        .bind(client::time::now_iso8601())
+        .execute(&mut *conn)
+        .await?;
+        
+        Ok(())
+    }
+    
+    /// Remove the redirect away from `path`, if any, because content now lives there
+    async fn clear_redirect(conn: &mut SqliteConnection, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query("DELETE FROM redirects WHERE source = ?")
+            .bind(normalize_redirect_path(path))
+            .execute(conn)
+            .await?;
+        
+        Ok(())
+    }
+    
+    fn redirect_from_row(row: &sqlx::sqlite::SqliteRow) -> Redirect {
+        Redirect {
+            id: row.get::<i64, _>("id") as u32,
+            source: row.get("source"),
+            target: row.get("target"),
+            status: row.get::<i64, _>("status") as u16,
+            hits: row.get::<i64, _>("hits") as u32,
+            created_at: row.get("created_at"),
+        }
+    }
+    
+    /// Store an analytics event
+    pub async fn record_analytics_event(&self, event: &AnalyticsEvent) -> Result<u32, Box<dyn std::error::Error>> {
+        let result = sqlx::query(
+            "INSERT INTO analytics_events (event_type, path, visitor_hash, referrer, device, timestamp) VALUES (?, ?, ?, ?, ?, ?)"
+        )
+        .bind(&event.event_type)
+        .bind(&event.path)
+        .bind(&event.visitor_hash)
+        .bind(&event.referrer)
+        .bind(&event.device)
+        .bind(&event.timestamp)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(result.last_insert_rowid() as u32)
+    }
+    
+    /// Analytics events recorded on `date` (`YYYY-MM-DD`), oldest first
+    pub async fn get_analytics_events(&self, date: &str) -> Result<Vec<AnalyticsEvent>, Box<dyn std::error::Error>> {
+        let rows = sqlx::query(
+            "SELECT id, event_type, path, visitor_hash, referrer, device, timestamp FROM analytics_events
+             WHERE timestamp >= ? AND timestamp < ? || 'U' ORDER BY timestamp"
+        )
+        .bind(date)
+        .bind(date)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(rows
+            .into_iter()
+            .map(|row| AnalyticsEvent {
+                id: row.get::<i64, _>("id") as u32,
+                event_type: row.get("event_type"),
+                path: row.get("path"),
+                visitor_hash: row.get("visitor_hash"),
+                referrer: row.get("referrer"),
+                device: row.get("device"),
+                timestamp: row.get("timestamp"),
+            })
+            .collect())
+    }
+    
+    /// Traffic summary for `date`: the rollup for finished days, otherwise computed from the raw events
+    pub async fn get_daily_stats(&self, date: &str) -> Result<DailyStats, Box<dyn std::error::Error>> {
+        if date < client::time::today_date().as_str() {
+            if let Some(stats) = self.get_daily_rollups(date, date).await?.pop() {
+                return Ok(stats);
+            }
+        }
+        
+        let events = self.get_analytics_events(date).await?;
+        Ok(DailyStats::from_events(date, &events))
+    }
+    
+    /// Traffic summaries for every day from `start` to `end`, inclusive
+    pub async fn get_daily_stats_range(&self, start: &str, end: &str) -> Result<Vec<DailyStats>, Box<dyn std::error::Error>> {
+        let today = client::time::today_date();
+        let rollups: HashMap<String, DailyStats> = self.get_daily_rollups(start, end).await?
+            .into_iter()
+            .map(|stats| (stats.date.clone(), stats))
+            .collect();
+        
+        let mut days = Vec::new();
+        for date in client::time::date_range(start, end) {
+            let stats = match rollups.get(&date) {
+                Some(stats) if date < today => stats.clone(),
+                _ => DailyStats::from_events(&date, &self.get_analytics_events(&date).await?),
+            };
+            days.push(stats);
+        }
+        
+        Ok(days)
+    }
+    
+    /// Stored daily rollups from `start` to `end`, inclusive
+    async fn get_daily_rollups(&self, start: &str, end: &str) -> Result<Vec<DailyStats>, Box<dyn std::error::Error>> {
+        let rows = sqlx::query(
+            "SELECT date, views, unique_visitors, avg_session_duration, bounce_rate, top_pages, top_referrers, devices
+             FROM analytics_daily WHERE date >= ? AND date <= ? ORDER BY date"
+        )
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(rows
+            .into_iter()
+            .map(|row| DailyStats {
+                date: row.get("date"),
+                total_views: row.get::<i64, _>("views") as u32,
+                unique_visitors: row.get::<i64, _>("unique_visitors") as u32,
+                avg_session_duration: row.get("avg_session_duration"),
+                bounce_rate: row.get("bounce_rate"),
+                top_pages: serde_json::from_str(row.get("top_pages")).unwrap_or_default(),
+                top_referrers: serde_json::from_str(row.get("top_referrers")).unwrap_or_default(),
+                devices: serde_json::from_str(row.get("devices")).unwrap_or_default(),
+            })
+            .collect())
+    }
+    
+    /// Page views per hour of `date`, from the hourly rollups and raw events
+    pub async fn get_hourly_stats(&self, date: &str) -> Result<Vec<HourlyStats>, Box<dyn std::error::Error>> {
+        let events = self.get_analytics_events(date).await?;
+        if !events.is_empty() {
+            return Ok(HourlyStats::from_events(date, &events));
+        }
+        
+        let rows = sqlx::query("SELECT hour, views, unique_visitors FROM analytics_hourly WHERE hour LIKE ? || '%' ORDER BY hour")
+            .bind(date)
+            .fetch_all(&self.pool)
+            .await?;
+        
+        Ok(rows
+            .into_iter()
+            .map(|row| HourlyStats {
+                hour: row.get("hour"),
+                views: row.get::<i64, _>("views") as u32,
+                unique_visitors: row.get::<i64, _>("unique_visitors") as u32,
+            })
+            .collect())
+    }
+    
+    /// Store performance metrics; a missing timestamp is set to now
+    pub async fn record_metrics(&self, metrics: &[Metric]) -> Result<(), Box<dyn std::error::Error>> {
+        let now = client::time::now_iso8601();
+        let mut tx = self.pool.begin().await?;
+        for metric in metrics {
+            sqlx::query("INSERT INTO metrics (name, value, route, timestamp) VALUES (?, ?, ?, ?)")
+                .bind(&metric.name)
+                .bind(metric.value)
+                .bind(metric.route())
+                .bind(if metric.timestamp.is_empty() { &now } else { &metric.timestamp })
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        
+        Ok(())
+    }
+    
+    /// p50/p75/p95 of every metric on every route from `start` to `end` (`YYYY-MM-DD`, inclusive)
+    pub async fn get_metric_summaries(&self, start: &str, end: &str) -> Result<Vec<MetricSummary>, Box<dyn std::error::Error>> {
+        let rows: Vec<(String, String, f64)> = sqlx::query_as(
+            "SELECT route, name, value FROM metrics WHERE timestamp >= ? AND timestamp < ? || 'U' ORDER BY route, name"
+        )
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        let mut groups: Vec<((String, String), Vec<f64>)> = Vec::new();
+        for (route, name, value) in rows {
+            match groups.last_mut() {
+                Some((key, values)) if key.0 == route && key.1 == name => values.push(value),
+                _ => groups.push(((route, name), vec![value])),
+            }
+        }
+        
+        Ok(groups
+            .into_iter()
+            .map(|((route, name), values)| MetricSummary::from_values(&route, &name, values))
+            .collect())
+    }
+    
+    /// Aggregate raw analytics events into the hourly and daily rollup tables, then delete raw
+    /// events and performance metrics older than `retention_days`. Days from the newest existing
+    /// rollup onwards are recomputed, so running this repeatedly is safe. Returns (days rolled up,
+    /// events deleted).
+    pub async fn rollup_analytics(&self, retention_days: u32) -> Result<(usize, u64), Box<dyn std::error::Error>> {
+        let newest: Option<String> = sqlx::query_scalar("SELECT MAX(date) FROM analytics_daily")
+            .fetch_one(&self.pool)
+            .await?;
+        let dates: Vec<String> = sqlx::query_scalar(
+            "SELECT DISTINCT substr(timestamp, 1, 10) AS date FROM analytics_events WHERE timestamp >= ? ORDER BY date"
+        )
+        .bind(newest.unwrap_or_default())
+        .fetch_all(&self.pool)
+        .await?;
+        
+        for date in &dates {
+            let events = self.get_analytics_events(date).await?;
+            let stats = DailyStats::from_events(date, &events);
+            
+            let mut tx = self.pool.begin().await?;
+            sqlx::query(
+                "INSERT OR REPLACE INTO analytics_daily
+                 (date, views, unique_visitors, avg_session_duration, bounce_rate, top_pages, top_referrers, devices)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+            )
+            .bind(&stats.date)
+            .bind(stats.total_views as i64)
+            .bind(stats.unique_visitors as i64)
+            .bind(stats.avg_session_duration)
+            .bind(stats.bounce_rate)
+            .bind(serde_json::to_string(&stats.top_pages)?)
+            .bind(serde_json::to_string(&stats.top_referrers)?)
+            .bind(serde_json::to_string(&stats.devices)?)
+            .execute(&mut *tx)
+            .await?;
+            
+            for hour in HourlyStats::from_events(date, &events) {
+                sqlx::query("INSERT OR REPLACE INTO analytics_hourly (hour, views, unique_visitors) VALUES (?, ?, ?)")
+                    .bind(&hour.hour)
+                    .bind(hour.views as i64)
+                    .bind(hour.unique_visitors as i64)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            tx.commit().await?;
+        }
+        
+        // Every day before the cutoff has been rolled up by now, so its raw rows can go
+        let cutoff = client::time::add_days(&client::time::today_date(), -(retention_days as i64)).unwrap_or_default();
+        let pruned = sqlx::query("DELETE FROM analytics_events WHERE timestamp < ?")
+            .bind(&cutoff)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+        sqlx::query("DELETE FROM metrics WHERE timestamp < ?")
+            .bind(&cutoff)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok((dates.len(), pruned))
+    }
+    
+    /// The visitor hash salt for `date`. A new salt replaces the previous day's, which is not
+    /// kept, so hashes can't be linked across days. Every instance sharing the database agrees
+    /// on the salt because only the first writer of the day replaces it.
+    pub async fn analytics_salt(&self, date: &str) -> Result<String, Box<dyn std::error::Error>> {
+        sqlx::query(
+            "INSERT INTO settings (key, value, description) VALUES ('analytics.salt', ?, 'Visitor hash salt; rotated daily')
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value WHERE substr(settings.value, 1, 10) != ?"
+        )
+        .bind(format!("{} {}", date, crate::analytics::new_salt()))
+        .bind(date)
+        .execute(&self.pool)
+        .await?;
+        
+        let value: String = sqlx::query_scalar("SELECT value FROM settings WHERE key = 'analytics.salt'")
+            .fetch_one(&self.pool)
+            .await?;
+        
+        Ok(value.split_once(' ').map(|(_, salt)| salt.to_string()).unwrap_or(value))
+    }
+    
+    /// Every scheduled job, soonest first
+    pub async fn get_scheduled_jobs(&self) -> Result<Vec<ScheduledContent>, Box<dyn std::error::Error>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM scheduled_jobs ORDER BY scheduled_at, id", SCHEDULED_JOB_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(rows.iter().filter_map(Self::scheduled_job_from_row).collect())
+    }
+    
+    /// Schedule a new job, or change a job that hasn't started yet
+    pub async fn save_scheduled_job(&self, job: &ScheduledContent) -> Result<u32, Box<dyn std::error::Error>> {
+        if job.id == 0 {
+            let result = sqlx::query(
+                "INSERT INTO scheduled_jobs (content_type, content_id, action, payload, scheduled_at, status, created_at, created_by)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+            )
+            .bind(job.content_type.as_str())
+            .bind(job.content_id as i64)
+            .bind(job.action.as_str())
+            .bind(&job.payload)
+            .bind(&job.scheduled_at)
+            .bind(ScheduleStatus::Pending.as_str())
+            .bind(client::time::now_iso8601())
+            .bind(job.created_by as i64)
+            .execute(&self.pool)
+            .await?;
+            
+            Ok(result.last_insert_rowid() as u32)
+        } else {
+            let result = sqlx::query(
+                "UPDATE scheduled_jobs SET content_type = ?, content_id = ?, action = ?, payload = ?, scheduled_at = ?
+                 WHERE id = ? AND status = ?"
+            )
+            .bind(job.content_type.as_str())
+            .bind(job.content_id as i64)
+            .bind(job.action.as_str())
+            .bind(&job.payload)
+            .bind(&job.scheduled_at)
+            .bind(job.id as i64)
+            .bind(ScheduleStatus::Pending.as_str())
+            .execute(&self.pool)
+            .await?;
+            
+            if result.rows_affected() == 0 {
+                return Err(format!("Scheduled job {} has already run or doesn't exist", job.id).into());
+            }
+            Ok(job.id)
+        }
+    }
+    
+    /// Cancel a job that hasn't started yet; returns whether one was cancelled
+    pub async fn cancel_scheduled_job(&self, id: u32) -> Result<bool, Box<dyn std::error::Error>> {
+        let result = sqlx::query("DELETE FROM scheduled_jobs WHERE id = ? AND status = ?")
+            .bind(id as i64)
+            .bind(ScheduleStatus::Pending.as_str())
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(result.rows_affected() > 0)
+    }
+    
+    /// Queue a failed job to run again at `scheduled_at`; returns whether there was one to retry
+    pub async fn retry_scheduled_job(&self, id: u32, scheduled_at: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        let result = sqlx::query(
+            "UPDATE scheduled_jobs SET status = ?, scheduled_at = ?, claimed_by = NULL, claimed_at = NULL, error = NULL, completed_at = NULL
+             WHERE id = ? AND status = ?"
+        )
+        .bind(ScheduleStatus::Pending.as_str())
+        .bind(scheduled_at)
+        .bind(id as i64)
+        .bind(ScheduleStatus::Failed.as_str())
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(result.rows_affected() > 0)
+    }
+    
+    /// Mark up to `limit` pending jobs due by `now` as processing by `worker` and return them.
+    /// Claiming is a single UPDATE, so when several server instances share the database each
+    /// job is handed to exactly one of them.
+    pub async fn claim_due_jobs(&self, worker: &str, now: &str, limit: u32) -> Result<Vec<ScheduledContent>, Box<dyn std::error::Error>> {
+        let rows = sqlx::query(&format!(
+            "UPDATE scheduled_jobs SET status = ?1, claimed_by = ?2, claimed_at = ?3
+             WHERE status = ?4 AND id IN (
+                 SELECT id FROM scheduled_jobs WHERE status = ?4 AND scheduled_at <= ?3 ORDER BY scheduled_at, id LIMIT ?5
+             )
+             RETURNING {}",
+            SCHEDULED_JOB_COLUMNS
+        ))
+        .bind(ScheduleStatus::Processing.as_str())
+        .bind(worker)
+        .bind(now)
+        .bind(ScheduleStatus::Pending.as_str())
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        let mut jobs: Vec<ScheduledContent> = rows.iter().filter_map(Self::scheduled_job_from_row).collect();
+        jobs.sort_by(|a, b| (&a.scheduled_at, a.id).cmp(&(&b.scheduled_at, b.id)));
+        Ok(jobs)
+    }
+    
+    /// Record the outcome of a job claimed by `worker`: completed, or failed with `error`
+    pub async fn finish_scheduled_job(&self, id: u32, worker: &str, error: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+        let status = if error.is_some() { ScheduleStatus::Failed } else { ScheduleStatus::Completed };
+        
+        sqlx::query("UPDATE scheduled_jobs SET status = ?, error = ?, completed_at = ? WHERE id = ? AND claimed_by = ? AND status = ?")
+            .bind(status.as_str())
+            .bind(error)
+            .bind(client::time::now_iso8601())
+            .bind(id as i64)
+            .bind(worker)
+            .bind(ScheduleStatus::Processing.as_str())
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(())
+    }
+    
+    /// Fail jobs claimed before `claimed_before` that never finished, e.g. because their server
+    /// stopped mid-run. They are not retried, since the action may already have happened.
+    pub async fn fail_abandoned_jobs(&self, claimed_before: &str) -> Result<u64, Box<dyn std::error::Error>> {
+        let result = sqlx::query(
+            "UPDATE scheduled_jobs SET status = ?, error = ?, completed_at = ? WHERE status = ? AND claimed_at < ?"
+        )
+        .bind(ScheduleStatus::Failed.as_str())
+        .bind("Interrupted before finishing; check the content and schedule it again if needed")
+        .bind(client::time::now_iso8601())
+        .bind(ScheduleStatus::Processing.as_str())
+        .bind(claimed_before)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(result.rows_affected())
+    }
+    
+    /// Jobs with values this version doesn't know are skipped
+    fn scheduled_job_from_row(row: &sqlx::sqlite::SqliteRow) -> Option<ScheduledContent> {
+        Some(ScheduledContent {
+            id: row.get::<i64, _>("id") as u32,
+            content_type: ContentType::parse(row.get("content_type"))?,
+            content_id: row.get::<i64, _>("content_id") as u32,
+            scheduled_at: row.get("scheduled_at"),
+            action: ScheduledAction::parse(row.get("action"))?,
+            status: ScheduleStatus::parse(row.get("status"))?,
+            created_at: row.get("created_at"),
+            created_by: row.get::<i64, _>("created_by") as u32,
+            payload: row.get("payload"),
+            error: row.get("error"),
+            completed_at: row.get("completed_at"),
+        })
+    }
+    
+    /// Every language, active or not, by code
+    pub async fn get_languages(&self) -> Result<Vec<Language>, Box<dyn std::error::Error>> {
+        let rows = sqlx::query("SELECT code, name, native_name, direction, active FROM languages ORDER BY code")
+            .fetch_all(&self.pool)
+            .await?;
+        
+        Ok(rows.iter().map(|row| Language {
+            code: row.get("code"),
+            name: row.get("name"),
+            native_name: row.get("native_name"),
+            direction: TextDirection::parse(row.get("direction")).unwrap_or(TextDirection::LeftToRight),
+            active: row.get("active"),
+        }).collect())
+    }
+    
+    /// Add a language, or update the one with the same code
+    pub async fn save_language(&self, language: &Language) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query(
+            "INSERT INTO languages (code, name, native_name, direction, active) VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT(code) DO UPDATE SET name = excluded.name, native_name = excluded.native_name,
+                 direction = excluded.direction, active = excluded.active"
+        )
+        .bind(&language.code)
+        .bind(&language.name)
+        .bind(&language.native_name)
+        .bind(language.direction.as_str())
+        .bind(language.active)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    /// The site's default language: what visitors see before choosing one, and the language
+    /// of content at unprefixed URLs. Stored as the SEO settings' content language.
+    pub async fn get_default_language(&self) -> Result<String, Box<dyn std::error::Error>> {
+        Ok(self.get_seo_settings().await?.language)
+    }
+    
+    pub async fn set_default_language(&self, code: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.set_setting("seo.language", code, Some("Default content language")).await
+    }
+    
+    /// `language`, or the site's default language when it's blank
+    async fn content_language(&self, language: &str) -> Result<String, Box<dyn std::error::Error>> {
+        match language.trim() {
+            "" => self.get_default_language().await,
+            language => Ok(language.to_string()),
+        }
+    }
+    
+    /// Translations in the given languages, or in every language when `languages` is empty
+    pub async fn get_translations(&self, languages: &[&str]) -> Result<Vec<Translation>, Box<dyn std::error::Error>> {
+        let sql = if languages.is_empty() {
+            "SELECT namespace, key, language_code, value FROM translations ORDER BY namespace, key, language_code".to_string()
+        } else {
+            format!(
+                "SELECT namespace, key, language_code, value FROM translations WHERE language_code IN ({}) ORDER BY namespace, key, language_code",
+                vec!["?"; languages.len()].join(", ")
+            )
+        };
+        
+        let mut query = sqlx::query(&sql);
+        for language in languages {
+            query = query.bind(*language);
+        }
+        let rows = query.fetch_all(&self.pool).await?;
+        
+        Ok(rows.iter().map(|row| Translation {
+            namespace: row.get("namespace"),
+            key: row.get("key"),
+            language_code: row.get("language_code"),
+            value: row.get("value"),
+        }).collect())
+    }
+    
+    /// Create or replace one message
+    pub async fn save_translation(&self, translation: &Translation) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query(
+            "INSERT INTO translations (namespace, key, language_code, value, updated_at) VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT(namespace, key, language_code) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at"
+        )
+        .bind(&translation.namespace)
+        .bind(&translation.key)
+        .bind(&translation.language_code)
+        .bind(&translation.value)
+        .bind(client::time::now_iso8601())
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    /// Store the messages an import adds or changes, and delete the ones it removes when
+    /// `remove_missing` is set
+    pub async fn apply_translation_import(&self, preview: &TranslationImportPreview, remove_missing: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let now = client::time::now_iso8601();
+        let mut tx = self.pool.begin().await?;
+        for message in preview.added.iter().chain(&preview.changed) {
+            sqlx::query(
+                "INSERT INTO translations (namespace, key, language_code, value, updated_at) VALUES (?, ?, ?, ?, ?)
+                 ON CONFLICT(namespace, key, language_code) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at"
+            )
+            .bind(&message.namespace)
+            .bind(&message.key)
+            .bind(&preview.language)
+            .bind(&message.value)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await?;
+        }
+        if remove_missing {
+            for message in &preview.removed {
+                sqlx::query("DELETE FROM translations WHERE namespace = ? AND key = ? AND language_code = ?")
+                    .bind(&message.namespace)
+                    .bind(&message.key)
+                    .bind(&preview.language)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+        tx.commit().await?;
+        
+        Ok(())
+    }
+    
+    /// Delete a message in every language
+    pub async fn delete_translation_key(&self, namespace: &str, key: &str) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query("DELETE FROM translations WHERE namespace = ? AND key = ?")
+            .bind(namespace)
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(())
+    }
+    
+    /// Get all settings whose key starts with `prefix`
+    pub async fn get_settings(&self, prefix: &str) -> Result<HashMap<String, String>, Box<dyn std::error::Error>> {
+        let rows: Vec<(String, String)> = sqlx::query_as("SELECT key, value FROM settings WHERE key LIKE ? || '%'")
+            .bind(prefix)
+            .fetch_all(&self.pool)
+            .await?;
+        
+        Ok(rows.into_iter().collect())
+    }
+    
+    /// Create or replace a setting
+    pub async fn set_setting(&self, key: &str, value: &str, description: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query(
+            "INSERT INTO settings (key, value, description) VALUES (?, ?, ?)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value"
+        )
+        .bind(key)
+        .bind(value)
+        .bind(description)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    /// Site-wide SEO defaults. Missing settings keep their built-in defaults, and the site
+    /// URL falls back to the `BASE_URL` environment variable.
+    pub async fn get_seo_settings(&self) -> Result<SeoSettings, Box<dyn std::error::Error>> {
+        let settings = self.get_settings("seo.").await?;
+        let mut seo = SeoSettings::default();
+        
+        if let Ok(base_url) = std::env::var("BASE_URL") {
+            seo.site_url = base_url;
+        }
+        
+        let text = |key: &str| settings.get(key).filter(|value| !value.trim().is_empty()).cloned();
+        if let Some(value) = text("seo.site_url") { seo.site_url = value; }
+        if let Some(value) = text("seo.site_title") { seo.site_title = value; }
+        if let Some(value) = text("seo.site_description") { seo.site_description = value; }
+        if let Some(value) = text("seo.keywords") { seo.keywords = value; }
+        if let Some(value) = text("seo.language") { seo.language = value; }
+        if let Some(value) = text("seo.robots") { seo.robots = value; }
+        if let Some(value) = text("seo.time_zone") { seo.time_zone = value; }
+        seo.default_image = text("seo.default_image");
+        seo.author = text("seo.author");
+        
+        Ok(seo)
+    }
+    
+    /// Save site-wide SEO defaults
+    pub async fn save_seo_settings(&self, seo: &SeoSettings) -> Result<(), Box<dyn std::error::Error>> {
+        let settings = [
+            ("seo.site_url", seo.site_url.as_str(), "Public URL of the site"),
+            ("seo.site_title", seo.site_title.as_str(), "Default page title"),
+            ("seo.site_description", seo.site_description.as_str(), "Default meta description"),
+            ("seo.keywords", seo.keywords.as_str(), "Default meta keywords"),
+            ("seo.default_image", seo.default_image.as_deref().unwrap_or(""), "Default Open Graph image"),
+            ("seo.author", seo.author.as_deref().unwrap_or(""), "Default author"),
+            ("seo.language", seo.language.as_str(), "Default content language"),
+            ("seo.robots", seo.robots.as_str(), "Default robots directive"),
+            ("seo.time_zone", seo.time_zone.as_str(), "Time zone dates are shown in"),
+        ];
+        
+        for (key, value, description) in settings {
+            self.set_setting(key, value, Some(description)).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Raw HTML allowed in markdown. A policy with no stored tags keeps its built-in default.
+    pub async fn get_html_settings(&self) -> Result<HtmlSettings, Box<dyn std::error::Error>> {
+        let settings = self.get_settings("html.").await?;
+        let list = |key: &str| settings.get(key).map(|value| {
+            value.split(',').map(str::trim).filter(|item| !item.is_empty()).map(str::to_string).collect::<Vec<_>>()
+        });
+        let policy = |prefix: &str, default: HtmlPolicy| match list(&format!("{}.tags", prefix)) {
+            Some(tags) => HtmlPolicy {
+                tags,
+                attributes: list(&format!("{}.attributes", prefix)).unwrap_or_default(),
+                nofollow: settings.get(&format!("{}.nofollow", prefix)).is_some_and(|value| value == "true"),
+            },
+            None => default,
+        };
+        
+        Ok(HtmlSettings {
+            posts: policy("html.posts", HtmlPolicy::posts()),
+            comments: policy("html.comments", HtmlPolicy::comments()),
+        })
+    }
+    
+    /// Save the raw HTML allowed in markdown
+    pub async fn save_html_settings(&self, html: &HtmlSettings) -> Result<(), Box<dyn std::error::Error>> {
+        let policies = [("html.posts", &html.posts, "posts and pages"), ("html.comments", &html.comments, "comments")];
+        for (prefix, policy, content) in policies {
+            self.set_setting(&format!("{}.tags", prefix), &policy.tags.join(","), Some(&format!("HTML tags allowed in {}", content))).await?;
+            self.set_setting(&format!("{}.attributes", prefix), &policy.attributes.join(","), Some(&format!("HTML attributes allowed in {}", content))).await?;
+            self.set_setting(&format!("{}.nofollow", prefix), if policy.nofollow { "true" } else { "false" }, Some(&format!("Mark links in {} nofollow", content))).await?;
+        }
+        
+        Ok(())
+    }
+    
     /// Initialize with default data
     pub async fn init_default_data(&self) -> Result<(), Box<dyn std::error::Error>> {
         // Check if we already have data
@@ -351,6 +2229,7 @@ Welcome to the future of content management!"#.to_string(),
                 created_at: chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
                 updated_at: chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
                 published: true,
+                ..Default::default()
             };
             
             self.save_post(&welcome_post).await?;
@@ -1,19 +1,157 @@
 //! This crate contains all shared fullstack server functions.
 use dioxus::prelude::*;
-use client::{Post, User, Session, UserRole};
+use client::{AnalyticsReport, ColorScheme, DailyStats, HourlyStats, HtmlSettings, Language, MediaFile, MetricSummary, Page, Post, Redirect, RequestLocale, ScheduleStatus, ScheduledContent, SeoSettings, User, Session, Theme, Translation, TranslationImportPreview, UserRole};
 
 #[cfg(not(target_arch = "wasm32"))]
 use sqlx::Row;
 
 #[cfg(not(target_arch = "wasm32"))]
-mod database;
+pub mod analytics;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod database;
 #[cfg(not(target_arch = "wasm32"))]
 mod email;
 #[cfg(not(target_arch = "wasm32"))]
+pub mod storage;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod theme_package;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod translation_files;
+#[cfg(not(target_arch = "wasm32"))]
 use database::Database;
 #[cfg(not(target_arch = "wasm32"))]
 use email::EmailService;
 
+/// Cookie holding the login session token
+#[cfg(not(target_arch = "wasm32"))]
+pub const SESSION_COOKIE: &str = "session";
+/// How long a login session lasts
+#[cfg(not(target_arch = "wasm32"))]
+const SESSION_TTL_DAYS: i64 = 30;
+
+/// Read a cookie from the current request
+#[cfg(feature = "server")]
+fn request_cookie(name: &str) -> Option<String> {
+    let context = dioxus::server::server_context();
+    let parts = context.request_parts();
+    parts.headers.get_all("cookie")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.to_string())
+}
+
+/// Add a `Set-Cookie` header to the response. `None` clears the cookie.
+#[cfg(feature = "server")]
+fn set_response_cookie(name: &str, value: Option<&str>, max_age_secs: i64, http_only: bool) -> Result<(), ServerFnError> {
+    let mut cookie = match value {
+        Some(value) => format!("{}={}; Path=/; Max-Age={}; SameSite=Lax", name, value, max_age_secs),
+        None => format!("{}=; Path=/; Max-Age=0; SameSite=Lax", name),
+    };
+    if http_only {
+        cookie.push_str("; HttpOnly");
+    }
+    
+    let header = cookie.parse()
+        .map_err(|_| ServerFnError::ServerError(format!("Invalid cookie value for {}", name)))?;
+    dioxus::server::server_context().headers_mut().append("set-cookie", header);
+    Ok(())
+}
+
+// Without the server there is no HTTP request to read cookies from or respond to
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "server")))]
+fn request_cookie(_name: &str) -> Option<String> {
+    None
+}
+
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "server")))]
+fn set_response_cookie(_name: &str, _value: Option<&str>, _max_age_secs: i64, _http_only: bool) -> Result<(), ServerFnError> {
+    Ok(())
+}
+
+/// Language the server negotiated for the current request, if it ran the negotiation
+#[cfg(feature = "server")]
+fn request_locale() -> Option<RequestLocale> {
+    dioxus::server::server_context().request_parts().extensions.get::<RequestLocale>().cloned()
+}
+
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "server")))]
+fn request_locale() -> Option<RequestLocale> {
+    None
+}
+
+/// The logged-in user of the current request, from the session cookie
+#[cfg(not(target_arch = "wasm32"))]
+async fn current_user_id(db: &Database) -> Result<Option<u32>, ServerFnError> {
+    let Some(token) = request_cookie(SESSION_COOKIE) else {
+        return Ok(None);
+    };
+    
+    db.get_session_user_id(&token).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Whether the current request comes from a logged-in author, editor or admin, who may
+/// preview content readers can't see yet
+#[cfg(not(target_arch = "wasm32"))]
+async fn can_preview(db: &Database) -> Result<bool, ServerFnError> {
+    let Some(user_id) = current_user_id(db).await? else {
+        return Ok(false);
+    };
+    
+    let role = db.get_user_role(user_id).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    Ok(matches!(role, Some(UserRole::Admin | UserRole::Editor | UserRole::Author)))
+}
+
+/// Roles that may change the site's settings, themes and languages
+#[cfg(not(target_arch = "wasm32"))]
+pub const ADMIN_ROLES: &[UserRole] = &[UserRole::Admin];
+
+/// Roles that may upload and delete media
+#[cfg(not(target_arch = "wasm32"))]
+pub const AUTHOR_ROLES: &[UserRole] = &[UserRole::Admin, UserRole::Editor, UserRole::Author];
+
+/// Roles that may manage content and see the site's analytics
+#[cfg(not(target_arch = "wasm32"))]
+pub const EDITOR_ROLES: &[UserRole] = &[UserRole::Admin, UserRole::Editor];
+
+/// The user holding the session `token`, if their role is one of `roles`
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn session_user_with_role(db: &Database, token: &str, roles: &[UserRole]) -> Result<Option<u32>, Box<dyn std::error::Error>> {
+    let Some(user_id) = db.get_session_user_id(token).await? else {
+        return Ok(None);
+    };
+    
+    let role = db.get_user_role(user_id).await?;
+    Ok(role.filter(|role| roles.contains(role)).map(|_| user_id))
+}
+
+/// The logged-in user of the current request; fails unless their role is one of `roles`
+#[cfg(not(target_arch = "wasm32"))]
+async fn require_role(db: &Database, roles: &[UserRole]) -> Result<u32, ServerFnError> {
+    let Some(token) = request_cookie(SESSION_COOKIE) else {
+        return Err(ServerFnError::ServerError("You need to log in to do that".to_string()));
+    };
+    
+    session_user_with_role(db, &token, roles).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?
+        .ok_or_else(|| ServerFnError::ServerError("You don't have permission to do that".to_string()))
+}
+
+/// Fail unless `code` is one of the site's active languages
+#[cfg(not(target_arch = "wasm32"))]
+async fn check_active_language(db: &Database, code: &str) -> Result<(), ServerFnError> {
+    let languages = db.get_languages().await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    if !languages.iter().any(|language| language.code == code && language.active) {
+        return Err(ServerFnError::ServerError(format!("{} is not an active language", code)));
+    }
+    Ok(())
+}
+
 /// Echo the user input on the server.
 #[server(Echo)]
 pub async fn echo(input: String) -> Result<String, ServerFnError> {
@@ -30,23 +168,47 @@ pub async fn get_posts() -> Result<Vec<Post>, ServerFnError> {
         .map_err(|e| ServerFnError::ServerError(e.to_string()))
 }
 
-/// Get post by ID
+/// Get post by ID. Like [`get_post_by_slug`], readers only get posts that are live.
 #[server(GetPostById)]
 pub async fn get_post_by_id(id: u32) -> Result<Option<Post>, ServerFnError> {
     let db = Database::init("sqlite://cms.db").await
         .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
     
-    db.get_post_by_id(id).await
-        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    let post = if can_preview(&db).await? {
+        db.get_post_by_id(id).await
+    } else {
+        db.get_live_post_by_id(id).await
+    };
+    post.map_err(|e| ServerFnError::ServerError(e.to_string()))
 }
 
-/// Get post by slug
+/// Get post by slug. Readers only get posts that are live; editors also get drafts,
+/// embargoed and expired posts so they can preview them.
 #[server(GetPostBySlug)]
 pub async fn get_post_by_slug(slug: String) -> Result<Option<Post>, ServerFnError> {
     let db = Database::init("sqlite://cms.db").await
         .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
     
-    db.get_post_by_slug(&slug).await
+    let post = if can_preview(&db).await? {
+        db.get_post_by_slug_for_preview(&slug).await
+    } else {
+        db.get_post_by_slug(&slug).await
+    };
+    post.map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Get every post, drafts included, for the admin editor and SEO report. Only authors,
+/// editors and admins may list them.
+#[server(GetAllPosts)]
+pub async fn get_all_posts() -> Result<Vec<Post>, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    if !can_preview(&db).await? {
+        return Err(ServerFnError::ServerError("Only authors, editors and admins can list unpublished posts".to_string()));
+    }
+    
+    db.get_all_posts().await
         .map_err(|e| ServerFnError::ServerError(e.to_string()))
 }
 
@@ -56,20 +218,586 @@ pub async fn save_post(post: Post) -> Result<u32, ServerFnError> {
     let db = Database::init("sqlite://cms.db").await
         .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
     
+    let mut post = post;
+    (post.publish_at, post.expire_at) = client::normalize_window(post.publish_at.as_deref(), post.expire_at.as_deref())
+        .map_err(ServerFnError::ServerError)?;
+    post.updated_at = client::time::now_iso8601();
+    if post.created_at.is_empty() {
+        post.created_at = post.updated_at.clone();
+    }
+    
     db.save_post(&post).await
         .map_err(|e| ServerFnError::ServerError(e.to_string()))
 }
 
+/// Plan a draft for a day (`YYYY-MM-DD`) on the content calendar, or clear its date with `None`
+#[server(SetPostTargetDate)]
+pub async fn set_post_target_date(id: u32, target_date: Option<String>) -> Result<(), ServerFnError> {
+    if let Some(date) = &target_date {
+        if date.len() != 10 || client::time::date_to_days(date).is_none() {
+            return Err(ServerFnError::ServerError(format!("\"{}\" is not a date", date)));
+        }
+    }
+    
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    match db.set_post_target_date(id, target_date.as_deref()).await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(ServerFnError::ServerError(format!("Post {} not found", id))),
+        Err(e) => Err(ServerFnError::ServerError(e.to_string())),
+    }
+}
+
+/// Get the translations of a post readers can see, the post itself included
+#[server(GetPostTranslations)]
+pub async fn get_post_translations(group: String) -> Result<Vec<Post>, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    db.get_post_translations(&group).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Start translating post `id` into `language`; returns the id of the new draft
+#[server(CreatePostTranslation)]
+pub async fn create_post_translation(id: u32, language: String) -> Result<u32, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    check_active_language(&db, &language).await?;
+    match db.create_post_translation(id, &language).await {
+        Ok(Some(id)) => Ok(id),
+        Ok(None) => Err(ServerFnError::ServerError(format!("Post {} not found", id))),
+        Err(e) => Err(ServerFnError::ServerError(e.to_string())),
+    }
+}
+
+/// Search published posts by title and content
+#[server(SearchPosts)]
+pub async fn search_posts(query: String) -> Result<Vec<Post>, ServerFnError> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    db.search_published_posts(query, 50).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Get all published pages
+#[server(GetPublishedPages)]
+pub async fn get_published_pages() -> Result<Vec<Page>, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    db.get_published_pages().await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Get every page, drafts included, for the admin. Only authors, editors and admins may
+/// list them.
+#[server(GetAllPages)]
+pub async fn get_all_pages() -> Result<Vec<Page>, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    if !can_preview(&db).await? {
+        return Err(ServerFnError::ServerError("Only authors, editors and admins can list unpublished pages".to_string()));
+    }
+    
+    db.get_all_pages().await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Get page by slug; like posts, hidden pages are only returned to editors for preview
+#[server(GetPageBySlug)]
+pub async fn get_page_by_slug(slug: String) -> Result<Option<Page>, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    let page = if can_preview(&db).await? {
+        db.get_page_by_slug_for_preview(&slug).await
+    } else {
+        db.get_page_by_slug(&slug).await
+    };
+    page.map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Get the translations of a page readers can see, the page itself included
+#[server(GetPageTranslations)]
+pub async fn get_page_translations(group: String) -> Result<Vec<Page>, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    db.get_page_translations(&group).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Start translating page `id` into `language`; returns the id of the new draft
+#[server(CreatePageTranslation)]
+pub async fn create_page_translation(id: u32, language: String) -> Result<u32, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    check_active_language(&db, &language).await?;
+    match db.create_page_translation(id, &language).await {
+        Ok(Some(id)) => Ok(id),
+        Ok(None) => Err(ServerFnError::ServerError(format!("Page {} not found", id))),
+        Err(e) => Err(ServerFnError::ServerError(e.to_string())),
+    }
+}
+
+/// Get every scheduled job, finished ones included
+#[server(GetScheduledJobs)]
+pub async fn get_scheduled_jobs() -> Result<Vec<ScheduledContent>, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    db.get_scheduled_jobs().await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Schedule an action, or change one that hasn't run yet. The server's scheduler runs it
+/// once `scheduled_at` (UTC) has passed.
+#[server(ScheduleContent)]
+pub async fn schedule_content(job: ScheduledContent) -> Result<u32, ServerFnError> {
+    job.validate().map_err(ServerFnError::ServerError)?;
+    
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    let created_by = require_role(&db, EDITOR_ROLES).await?;
+    
+    let job = ScheduledContent {
+        scheduled_at: client::time::normalize_timestamp(&job.scheduled_at).unwrap_or_default(),
+        status: ScheduleStatus::Pending,
+        created_by,
+        ..job
+    };
+    
+    db.save_scheduled_job(&job).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Cancel a scheduled job that hasn't run yet
+#[server(CancelScheduledJob)]
+pub async fn cancel_scheduled_job(id: u32) -> Result<(), ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    require_role(&db, EDITOR_ROLES).await?;
+    
+    match db.cancel_scheduled_job(id).await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(ServerFnError::ServerError(format!("Scheduled job {} has already run or doesn't exist", id))),
+        Err(e) => Err(ServerFnError::ServerError(e.to_string())),
+    }
+}
+
+/// Run a failed scheduled job again as soon as the scheduler next polls
+#[server(RetryScheduledJob)]
+pub async fn retry_scheduled_job(id: u32) -> Result<(), ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    require_role(&db, EDITOR_ROLES).await?;
+    
+    match db.retry_scheduled_job(id, &client::time::now_iso8601()).await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(ServerFnError::ServerError(format!("Scheduled job {} hasn't failed or doesn't exist", id))),
+        Err(e) => Err(ServerFnError::ServerError(e.to_string())),
+    }
+}
+
+/// Get every language, inactive ones included
+#[server(GetLanguages)]
+pub async fn get_languages() -> Result<Vec<Language>, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    db.get_languages().await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Add or update a language
+#[server(SaveLanguage)]
+pub async fn save_language(language: Language) -> Result<(), ServerFnError> {
+    let language = Language { code: language.code.trim().to_lowercase(), ..language };
+    if language.code.is_empty() || language.name.trim().is_empty() {
+        return Err(ServerFnError::ServerError("Language code and name are required".to_string()));
+    }
+    
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    require_role(&db, ADMIN_ROLES).await?;
+    
+    db.save_language(&language).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Get the language visitors see before choosing one
+#[server(GetDefaultLanguage)]
+pub async fn get_default_language() -> Result<String, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    db.get_default_language().await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Set the language visitors see before choosing one
+#[server(SetDefaultLanguage)]
+pub async fn set_default_language(code: String) -> Result<(), ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    require_role(&db, ADMIN_ROLES).await?;
+    
+    check_active_language(&db, &code).await?;
+    db.set_default_language(&code).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Get the language of the current request and the visitor's preferred language
+#[server(GetRequestLocale)]
+pub async fn get_request_locale() -> Result<RequestLocale, ServerFnError> {
+    if let Some(locale) = request_locale() {
+        return Ok(locale);
+    }
+    
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    let language = db.get_default_language().await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    let languages = db.get_languages().await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    Ok(RequestLocale { direction: client::TextDirection::of(&language, &languages), language: language.clone(), preferred: language })
+}
+
+/// Choose the visitor's language. `None` goes back to negotiating it from the browser.
+///
+/// Always stored in a cookie so it applies during server rendering; logged-in users also
+/// get it saved to their profile.
+#[server(SetLanguagePreference)]
+pub async fn set_language_preference(language: Option<String>) -> Result<(), ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    if let Some(code) = &language {
+        check_active_language(&db, code).await?;
+    }
+    
+    if let Some(user_id) = current_user_id(&db).await? {
+        db.set_user_language(user_id, language.as_deref()).await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    }
+    
+    set_response_cookie(client::LANGUAGE_COOKIE, language.as_deref(), 365 * 86400, false)
+}
+
+/// Get the messages needed to show the site in `language`: its own, plus the default
+/// language's for anything not translated yet
+#[server(GetTranslations)]
+pub async fn get_translations(language: String) -> Result<Vec<Translation>, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    db.get_translations(&[&language, client::DEFAULT_LANGUAGE]).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Get every message in every language, for editing
+#[server(GetAllTranslations)]
+pub async fn get_all_translations() -> Result<Vec<Translation>, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    db.get_translations(&[]).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Create or replace a message after checking that it parses
+#[server(SaveTranslation)]
+pub async fn save_translation(translation: Translation) -> Result<(), ServerFnError> {
+    translation.validate().map_err(ServerFnError::ServerError)?;
+    
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    require_role(&db, ADMIN_ROLES).await?;
+    
+    db.save_translation(&translation).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Read an uploaded PO or XLIFF file and compare it with the stored messages of its language
+#[cfg(not(target_arch = "wasm32"))]
+async fn translation_import_preview(db: &Database, file_name: &str, data: &str) -> Result<TranslationImportPreview, ServerFnError> {
+    let format = client::TranslationFileFormat::from_file_name(file_name)
+        .ok_or_else(|| ServerFnError::ServerError(format!("{} is not a .po or .xlf file", file_name)))?;
+    let file = translation_files::parse(format, data)
+        .map_err(|e| ServerFnError::ServerError(format!("Failed to read {}: {}", file_name, e)))?;
+    
+    let languages = db.get_languages().await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    if !languages.iter().any(|language| language.code == file.language) {
+        return Err(ServerFnError::ServerError(format!("{} is not one of the site's languages", file.language)));
+    }
+    
+    let source_language = db.get_default_language().await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    let stored = db.get_translations(&[&file.language, &source_language]).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    Ok(translation_files::preview_import(&file, &stored, &stored, &source_language))
+}
+
+/// Preview importing a PO or XLIFF file: the messages it would add, change and remove,
+/// and the ones left out because of unknown keys or mismatched placeholders
+#[server(PreviewTranslationImport)]
+pub async fn preview_translation_import(file_name: String, data: String) -> Result<TranslationImportPreview, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    translation_import_preview(&db, &file_name, &data).await
+}
+
+/// Import a PO or XLIFF file. Flagged messages are skipped; stored messages missing from
+/// the file are only deleted with `remove_missing`.
+#[server(ImportTranslations)]
+pub async fn import_translations(file_name: String, data: String, remove_missing: bool) -> Result<TranslationImportPreview, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    require_role(&db, ADMIN_ROLES).await?;
+    
+    let preview = translation_import_preview(&db, &file_name, &data).await?;
+    db.apply_translation_import(&preview, remove_missing).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    Ok(preview)
+}
+
+/// Delete a message in every language
+#[server(DeleteTranslation)]
+pub async fn delete_translation(namespace: String, key: String) -> Result<(), ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    require_role(&db, ADMIN_ROLES).await?;
+    
+    db.delete_translation_key(&namespace, &key).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Get all redirects
+#[server(GetRedirects)]
+pub async fn get_redirects() -> Result<Vec<Redirect>, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    db.get_redirects().await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Create or update a redirect
+#[server(SaveRedirect)]
+pub async fn save_redirect(redirect: Redirect) -> Result<u32, ServerFnError> {
+    let redirect = Redirect {
+        source: client::normalize_redirect_path(&redirect.source),
+        target: redirect.target.trim().to_string(),
+        ..redirect
+    };
+    redirect.validate().map_err(ServerFnError::ServerError)?;
+    
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    require_role(&db, EDITOR_ROLES).await?;
+    
+    db.save_redirect(&redirect).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Delete a redirect
+#[server(DeleteRedirect)]
+pub async fn delete_redirect(id: u32) -> Result<(), ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    require_role(&db, EDITOR_ROLES).await?;
+    
+    db.delete_redirect(id).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Import redirects, e.g. from a CSV file. Nothing is saved unless every redirect is valid;
+/// returns the number saved.
+#[server(ImportRedirects)]
+pub async fn import_redirects(redirects: Vec<Redirect>) -> Result<usize, ServerFnError> {
+    let errors: Vec<String> = redirects
+        .iter()
+        .filter_map(|redirect| redirect.validate().err())
+        .collect();
+    if !errors.is_empty() {
+        return Err(ServerFnError::ServerError(errors.join("; ")));
+    }
+    
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    require_role(&db, EDITOR_ROLES).await?;
+    
+    for redirect in &redirects {
+        db.save_redirect(&Redirect { id: 0, ..redirect.clone() }).await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    }
+    
+    Ok(redirects.len())
+}
+
+/// Traffic summary for a day (`YYYY-MM-DD`) from the collected analytics events
+#[server(GetDailyStats)]
+pub async fn get_daily_stats(date: String) -> Result<DailyStats, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    require_role(&db, EDITOR_ROLES).await?;
+    
+    db.get_daily_stats(&date).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Analytics for `start` to `end` (`YYYY-MM-DD`, inclusive), compared with the period of
+/// the same length just before it
+#[server(GetAnalyticsReport)]
+pub async fn get_analytics_report(start: String, end: String) -> Result<AnalyticsReport, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    require_role(&db, EDITOR_ROLES).await?;
+    
+    analytics_report(&db, &start, &end).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Build an analytics report; shared by the server function and the CSV export
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn analytics_report(db: &Database, start: &str, end: &str) -> Result<AnalyticsReport, Box<dyn std::error::Error>> {
+    let (Some(first), Some(last)) = (client::time::date_to_days(start), client::time::date_to_days(end)) else {
+        return Err(format!("Invalid date range {} to {}", start, end).into());
+    };
+    if last < first {
+        return Err("The end date is before the start date".into());
+    }
+    if last - first > 366 {
+        return Err("Date ranges are limited to one year".into());
+    }
+    
+    let length = last - first + 1;
+    let previous_start = client::time::days_to_date(first - length);
+    let previous_end = client::time::days_to_date(first - 1);
+    
+    let days = db.get_daily_stats_range(start, end).await?;
+    let previous = db.get_daily_stats_range(&previous_start, &previous_end).await?;
+    
+    Ok(AnalyticsReport::new(start, end, days, &previous))
+}
+
+/// Per-route percentiles of Web Vitals and server render times from `start` to `end`
+#[server(GetMetricSummaries)]
+pub async fn get_metric_summaries(start: String, end: String) -> Result<Vec<MetricSummary>, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    require_role(&db, EDITOR_ROLES).await?;
+    
+    db.get_metric_summaries(&start, &end).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Page views per hour of a day (`YYYY-MM-DD`)
+#[server(GetHourlyStats)]
+pub async fn get_hourly_stats(date: String) -> Result<Vec<HourlyStats>, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    require_role(&db, EDITOR_ROLES).await?;
+    
+    db.get_hourly_stats(&date).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Get the site-wide SEO defaults
+#[server(GetSeoSettings)]
+pub async fn get_seo_settings() -> Result<SeoSettings, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    db.get_seo_settings().await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Save the site-wide SEO defaults
+#[server(SaveSeoSettings)]
+pub async fn save_seo_settings(settings: SeoSettings) -> Result<(), ServerFnError> {
+    if settings.site_title.trim().is_empty() {
+        return Err(ServerFnError::ServerError("Site title is required".to_string()));
+    }
+    if client::TimeZone::parse(&settings.time_zone).is_none() {
+        return Err(ServerFnError::ServerError(format!("Unknown time zone: {}", settings.time_zone)));
+    }
+    
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    require_role(&db, ADMIN_ROLES).await?;
+    
+    db.save_seo_settings(&settings).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Get the raw HTML allowed in markdown across the site
+#[server(GetHtmlSettings)]
+pub async fn get_html_settings() -> Result<HtmlSettings, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    db.get_html_settings().await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Save the raw HTML allowed in markdown across the site
+#[server(SaveHtmlSettings)]
+pub async fn save_html_settings(settings: HtmlSettings) -> Result<(), ServerFnError> {
+    settings.posts.validate()
+        .and(settings.comments.validate())
+        .map_err(ServerFnError::ServerError)?;
+    
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    require_role(&db, ADMIN_ROLES).await?;
+    
+    db.save_html_settings(&settings).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
 /// Authenticate user
 #[server(AuthenticateUser)]
 pub async fn authenticate_user(username: String, password: String) -> Result<Session, ServerFnError> {
     let db = Database::init("sqlite://cms.db").await
         .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
     
-    match db.get_user_by_username(&username).await {
-        Ok(Some(user)) => {
+    let user = db.get_user_by_username(&username).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    match user {
+        Some(user) => {
             // In a real implementation, you'd use proper password hashing
             if user.password_hash == password || user.password_hash == format!("hash_{}", password) {
+                let token = uuid::Uuid::new_v4().to_string();
+                db.create_session(&token, user.id, SESSION_TTL_DAYS).await
+                    .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+                set_response_cookie(SESSION_COOKIE, Some(&token), SESSION_TTL_DAYS * 86400, true)?;
+                
+                // Carry the profile's color scheme and language over to this browser
+                if let Some(scheme) = user.color_scheme {
+                    set_response_cookie(client::COLOR_SCHEME_COOKIE, Some(scheme.as_str()), SESSION_TTL_DAYS * 86400 * 12, false)?;
+                }
+                let language = db.get_user_language(user.id).await
+                    .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+                if let Some(language) = language {
+                    set_response_cookie(client::LANGUAGE_COOKIE, Some(&language), SESSION_TTL_DAYS * 86400 * 12, false)?;
+                }
+                
                 Ok(Session {
                     user_id: Some(user.id),
                     username: Some(user.username),
@@ -80,8 +808,7 @@ pub async fn authenticate_user(username: String, password: String) -> Result<Ses
                 Err(ServerFnError::ServerError("Invalid credentials".to_string()))
             }
         },
-        Ok(None) => Err(ServerFnError::ServerError("User not found".to_string())),
-        Err(e) => Err(ServerFnError::ServerError(e.to_string())),
+        None => Err(ServerFnError::ServerError("User not found".to_string())),
     }
 }
 
@@ -170,6 +897,7 @@ pub async fn register_user(
         active: true,
         email_verified: false,
         verification_token: Some(verification_token.clone()),
+        color_scheme: None,
     };
     
     // Save user to database
@@ -240,3 +968,227 @@ pub async fn is_first_user() -> Result<bool, ServerFnError> {
     
     Ok(user_count == 0)
 }
+
+/// How long presigned media URLs stay valid
+#[cfg(not(target_arch = "wasm32"))]
+const MEDIA_URL_TTL_SECS: u64 = 3600;
+
+/// Upload a media file to the configured storage backend
+#[server(UploadMedia)]
+pub async fn upload_media(
+    original_name: String,
+    mime_type: String,
+    data: Vec<u8>,
+) -> Result<MediaFile, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    let uploaded_by = require_role(&db, AUTHOR_ROLES).await?;
+    let storage = storage::storage_from_env()
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    // Keep the original extension but never trust the rest of the client-supplied name
+    let safe_name: String = original_name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+    let filename = format!("{}-{}", uuid::Uuid::new_v4(), safe_name.trim_start_matches('.'));
+    
+    storage.put(&filename, data.clone(), &mime_type).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    let mut media = MediaFile {
+        id: 0, // Will be auto-assigned
+        filename,
+        original_name,
+        mime_type,
+        file_size: data.len() as u64,
+        uploaded_at: chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        uploaded_by: Some(uploaded_by),
+        alt_text: None,
+    };
+    
+    media.id = db.create_media(&media).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    Ok(media)
+}
+
+/// Get all uploaded media files
+#[server(GetMediaFiles)]
+pub async fn get_media_files() -> Result<Vec<MediaFile>, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    db.get_media_files().await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Get a URL the browser can load a media file from
+#[server(GetMediaUrl)]
+pub async fn get_media_url(id: u32) -> Result<Option<String>, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    let storage = storage::storage_from_env()
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    match db.get_media_by_id(id).await.map_err(|e| ServerFnError::ServerError(e.to_string()))? {
+        Some(media) => storage.presigned_url(&media.filename, MEDIA_URL_TTL_SECS)
+            .map(Some)
+            .map_err(|e| ServerFnError::ServerError(e.to_string())),
+        None => Ok(None),
+    }
+}
+
+/// Delete a media file from storage and the database
+#[server(DeleteMedia)]
+pub async fn delete_media(id: u32) -> Result<(), ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    require_role(&db, AUTHOR_ROLES).await?;
+    let storage = storage::storage_from_env()
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    if let Some(media) = db.get_media_by_id(id).await.map_err(|e| ServerFnError::ServerError(e.to_string()))? {
+        storage.delete(&media.filename).await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+        db.delete_media(id).await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    }
+    
+    Ok(())
+}
+
+/// Short content hash of a stylesheet, used to cache-bust theme URLs
+#[cfg(not(target_arch = "wasm32"))]
+pub fn theme_css_version(css: &str) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(css.as_bytes()))[..12].to_string()
+}
+
+/// Get all themes
+#[server(GetThemes)]
+pub async fn get_themes() -> Result<Vec<Theme>, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    db.get_themes().await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Create or update a theme
+#[server(SaveTheme)]
+pub async fn save_theme(theme: Theme) -> Result<u32, ServerFnError> {
+    if theme.name.trim().is_empty() {
+        return Err(ServerFnError::ServerError("Theme name is required".to_string()));
+    }
+    
+    theme_package::ThemeManifest::from_theme(&theme).validate()
+        .map_err(|errors| ServerFnError::ServerError(errors.join("; ")))?;
+    
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    require_role(&db, ADMIN_ROLES).await?;
+    
+    db.save_theme(&theme).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Make a theme the active site theme
+#[server(ActivateTheme)]
+pub async fn activate_theme(id: u32) -> Result<(), ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    require_role(&db, ADMIN_ROLES).await?;
+    
+    db.activate_theme(id).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Delete an inactive theme
+#[server(DeleteTheme)]
+pub async fn delete_theme(id: u32) -> Result<(), ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    require_role(&db, ADMIN_ROLES).await?;
+    
+    db.delete_theme(id).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Get the cache-busted URL of the active theme's stylesheet, if a theme is active
+#[server(GetActiveThemeStylesheet)]
+pub async fn get_active_theme_stylesheet() -> Result<Option<String>, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    let theme = db.get_active_theme().await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    let scheme = request_cookie(client::COLOR_SCHEME_COOKIE).as_deref().and_then(ColorScheme::parse);
+    
+    Ok(theme.map(|theme| {
+        let mut href = format!("/theme/active.css?v={}", theme_css_version(&theme.stylesheet()));
+        if let Some(scheme) = scheme.filter(|_| theme.supports_color_schemes()) {
+            href.push_str(&format!("&scheme={}", scheme.as_str()));
+        }
+        href
+    }))
+}
+
+/// Get the color scheme chosen by the current visitor
+#[server(GetColorScheme)]
+pub async fn get_color_scheme() -> Result<Option<ColorScheme>, ServerFnError> {
+    if let Some(scheme) = request_cookie(client::COLOR_SCHEME_COOKIE) {
+        return Ok(ColorScheme::parse(&scheme));
+    }
+    
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    match current_user_id(&db).await? {
+        Some(user_id) => db.get_user_color_scheme(user_id).await
+            .map_err(|e| ServerFnError::ServerError(e.to_string())),
+        None => Ok(None),
+    }
+}
+
+/// Choose the visitor's color scheme. `None` goes back to the theme's own palette.
+///
+/// Always stored in a cookie so it applies during server rendering; logged-in users also
+/// get it saved to their profile.
+#[server(SetColorScheme)]
+pub async fn set_color_scheme(scheme: Option<ColorScheme>) -> Result<(), ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    if let Some(user_id) = current_user_id(&db).await? {
+        db.set_user_color_scheme(user_id, scheme).await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    }
+    
+    set_response_cookie(client::COLOR_SCHEME_COOKIE, scheme.map(|scheme| scheme.as_str()), 365 * 86400, false)
+}
+
+/// Get the active theme, including its variables and layout templates
+#[server(GetActiveTheme)]
+pub async fn get_active_theme() -> Result<Option<Theme>, ServerFnError> {
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    db.get_active_theme().await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Import a zipped theme package. A theme with the same name is replaced in place.
+#[server(ImportTheme)]
+pub async fn import_theme(data: Vec<u8>) -> Result<Theme, ServerFnError> {
+    let theme = theme_package::import_package(&data)
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    
+    let db = Database::init("sqlite://cms.db").await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
+    require_role(&db, ADMIN_ROLES).await?;
+    
+    db.import_theme(theme).await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
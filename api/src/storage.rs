@@ -0,0 +1,436 @@
+//! Pluggable storage backends for uploaded media.
+//!
+//! The backend is selected from the environment (see [`StorageConfig::from_env`]) so that
+//! several app instances can share one bucket instead of each writing to its own disk.
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::env;
+use std::future::Future;
+use std::path::{Component, Path, PathBuf};
+use std::pin::Pin;
+
+pub type StorageError = Box<dyn std::error::Error + Send + Sync>;
+pub type StorageResult<T> = Result<T, StorageError>;
+pub type StorageFuture<'a, T> = Pin<Box<dyn Future<Output = StorageResult<T>> + Send + 'a>>;
+
+/// Object storage used for media uploads
+pub trait MediaStorage: Send + Sync {
+    /// Short backend name, used in logs
+    fn name(&self) -> &'static str;
+
+    /// Store `data` under `key`, replacing any existing object
+    fn put<'a>(&'a self, key: &'a str, data: Vec<u8>, content_type: &'a str) -> StorageFuture<'a, ()>;
+
+    /// Fetch the object stored under `key`, or `None` if it doesn't exist
+    fn get<'a>(&'a self, key: &'a str) -> StorageFuture<'a, Option<Vec<u8>>>;
+
+    /// Remove the object stored under `key`. Deleting a missing key is not an error.
+    fn delete<'a>(&'a self, key: &'a str) -> StorageFuture<'a, ()>;
+
+    /// URL a browser can use to download `key` for at least `expires_in_secs` seconds
+    fn presigned_url(&self, key: &str, expires_in_secs: u64) -> StorageResult<String>;
+}
+
+/// Which backend to use and how to reach it
+#[derive(Debug, Clone)]
+pub enum StorageConfig {
+    Local {
+        root: PathBuf,
+        public_base_url: String,
+    },
+    S3(S3Config),
+}
+
+/// Connection settings for an S3-compatible service (AWS, MinIO, R2, ...)
+#[derive(Debug, Clone)]
+pub struct S3Config {
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+}
+
+impl StorageConfig {
+    /// Read the storage configuration from the environment.
+    ///
+    /// `MEDIA_STORAGE` selects the backend (`local` or `s3`, default `local`).
+    /// The local backend uses `MEDIA_UPLOAD_DIR` (default `uploads`); the S3 backend
+    /// requires `S3_BUCKET`, `S3_ACCESS_KEY_ID` and `S3_SECRET_ACCESS_KEY`, and accepts
+    /// `S3_ENDPOINT` and `S3_REGION` for non-AWS services.
+    pub fn from_env() -> StorageResult<Self> {
+        let backend = env::var("MEDIA_STORAGE").unwrap_or_else(|_| "local".to_string());
+
+        match backend.to_lowercase().as_str() {
+            "local" => Ok(Self::local_from_env()),
+            "s3" => {
+                let required = |name: &str| {
+                    env::var(name).map_err(|_| format!("{} must be set when MEDIA_STORAGE=s3", name))
+                };
+                let region = env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_string());
+                let endpoint = env::var("S3_ENDPOINT")
+                    .unwrap_or_else(|_| format!("https://s3.{}.amazonaws.com", region));
+
+                Ok(Self::S3(S3Config {
+                    endpoint: endpoint.trim_end_matches('/').to_string(),
+                    bucket: required("S3_BUCKET")?,
+                    region,
+                    access_key_id: required("S3_ACCESS_KEY_ID")?,
+                    secret_access_key: required("S3_SECRET_ACCESS_KEY")?,
+                }))
+            },
+            other => Err(format!("Unknown MEDIA_STORAGE backend: {}", other).into()),
+        }
+    }
+
+    /// Local filesystem configuration, regardless of `MEDIA_STORAGE`
+    pub fn local_from_env() -> Self {
+        Self::Local {
+            root: PathBuf::from(env::var("MEDIA_UPLOAD_DIR").unwrap_or_else(|_| "uploads".to_string())),
+            public_base_url: "/uploads".to_string(),
+        }
+    }
+
+    /// Build the configured backend
+    pub fn build(&self) -> StorageResult<Box<dyn MediaStorage>> {
+        match self {
+            Self::Local { root, public_base_url } => {
+                Ok(Box::new(LocalStorage::new(root.clone(), public_base_url.clone())?))
+            },
+            Self::S3(config) => Ok(Box::new(S3Storage::new(config.clone()))),
+        }
+    }
+}
+
+/// Build the backend selected by the environment
+pub fn storage_from_env() -> StorageResult<Box<dyn MediaStorage>> {
+    StorageConfig::from_env()?.build()
+}
+
+/// Reject keys that could escape the storage root or produce odd object names
+pub fn validate_key(key: &str) -> StorageResult<()> {
+    let path = Path::new(key);
+    let is_clean = !key.is_empty()
+        && !key.starts_with('/')
+        && path.components().all(|c| matches!(c, Component::Normal(_)));
+
+    if is_clean {
+        Ok(())
+    } else {
+        Err(format!("Invalid storage key: {}", key).into())
+    }
+}
+
+/// Stores media on the local filesystem
+pub struct LocalStorage {
+    root: PathBuf,
+    public_base_url: String,
+}
+
+impl LocalStorage {
+    pub fn new(root: PathBuf, public_base_url: String) -> StorageResult<Self> {
+        std::fs::create_dir_all(&root)?;
+        Ok(Self { root, public_base_url })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn path_for(&self, key: &str) -> StorageResult<PathBuf> {
+        validate_key(key)?;
+        Ok(self.root.join(key))
+    }
+
+    /// List every stored key, relative to the root
+    pub fn list_keys(&self) -> StorageResult<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut dirs = vec![self.root.clone()];
+
+        while let Some(dir) = dirs.pop() {
+            for entry in std::fs::read_dir(&dir)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if let Ok(relative) = path.strip_prefix(&self.root) {
+                    let key = relative
+                        .components()
+                        .map(|c| c.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    keys.push(key);
+                }
+            }
+        }
+
+        keys.sort();
+        Ok(keys)
+    }
+}
+
+impl MediaStorage for LocalStorage {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    fn put<'a>(&'a self, key: &'a str, data: Vec<u8>, _content_type: &'a str) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            let path = self.path_for(key)?;
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(path, data).await?;
+            Ok(())
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> StorageFuture<'a, Option<Vec<u8>>> {
+        Box::pin(async move {
+            match tokio::fs::read(self.path_for(key)?).await {
+                Ok(data) => Ok(Some(data)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e.into()),
+            }
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            match tokio::fs::remove_file(self.path_for(key)?).await {
+                Ok(()) => Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                Err(e) => Err(e.into()),
+            }
+        })
+    }
+
+    fn presigned_url(&self, key: &str, _expires_in_secs: u64) -> StorageResult<String> {
+        // Local files are served publicly by ba-server, so the plain URL never expires
+        validate_key(key)?;
+        Ok(format!("{}/{}", self.public_base_url.trim_end_matches('/'), uri_encode(key, false)))
+    }
+}
+
+type HmacSha256 = Hmac<Sha256>;
+
+const EMPTY_PAYLOAD_SHA256: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+/// Stores media in an S3-compatible bucket using path-style requests signed with SigV4
+pub struct S3Storage {
+    config: S3Config,
+    client: reqwest::Client,
+}
+
+impl S3Storage {
+    pub fn new(config: S3Config) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    fn host(&self) -> &str {
+        self.config
+            .endpoint
+            .trim_start_matches("https://")
+            .trim_start_matches("http://")
+    }
+
+    fn canonical_uri(&self, key: &str) -> String {
+        format!("/{}/{}", uri_encode(&self.config.bucket, true), uri_encode(key, false))
+    }
+
+    fn credential_scope(&self, date: &str) -> String {
+        format!("{}/{}/s3/aws4_request", date, self.config.region)
+    }
+
+    fn signing_key(&self, date: &str) -> Vec<u8> {
+        let secret = format!("AWS4{}", self.config.secret_access_key);
+        let k_date = hmac_sha256(secret.as_bytes(), date.as_bytes());
+        let k_region = hmac_sha256(&k_date, self.config.region.as_bytes());
+        let k_service = hmac_sha256(&k_region, b"s3");
+        hmac_sha256(&k_service, b"aws4_request")
+    }
+
+    fn signature(&self, date: &str, amz_date: &str, canonical_request: &str) -> String {
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            self.credential_scope(date),
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+        hex::encode(hmac_sha256(&self.signing_key(date), string_to_sign.as_bytes()))
+    }
+
+    /// Send a request signed with an `Authorization` header
+    async fn send(
+        &self,
+        method: reqwest::Method,
+        key: &str,
+        body: Vec<u8>,
+        content_type: Option<&str>,
+    ) -> StorageResult<reqwest::Response> {
+        validate_key(key)?;
+
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = if body.is_empty() {
+            EMPTY_PAYLOAD_SHA256.to_string()
+        } else {
+            hex::encode(Sha256::digest(&body))
+        };
+        let uri = self.canonical_uri(key);
+
+        let canonical_request = format!(
+            "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\nhost;x-amz-content-sha256;x-amz-date\n{}",
+            method.as_str(),
+            uri,
+            self.host(),
+            payload_hash,
+            amz_date,
+            payload_hash
+        );
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={}",
+            self.config.access_key_id,
+            self.credential_scope(&date),
+            self.signature(&date, &amz_date, &canonical_request)
+        );
+
+        let mut request = self
+            .client
+            .request(method, format!("{}{}", self.config.endpoint, uri))
+            .header("x-amz-content-sha256", payload_hash)
+            .header("x-amz-date", amz_date)
+            .header("authorization", authorization);
+
+        if let Some(content_type) = content_type {
+            request = request.header("content-type", content_type);
+        }
+
+        Ok(request.body(body).send().await?)
+    }
+}
+
+impl MediaStorage for S3Storage {
+    fn name(&self) -> &'static str {
+        "s3"
+    }
+
+    fn put<'a>(&'a self, key: &'a str, data: Vec<u8>, content_type: &'a str) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            let response = self.send(reqwest::Method::PUT, key, data, Some(content_type)).await?;
+            if !response.status().is_success() {
+                return Err(format!("S3 PUT {} failed with status {}", key, response.status()).into());
+            }
+            Ok(())
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> StorageFuture<'a, Option<Vec<u8>>> {
+        Box::pin(async move {
+            let response = self.send(reqwest::Method::GET, key, Vec::new(), None).await?;
+            match response.status() {
+                reqwest::StatusCode::NOT_FOUND => Ok(None),
+                status if status.is_success() => Ok(Some(response.bytes().await?.to_vec())),
+                status => Err(format!("S3 GET {} failed with status {}", key, status).into()),
+            }
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            let response = self.send(reqwest::Method::DELETE, key, Vec::new(), None).await?;
+            let status = response.status();
+            if !status.is_success() && status != reqwest::StatusCode::NOT_FOUND {
+                return Err(format!("S3 DELETE {} failed with status {}", key, status).into());
+            }
+            Ok(())
+        })
+    }
+
+    fn presigned_url(&self, key: &str, expires_in_secs: u64) -> StorageResult<String> {
+        validate_key(key)?;
+
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let credential = format!("{}/{}", self.config.access_key_id, self.credential_scope(&date));
+        let uri = self.canonical_uri(key);
+
+        // Query parameters must be sorted by name for the canonical request
+        let query = format!(
+            "X-Amz-Algorithm=AWS4-HMAC-SHA256&X-Amz-Credential={}&X-Amz-Date={}&X-Amz-Expires={}&X-Amz-SignedHeaders=host",
+            uri_encode(&credential, true),
+            amz_date,
+            expires_in_secs.clamp(1, 604_800)
+        );
+        let canonical_request = format!("GET\n{}\n{}\nhost:{}\n\nhost\nUNSIGNED-PAYLOAD", uri, query, self.host());
+        let signature = self.signature(&date, &amz_date, &canonical_request);
+
+        Ok(format!("{}{}?{}&X-Amz-Signature={}", self.config.endpoint, uri, query, signature))
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Percent-encode per the SigV4 rules; `/` is kept unless `encode_slash` is set
+fn uri_encode(input: &str, encode_slash: bool) -> String {
+    let mut encoded = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            b'/' if !encode_slash => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Guess a MIME type from a file extension, for files that predate the media table
+pub fn mime_type_for(key: &str) -> &'static str {
+    let extension = Path::new(key)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mp3" => "audio/mpeg",
+        "pdf" => "application/pdf",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Copy every file from a local upload directory into `destination`.
+///
+/// Files are copied, not moved, so the local directory can be kept until the new
+/// backend has been verified. Returns the number of files copied.
+pub async fn migrate_local_files(source: &LocalStorage, destination: &dyn MediaStorage) -> StorageResult<usize> {
+    let keys = source.list_keys()?;
+
+    for key in &keys {
+        let data = source
+            .get(key)
+            .await?
+            .ok_or_else(|| format!("{} disappeared during migration", key))?;
+        destination.put(key, data, mime_type_for(key)).await?;
+        log::info!("Migrated {} to {} storage", key, destination.name());
+    }
+
+    Ok(keys.len())
+}
@@ -0,0 +1,223 @@
+//! Theme packages: a zip archive holding a `theme.json` manifest, a stylesheet,
+//! named design variables and the layout templates pages can choose between.
+//!
+//! ```text
+//! theme.json   manifest (name, version, variables, templates)
+//! style.css    stylesheet named by the manifest's `stylesheet` field
+//! ```
+
+use client::{Theme, ThemeTemplate, ThemeVariable};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::{Cursor, Read, Write};
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+/// Name of the manifest inside a package
+pub const MANIFEST_FILE: &str = "theme.json";
+
+/// Upper bound on the uncompressed size of an imported package
+const MAX_PACKAGE_SIZE: u64 = 2 * 1024 * 1024;
+
+/// Contents of `theme.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeManifest {
+    pub name: String,
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default = "default_stylesheet")]
+    pub stylesheet: String,
+    #[serde(default)]
+    pub variables: Vec<ThemeVariable>,
+    #[serde(default)]
+    pub templates: Vec<ThemeTemplate>,
+}
+
+fn default_stylesheet() -> String {
+    "style.css".to_string()
+}
+
+impl ThemeManifest {
+    pub fn from_theme(theme: &Theme) -> Self {
+        Self {
+            name: theme.name.clone(),
+            version: theme.version.clone(),
+            description: theme.description.clone(),
+            author: None,
+            stylesheet: default_stylesheet(),
+            variables: theme.variables.clone(),
+            templates: theme.templates.clone(),
+        }
+    }
+
+    /// Build an (unsaved, inactive) theme from the manifest and its stylesheet
+    pub fn into_theme(self, css_content: String) -> Theme {
+        Theme {
+            id: 0,
+            name: self.name,
+            description: self.description,
+            css_content,
+            active: false,
+            version: self.version,
+            variables: self.variables,
+            templates: self.templates,
+        }
+    }
+
+    /// Check the manifest, returning every problem found rather than just the first
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        if self.name.trim().is_empty() {
+            errors.push("`name` must not be empty".to_string());
+        } else if self.name.chars().count() > 100 {
+            errors.push("`name` must be at most 100 characters".to_string());
+        }
+
+        if !is_semver(&self.version) {
+            errors.push(format!("`version` must look like 1.2.3, got '{}'", self.version));
+        }
+
+        if !self.stylesheet.ends_with(".css") || !is_safe_path(&self.stylesheet) {
+            errors.push(format!("`stylesheet` must be a relative path to a .css file, got '{}'", self.stylesheet));
+        }
+
+        let mut variable_names = HashSet::new();
+        for variable in &self.variables {
+            if let Err(e) = variable.validate() {
+                errors.push(e);
+            }
+            if !variable_names.insert(variable.name.as_str()) {
+                errors.push(format!("Variable '{}' is defined more than once", variable.name));
+            }
+        }
+
+        let mut template_names = HashSet::new();
+        for template in &self.templates {
+            let valid_name = !template.name.is_empty()
+                && template.name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+            if !valid_name {
+                errors.push(format!("Template name '{}' may only contain a-z, 0-9 and '-'", template.name));
+            }
+            if !template_names.insert(template.name.as_str()) {
+                errors.push(format!("Template '{}' is defined more than once", template.name));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// `MAJOR.MINOR.PATCH` with an optional `-prerelease` suffix
+fn is_semver(version: &str) -> bool {
+    let core = version.split_once('-').map_or(version, |(core, _)| core);
+    let parts: Vec<&str> = core.split('.').collect();
+    parts.len() == 3 && parts.iter().all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn is_safe_path(path: &str) -> bool {
+    !path.is_empty()
+        && !path.starts_with('/')
+        && !path.contains('\\')
+        && path.split('/').all(|segment| !segment.is_empty() && segment != "." && segment != "..")
+}
+
+/// Parse and validate a manifest
+pub fn parse_manifest(json: &str) -> Result<ThemeManifest, Box<dyn std::error::Error>> {
+    let manifest: ThemeManifest = serde_json::from_str(json)
+        .map_err(|e| format!("{} is not valid: {}", MANIFEST_FILE, e))?;
+
+    manifest
+        .validate()
+        .map_err(|errors| format!("Invalid {}: {}", MANIFEST_FILE, errors.join("; ")))?;
+
+    Ok(manifest)
+}
+
+/// Read a theme package. The returned theme is unsaved (`id` 0) and inactive.
+pub fn import_package(data: &[u8]) -> Result<Theme, Box<dyn std::error::Error>> {
+    let mut archive = ZipArchive::new(Cursor::new(data)).map_err(|e| format!("Not a valid zip archive: {}", e))?;
+
+    let mut total_size = 0u64;
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i)?;
+        if entry.enclosed_name().is_none() {
+            return Err(format!("Package contains an unsafe path: {}", entry.name()).into());
+        }
+        total_size += entry.size();
+    }
+    if total_size > MAX_PACKAGE_SIZE {
+        return Err(format!("Package is larger than {} bytes when extracted", MAX_PACKAGE_SIZE).into());
+    }
+
+    let manifest = parse_manifest(&read_text(&mut archive, MANIFEST_FILE)?)?;
+    let css = read_text(&mut archive, &manifest.stylesheet)?;
+
+    Ok(manifest.into_theme(css))
+}
+
+fn read_text(archive: &mut ZipArchive<Cursor<&[u8]>>, name: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let mut entry = archive
+        .by_name(name)
+        .map_err(|_| format!("Package is missing {}", name))?;
+
+    let mut text = String::new();
+    entry
+        .by_ref()
+        .take(MAX_PACKAGE_SIZE)
+        .read_to_string(&mut text)
+        .map_err(|_| format!("{} must be UTF-8 text", name))?;
+
+    Ok(text)
+}
+
+/// Write a theme as a package that `import_package` can read back
+pub fn export_package(theme: &Theme) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let manifest = ThemeManifest::from_theme(theme);
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+
+    zip.start_file(MANIFEST_FILE, options)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+
+    zip.start_file(manifest.stylesheet.as_str(), options)?;
+    zip.write_all(theme.css_content.as_bytes())?;
+
+    Ok(zip.finish()?.into_inner())
+}
+
+/// File name to offer when downloading a theme package
+pub fn package_file_name(theme: &Theme) -> String {
+    let slug: String = theme
+        .name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    format!("{}-{}.zip", slug.trim_matches('-'), theme.version)
+}
+
+/// Themes shipped with the CMS, seeded into an empty database
+pub fn builtin_themes() -> Vec<Theme> {
+    let packages = [
+        (include_str!("../themes/dark-professional/theme.json"), include_str!("../themes/dark-professional/style.css")),
+        (include_str!("../themes/light-professional/theme.json"), include_str!("../themes/light-professional/style.css")),
+        (include_str!("../themes/vibrant-colors/theme.json"), include_str!("../themes/vibrant-colors/style.css")),
+    ];
+
+    packages
+        .into_iter()
+        .map(|(manifest, css)| {
+            parse_manifest(manifest)
+                .expect("shipped theme manifests are valid")
+                .into_theme(css.to_string())
+        })
+        .collect()
+}
@@ -0,0 +1,436 @@
+//! Translation files for CAT tools: gettext PO and XLIFF 2.0, one namespace of one
+//! language per file.
+//!
+//! Both carry each message's id, its text in the source (default) language and its
+//! translation:
+//!
+//! ```text
+//! PO       msgctxt "common:home" / msgid "Home" / msgstr "Inicio"
+//! XLIFF    <file id="common"><unit id="home"><segment><source>Home</source><target>Inicio</target>
+//! ```
+//!
+//! Imports are compared with the stored messages first; see [`preview_import`].
+
+use client::{
+    message_arguments, split_message_id, validate_message, FlaggedMessage, ImportIssue, ImportedMessage,
+    TranslationFileFormat, TranslationImportPreview, Translation,
+};
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// XML namespace of XLIFF 2.0 documents
+const XLIFF_NAMESPACE: &str = "urn:oasis:names:tc:xliff:document:2.0";
+
+/// Messages read from a translation file
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranslationFile {
+    pub format: TranslationFileFormat,
+    /// Language of the translations
+    pub language: String,
+    /// Namespaces the file covers, including ones without any translated message
+    pub namespaces: BTreeSet<String>,
+    /// Translated messages; untranslated entries are left out
+    pub messages: Vec<Translation>,
+}
+
+/// Write one namespace of `language` for translators. Every message of the source language
+/// is included, with its translation when there is one.
+pub fn export(
+    format: TranslationFileFormat,
+    namespace: &str,
+    source_language: &str,
+    language: &str,
+    source: &[Translation],
+    translations: &[Translation],
+) -> String {
+    let translated: HashMap<&str, &str> = translations
+        .iter()
+        .filter(|translation| translation.namespace == namespace && translation.language_code == language)
+        .map(|translation| (translation.key.as_str(), translation.value.as_str()))
+        .collect();
+    let entries: BTreeMap<&str, (&str, Option<&str>)> = source
+        .iter()
+        .filter(|message| message.namespace == namespace && message.language_code == source_language)
+        .map(|message| (message.key.as_str(), (message.value.as_str(), translated.get(message.key.as_str()).copied())))
+        .collect();
+
+    match format {
+        TranslationFileFormat::Po => export_po(namespace, source_language, language, &entries),
+        TranslationFileFormat::Xliff => export_xliff(namespace, source_language, language, &entries),
+    }
+}
+
+/// Download name of an exported namespace, e.g. `common.es.po`
+pub fn file_name(format: TranslationFileFormat, namespace: &str, language: &str) -> String {
+    format!("{}.{}.{}", namespace, language, format.extension())
+}
+
+fn export_po(namespace: &str, source_language: &str, language: &str, entries: &BTreeMap<&str, (&str, Option<&str>)>) -> String {
+    let mut po = format!("# Messages of the \"{}\" namespace\n", namespace);
+    po.push_str("msgid \"\"\nmsgstr \"\"\n");
+    for header in [
+        format!("Language: {}", language),
+        "MIME-Version: 1.0".to_string(),
+        "Content-Type: text/plain; charset=UTF-8".to_string(),
+        "Content-Transfer-Encoding: 8bit".to_string(),
+        format!("X-Source-Language: {}", source_language),
+        format!("X-Namespace: {}", namespace),
+    ] {
+        po.push_str(&format!("\"{}\\n\"\n", header));
+    }
+
+    for (key, (source, translation)) in entries {
+        po.push_str(&format!(
+            "\nmsgctxt {}\nmsgid {}\nmsgstr {}\n",
+            po_string(&format!("{}:{}", namespace, key)),
+            po_string(source),
+            po_string(translation.unwrap_or_default()),
+        ));
+    }
+    po
+}
+
+fn po_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn export_xliff(namespace: &str, source_language: &str, language: &str, entries: &BTreeMap<&str, (&str, Option<&str>)>) -> String {
+    let mut xliff = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xliff.push_str(&format!(
+        "<xliff xmlns=\"{}\" version=\"2.0\" srcLang=\"{}\" trgLang=\"{}\">\n",
+        XLIFF_NAMESPACE,
+        escape(source_language),
+        escape(language),
+    ));
+    xliff.push_str(&format!("  <file id=\"{}\">\n", escape(namespace)));
+    for (key, (source, translation)) in entries {
+        xliff.push_str(&format!("    <unit id=\"{}\">\n", escape(*key)));
+        match translation {
+            Some(translation) => {
+                xliff.push_str("      <segment state=\"translated\">\n");
+                xliff.push_str(&format!("        <source>{}</source>\n", escape(*source)));
+                xliff.push_str(&format!("        <target>{}</target>\n", escape(*translation)));
+            },
+            None => {
+                xliff.push_str("      <segment state=\"initial\">\n");
+                xliff.push_str(&format!("        <source>{}</source>\n", escape(*source)));
+            },
+        }
+        xliff.push_str("      </segment>\n    </unit>\n");
+    }
+    xliff.push_str("  </file>\n</xliff>\n");
+    xliff
+}
+
+/// Read a translation file
+pub fn parse(format: TranslationFileFormat, text: &str) -> Result<TranslationFile, Box<dyn std::error::Error>> {
+    match format {
+        TranslationFileFormat::Po => parse_po(text),
+        TranslationFileFormat::Xliff => parse_xliff(text),
+    }
+}
+
+/// A PO entry being read
+#[derive(Default)]
+struct PoEntry {
+    context: Option<String>,
+    id: Option<String>,
+    translation: Option<String>,
+    /// Line the entry starts on, for errors
+    line: usize,
+}
+
+/// Which string of the entry continuation lines belong to
+#[derive(Clone, Copy)]
+enum PoField {
+    Context,
+    Id,
+    Translation,
+    /// `msgid_plural` and `msgstr[1]` onwards; plurals are written in the message itself
+    Ignored,
+}
+
+fn parse_po(text: &str) -> Result<TranslationFile, Box<dyn std::error::Error>> {
+    let mut entries = Vec::new();
+    let mut entry = PoEntry::default();
+    let mut field = PoField::Ignored;
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        // Comments, including obsolete `#~` entries
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (keyword, rest) = match line.split_once(char::is_whitespace) {
+            Some((keyword, rest)) if !line.starts_with('"') => (keyword, rest.trim()),
+            _ => ("", line),
+        };
+        let value = po_unquote(rest).ok_or_else(|| format!("Line {}: expected a quoted string", index + 1))?;
+
+        let next = match keyword {
+            "" => None,
+            "msgctxt" => Some(PoField::Context),
+            "msgid" => Some(PoField::Id),
+            "msgstr" | "msgstr[0]" => Some(PoField::Translation),
+            "msgid_plural" => Some(PoField::Ignored),
+            keyword if keyword.starts_with("msgstr[") => Some(PoField::Ignored),
+            keyword => return Err(format!("Line {}: unknown keyword {}", index + 1, keyword).into()),
+        };
+        if let Some(next) = next {
+            // A context or id after the translation starts the next entry
+            let starts_entry = matches!(next, PoField::Context) || (matches!(next, PoField::Id) && entry.id.is_some());
+            if starts_entry && (entry.id.is_some() || entry.context.is_some()) {
+                entries.push(std::mem::take(&mut entry));
+            }
+            if entry.id.is_none() && entry.context.is_none() {
+                entry.line = index + 1;
+            }
+            field = next;
+        }
+
+        let target = match field {
+            PoField::Context => &mut entry.context,
+            PoField::Id => &mut entry.id,
+            PoField::Translation => &mut entry.translation,
+            PoField::Ignored => continue,
+        };
+        target.get_or_insert_with(String::new).push_str(&value);
+    }
+    if entry.id.is_some() {
+        entries.push(entry);
+    }
+
+    let mut language = None;
+    let mut namespaces = BTreeSet::new();
+    let mut messages = Vec::new();
+    for entry in entries {
+        // The header is the entry with an empty id and no context
+        if entry.context.is_none() && entry.id.as_deref() == Some("") {
+            for header in entry.translation.unwrap_or_default().lines() {
+                match header.split_once(':') {
+                    Some(("Language", value)) => language = Some(value.trim().to_string()),
+                    Some(("X-Namespace", value)) => {
+                        namespaces.insert(value.trim().to_string());
+                    },
+                    _ => {},
+                }
+            }
+            continue;
+        }
+
+        let context = entry
+            .context
+            .ok_or_else(|| format!("Line {}: entry has no msgctxt naming its message", entry.line))?;
+        let (namespace, key) = split_message_id(&context);
+        namespaces.insert(namespace.to_string());
+        match entry.translation {
+            Some(value) if !value.is_empty() => messages.push(Translation::new(namespace, key, "", &value)),
+            _ => {},
+        }
+    }
+
+    let language = language.filter(|language| !language.is_empty()).ok_or("The file has no Language header")?;
+    for message in &mut messages {
+        message.language_code = language.clone();
+    }
+    Ok(TranslationFile { format: TranslationFileFormat::Po, language, namespaces, messages })
+}
+
+/// Contents of a quoted PO string
+fn po_unquote(quoted: &str) -> Option<String> {
+    let inner = quoted.strip_prefix('"')?.strip_suffix('"')?;
+    let mut value = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => value.push('\n'),
+            't' => value.push('\t'),
+            'r' => value.push('\r'),
+            c => value.push(c),
+        }
+    }
+    Some(value)
+}
+
+fn attribute(element: &BytesStart, name: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    Ok(match element.try_get_attribute(name)? {
+        Some(attribute) => Some(attribute.unescape_value()?.into_owned()),
+        None => None,
+    })
+}
+
+fn parse_xliff(text: &str) -> Result<TranslationFile, Box<dyn std::error::Error>> {
+    let mut reader = Reader::from_str(text);
+
+    let mut language = None;
+    let mut namespaces = BTreeSet::new();
+    let mut messages = Vec::new();
+    let mut namespace: Option<String> = None;
+    let mut unit: Option<(String, String)> = None;
+    // Inside a <target>, whose text is the translation
+    let mut in_target = false;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(element) => match element.local_name().as_ref() {
+                b"xliff" => {
+                    if attribute(&element, "version")?.as_deref().is_some_and(|version| !version.starts_with('2')) {
+                        return Err("Only XLIFF 2.0 files can be imported".into());
+                    }
+                    language = attribute(&element, "trgLang")?;
+                },
+                b"file" => {
+                    let id = attribute(&element, "id")?.ok_or("A <file> has no id naming its namespace")?;
+                    namespaces.insert(id.clone());
+                    namespace = Some(id);
+                },
+                b"unit" => {
+                    let id = attribute(&element, "id")?.ok_or("A <unit> has no id naming its message")?;
+                    unit = Some((id, String::new()));
+                },
+                b"target" => in_target = true,
+                _ => {},
+            },
+            // Inline placeholders written by CAT tools, e.g. <ph id="1" equiv="{ $name }"/>
+            Event::Empty(element) if in_target => {
+                if let (Some(equiv), Some((_, value))) = (attribute(&element, "equiv")?, unit.as_mut()) {
+                    value.push_str(&equiv);
+                }
+            },
+            Event::Text(text) if in_target => {
+                if let Some((_, value)) = unit.as_mut() {
+                    value.push_str(&text.unescape()?);
+                }
+            },
+            Event::CData(data) if in_target => {
+                if let Some((_, value)) = unit.as_mut() {
+                    value.push_str(std::str::from_utf8(&data)?);
+                }
+            },
+            Event::End(element) => match element.local_name().as_ref() {
+                b"target" => in_target = false,
+                b"unit" => {
+                    let (key, value) = unit.take().ok_or("Unbalanced <unit>")?;
+                    let namespace = namespace.as_deref().ok_or("A <unit> is outside any <file>")?;
+                    if !value.is_empty() {
+                        messages.push(Translation::new(namespace, &key, "", &value));
+                    }
+                },
+                b"file" => namespace = None,
+                _ => {},
+            },
+            Event::Eof => break,
+            _ => {},
+        }
+    }
+
+    let language = language.filter(|language| !language.is_empty()).ok_or("The file has no trgLang")?;
+    for message in &mut messages {
+        message.language_code = language.clone();
+    }
+    Ok(TranslationFile { format: TranslationFileFormat::Xliff, language, namespaces, messages })
+}
+
+/// Compare an imported file with the messages stored for its language. `source` holds the
+/// messages of the source language, which imported keys and placeholders are checked against.
+pub fn preview_import(file: &TranslationFile, stored: &[Translation], source: &[Translation], source_language: &str) -> TranslationImportPreview {
+    let stored: HashMap<(&str, &str), &str> = stored
+        .iter()
+        .filter(|message| message.language_code == file.language)
+        .map(|message| ((message.namespace.as_str(), message.key.as_str()), message.value.as_str()))
+        .collect();
+    let source: HashMap<(&str, &str), &str> = source
+        .iter()
+        .filter(|message| message.language_code == source_language)
+        .map(|message| ((message.namespace.as_str(), message.key.as_str()), message.value.as_str()))
+        .collect();
+    // The last of duplicate entries wins
+    let imported: BTreeMap<(&str, &str), &str> = file
+        .messages
+        .iter()
+        .map(|message| ((message.namespace.as_str(), message.key.as_str()), message.value.as_str()))
+        .collect();
+
+    let mut preview = TranslationImportPreview {
+        language: file.language.clone(),
+        format: file.format,
+        namespaces: file.namespaces.iter().cloned().collect(),
+        added: Vec::new(),
+        changed: Vec::new(),
+        removed: Vec::new(),
+        unchanged: 0,
+        flagged: Vec::new(),
+    };
+
+    for (&(namespace, key), &value) in &imported {
+        let issue = if let Err(e) = validate_message(value) {
+            Some(ImportIssue::Invalid(e))
+        } else if file.language == source_language {
+            // The file is the source itself
+            None
+        } else {
+            match source.get(&(namespace, key)) {
+                None => Some(ImportIssue::UnknownKey),
+                Some(source) => {
+                    let expected = message_arguments(source).unwrap_or_default();
+                    let found = message_arguments(value).unwrap_or_default();
+                    (expected != found).then(|| ImportIssue::PlaceholderMismatch {
+                        source: expected.into_iter().collect(),
+                        translation: found.into_iter().collect(),
+                    })
+                },
+            }
+        };
+        if let Some(issue) = issue {
+            preview.flagged.push(FlaggedMessage {
+                namespace: namespace.to_string(),
+                key: key.to_string(),
+                value: value.to_string(),
+                issue,
+            });
+            continue;
+        }
+
+        let message = |previous: Option<&str>| ImportedMessage {
+            namespace: namespace.to_string(),
+            key: key.to_string(),
+            value: value.to_string(),
+            previous: previous.map(str::to_string),
+        };
+        match stored.get(&(namespace, key)) {
+            None => preview.added.push(message(None)),
+            Some(&previous) if previous == value => preview.unchanged += 1,
+            Some(&previous) => preview.changed.push(message(Some(previous))),
+        }
+    }
+
+    let mut removed: Vec<Translation> = stored
+        .iter()
+        .filter(|((namespace, key), _)| file.namespaces.contains(*namespace) && !imported.contains_key(&(*namespace, *key)))
+        .map(|(&(namespace, key), &value)| Translation::new(namespace, key, &file.language, value))
+        .collect();
+    removed.sort_by(|a, b| (&a.namespace, &a.key).cmp(&(&b.namespace, &b.key)));
+    preview.removed = removed;
+
+    preview
+}
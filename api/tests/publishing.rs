@@ -0,0 +1,96 @@
+//! Embargo and expiry windows on posts and pages, as readers and editors see them.
+
+use api::database::Database;
+use client::{Page, Post};
+
+const PAST: &str = "2000-01-01T00:00:00Z";
+const FUTURE: &str = "2999-01-01T00:00:00Z";
+
+async fn database() -> Database {
+    Database::init("sqlite::memory:").await.unwrap()
+}
+
+fn post(slug: &str, publish_at: Option<&str>, expire_at: Option<&str>) -> Post {
+    Post {
+        slug: slug.to_string(),
+        title: slug.to_string(),
+        published: true,
+        publish_at: publish_at.map(str::to_string),
+        expire_at: expire_at.map(str::to_string),
+        created_at: PAST.to_string(),
+        updated_at: PAST.to_string(),
+        ..Post::default()
+    }
+}
+
+/// Slugs of the posts readers can see now
+async fn live_slugs(db: &Database) -> Vec<String> {
+    let mut slugs: Vec<String> = db.get_published_posts().await.unwrap().into_iter().map(|post| post.slug).collect();
+    slugs.sort();
+    slugs
+}
+
+#[tokio::test]
+async fn posts_are_live_only_inside_their_window() {
+    let db = database().await;
+    let mut ids = Vec::new();
+    for post in [
+        post("always", None, None),
+        post("embargoed", Some(FUTURE), None),
+        post("expired", None, Some(PAST)),
+        post("released", Some(PAST), Some(FUTURE)),
+        post("draft", None, None),
+    ] {
+        let post = Post { published: post.slug != "draft", ..post };
+        ids.push(db.save_post(&post).await.unwrap());
+    }
+
+    assert_eq!(live_slugs(&db).await, ["always", "released"]);
+    for (slug, live) in [("always", true), ("embargoed", false), ("expired", false), ("released", true), ("draft", false)] {
+        assert_eq!(db.get_post_by_slug(slug).await.unwrap().is_some(), live, "{}", slug);
+        // Editors still see every post
+        assert!(db.get_post_by_slug_for_preview(slug).await.unwrap().is_some(), "{}", slug);
+    }
+
+    let mut live_by_id = Vec::new();
+    for id in ids {
+        live_by_id.push(db.get_live_post_by_id(id).await.unwrap().is_some());
+    }
+    assert_eq!(live_by_id, [true, false, false, true, false]);
+}
+
+#[tokio::test]
+async fn the_window_opens_at_publish_at_and_closes_at_expire_at() {
+    let db = database().await;
+    let now = client::time::now_iso8601();
+    // An embargo ending now has ended; an expiry at the same moment has passed
+    db.save_post(&post("opens-now", Some(&now), None)).await.unwrap();
+    db.save_post(&post("closes-now", None, Some(&now))).await.unwrap();
+
+    assert!(db.get_post_by_slug("opens-now").await.unwrap().is_some());
+    assert!(db.get_post_by_slug("closes-now").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn pages_follow_the_same_window() {
+    let db = database().await;
+    for (slug, publish_at, expire_at) in [("open", None, None), ("later", Some(FUTURE), None), ("gone", None, Some(PAST))] {
+        let page = Page {
+            slug: slug.to_string(),
+            title: slug.to_string(),
+            published: true,
+            publish_at: publish_at.map(str::to_string),
+            expire_at: expire_at.map(str::to_string),
+            created_at: PAST.to_string(),
+            updated_at: PAST.to_string(),
+            ..Page::default()
+        };
+        db.save_page(&page).await.unwrap();
+    }
+
+    let live: Vec<String> = db.get_published_pages().await.unwrap().into_iter().map(|page| page.slug).collect();
+    assert!(live.contains(&"open".to_string()), "{:?}", live);
+    assert!(!live.contains(&"later".to_string()) && !live.contains(&"gone".to_string()), "{:?}", live);
+    assert!(db.get_page_by_slug("later").await.unwrap().is_none());
+    assert!(db.get_page_by_slug_for_preview("later").await.unwrap().is_some());
+}
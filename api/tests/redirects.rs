@@ -0,0 +1,106 @@
+//! Redirects recorded when content moves, against a throwaway database.
+
+use api::database::Database;
+use client::{Post, Redirect};
+
+/// A fresh database file in the temp directory
+async fn database(name: &str) -> Database {
+    let dir = std::env::temp_dir().join(format!("bananabit-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    Database::init(&format!("sqlite://{}/cms.db", dir.display())).await.unwrap()
+}
+
+fn post(slug: &str, language: &str) -> Post {
+    Post {
+        slug: slug.to_string(),
+        title: slug.to_string(),
+        language: language.to_string(),
+        created_at: "2024-01-01T00:00:00Z".to_string(),
+        updated_at: "2024-01-01T00:00:00Z".to_string(),
+        ..Post::default()
+    }
+}
+
+async fn target(db: &Database, path: &str) -> Option<String> {
+    db.find_redirect(path).await.unwrap().map(|redirect| redirect.target)
+}
+
+#[tokio::test]
+async fn renaming_a_translation_redirects_its_localized_path() {
+    let db = database("localized-rename").await;
+    let mut hola = post("hola", "es");
+    hola.id = db.save_post(&hola).await.unwrap();
+
+    hola.slug = "hola-mundo".to_string();
+    db.save_post(&hola).await.unwrap();
+
+    assert_eq!(target(&db, "/es/post/hola").await.as_deref(), Some("/es/post/hola-mundo"));
+    assert_eq!(target(&db, "/post/hola").await, None);
+}
+
+#[tokio::test]
+async fn changing_the_language_redirects_to_the_prefixed_path() {
+    let db = database("language-change").await;
+    let mut hello = post("hello", "");
+    hello.id = db.save_post(&hello).await.unwrap();
+
+    hello.language = "es".to_string();
+    db.save_post(&hello).await.unwrap();
+    assert_eq!(target(&db, "/post/hello").await.as_deref(), Some("/es/post/hello"));
+
+    // Moving back clears the redirect away from the path the post lives at again
+    hello.language = "en".to_string();
+    db.save_post(&hello).await.unwrap();
+    assert_eq!(target(&db, "/es/post/hello").await.as_deref(), Some("/post/hello"));
+    assert_eq!(target(&db, "/post/hello").await, None);
+}
+
+#[tokio::test]
+async fn repeated_renames_redirect_straight_to_the_latest_slug() {
+    let db = database("rename-chain").await;
+    let mut post = post("first", "");
+    post.id = db.save_post(&post).await.unwrap();
+
+    for slug in ["second", "third"] {
+        post.slug = slug.to_string();
+        db.save_post(&post).await.unwrap();
+    }
+
+    // One hop from every old slug, no chain through /post/second
+    assert_eq!(target(&db, "/post/first").await.as_deref(), Some("/post/third"));
+    assert_eq!(target(&db, "/post/second").await.as_deref(), Some("/post/third"));
+    assert_eq!(target(&db, "/post/third").await, None);
+}
+
+#[tokio::test]
+async fn renaming_back_does_not_loop() {
+    let db = database("rename-back").await;
+    let mut post = post("draft", "");
+    post.id = db.save_post(&post).await.unwrap();
+
+    post.slug = "final".to_string();
+    db.save_post(&post).await.unwrap();
+    post.slug = "draft".to_string();
+    db.save_post(&post).await.unwrap();
+
+    assert_eq!(target(&db, "/post/draft").await, None);
+    assert_eq!(target(&db, "/post/final").await.as_deref(), Some("/post/draft"));
+    assert_eq!(db.get_redirects().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn gone_redirects_are_not_repointed() {
+    let db = database("rename-gone").await;
+    db.save_redirect(&Redirect { source: "/old".to_string(), target: "/post/a".to_string(), status: 410, ..Redirect::default() })
+        .await
+        .unwrap();
+    db.save_redirect(&Redirect::permanent("/older", "/post/a")).await.unwrap();
+    let mut post = post("a", "");
+    post.id = db.save_post(&post).await.unwrap();
+
+    post.slug = "b".to_string();
+    db.save_post(&post).await.unwrap();
+
+    assert_eq!(target(&db, "/older").await.as_deref(), Some("/post/b"));
+    assert_eq!(target(&db, "/old").await.as_deref(), Some("/post/a"));
+}
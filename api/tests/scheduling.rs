@@ -0,0 +1,134 @@
+//! Claiming, finishing, retrying and abandoning scheduled jobs, as the server's scheduler does.
+
+use api::database::Database;
+use client::{ContentType, ScheduleStatus, ScheduledAction, ScheduledContent};
+
+const EARLIER: &str = "2024-01-01T09:00:00Z";
+const NOW: &str = "2024-01-01T10:00:00Z";
+const LATER: &str = "2024-01-01T11:00:00Z";
+
+async fn database() -> Database {
+    Database::init("sqlite::memory:").await.unwrap()
+}
+
+/// Schedule publishing post `content_id` at `scheduled_at`
+async fn schedule(db: &Database, content_id: u32, scheduled_at: &str) -> u32 {
+    let job = ScheduledContent::new(ContentType::Post, content_id, ScheduledAction::Publish, scheduled_at);
+    db.save_scheduled_job(&job).await.unwrap()
+}
+
+async fn job(db: &Database, id: u32) -> ScheduledContent {
+    db.get_scheduled_jobs().await.unwrap().into_iter().find(|job| job.id == id).unwrap()
+}
+
+fn ids(jobs: &[ScheduledContent]) -> Vec<u32> {
+    jobs.iter().map(|job| job.id).collect()
+}
+
+#[tokio::test]
+async fn due_jobs_are_claimed_once() {
+    let db = database().await;
+    let second = schedule(&db, 2, NOW).await;
+    let first = schedule(&db, 1, EARLIER).await;
+    let upcoming = schedule(&db, 3, LATER).await;
+
+    // Oldest first, and nothing that isn't due yet
+    let claimed = db.claim_due_jobs("a", NOW, 10).await.unwrap();
+    assert_eq!(ids(&claimed), vec![first, second]);
+    assert!(claimed.iter().all(|job| job.status == ScheduleStatus::Processing));
+    assert_eq!(job(&db, upcoming).await.status, ScheduleStatus::Pending);
+
+    // Another instance polling at the same time gets nothing
+    assert!(db.claim_due_jobs("b", NOW, 10).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn concurrent_claims_share_out_jobs() {
+    let db = database().await;
+    let mut scheduled = Vec::new();
+    for content_id in 1..=20 {
+        scheduled.push(schedule(&db, content_id, EARLIER).await);
+    }
+
+    let (a, b) = tokio::join!(db.claim_due_jobs("a", NOW, 15), db.claim_due_jobs("b", NOW, 15));
+    let mut claimed = [ids(&a.unwrap()), ids(&b.unwrap())].concat();
+    claimed.sort();
+    assert_eq!(claimed, scheduled);
+}
+
+#[tokio::test]
+async fn claims_respect_the_limit() {
+    let db = database().await;
+    let first = schedule(&db, 1, EARLIER).await;
+    let second = schedule(&db, 2, EARLIER).await;
+
+    assert_eq!(ids(&db.claim_due_jobs("a", NOW, 1).await.unwrap()), vec![first]);
+    assert_eq!(ids(&db.claim_due_jobs("a", NOW, 1).await.unwrap()), vec![second]);
+}
+
+#[tokio::test]
+async fn only_the_claiming_worker_finishes_a_job() {
+    let db = database().await;
+    let done = schedule(&db, 1, EARLIER).await;
+    let failed = schedule(&db, 2, EARLIER).await;
+    db.claim_due_jobs("a", NOW, 10).await.unwrap();
+
+    db.finish_scheduled_job(done, "b", None).await.unwrap();
+    assert_eq!(job(&db, done).await.status, ScheduleStatus::Processing);
+
+    db.finish_scheduled_job(done, "a", None).await.unwrap();
+    db.finish_scheduled_job(failed, "a", Some("Post 2 not found")).await.unwrap();
+    let done = job(&db, done).await;
+    assert_eq!(done.status, ScheduleStatus::Completed);
+    assert!(done.error.is_none() && done.completed_at.is_some());
+    let failed = job(&db, failed).await;
+    assert_eq!(failed.status, ScheduleStatus::Failed);
+    assert_eq!(failed.error.as_deref(), Some("Post 2 not found"));
+}
+
+#[tokio::test]
+async fn failed_jobs_are_retried() {
+    let db = database().await;
+    let id = schedule(&db, 1, EARLIER).await;
+    db.claim_due_jobs("a", NOW, 10).await.unwrap();
+    db.finish_scheduled_job(id, "a", Some("database is locked")).await.unwrap();
+
+    // Failed jobs stay put until retried
+    assert!(db.claim_due_jobs("a", LATER, 10).await.unwrap().is_empty());
+    assert!(db.retry_scheduled_job(id, NOW).await.unwrap());
+    let retried = job(&db, id).await;
+    assert_eq!(retried.status, ScheduleStatus::Pending);
+    assert_eq!(retried.scheduled_at, NOW);
+    assert!(retried.error.is_none() && retried.completed_at.is_none());
+
+    // Any instance can pick it up again, and finish it this time
+    assert_eq!(ids(&db.claim_due_jobs("b", NOW, 10).await.unwrap()), vec![id]);
+    db.finish_scheduled_job(id, "b", None).await.unwrap();
+    assert_eq!(job(&db, id).await.status, ScheduleStatus::Completed);
+
+    // Completed and pending jobs can't be retried
+    assert!(!db.retry_scheduled_job(id, NOW).await.unwrap());
+    let pending = schedule(&db, 2, LATER).await;
+    assert!(!db.retry_scheduled_job(pending, NOW).await.unwrap());
+}
+
+#[tokio::test]
+async fn abandoned_jobs_are_marked_failed() {
+    let db = database().await;
+    let abandoned = schedule(&db, 1, EARLIER).await;
+    db.claim_due_jobs("a", EARLIER, 10).await.unwrap();
+    let running = schedule(&db, 2, NOW).await;
+    db.claim_due_jobs("b", NOW, 10).await.unwrap();
+    let pending = schedule(&db, 3, LATER).await;
+
+    assert_eq!(db.fail_abandoned_jobs(NOW).await.unwrap(), 1);
+    let abandoned = job(&db, abandoned).await;
+    assert_eq!(abandoned.status, ScheduleStatus::Failed);
+    assert!(abandoned.error.is_some() && abandoned.completed_at.is_some());
+    assert_eq!(job(&db, running).await.status, ScheduleStatus::Processing);
+    assert_eq!(job(&db, pending).await.status, ScheduleStatus::Pending);
+
+    // Its server can no longer record an outcome for it
+    db.finish_scheduled_job(abandoned.id, "a", None).await.unwrap();
+    assert_eq!(job(&db, abandoned.id).await.status, ScheduleStatus::Failed);
+}
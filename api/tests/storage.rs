@@ -0,0 +1,214 @@
+//! Media storage backends: the local filesystem, and S3 against a stub server standing in
+//! for MinIO.
+
+use api::storage::{migrate_local_files, validate_key, LocalStorage, MediaStorage, S3Config, S3Storage};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// An empty directory for one test
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("bananabit-storage-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[tokio::test]
+async fn local_storage_round_trips() {
+    let storage = LocalStorage::new(temp_dir("local"), "/uploads".to_string()).unwrap();
+
+    storage.put("2024/cat photo.png", b"meow".to_vec(), "image/png").await.unwrap();
+    assert_eq!(storage.get("2024/cat photo.png").await.unwrap().as_deref(), Some(&b"meow"[..]));
+    assert_eq!(storage.list_keys().unwrap(), ["2024/cat photo.png"]);
+    assert_eq!(storage.presigned_url("2024/cat photo.png", 60).unwrap(), "/uploads/2024/cat%20photo.png");
+
+    storage.delete("2024/cat photo.png").await.unwrap();
+    assert_eq!(storage.get("2024/cat photo.png").await.unwrap(), None);
+    // Deleting again is fine
+    storage.delete("2024/cat photo.png").await.unwrap();
+}
+
+#[tokio::test]
+async fn keys_stay_inside_the_root() {
+    for key in ["../secret", "a/../../b", "/etc/passwd", "", "./a"] {
+        assert!(validate_key(key).is_err(), "{:?} was accepted", key);
+    }
+    assert!(validate_key("2024/05/photo.jpg").is_ok());
+
+    let storage = LocalStorage::new(temp_dir("escape"), "/uploads".to_string()).unwrap();
+    assert!(storage.put("../escaped", b"x".to_vec(), "text/plain").await.is_err());
+    assert!(storage.get("/etc/passwd").await.is_err());
+}
+
+#[tokio::test]
+async fn local_files_migrate_to_another_backend() {
+    let source = LocalStorage::new(temp_dir("migrate-from"), "/uploads".to_string()).unwrap();
+    let destination = LocalStorage::new(temp_dir("migrate-to"), "/media".to_string()).unwrap();
+    source.put("a.png", b"a".to_vec(), "image/png").await.unwrap();
+    source.put("nested/b.pdf", b"b".to_vec(), "application/pdf").await.unwrap();
+
+    assert_eq!(migrate_local_files(&source, &destination).await.unwrap(), 2);
+    assert_eq!(destination.list_keys().unwrap(), ["a.png", "nested/b.pdf"]);
+    assert_eq!(destination.get("nested/b.pdf").await.unwrap().as_deref(), Some(&b"b"[..]));
+    // Copied, not moved
+    assert_eq!(source.list_keys().unwrap().len(), 2);
+}
+
+/// A request as the stub server received it
+#[derive(Debug, Clone)]
+struct Received {
+    method: String,
+    path: String,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+/// Serves a bucket from memory, recording every request. Objects named `broken` fail.
+async fn stub_s3() -> (String, Arc<Mutex<Vec<Received>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let objects: Arc<Mutex<HashMap<String, Vec<u8>>>> = Arc::default();
+
+    let log = received.clone();
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut data = Vec::new();
+            let mut buffer = [0; 4096];
+            let request = loop {
+                let n = socket.read(&mut buffer).await.unwrap();
+                data.extend_from_slice(&buffer[..n]);
+                let Some(end) = data.windows(4).position(|w| w == b"\r\n\r\n") else { continue };
+                let head = String::from_utf8_lossy(&data[..end]).to_string();
+                let mut lines = head.lines();
+                let mut start = lines.next().unwrap().split(' ');
+                let (method, path) = (start.next().unwrap().to_string(), start.next().unwrap().to_string());
+                let headers: HashMap<String, String> = lines
+                    .filter_map(|line| line.split_once(": "))
+                    .map(|(name, value)| (name.to_ascii_lowercase(), value.to_string()))
+                    .collect();
+                let length: usize = headers.get("content-length").map_or(0, |length| length.parse().unwrap());
+                if data.len() >= end + 4 + length {
+                    break Received { method, path, headers, body: data[end + 4..end + 4 + length].to_vec() };
+                }
+            };
+            log.lock().unwrap().push(request.clone());
+
+            let (status, body) = {
+                let mut objects = objects.lock().unwrap();
+                match request.method.as_str() {
+                    _ if request.path.ends_with("/broken") => ("500 Internal Server Error", Vec::new()),
+                    "PUT" => {
+                        objects.insert(request.path.clone(), request.body.clone());
+                        ("200 OK", Vec::new())
+                    },
+                    "GET" => match objects.get(&request.path) {
+                        Some(body) => ("200 OK", body.clone()),
+                        None => ("404 Not Found", b"<Error><Code>NoSuchKey</Code></Error>".to_vec()),
+                    },
+                    "DELETE" => ("204 No Content", Vec::new()),
+                    _ => ("405 Method Not Allowed", Vec::new()),
+                }
+            };
+            let head = format!("HTTP/1.1 {}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n", status, body.len());
+            socket.write_all(head.as_bytes()).await.unwrap();
+            socket.write_all(&body).await.unwrap();
+        }
+    });
+
+    (endpoint, received)
+}
+
+fn s3_config(endpoint: &str) -> S3Config {
+    S3Config {
+        endpoint: endpoint.to_string(),
+        bucket: "media".to_string(),
+        region: "eu-west-1".to_string(),
+        access_key_id: "AKIDEXAMPLE".to_string(),
+        secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+    }
+}
+
+fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).unwrap();
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// The signature SigV4 gives the request, worked out from what was sent
+fn expected_signature(request: &Received, config: &S3Config) -> String {
+    let amz_date = &request.headers["x-amz-date"];
+    let date = &amz_date[..8];
+    let canonical_request = format!(
+        "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\nhost;x-amz-content-sha256;x-amz-date\n{}",
+        request.method,
+        request.path,
+        request.headers["host"],
+        request.headers["x-amz-content-sha256"],
+        amz_date,
+        request.headers["x-amz-content-sha256"],
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, config.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes())),
+    );
+    let key = [config.region.as_str(), "s3", "aws4_request"]
+        .iter()
+        .fold(hmac(format!("AWS4{}", config.secret_access_key).as_bytes(), date), |key, part| hmac(&key, part));
+    hex::encode(hmac(&key, &string_to_sign))
+}
+
+#[tokio::test]
+async fn s3_requests_are_signed_and_responses_parsed() {
+    let (endpoint, received) = stub_s3().await;
+    let config = s3_config(&endpoint);
+    let storage = S3Storage::new(config.clone());
+
+    storage.put("2024/cat photo.png", b"meow".to_vec(), "image/png").await.unwrap();
+    assert_eq!(storage.get("2024/cat photo.png").await.unwrap().as_deref(), Some(&b"meow"[..]));
+    assert_eq!(storage.get("missing.png").await.unwrap(), None);
+    storage.delete("2024/cat photo.png").await.unwrap();
+    assert!(storage.put("broken", b"x".to_vec(), "text/plain").await.is_err());
+    assert!(storage.get("broken").await.is_err());
+    assert!(storage.get("../outside").await.is_err());
+
+    let received = received.lock().unwrap().clone();
+    let methods: Vec<_> = received.iter().map(|request| request.method.as_str()).collect();
+    assert_eq!(methods, ["PUT", "GET", "GET", "DELETE", "PUT", "GET"]);
+
+    let put = &received[0];
+    assert_eq!(put.path, "/media/2024/cat%20photo.png");
+    assert_eq!(put.headers["content-type"], "image/png");
+    assert_eq!(put.headers["x-amz-content-sha256"], hex::encode(Sha256::digest(b"meow")));
+    for request in &received {
+        let date = &request.headers["x-amz-date"][..8];
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/{}/eu-west-1/s3/aws4_request, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={}",
+            date,
+            expected_signature(request, &config),
+        );
+        assert_eq!(request.headers["authorization"], authorization, "{} {}", request.method, request.path);
+    }
+}
+
+#[test]
+fn s3_presigned_urls_carry_the_query_signature() {
+    let storage = S3Storage::new(s3_config("https://minio.example.com"));
+    let url = storage.presigned_url("a b.png", 9_999_999).unwrap();
+
+    assert!(url.starts_with("https://minio.example.com/media/a%20b.png?X-Amz-Algorithm=AWS4-HMAC-SHA256&X-Amz-Credential=AKIDEXAMPLE%2F"), "{}", url);
+    assert!(url.contains("%2Feu-west-1%2Fs3%2Faws4_request&X-Amz-Date="), "{}", url);
+    // Capped at the seven days S3 allows
+    assert!(url.contains("&X-Amz-Expires=604800&X-Amz-SignedHeaders=host&X-Amz-Signature="), "{}", url);
+    let signature = url.rsplit("X-Amz-Signature=").next().unwrap();
+    assert_eq!(signature.len(), 64);
+    assert!(signature.chars().all(|c| c.is_ascii_hexdigit()));
+}
@@ -0,0 +1,68 @@
+//! Round trips through the PO and XLIFF formats, and the import preview.
+
+use api::translation_files::{export, parse, preview_import};
+use client::{ImportIssue, Translation, TranslationFileFormat};
+
+fn messages() -> Vec<Translation> {
+    vec![
+        Translation::new("common", "greeting", "en", "Hello, \"{ $name }\"\nWelcome back"),
+        Translation::new("common", "count", "en", "{ $count -> [one] One post *[other] { $count } posts }"),
+        Translation::new("common", "save", "en", "Save & <close>"),
+        Translation::new("common", "greeting", "es", "Hola, \"{ $name }\"\nBienvenido"),
+        Translation::new("common", "save", "es", "Guardar y <cerrar>"),
+    ]
+}
+
+#[test]
+fn exported_files_read_back() {
+    for format in TranslationFileFormat::ALL {
+        let text = export(format, "common", "en", "es", &messages(), &messages());
+        let file = parse(format, &text).unwrap_or_else(|e| panic!("{:?}: {}\n{}", format, e, text));
+
+        assert_eq!(file.language, "es");
+        assert_eq!(file.namespaces.iter().collect::<Vec<_>>(), ["common"]);
+        // The untranslated `count` is left out
+        let mut imported = file.messages.clone();
+        imported.sort_by(|a, b| a.key.cmp(&b.key));
+        assert_eq!(imported, [messages()[3].clone(), messages()[4].clone()], "{:?}", format);
+    }
+}
+
+#[test]
+fn preview_flags_unknown_keys_and_placeholders() {
+    let po = r#"
+msgid ""
+msgstr "Language: es\n"
+
+msgctxt "common:greeting"
+msgid "Hello"
+msgstr "Hola, { $nombre }"
+
+msgctxt "common:count"
+msgid "Posts"
+msgstr "{ $count -> [one] Una entrada *[other] { $count } entradas }"
+
+msgctxt "common:unknown"
+msgid ""
+msgstr "Desconocido"
+"#;
+    let file = parse(TranslationFileFormat::Po, po).unwrap();
+    let preview = preview_import(&file, &messages(), &messages(), "en");
+
+    assert_eq!(preview.added.iter().map(|message| message.key.as_str()).collect::<Vec<_>>(), ["count"]);
+    assert!(preview.changed.is_empty());
+    // Stored but missing from the file
+    assert_eq!(preview.removed.iter().map(|message| message.key.as_str()).collect::<Vec<_>>(), ["save"]);
+
+    let flagged: Vec<_> = preview.flagged.iter().map(|message| (message.key.as_str(), &message.issue)).collect();
+    assert_eq!(flagged, [
+        ("greeting", &ImportIssue::PlaceholderMismatch { source: vec!["name".to_string()], translation: vec!["nombre".to_string()] }),
+        ("unknown", &ImportIssue::UnknownKey),
+    ]);
+}
+
+#[test]
+fn files_without_a_language_are_rejected() {
+    assert!(parse(TranslationFileFormat::Po, "msgctxt \"common:save\"\nmsgid \"Save\"\nmsgstr \"Guardar\"\n").is_err());
+    assert!(parse(TranslationFileFormat::Xliff, r#"<xliff version="2.0" srcLang="en"><file id="common"></file></xliff>"#).is_err());
+}
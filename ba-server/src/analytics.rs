@@ -0,0 +1,262 @@
+//! First-party analytics: the collection beacon, server render timing, the rollup job and
+//! CSV export. No cookies are set and no IP addresses are stored.
+
+use api::{analytics, database::Database};
+use axum::{
+    extract::{ConnectInfo, FromRef, Query, Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Router,
+};
+use client::{AnalyticsEvent, Metric};
+use dioxus::logger::tracing::{info, warn};
+use serde::Deserialize;
+use std::{collections::HashMap, net::SocketAddr, sync::Arc, time::{Duration, Instant}};
+
+/// How often raw events are rolled up
+const ROLLUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Days raw events are kept, unless `ANALYTICS_RETENTION_DAYS` says otherwise
+const DEFAULT_RETENTION_DAYS: u32 = 30;
+
+/// Largest beacon body accepted
+const MAX_BEACON_SIZE: usize = 2048;
+
+/// Beacon payload sent by `ui::extensions::AnalyticsBeacon`
+#[derive(Deserialize)]
+struct Beacon {
+    event: String,
+    path: String,
+    referrer: Option<String>,
+    /// Web Vitals, for `vitals` beacons
+    #[serde(default)]
+    metrics: HashMap<String, f64>,
+}
+
+/// Beacon event carrying Web Vitals instead of a page view
+const VITALS_EVENT: &str = "vitals";
+
+/// State of the analytics routes
+#[derive(Clone)]
+struct AnalyticsState {
+    db: Arc<Database>,
+    /// Whether the server is behind a reverse proxy that sets `X-Forwarded-For`, as set by
+    /// `TRUSTED_PROXY`. Otherwise the header is whatever the visitor sent.
+    trusted_proxy: bool,
+}
+
+impl FromRef<AnalyticsState> for Arc<Database> {
+    fn from_ref(state: &AnalyticsState) -> Self {
+        state.db.clone()
+    }
+}
+
+#[derive(Deserialize)]
+struct ExportQuery {
+    start: String,
+    end: String,
+}
+
+/// Router serving `POST /collect`, which records page views, page leaves and Web Vitals, and
+/// `/analytics/export.csv?start=&end=`, which downloads daily totals for a date range to
+/// admins and editors.
+///
+/// Requests with `DNT: 1` or `Sec-GPC: 1`, and requests from bots, are acknowledged but not
+/// stored. The beacon is sent with `navigator.sendBeacon`, so the body arrives as text.
+pub fn analytics_router(db: Arc<Database>) -> Router {
+    let trusted_proxy = std::env::var("TRUSTED_PROXY").is_ok_and(|value| matches!(value.trim(), "1" | "true"));
+
+    Router::new()
+        .route("/collect", post(collect))
+        .route("/analytics/export.csv", get(export_csv))
+        .with_state(AnalyticsState { db, trusted_proxy })
+}
+
+/// Roll up raw events every hour and prune the ones past the retention window
+pub fn spawn_rollup_task(db: Arc<Database>) {
+    let retention_days = std::env::var("ANALYTICS_RETENTION_DAYS")
+        .ok()
+        .and_then(|days| days.parse().ok())
+        .unwrap_or(DEFAULT_RETENTION_DAYS);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(ROLLUP_INTERVAL);
+        loop {
+            interval.tick().await;
+            match db.rollup_analytics(retention_days).await.map_err(|e| e.to_string()) {
+                Ok((days, pruned)) => info!("📊 Rolled up analytics for {} days, pruned {} raw events", days, pruned),
+                Err(e) => warn!("Analytics rollup failed: {}", e),
+            }
+        }
+    });
+}
+
+async fn export_csv(State(db): State<Arc<Database>>, headers: HeaderMap, Query(query): Query<ExportQuery>) -> Response {
+    match can_export(&db, &headers).await {
+        Ok(true) => {}
+        Ok(false) => return StatusCode::FORBIDDEN.into_response(),
+        Err(e) => {
+            warn!("Failed to check the analytics export session: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    }
+
+    match api::analytics_report(&db, &query.start, &query.end).await.map_err(|e| e.to_string()) {
+        Ok(report) => (
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"analytics-{}-to-{}.csv\"", report.start, report.end),
+                ),
+            ],
+            report.to_csv(),
+        )
+            .into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, e).into_response(),
+    }
+}
+
+/// Whether the request comes from a logged-in admin or editor, who may download analytics
+async fn can_export(db: &Database, headers: &HeaderMap) -> Result<bool, String> {
+    let Some(token) = crate::locale::request_cookie(headers, api::SESSION_COOKIE) else {
+        return Ok(false);
+    };
+    let user = api::session_user_with_role(db, &token, api::EDITOR_ROLES).await.map_err(|e| e.to_string())?;
+    Ok(user.is_some())
+}
+
+async fn collect(
+    State(AnalyticsState { db, trusted_proxy }): State<AnalyticsState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    body: String,
+) -> Response {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+
+    if analytics::do_not_track(header("dnt"), header("sec-gpc")) || analytics::is_bot(header("user-agent").map(str::trim)) {
+        return StatusCode::NO_CONTENT.into_response();
+    }
+
+    if body.len() > MAX_BEACON_SIZE {
+        return StatusCode::PAYLOAD_TOO_LARGE.into_response();
+    }
+    let Ok(beacon) = serde_json::from_str::<Beacon>(&body) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    if !beacon.path.starts_with('/') {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+    if beacon.event == VITALS_EVENT {
+        return record_vitals(&db, &beacon).await;
+    }
+    if ![AnalyticsEvent::PAGE_VIEW, AnalyticsEvent::LEAVE].contains(&beacon.event.as_str()) {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+
+    let timestamp = client::time::now_iso8601();
+    let ip = client_ip(&headers, addr, trusted_proxy);
+
+    // Errors are stringified so nothing that isn't `Send` is held across an await
+    let salt = match db.analytics_salt(&timestamp[..10]).await.map_err(|e| e.to_string()) {
+        Ok(salt) => salt,
+        Err(e) => {
+            warn!("Failed to load analytics salt: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let event = AnalyticsEvent {
+        event_type: beacon.event,
+        path: beacon.path.split(['?', '#']).next().unwrap_or_default().to_string(),
+        visitor_hash: analytics::visitor_hash(&salt, &ip, header("user-agent").unwrap_or_default()),
+        referrer: analytics::referrer_host(beacon.referrer.as_deref(), header("host")),
+        device: Some(analytics::device_class(header("user-agent").unwrap_or_default()).to_string()),
+        timestamp,
+        ..Default::default()
+    };
+
+    match db.record_analytics_event(&event).await.map_err(|e| e.to_string()) {
+        Ok(_) => ([(header::CACHE_CONTROL, "no-store")], StatusCode::NO_CONTENT).into_response(),
+        Err(e) => {
+            warn!("Failed to record analytics event: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Address of the visitor: the connection's, or behind a trusted reverse proxy the first
+/// `X-Forwarded-For` entry
+fn client_ip(headers: &HeaderMap, addr: SocketAddr, trusted_proxy: bool) -> String {
+    let forwarded = headers
+        .get("x-forwarded-for")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|forwarded| forwarded.split(',').next())
+        .map(|ip| ip.trim().to_string())
+        .filter(|ip| !ip.is_empty());
+
+    match forwarded {
+        Some(ip) if trusted_proxy => ip,
+        _ => addr.ip().to_string(),
+    }
+}
+
+/// Store the Web Vitals of a page load; unknown names and implausible values are dropped
+async fn record_vitals(db: &Database, beacon: &Beacon) -> Response {
+    let route = ui::route_pattern(&beacon.path);
+    let metrics: Vec<Metric> = beacon
+        .metrics
+        .iter()
+        .filter(|(name, value)| Metric::WEB_VITALS.contains(&name.as_str()) && value.is_finite() && (0.0..60_000.0).contains(*value))
+        .map(|(name, value)| Metric::new(name, *value, route))
+        .collect();
+
+    if metrics.is_empty() {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+
+    match db.record_metrics(&metrics).await.map_err(|e| e.to_string()) {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => {
+            warn!("Failed to record Web Vitals: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Middleware timing server-rendered pages. The duration is stored as a `server_render`
+/// metric for the route and sent to the browser in a `Server-Timing` header.
+pub async fn render_timing_layer(State(db): State<Arc<Database>>, request: Request, next: Next) -> Response {
+    if request.method() != Method::GET {
+        return next.run(request).await;
+    }
+
+    let route = ui::route_pattern(request.uri().path());
+    let started = Instant::now();
+    let mut response = next.run(request).await;
+    let elapsed_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+    let is_page = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with("text/html"));
+    if !is_page || !response.status().is_success() {
+        return response;
+    }
+
+    if let Ok(value) = HeaderValue::from_str(&format!("render;dur={:.1}", elapsed_ms)) {
+        response.headers_mut().insert("server-timing", value);
+    }
+
+    // Store in the background so the response isn't held up by the write
+    tokio::spawn(async move {
+        let metric = Metric::new(Metric::SERVER_RENDER, elapsed_ms, route);
+        if let Err(e) = db.record_metrics(&[metric]).await.map_err(|e| e.to_string()) {
+            warn!("Failed to record render time: {}", e);
+        }
+    });
+
+    response
+}
@@ -0,0 +1,103 @@
+//! Choosing the language of each request: the URL prefix, then the visitor's saved
+//! preference (cookie or profile), then `Accept-Language`, then the site's default.
+
+use api::database::Database;
+use axum::{
+    body::{Body, Bytes},
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use client::{HtmlLocaleRewriter, RequestLocale};
+use dioxus::logger::tracing::warn;
+use futures_util::{stream, StreamExt};
+use std::sync::Arc;
+
+/// Middleware negotiating the language of page and server function requests. The result is
+/// put in the request's extensions for server rendering (see `api::get_request_locale`), and
+/// pages get it as the `lang` and `dir` of their `<html>` element and in `Content-Language`.
+/// Assets, uploads, stylesheets, the sitemap and other files are passed straight through.
+pub async fn locale_layer(State(db): State<Arc<Database>>, mut request: Request, next: Next) -> Response {
+    let path = request.uri().path();
+    let is_page_route = path.parse::<ui::Route>().is_ok();
+    if !is_page_route && !path.starts_with("/api/") {
+        return next.run(request).await;
+    }
+
+    let locale = match negotiate(&db, request.uri().path(), request.headers()).await {
+        Ok(locale) => locale,
+        Err(e) => {
+            warn!("Language negotiation failed for {}: {}", request.uri().path(), e);
+            return next.run(request).await;
+        }
+    };
+    request.extensions_mut().insert(locale.clone());
+
+    let mut response = next.run(request).await;
+
+    let is_page = is_page_route && response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with("text/html"));
+    if !is_page {
+        return response;
+    }
+
+    // The same URL can be served in other languages to other visitors
+    response.headers_mut().append(header::VARY, HeaderValue::from_static("Accept-Language, Cookie"));
+
+    if let Ok(value) = HeaderValue::from_str(&locale.language) {
+        response.headers_mut().insert(header::CONTENT_LANGUAGE, value);
+    }
+    // The length changes with the added attributes
+    response.headers_mut().remove(header::CONTENT_LENGTH);
+
+    let (parts, body) = response.into_parts();
+    // Pages are streamed, so `<html>` may arrive over several chunks
+    let rewriter = HtmlLocaleRewriter::new(&locale);
+    let body = stream::unfold(Some((body.into_data_stream(), rewriter)), |state| async move {
+        let (mut body, mut rewriter) = state?;
+        match body.next().await {
+            Some(Ok(chunk)) => Some((Ok(Bytes::from(rewriter.push(&chunk))), Some((body, rewriter)))),
+            Some(Err(e)) => Some((Err(e), None)),
+            None => Some((Ok(Bytes::from(rewriter.finish())), None)),
+        }
+    });
+
+    Response::from_parts(parts, Body::from_stream(body))
+}
+
+/// Language for a request to `path`
+async fn negotiate(db: &Database, path: &str, headers: &HeaderMap) -> Result<RequestLocale, String> {
+    let languages = db.get_languages().await.map_err(|e| e.to_string())?;
+    let default_language = db.get_default_language().await.map_err(|e| e.to_string())?;
+
+    let preference = match request_cookie(headers, client::LANGUAGE_COOKIE) {
+        Some(language) => Some(language),
+        // A logged-in user on a browser without the cookie yet
+        None => match request_cookie(headers, api::SESSION_COOKIE) {
+            Some(token) => match db.get_session_user_id(&token).await.map_err(|e| e.to_string())? {
+                Some(user_id) => db.get_user_language(user_id).await.map_err(|e| e.to_string())?,
+                None => None,
+            },
+            None => None,
+        },
+    };
+    let accept_language = headers.get(header::ACCEPT_LANGUAGE).and_then(|value| value.to_str().ok());
+
+    Ok(RequestLocale::negotiate(path, preference.as_deref(), accept_language, &languages, &default_language))
+}
+
+/// The cookie `name` sent with a request
+pub(crate) fn request_cookie(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.to_string())
+}
@@ -1,12 +1,30 @@
 use dioxus::prelude::*;
 use ui::App;
 
+mod analytics;
+mod locale;
+mod media;
+mod redirects;
+mod scheduler;
+mod seo;
+mod theme;
+mod translations;
+
 fn main() {
     use axum::{routing::get_service, Router};
     use dioxus::logger::tracing::*;
     use tower_http::services::ServeDir;
 
     tokio::runtime::Runtime::new().unwrap().block_on(async {
+        // `ba-server migrate-media` copies local uploads into the configured backend and exits
+        if std::env::args().nth(1).as_deref() == Some("migrate-media") {
+            if let Err(e) = media::migrate_media().await {
+                error!("🔥 Media migration failed: {}", e);
+                std::process::exit(1);
+            }
+            return;
+        }
+
         let addr = dioxus::cli_config::fullstack_address_or_localhost();
         info!("🚀 Starting web server on http://{}", addr);
 
@@ -17,21 +35,60 @@ fn main() {
         } else {
             "ba-server/assets" // Local development path
         };
-        
+
+        let storage_config = api::storage::StorageConfig::from_env().unwrap_or_else(|e| {
+            error!("🔥 Invalid media storage configuration, falling back to local: {}", e);
+            api::storage::StorageConfig::local_from_env()
+        });
+
+        let db = match api::database::Database::init("sqlite://cms.db").await {
+            Ok(db) => std::sync::Arc::new(db),
+            Err(e) => {
+                error!("🔥 Failed to open database: {}", e);
+                std::process::exit(1);
+            }
+        };
+
+        // Aggregate analytics in the background
+        analytics::spawn_rollup_task(db.clone());
+        // Run scheduled publishing, unpublishing, updates and deletions when they fall due
+        scheduler::spawn_scheduler_task(db.clone());
+
+        // Public URL of the site, used for absolute links in the sitemap and robots.txt
+        let base_url = std::env::var("BASE_URL").unwrap_or_else(|_| format!("http://{}", addr));
+
         let app = Router::new()
             // Serve static assets from the appropriate directory
             .nest_service("/assets", get_service(ServeDir::new(assets_path)))
+            // Serve uploaded media from the configured storage backend
+            .merge(media::uploads_router(&storage_config))
+            // Serve the active theme's stylesheet
+            .merge(theme::theme_router(db.clone()))
+            // Serve robots.txt and the sitemap generated from published content
+            .merge(seo::seo_router(db.clone(), base_url))
+            // Serve PO and XLIFF files for translators
+            .merge(translations::translations_router(db.clone()))
+            // Collect cookieless page view beacons and export analytics
+            .merge(analytics::analytics_router(db.clone()))
             // IMPORTANT: Dioxus needs to handle all routes for SPA
             .serve_dioxus_application(
                 ServeConfig::builder()
                     .build()
                     .expect("Failed to build serve config"),
                 App,
-            );
+            )
+            // Pick the language of each request from its URL, the visitor's preference or their browser
+            .layer(axum::middleware::from_fn_with_state(db.clone(), locale::locale_layer))
+            // Answer requests for moved or removed URLs before they reach the routes above
+            .layer(axum::middleware::from_fn_with_state(db.clone(), redirects::redirect_layer))
+            // Record how long each page took to render
+            .layer(axum::middleware::from_fn_with_state(db.clone(), analytics::render_timing_layer));
 
         // --- Start server ---
-        if let Err(e) = axum::serve(tokio::net::TcpListener::bind(addr).await.unwrap(), app).await {
+        // The peer address is needed for analytics visitor hashes
+        let service = app.into_make_service_with_connect_info::<std::net::SocketAddr>();
+        if let Err(e) = axum::serve(tokio::net::TcpListener::bind(addr).await.unwrap(), service).await {
             error!("🔥 Server error: {}", e);
         }
     });
-}
\ No newline at end of file
+}
@@ -0,0 +1,71 @@
+//! Serving uploaded media from the configured storage backend.
+
+use api::storage::{self, LocalStorage, MediaStorage, StorageConfig};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Redirect, Response},
+    routing::{get, get_service},
+    Router,
+};
+use dioxus::logger::tracing::*;
+use std::sync::Arc;
+use tower_http::services::ServeDir;
+
+/// How long redirects to presigned URLs stay valid
+const PRESIGNED_URL_TTL_SECS: u64 = 3600;
+
+/// Router serving `/uploads/*` for the configured storage backend.
+///
+/// Local uploads are served straight from disk; remote backends get a redirect to a
+/// short-lived presigned URL so the bytes never pass through this server.
+pub fn uploads_router(config: &StorageConfig) -> Router {
+    match config {
+        StorageConfig::Local { root, .. } => {
+            if let Err(e) = std::fs::create_dir_all(root) {
+                error!("🔥 Could not create upload directory {}: {}", root.display(), e);
+            }
+            Router::new().nest_service("/uploads", get_service(ServeDir::new(root)))
+        },
+        StorageConfig::S3(_) => match config.build() {
+            Ok(storage) => Router::new()
+                .route("/uploads/{*key}", get(redirect_to_presigned))
+                .with_state(Arc::from(storage)),
+            Err(e) => {
+                error!("🔥 Media storage is misconfigured, uploads will not be served: {}", e);
+                Router::new()
+            },
+        },
+    }
+}
+
+async fn redirect_to_presigned(
+    State(storage): State<Arc<dyn MediaStorage>>,
+    Path(key): Path<String>,
+) -> Response {
+    match storage.presigned_url(&key, PRESIGNED_URL_TTL_SECS) {
+        Ok(url) => Redirect::temporary(&url).into_response(),
+        Err(_) => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Copy files from the local upload directory into the configured backend.
+///
+/// Run with `ba-server migrate-media` after switching `MEDIA_STORAGE` to a remote backend.
+pub async fn migrate_media() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let destination = storage::storage_from_env()?;
+    if destination.name() == "local" {
+        return Err("MEDIA_STORAGE is set to local; configure the target backend before migrating".into());
+    }
+
+    let StorageConfig::Local { root, public_base_url } = StorageConfig::local_from_env() else {
+        unreachable!("local_from_env always returns a local configuration");
+    };
+    let source = LocalStorage::new(root, public_base_url)?;
+
+    info!("📦 Migrating media from {} to {} storage", source.root().display(), destination.name());
+    let count = storage::migrate_local_files(&source, destination.as_ref()).await?;
+    info!("✅ Migrated {} media files", count);
+
+    Ok(())
+}
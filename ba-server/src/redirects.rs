@@ -0,0 +1,99 @@
+//! Serving stored redirects, and moving posts and pages to their canonical URLs, before
+//! requests reach the app's routes.
+
+use api::database::Database;
+use axum::{
+    extract::{Request, State},
+    http::{header, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use dioxus::logger::tracing::warn;
+use std::sync::Arc;
+use ui::Route;
+
+/// Middleware answering requests whose path has a redirect with a 301/302 to its target,
+/// or 410 Gone. Posts and pages requested under the wrong language prefix get a 301 to their
+/// canonical URL. Everything else is passed through to the router.
+pub async fn redirect_layer(State(db): State<Arc<Database>>, request: Request, next: Next) -> Response {
+    if !matches!(*request.method(), Method::GET | Method::HEAD) || is_internal_path(request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    // Errors are stringified so nothing that isn't `Send` is held across the awaits below
+    let redirect = match db.find_redirect(request.uri().path()).await.map_err(|e| e.to_string()) {
+        Ok(Some(redirect)) => redirect,
+        Ok(None) => return match canonical_route(&db, request.uri().path()).await {
+            Ok(Some(route)) => {
+                let location = with_query(&route.to_string(), request.uri().query());
+                (StatusCode::MOVED_PERMANENTLY, [(header::LOCATION, location)]).into_response()
+            }
+            Ok(None) => next.run(request).await,
+            Err(e) => {
+                warn!("Canonical URL lookup failed for {}: {}", request.uri().path(), e);
+                next.run(request).await
+            }
+        },
+        Err(e) => {
+            warn!("Redirect lookup failed for {}: {}", request.uri().path(), e);
+            return next.run(request).await;
+        }
+    };
+
+    if let Err(e) = db.record_redirect_hit(redirect.id).await.map_err(|e| e.to_string()) {
+        warn!("Failed to count redirect hit for {}: {}", redirect.source, e);
+    }
+
+    if redirect.is_gone() {
+        return StatusCode::GONE.into_response();
+    }
+
+    let status = StatusCode::from_u16(redirect.status).unwrap_or(StatusCode::MOVED_PERMANENTLY);
+    let location = with_query(&redirect.target, request.uri().query());
+
+    (status, [(header::LOCATION, location)]).into_response()
+}
+
+/// The route a post or page should be served from, when `path` reaches it under another
+/// language prefix, e.g. `/fr/post/hello` for an English post
+async fn canonical_route(db: &Database, path: &str) -> Result<Option<Route>, String> {
+    let Ok(route) = path.parse::<Route>() else {
+        return Ok(None);
+    };
+    
+    let canonical = match &route {
+        Route::PostRoute { slug } | Route::LocalizedPostRoute { slug, .. } => {
+            let Some(post) = db.get_post_by_slug(slug).await.map_err(|e| e.to_string())? else {
+                return Ok(None);
+            };
+            let default_language = db.get_default_language().await.map_err(|e| e.to_string())?;
+            post.path(&default_language)
+        }
+        Route::PageRoute { slug } | Route::LocalizedPageRoute { slug, .. } => {
+            let Some(page) = db.get_page_by_slug(slug).await.map_err(|e| e.to_string())? else {
+                return Ok(None);
+            };
+            let default_language = db.get_default_language().await.map_err(|e| e.to_string())?;
+            page.path(&default_language)
+        }
+        _ => return Ok(None),
+    };
+    
+    // Compared as routes, since the request path may percent-encode the slug
+    Ok(canonical.parse::<Route>().ok().filter(|canonical| *canonical != route))
+}
+
+/// Assets, uploads, translation files and server functions are never redirected
+fn is_internal_path(path: &str) -> bool {
+    ["/assets/", "/uploads/", "/api/", "/theme/", "/i18n/"]
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+}
+
+/// Keep the original query string unless the target has its own
+fn with_query(target: &str, query: Option<&str>) -> String {
+    match query {
+        Some(query) if !query.is_empty() && !target.contains('?') => format!("{}?{}", target, query),
+        _ => target.to_string(),
+    }
+}
@@ -0,0 +1,142 @@
+//! Background runner for scheduled publishing, unpublishing, updates and deletions.
+//!
+//! Jobs live in the `scheduled_jobs` table. Each poll claims the jobs that are due in one
+//! statement, so several server instances sharing the database never run the same job twice.
+
+use api::database::Database;
+use client::{ContentType, Page, Post, ScheduledAction, ScheduledContent};
+use dioxus::logger::tracing::{info, warn};
+use std::{sync::Arc, time::Duration};
+
+/// How often due jobs are looked for
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// Most jobs claimed per poll
+const BATCH_SIZE: u32 = 20;
+/// Jobs still processing this long after being claimed were abandoned by their server
+const ABANDONED_AFTER: chrono::TimeDelta = chrono::TimeDelta::minutes(10);
+
+/// Poll for due jobs and run them until the server stops
+pub fn spawn_scheduler_task(db: Arc<Database>) {
+    let worker = format!("{}-{}", std::process::id(), client::time::generate_id());
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = run_due_jobs(&db, &worker).await {
+                warn!("Scheduler poll failed: {}", e);
+            }
+        }
+    });
+}
+
+async fn run_due_jobs(db: &Database, worker: &str) -> Result<(), String> {
+    let now = chrono::Utc::now();
+
+    let abandoned = db
+        .fail_abandoned_jobs(&format_timestamp(now - ABANDONED_AFTER))
+        .await
+        .map_err(|e| e.to_string())?;
+    if abandoned > 0 {
+        warn!("⏰ Marked {} abandoned scheduled jobs as failed", abandoned);
+    }
+
+    let jobs = db
+        .claim_due_jobs(worker, &format_timestamp(now), BATCH_SIZE)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    for job in jobs {
+        let outcome = run_job(db, &job).await;
+        match &outcome {
+            Ok(()) => info!("⏰ Ran scheduled {:?} of {:?} {}", job.action, job.content_type, job.content_id),
+            Err(e) => warn!("Scheduled {:?} of {:?} {} failed: {}", job.action, job.content_type, job.content_id, e),
+        }
+
+        db.finish_scheduled_job(job.id, worker, outcome.err().as_deref())
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Carry out a job's action on its post, page or media file
+async fn run_job(db: &Database, job: &ScheduledContent) -> Result<(), String> {
+    job.validate()?;
+    let id = job.content_id;
+
+    let found = match (job.content_type, job.action) {
+        (ContentType::Post, ScheduledAction::Publish) => db.set_post_published(id, true).await.map_err(|e| e.to_string())?,
+        (ContentType::Post, ScheduledAction::Unpublish) => db.set_post_published(id, false).await.map_err(|e| e.to_string())?,
+        (ContentType::Post, ScheduledAction::Delete) => db.delete_post(id).await.map_err(|e| e.to_string())?,
+        (ContentType::Post, ScheduledAction::Update) => update_post(db, job).await?,
+        (ContentType::Page, ScheduledAction::Publish) => db.set_page_published(id, true).await.map_err(|e| e.to_string())?,
+        (ContentType::Page, ScheduledAction::Unpublish) => db.set_page_published(id, false).await.map_err(|e| e.to_string())?,
+        (ContentType::Page, ScheduledAction::Delete) => db.delete_page(id).await.map_err(|e| e.to_string())?,
+        (ContentType::Page, ScheduledAction::Update) => update_page(db, job).await?,
+        (ContentType::Media, ScheduledAction::Delete) => return delete_media(db, id).await,
+        (ContentType::Media, action) => return Err(format!("Media files can't be scheduled to {}", action.label().to_lowercase())),
+    };
+
+    if found {
+        Ok(())
+    } else {
+        Err(format!("{} {} not found", job.content_type.as_str(), id))
+    }
+}
+
+/// Save the post in the job's payload over the current one, keeping its id and creation time
+async fn update_post(db: &Database, job: &ScheduledContent) -> Result<bool, String> {
+    let update: Post = parse_payload(job)?;
+    let Some(current) = db.get_post_by_id(job.content_id).await.map_err(|e| e.to_string())? else {
+        return Ok(false);
+    };
+
+    let post = Post {
+        id: current.id,
+        created_at: current.created_at,
+        updated_at: client::time::now_iso8601(),
+        ..update
+    };
+    db.save_post(&post).await.map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/// Save the page in the job's payload over the current one, keeping its id and creation time
+async fn update_page(db: &Database, job: &ScheduledContent) -> Result<bool, String> {
+    let update: Page = parse_payload(job)?;
+    let Some(current) = db.get_page_by_id(job.content_id).await.map_err(|e| e.to_string())? else {
+        return Ok(false);
+    };
+
+    let page = Page {
+        id: current.id,
+        created_at: current.created_at,
+        updated_at: client::time::now_iso8601(),
+        ..update
+    };
+    db.save_page(&page).await.map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+fn parse_payload<T: serde::de::DeserializeOwned>(job: &ScheduledContent) -> Result<T, String> {
+    let payload = job.payload.as_deref().ok_or("An update needs the new content")?;
+    serde_json::from_str(payload).map_err(|e| format!("Invalid update content: {}", e))
+}
+
+async fn delete_media(db: &Database, id: u32) -> Result<(), String> {
+    let media = db
+        .get_media_by_id(id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("media {} not found", id))?;
+
+    let storage = api::storage::storage_from_env().map_err(|e| e.to_string())?;
+    storage.delete(&media.filename).await.map_err(|e| e.to_string())?;
+    db.delete_media(id).await.map_err(|e| e.to_string())
+}
+
+fn format_timestamp(time: chrono::DateTime<chrono::Utc>) -> String {
+    time.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
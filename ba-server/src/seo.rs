@@ -0,0 +1,124 @@
+//! `/sitemap.xml` and `/robots.txt`, generated from published posts and pages.
+
+use api::database::Database;
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use std::sync::Arc;
+use ui::extensions::{sitemap_file_entries, sitemap_files, sitemap_index_xml, sitemap_xml, SeoExtension, SitemapEntry, MAX_SITEMAP_URLS};
+
+#[derive(Clone)]
+struct SeoState {
+    db: Arc<Database>,
+    base_url: String,
+}
+
+/// Router serving `/robots.txt`, `/sitemap.xml` and, for sites with more than
+/// `MAX_SITEMAP_URLS` URLs, the `/sitemap/{n}.xml` files listed by the sitemap index.
+pub fn seo_router(db: Arc<Database>, base_url: String) -> Router {
+    Router::new()
+        .route("/robots.txt", get(robots_txt))
+        .route("/sitemap.xml", get(sitemap))
+        .route("/sitemap/{file}", get(sitemap_page))
+        .with_state(SeoState {
+            db,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+}
+
+async fn robots_txt(State(state): State<SeoState>) -> Response {
+    (
+        [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+        SeoExtension::new().generate_robots_txt(&state.base_url),
+    )
+        .into_response()
+}
+
+async fn sitemap(State(state): State<SeoState>) -> Response {
+    let entries = match sitemap_entries(&state).await {
+        Ok(entries) => entries,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    };
+
+    if entries.len() <= MAX_SITEMAP_URLS {
+        return xml_response(sitemap_xml(&entries));
+    }
+
+    xml_response(sitemap_index_xml(&sitemap_files(&entries, &state.base_url)))
+}
+
+async fn sitemap_page(State(state): State<SeoState>, Path(file): Path<String>) -> Response {
+    let Some(number) = file.strip_suffix(".xml").and_then(|number| number.parse::<usize>().ok()) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let entries = match sitemap_entries(&state).await {
+        Ok(entries) => entries,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    };
+
+    match sitemap_file_entries(&entries, number) {
+        Some(chunk) => xml_response(sitemap_xml(chunk)),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+fn xml_response(xml: String) -> Response {
+    ([(header::CONTENT_TYPE, "application/xml; charset=utf-8")], xml).into_response()
+}
+
+/// Home page, published posts (newest first) and published pages, each at its language's URL
+async fn sitemap_entries(state: &SeoState) -> Result<Vec<SitemapEntry>, String> {
+    let posts = state.db.get_published_posts().await.map_err(|e| e.to_string())?;
+    let pages = state.db.get_published_pages().await.map_err(|e| e.to_string())?;
+    let default_language = state.db.get_default_language().await.map_err(|e| e.to_string())?;
+    let url = |language: &str, path: String| format!("{}{}", state.base_url, client::localized_path(language, &default_language, &path));
+
+    let newest = posts.iter().map(|post| lastmod(&post.updated_at)).max();
+    let mut entries = vec![SitemapEntry {
+        url: format!("{}/", state.base_url),
+        last_modified: newest.unwrap_or_else(client::time::today_date),
+        change_frequency: "daily".to_string(),
+        priority: 1.0,
+    }];
+
+    entries.extend(posts.iter().map(|post| SitemapEntry {
+        url: url(&post.language, format!("/post/{}", encode_path_segment(&post.slug))),
+        last_modified: lastmod(&post.updated_at),
+        change_frequency: "weekly".to_string(),
+        priority: 0.8,
+    }));
+
+    entries.extend(pages.iter().map(|page| SitemapEntry {
+        url: url(&page.language, format!("/page/{}", encode_path_segment(&page.slug))),
+        last_modified: lastmod(&page.updated_at),
+        change_frequency: "monthly".to_string(),
+        priority: 0.6,
+    }));
+
+    Ok(entries)
+}
+
+/// W3C date for `<lastmod>`; timestamps are stored as RFC 3339 or SQLite `datetime()` text
+fn lastmod(updated_at: &str) -> String {
+    match updated_at.get(..10) {
+        Some(date) if chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok() => date.to_string(),
+        _ => client::time::today_date(),
+    }
+}
+
+/// Percent-encode a slug for use as a URL path segment
+fn encode_path_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
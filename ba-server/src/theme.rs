@@ -0,0 +1,85 @@
+//! Serving the active theme's stylesheet and theme package downloads.
+
+use api::{database::Database, theme_package};
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use client::ColorScheme;
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+struct StylesheetQuery {
+    v: Option<String>,
+    /// Reader's color scheme, added to the URL by `api::get_active_theme_stylesheet`
+    scheme: Option<String>,
+}
+
+/// Router serving `/theme/active.css` and `/theme/{id}/export.zip`.
+///
+/// `App` links the stylesheet with a `?v=` content hash. When the hash matches the
+/// active theme the response is cached forever; any other request is revalidated, so
+/// activating or editing a theme takes effect on the next page load.
+pub fn theme_router(db: Arc<Database>) -> Router {
+    Router::new()
+        .route("/theme/active.css", get(active_stylesheet))
+        .route("/theme/{id}/export.zip", get(export_theme))
+        .with_state(db)
+}
+
+async fn active_stylesheet(State(db): State<Arc<Database>>, Query(query): Query<StylesheetQuery>) -> Response {
+    let theme = match db.get_active_theme().await {
+        Ok(theme) => theme,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    };
+
+    // The version hash covers the theme itself; the scheme is a separate cache key in the URL
+    let scheme = query.scheme.as_deref().and_then(ColorScheme::parse);
+    let (version, css) = match theme {
+        Some(theme) => (api::theme_css_version(&theme.stylesheet()), theme.stylesheet_for(scheme)),
+        None => (api::theme_css_version(""), String::new()),
+    };
+    let cache_control = if query.v.as_deref() == Some(version.as_str()) {
+        "public, max-age=31536000, immutable"
+    } else {
+        "no-cache"
+    };
+
+    (
+        [
+            (header::CONTENT_TYPE, "text/css; charset=utf-8"),
+            (header::CACHE_CONTROL, cache_control),
+        ],
+        css,
+    )
+        .into_response()
+}
+
+async fn export_theme(State(db): State<Arc<Database>>, Path(id): Path<u32>) -> Response {
+    let theme = match db.get_theme_by_id(id).await {
+        Ok(Some(theme)) => theme,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    };
+
+    let package = match theme_package::export_package(&theme) {
+        Ok(package) => package,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    };
+
+    (
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", theme_package::package_file_name(&theme)),
+            ),
+        ],
+        package,
+    )
+        .into_response()
+}
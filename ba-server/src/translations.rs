@@ -0,0 +1,54 @@
+//! Downloading translation files for translators' CAT tools.
+
+use api::{database::Database, translation_files};
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use client::TranslationFileFormat;
+use std::sync::Arc;
+
+/// Router serving `/i18n/{language}/{namespace}.po` and `/i18n/{language}/{namespace}.xlf`:
+/// every message of the namespace in the default language, with its translation into
+/// `language` where there is one.
+pub fn translations_router(db: Arc<Database>) -> Router {
+    Router::new()
+        .route("/i18n/{language}/{file}", get(export_translations))
+        .with_state(db)
+}
+
+async fn export_translations(State(db): State<Arc<Database>>, Path((language, file)): Path<(String, String)>) -> Response {
+    let (Some(format), Some((namespace, _))) = (TranslationFileFormat::from_file_name(&file), file.rsplit_once('.')) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    match db.get_languages().await {
+        Ok(languages) if languages.iter().any(|known| known.code == language) => {},
+        Ok(_) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+    let source_language = match db.get_default_language().await {
+        Ok(code) => code,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    };
+    let messages = match db.get_translations(&[&language, &source_language]).await {
+        Ok(messages) => messages,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    };
+
+    let body = translation_files::export(format, namespace, &source_language, &language, &messages, &messages);
+    (
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", translation_files::file_name(format, namespace, &language)),
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
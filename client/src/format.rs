@@ -0,0 +1,417 @@
+//! Locale-aware formatting of dates, relative times, numbers and file sizes.
+//!
+//! Everything is computed here rather than with `chrono` or `Intl`, so a page rendered
+//! on the server shows exactly what the browser shows after hydration.
+
+use crate::i18n::{plural_category, PluralCategory};
+use crate::time::{date_to_days, days_to_date, timestamp_seconds};
+
+/// Daylight saving rules, for the time zones that have them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DaylightSaving {
+    /// Last Sunday of March to last Sunday of October, switching at 01:00 UTC
+    Europe,
+    /// Second Sunday of March to first Sunday of November, switching at 02:00 local time
+    NorthAmerica,
+}
+
+/// Time zones that can be picked for the site: name, standard offset from UTC in minutes
+/// and daylight saving rule
+const TIME_ZONES: &[(&str, i32, Option<DaylightSaving>)] = &[
+    ("UTC", 0, None),
+    ("Europe/London", 0, Some(DaylightSaving::Europe)),
+    ("Europe/Dublin", 0, Some(DaylightSaving::Europe)),
+    ("Europe/Lisbon", 0, Some(DaylightSaving::Europe)),
+    ("Europe/Madrid", 60, Some(DaylightSaving::Europe)),
+    ("Europe/Paris", 60, Some(DaylightSaving::Europe)),
+    ("Europe/Brussels", 60, Some(DaylightSaving::Europe)),
+    ("Europe/Amsterdam", 60, Some(DaylightSaving::Europe)),
+    ("Europe/Berlin", 60, Some(DaylightSaving::Europe)),
+    ("Europe/Rome", 60, Some(DaylightSaving::Europe)),
+    ("Europe/Stockholm", 60, Some(DaylightSaving::Europe)),
+    ("Europe/Warsaw", 60, Some(DaylightSaving::Europe)),
+    ("Europe/Athens", 120, Some(DaylightSaving::Europe)),
+    ("Europe/Helsinki", 120, Some(DaylightSaving::Europe)),
+    ("Europe/Kyiv", 120, Some(DaylightSaving::Europe)),
+    ("Europe/Istanbul", 180, None),
+    ("Europe/Moscow", 180, None),
+    ("Africa/Lagos", 60, None),
+    ("Africa/Johannesburg", 120, None),
+    ("Africa/Nairobi", 180, None),
+    ("Asia/Riyadh", 180, None),
+    ("Asia/Dubai", 240, None),
+    ("Asia/Karachi", 300, None),
+    ("Asia/Kolkata", 330, None),
+    ("Asia/Bangkok", 420, None),
+    ("Asia/Shanghai", 480, None),
+    ("Asia/Singapore", 480, None),
+    ("Asia/Tokyo", 540, None),
+    ("Asia/Seoul", 540, None),
+    ("Australia/Brisbane", 600, None),
+    ("Pacific/Honolulu", -600, None),
+    ("America/Anchorage", -540, Some(DaylightSaving::NorthAmerica)),
+    ("America/Los_Angeles", -480, Some(DaylightSaving::NorthAmerica)),
+    ("America/Vancouver", -480, Some(DaylightSaving::NorthAmerica)),
+    ("America/Phoenix", -420, None),
+    ("America/Denver", -420, Some(DaylightSaving::NorthAmerica)),
+    ("America/Chicago", -360, Some(DaylightSaving::NorthAmerica)),
+    ("America/Mexico_City", -360, None),
+    ("America/New_York", -300, Some(DaylightSaving::NorthAmerica)),
+    ("America/Toronto", -300, Some(DaylightSaving::NorthAmerica)),
+    ("America/Bogota", -300, None),
+    ("America/Halifax", -240, Some(DaylightSaving::NorthAmerica)),
+    ("America/Sao_Paulo", -180, None),
+    ("America/Argentina/Buenos_Aires", -180, None),
+];
+
+/// Time zone dates and times are shown in: one of the named zones in [`TimeZone::names`],
+/// or a fixed offset like `UTC+05:30`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeZone {
+    name: String,
+    /// Standard offset from UTC in minutes
+    offset: i32,
+    dst: Option<DaylightSaving>,
+}
+
+impl Default for TimeZone {
+    fn default() -> Self {
+        Self::utc()
+    }
+}
+
+impl TimeZone {
+    pub fn utc() -> Self {
+        Self { name: "UTC".to_string(), offset: 0, dst: None }
+    }
+
+    /// Names of the time zones [`TimeZone::parse`] knows besides fixed offsets
+    pub fn names() -> impl Iterator<Item = &'static str> {
+        TIME_ZONES.iter().map(|(name, _, _)| *name)
+    }
+
+    /// A named time zone, or a fixed offset written `+02:00`, `UTC-3` or `UTC+05:30`
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if let Some((name, offset, dst)) = TIME_ZONES.iter().find(|(name, _, _)| name.eq_ignore_ascii_case(value)) {
+            return Some(Self { name: name.to_string(), offset: *offset, dst: *dst });
+        }
+        if value.is_empty() || value.eq_ignore_ascii_case("GMT") || value == "Z" {
+            return Some(Self::utc());
+        }
+
+        let upper = value.to_ascii_uppercase();
+        let offset = upper.strip_prefix("UTC").or_else(|| upper.strip_prefix("GMT")).unwrap_or(&upper);
+        let (sign, offset) = match offset.split_at_checked(1)? {
+            ("+", offset) => (1, offset),
+            ("-", offset) => (-1, offset),
+            _ => return None,
+        };
+        let (hours, minutes) = offset.split_once(':').unwrap_or((offset, "0"));
+        let (hours, minutes): (i32, i32) = (hours.parse().ok()?, minutes.parse().ok()?);
+        if hours > 14 || minutes > 59 {
+            return None;
+        }
+
+        let offset = sign * (hours * 60 + minutes);
+        match offset {
+            0 => Some(Self::utc()),
+            _ => Some(Self { name: format!("UTC{}{:02}:{:02}", if sign < 0 { '-' } else { '+' }, hours, minutes), offset, dst: None }),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Offset from UTC in minutes at `seconds` since 1970-01-01T00:00:00Z
+    pub fn offset_at(&self, seconds: i64) -> i32 {
+        let year: i64 = days_to_date(seconds.div_euclid(86_400))[..4].parse().unwrap_or(1970);
+        let daylight = match self.dst {
+            None => false,
+            Some(DaylightSaving::Europe) => {
+                let start = last_sunday(year, 3) * 86_400 + 3_600;
+                let end = last_sunday(year, 10) * 86_400 + 3_600;
+                (start..end).contains(&seconds)
+            },
+            Some(DaylightSaving::NorthAmerica) => {
+                let offset = self.offset as i64 * 60;
+                let start = nth_sunday(year, 3, 2) * 86_400 + 7_200 - offset;
+                let end = nth_sunday(year, 11, 1) * 86_400 + 7_200 - offset - 3_600;
+                (start..end).contains(&seconds)
+            },
+        };
+        self.offset + if daylight { 60 } else { 0 }
+    }
+}
+
+/// 0 for Sunday through 6 for Saturday
+fn weekday(days: i64) -> i64 {
+    // 1970-01-01 was a Thursday
+    (days + 4).rem_euclid(7)
+}
+
+/// Day number of the `n`th Sunday of a month
+fn nth_sunday(year: i64, month: i64, n: i64) -> i64 {
+    let first = date_to_days(&format!("{:04}-{:02}-01", year, month)).unwrap_or_default();
+    first + (7 - weekday(first)) % 7 + 7 * (n - 1)
+}
+
+/// Day number of the last Sunday of a month
+fn last_sunday(year: i64, month: i64) -> i64 {
+    let (year, month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    let last = date_to_days(&format!("{:04}-{:02}-01", year, month)).unwrap_or_default() - 1;
+    last - weekday(last)
+}
+
+/// How a language writes dates and numbers
+struct LocaleData {
+    months: [&'static str; 12],
+    /// With `{day}`, `{month}` and `{year}`
+    date: &'static str,
+    /// Between the date and the time
+    at: &'static str,
+    twelve_hour: bool,
+    decimal: &'static str,
+    group: &'static str,
+    /// Integer digits needed before thousands are grouped
+    min_grouping: usize,
+    /// With `{}` for the amount of time
+    past: &'static str,
+    future: &'static str,
+    now: &'static str,
+    /// Singular and plural of second, minute, hour, day, week, month and year
+    units: [(&'static str, &'static str); 7],
+    bytes: [&'static str; 5],
+}
+
+const ENGLISH: LocaleData = LocaleData {
+    months: ["January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November", "December"],
+    date: "{month} {day}, {year}",
+    at: ", ",
+    twelve_hour: true,
+    decimal: ".",
+    group: ",",
+    min_grouping: 4,
+    past: "{} ago",
+    future: "in {}",
+    now: "just now",
+    units: [("second", "seconds"), ("minute", "minutes"), ("hour", "hours"), ("day", "days"), ("week", "weeks"), ("month", "months"), ("year", "years")],
+    bytes: ["B", "KB", "MB", "GB", "TB"],
+};
+
+const SPANISH: LocaleData = LocaleData {
+    months: ["enero", "febrero", "marzo", "abril", "mayo", "junio", "julio", "agosto", "septiembre", "octubre", "noviembre", "diciembre"],
+    date: "{day} de {month} de {year}",
+    at: ", ",
+    twelve_hour: false,
+    decimal: ",",
+    group: ".",
+    min_grouping: 5,
+    past: "hace {}",
+    future: "dentro de {}",
+    now: "ahora",
+    units: [("segundo", "segundos"), ("minuto", "minutos"), ("hora", "horas"), ("día", "días"), ("semana", "semanas"), ("mes", "meses"), ("año", "años")],
+    bytes: ["B", "KB", "MB", "GB", "TB"],
+};
+
+const FRENCH: LocaleData = LocaleData {
+    months: ["janvier", "février", "mars", "avril", "mai", "juin", "juillet", "août", "septembre", "octobre", "novembre", "décembre"],
+    date: "{day} {month} {year}",
+    at: " à ",
+    twelve_hour: false,
+    decimal: ",",
+    group: "\u{202f}",
+    min_grouping: 4,
+    past: "il y a {}",
+    future: "dans {}",
+    now: "à l’instant",
+    units: [("seconde", "secondes"), ("minute", "minutes"), ("heure", "heures"), ("jour", "jours"), ("semaine", "semaines"), ("mois", "mois"), ("an", "ans")],
+    bytes: ["o", "ko", "Mo", "Go", "To"],
+};
+
+const GERMAN: LocaleData = LocaleData {
+    months: ["Januar", "Februar", "März", "April", "Mai", "Juni", "Juli", "August", "September", "Oktober", "November", "Dezember"],
+    date: "{day}. {month} {year}",
+    at: ", ",
+    twelve_hour: false,
+    decimal: ",",
+    group: ".",
+    min_grouping: 4,
+    past: "vor {}",
+    future: "in {}",
+    now: "gerade eben",
+    // Dative, as both phrases take it
+    units: [("Sekunde", "Sekunden"), ("Minute", "Minuten"), ("Stunde", "Stunden"), ("Tag", "Tagen"), ("Woche", "Wochen"), ("Monat", "Monaten"), ("Jahr", "Jahren")],
+    bytes: ["B", "KB", "MB", "GB", "TB"],
+};
+
+/// Formats values for readers of one language in one time zone. Languages without
+/// formatting rules here get the English ones; values that can't be parsed come back as given.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Formatter {
+    language: String,
+    time_zone: TimeZone,
+}
+
+impl Default for Formatter {
+    fn default() -> Self {
+        Self::new(crate::i18n::DEFAULT_LANGUAGE, TimeZone::utc())
+    }
+}
+
+impl Formatter {
+    pub fn new(language: &str, time_zone: TimeZone) -> Self {
+        Self { language: language.to_string(), time_zone }
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    pub fn time_zone(&self) -> &TimeZone {
+        &self.time_zone
+    }
+
+    fn locale(&self) -> &'static LocaleData {
+        let language = self.language.split(['-', '_']).next().unwrap_or_default().to_lowercase();
+        match language.as_str() {
+            "es" => &SPANISH,
+            "fr" => &FRENCH,
+            "de" => &GERMAN,
+            _ => &ENGLISH,
+        }
+    }
+
+    /// Local date and minutes past midnight of a timestamp. Bare `YYYY-MM-DD` dates are
+    /// already local.
+    fn local(&self, timestamp: &str) -> Option<(i64, i64)> {
+        let seconds = timestamp_seconds(timestamp)?;
+        let local = match timestamp.trim().len() {
+            10 => seconds,
+            _ => seconds + self.time_zone.offset_at(seconds) as i64 * 60,
+        };
+        Some((local.div_euclid(86_400), local.rem_euclid(86_400) / 60))
+    }
+
+    fn format_date(&self, days: i64) -> String {
+        let date = days_to_date(days);
+        let (year, month, day) = (&date[..date.len() - 6], &date[date.len() - 5..date.len() - 3], &date[date.len() - 2..]);
+        let month: usize = month.parse().unwrap_or(1);
+        self.locale()
+            .date
+            .replace("{day}", day.trim_start_matches('0'))
+            .replace("{month}", self.locale().months[month - 1])
+            .replace("{year}", year)
+    }
+
+    fn format_time(&self, minutes: i64) -> String {
+        let (hour, minute) = (minutes / 60, minutes % 60);
+        if self.locale().twelve_hour {
+            let period = if hour < 12 { "AM" } else { "PM" };
+            format!("{}:{:02}\u{a0}{}", (hour + 11) % 12 + 1, minute, period)
+        } else {
+            format!("{:02}:{:02}", hour, minute)
+        }
+    }
+
+    /// `January 5, 2024`
+    pub fn date(&self, timestamp: &str) -> String {
+        match self.local(timestamp) {
+            Some((days, _)) => self.format_date(days),
+            None => timestamp.to_string(),
+        }
+    }
+
+    /// `3:04 PM`
+    pub fn time(&self, timestamp: &str) -> String {
+        match self.local(timestamp) {
+            Some((_, minutes)) => self.format_time(minutes),
+            None => timestamp.to_string(),
+        }
+    }
+
+    /// `January 5, 2024, 3:04 PM`
+    pub fn datetime(&self, timestamp: &str) -> String {
+        match self.local(timestamp) {
+            Some((days, minutes)) => format!("{}{}{}", self.format_date(days), self.locale().at, self.format_time(minutes)),
+            None => timestamp.to_string(),
+        }
+    }
+
+    /// How long before or after `now` a timestamp is: `3 days ago`, `in 2 hours`
+    pub fn relative(&self, timestamp: &str, now: &str) -> String {
+        let (Some(seconds), Some(now)) = (timestamp_seconds(timestamp), timestamp_seconds(now)) else {
+            return timestamp.to_string();
+        };
+        let locale = self.locale();
+        let difference = seconds - now;
+        let elapsed = difference.unsigned_abs() as f64;
+
+        let days = elapsed / 86_400.0;
+        let (amount, unit) = if elapsed < 45.0 {
+            return locale.now.to_string();
+        } else if elapsed < 45.0 * 60.0 {
+            (elapsed / 60.0, 1)
+        } else if elapsed < 22.0 * 3_600.0 {
+            (elapsed / 3_600.0, 2)
+        } else if days < 6.5 {
+            (days, 3)
+        } else if days < 26.0 {
+            (days / 7.0, 4)
+        } else if days < 320.0 {
+            (days / 30.44, 5)
+        } else {
+            (days / 365.25, 6)
+        };
+        let amount = amount.round().max(1.0);
+
+        let (one, other) = locale.units[unit];
+        let unit = match plural_category(&self.language, amount) {
+            PluralCategory::One => one,
+            _ => other,
+        };
+        let span = format!("{} {}", self.number(amount, 0), unit);
+        let phrase = if difference < 0 { locale.past } else { locale.future };
+        phrase.replace("{}", &span)
+    }
+
+    /// `12,345.68` with at most `max_fraction_digits` digits after the decimal separator
+    pub fn number(&self, value: f64, max_fraction_digits: usize) -> String {
+        let locale = self.locale();
+        let formatted = format!("{:.*}", max_fraction_digits, value.abs());
+        let (integer, fraction) = formatted.split_once('.').unwrap_or((&formatted, ""));
+        let fraction = fraction.trim_end_matches('0');
+
+        let mut number = String::new();
+        if value < 0.0 && formatted.chars().any(|c| c.is_ascii_digit() && c != '0') {
+            number.push('-');
+        }
+        for (i, digit) in integer.chars().enumerate() {
+            let remaining = integer.len() - i;
+            if i > 0 && remaining % 3 == 0 && integer.len() >= locale.min_grouping {
+                number.push_str(locale.group);
+            }
+            number.push(digit);
+        }
+        if !fraction.is_empty() {
+            number.push_str(locale.decimal);
+            number.push_str(fraction);
+        }
+        number
+    }
+
+    /// `15.4 KB`, in decimal units
+    pub fn file_size(&self, bytes: u64) -> String {
+        let units = self.locale().bytes;
+        let mut value = bytes as f64;
+        let mut unit = 0;
+        // Compared after rounding, so 999,960 bytes is 1 MB rather than 1,000 KB
+        while value >= 999.95 && unit < units.len() - 1 {
+            value /= 1000.0;
+            unit += 1;
+        }
+        let digits = if unit == 0 { 0 } else { 1 };
+        format!("{} {}", self.number(value, digits), units[unit])
+    }
+}
@@ -0,0 +1,233 @@
+//! Metadata at the top of a markdown document, as written by authors in their own
+//! editors: YAML between `---` lines or TOML between `+++` lines.
+//!
+//! ```text
+//! ---
+//! title: Hello
+//! tags: [rust, cms]
+//! draft: true
+//! ---
+//! # Hello
+//! ```
+
+use serde::{Deserialize, Deserializer, Serialize};
+use crate::time::normalize_timestamp;
+use crate::types::{Page, Post};
+
+/// Syntax of a front matter block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FrontMatterFormat {
+    /// Between `---` lines
+    Yaml,
+    /// Between `+++` lines
+    Toml,
+}
+
+impl FrontMatterFormat {
+    fn delimiter(&self) -> &'static str {
+        match self {
+            Self::Yaml => "---",
+            Self::Toml => "+++",
+        }
+    }
+}
+
+/// A front matter problem, with the line of the document it's on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrontMatterError {
+    /// 1-based line in the whole document
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for FrontMatterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for FrontMatterError {}
+
+/// Parsed front matter. Keys other than these are ignored, so files written for other
+/// static site generators import cleanly.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FrontMatter {
+    pub title: Option<String>,
+    pub slug: Option<String>,
+    /// `YYYY-MM-DD` or a UTC timestamp
+    #[serde(deserialize_with = "date_value")]
+    pub date: Option<String>,
+    /// A list, or one comma-separated string
+    #[serde(deserialize_with = "tag_list")]
+    pub tags: Vec<String>,
+    #[serde(alias = "summary")]
+    pub description: Option<String>,
+    pub draft: Option<bool>,
+    pub template: Option<String>,
+    #[serde(alias = "cover", alias = "image", alias = "cover-image", alias = "coverImage")]
+    pub cover_image: Option<String>,
+}
+
+/// TOML has a date type of its own; YAML dates are plain strings
+fn date_value<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Date {
+        Text(String),
+        Toml(toml::value::Datetime),
+    }
+
+    Ok(Option::<Date>::deserialize(deserializer)?.map(|date| match date {
+        Date::Text(text) => text,
+        Date::Toml(date) => date.to_string(),
+    }))
+}
+
+fn tag_list<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Tags {
+        List(Vec<String>),
+        Text(String),
+    }
+
+    let tags = match Option::<Tags>::deserialize(deserializer)? {
+        Some(Tags::List(tags)) => tags,
+        Some(Tags::Text(text)) => text.split(',').map(str::to_string).collect(),
+        None => Vec::new(),
+    };
+    Ok(tags.into_iter().map(|tag| tag.trim().to_string()).filter(|tag| !tag.is_empty()).collect())
+}
+
+/// A front matter block found at the start of a document
+struct Block<'a> {
+    format: FrontMatterFormat,
+    /// Starts on the document's second line
+    text: &'a str,
+    body: &'a str,
+}
+
+/// The front matter block of `document` and what follows it. `Err` when the block is
+/// opened but never closed.
+fn split(document: &str) -> Result<Option<Block<'_>>, FrontMatterError> {
+    let document = document.strip_prefix('\u{feff}').unwrap_or(document);
+    let (first, rest) = document.split_once('\n').unwrap_or((document, ""));
+    let format = match first.trim_end() {
+        "---" => FrontMatterFormat::Yaml,
+        "+++" => FrontMatterFormat::Toml,
+        _ => return Ok(None),
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        // YAML documents may also end with `...`
+        if trimmed == format.delimiter() || (format == FrontMatterFormat::Yaml && trimmed == "...") {
+            return Ok(Some(Block { format, text: &rest[..offset], body: &rest[offset + line.len()..] }));
+        }
+        offset += line.len();
+    }
+
+    Err(FrontMatterError { line: 1, message: format!("front matter is never closed with \"{}\"", format.delimiter()) })
+}
+
+/// `document` without its front matter block, if it has a complete one
+pub fn strip_front_matter(document: &str) -> &str {
+    match split(document) {
+        Ok(Some(block)) => block.body,
+        _ => document,
+    }
+}
+
+impl FrontMatter {
+    /// The front matter of a markdown document, if any, and the markdown after it
+    pub fn parse(document: &str) -> Result<(Option<Self>, &str), FrontMatterError> {
+        let Some(block) = split(document)? else {
+            return Ok((None, document));
+        };
+
+        let at_line = |line: usize, message: String| FrontMatterError { line: line + 1, message };
+        let front_matter: Self = match block.format {
+            // An empty YAML block is `null` rather than an empty mapping
+            FrontMatterFormat::Yaml if block.text.trim().is_empty() => Self::default(),
+            FrontMatterFormat::Yaml => serde_norway::from_str(block.text).map_err(|e| {
+                let line = e.location().map_or(1, |location| location.line());
+                let message = e.to_string();
+                let message = message.split_once(" at line ").map_or(message.as_str(), |(message, _)| message);
+                at_line(line, message.to_string())
+            })?,
+            FrontMatterFormat::Toml => toml::from_str(block.text).map_err(|e| {
+                let line = e.span().map_or(1, |span| block.text[..span.start].matches('\n').count() + 1);
+                at_line(line, e.message().to_string())
+            })?,
+        };
+
+        front_matter.validate().map_err(|(key, message)| at_line(key_line(block.text, key), message))?;
+        Ok((Some(front_matter), block.body))
+    }
+
+    /// Checks beyond the types, as the offending key and what's wrong with it
+    fn validate(&self) -> Result<(), (&'static str, String)> {
+        if self.title.as_ref().is_some_and(|title| title.trim().is_empty()) {
+            return Err(("title", "title can't be empty".to_string()));
+        }
+        if let Some(slug) = &self.slug {
+            if slug.is_empty() || !slug.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+                return Err(("slug", format!("slug \"{}\" may only contain lowercase letters, digits and hyphens", slug)));
+            }
+        }
+        if let Some(date) = &self.date {
+            if self.created_at().is_none() {
+                return Err(("date", format!("\"{}\" is not a YYYY-MM-DD date or a UTC timestamp", date)));
+            }
+        }
+        Ok(())
+    }
+
+    /// The date as a `YYYY-MM-DDTHH:MM:SSZ` timestamp
+    fn created_at(&self) -> Option<String> {
+        let date = self.date.as_deref()?.trim();
+        match date.len() {
+            10 => normalize_timestamp(&format!("{}T00:00:00Z", date)),
+            _ => normalize_timestamp(date),
+        }
+    }
+
+    /// Copy the fields that are set onto a post. The date only sticks for new posts, as
+    /// saving keeps a stored post's creation time.
+    pub fn apply_to_post(&self, post: &mut Post) {
+        if let Some(title) = &self.title { post.title = title.clone(); }
+        if let Some(slug) = &self.slug { post.slug = slug.clone(); }
+        if let Some(created_at) = self.created_at() { post.created_at = created_at; }
+        if !self.tags.is_empty() { post.meta_keywords = Some(self.tags.join(", ")); }
+        if let Some(description) = &self.description { post.meta_description = Some(description.clone()); }
+        if let Some(draft) = self.draft { post.published = !draft; }
+        if let Some(template) = &self.template { post.template = Some(template.clone()); }
+        if let Some(cover_image) = &self.cover_image { post.cover_image = Some(cover_image.clone()); }
+    }
+
+    /// Copy the fields that are set onto a page, as for [`FrontMatter::apply_to_post`]
+    pub fn apply_to_page(&self, page: &mut Page) {
+        if let Some(title) = &self.title { page.title = title.clone(); }
+        if let Some(slug) = &self.slug { page.slug = slug.clone(); }
+        if let Some(created_at) = self.created_at() { page.created_at = created_at; }
+        if !self.tags.is_empty() { page.meta_keywords = Some(self.tags.join(", ")); }
+        if let Some(description) = &self.description { page.meta_description = Some(description.clone()); }
+        if let Some(draft) = self.draft { page.published = !draft; }
+        if let Some(template) = &self.template { page.template = template.clone(); }
+        if let Some(cover_image) = &self.cover_image { page.cover_image = Some(cover_image.clone()); }
+    }
+}
+
+/// Line of `block` where `key` is set, counting from 1; the first line if it can't be found
+fn key_line(block: &str, key: &str) -> usize {
+    block
+        .lines()
+        .position(|line| {
+            line.trim_start()
+                .strip_prefix(key)
+                .is_some_and(|rest| rest.trim_start().starts_with([':', '=']))
+        })
+        .map_or(1, |index| index + 1)
+}
@@ -0,0 +1,323 @@
+//! Allow-list sanitizing of the raw HTML markdown may contain. HTML is split into tokens
+//! here; the markdown renderer turns the tags a policy keeps into elements and drops the rest.
+
+use serde::{Deserialize, Serialize};
+
+/// Every tag raw HTML can be rendered as; a policy picks from these
+pub const HTML_TAGS: &[&str] = &[
+    "a", "abbr", "b", "blockquote", "br", "caption", "cite", "code", "dd", "del", "details", "dfn", "div",
+    "dl", "dt", "em", "figcaption", "figure", "h1", "h2", "h3", "h4", "h5", "h6", "hr", "i", "img", "ins",
+    "kbd", "li", "mark", "ol", "p", "pre", "q", "s", "samp", "small", "span", "strong", "sub", "summary",
+    "sup", "table", "tbody", "td", "tfoot", "th", "thead", "time", "tr", "u", "ul", "var", "wbr",
+];
+
+/// Every attribute raw HTML can keep. Event handlers and `style` are never allowed.
+pub const HTML_ATTRIBUTES: &[&str] = &[
+    "abbr", "alt", "cite", "class", "colspan", "datetime", "dir", "height", "href", "id", "lang", "loading",
+    "open", "reversed", "rowspan", "src", "start", "title", "width",
+];
+
+/// Tags without content or an end tag
+pub const VOID_TAGS: &[&str] = &["br", "hr", "img", "wbr"];
+
+/// Tags dropped together with their content, rather than replaced by it
+pub const DROPPED_TAGS: &[&str] = &[
+    "embed", "iframe", "math", "noscript", "object", "script", "style", "svg", "template", "textarea", "title",
+];
+
+const URL_ATTRIBUTES: &[&str] = &["cite", "href", "src"];
+
+/// Which raw HTML markdown may contain. Anything else is stripped: disallowed tags leave
+/// their content behind, disallowed attributes are dropped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HtmlPolicy {
+    /// From [`HTML_TAGS`]
+    pub tags: Vec<String>,
+    /// From [`HTML_ATTRIBUTES`], allowed on any of the tags
+    pub attributes: Vec<String>,
+    /// Mark links `rel="nofollow ugc"`, for content from visitors
+    #[serde(default)]
+    pub nofollow: bool,
+}
+
+impl Default for HtmlPolicy {
+    fn default() -> Self {
+        Self::posts()
+    }
+}
+
+impl HtmlPolicy {
+    /// Everything but `id`, which could clash with heading ids
+    pub fn posts() -> Self {
+        Self {
+            tags: HTML_TAGS.iter().map(|tag| tag.to_string()).collect(),
+            attributes: HTML_ATTRIBUTES.iter().filter(|name| **name != "id").map(|name| name.to_string()).collect(),
+            nofollow: false,
+        }
+    }
+
+    /// Inline formatting, links and lists only
+    pub fn comments() -> Self {
+        let tags = [
+            "a", "abbr", "b", "blockquote", "br", "code", "del", "em", "i", "ins", "kbd", "li", "mark", "ol", "p",
+            "pre", "q", "s", "strong", "sub", "sup", "ul",
+        ];
+        Self {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            attributes: ["dir", "href", "lang", "title"].iter().map(|name| name.to_string()).collect(),
+            nofollow: true,
+        }
+    }
+
+    /// Tags and attributes that can't be rendered, so the policy can't be saved
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(tag) = self.tags.iter().find(|tag| !HTML_TAGS.contains(&tag.as_str())) {
+            return Err(format!("<{}> can't be allowed", tag));
+        }
+        if let Some(name) = self.attributes.iter().find(|name| !HTML_ATTRIBUTES.contains(&name.as_str())) {
+            return Err(format!("The {} attribute can't be allowed", name));
+        }
+        Ok(())
+    }
+
+    /// The tag as the policy lets it through, with names from [`HTML_TAGS`] and
+    /// [`HTML_ATTRIBUTES`]; `None` if the tag isn't allowed
+    pub fn sanitize(&self, name: &str, attributes: &[(String, String)]) -> Option<SanitizedTag> {
+        let name = *HTML_TAGS.iter().find(|tag| **tag == name)?;
+        if !self.tags.iter().any(|tag| tag == name) {
+            return None;
+        }
+
+        let mut kept: Vec<(&'static str, String)> = Vec::new();
+        for (attribute, value) in attributes {
+            let Some(attribute) = HTML_ATTRIBUTES.iter().find(|allowed| **allowed == attribute) else { continue };
+            if !self.attributes.iter().any(|allowed| allowed == attribute)
+                || (URL_ATTRIBUTES.contains(attribute) && !is_safe_url(value))
+                || kept.iter().any(|(name, _)| name == attribute)
+            {
+                continue;
+            }
+            kept.push((attribute, value.clone()));
+        }
+        if name == "a" && self.nofollow && kept.iter().any(|(name, _)| *name == "href") {
+            kept.push(("rel", "nofollow ugc noopener".to_string()));
+        }
+
+        Some(SanitizedTag { name, attributes: kept })
+    }
+}
+
+/// A tag that passed a policy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SanitizedTag {
+    pub name: &'static str,
+    pub attributes: Vec<(&'static str, String)>,
+}
+
+/// Raw HTML allowed in markdown across the site
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HtmlSettings {
+    /// Posts and pages, written by editors
+    pub posts: HtmlPolicy,
+    /// Comments, written by visitors
+    #[serde(default = "HtmlPolicy::comments")]
+    pub comments: HtmlPolicy,
+}
+
+impl HtmlSettings {
+    pub fn new() -> Self {
+        Self { posts: HtmlPolicy::posts(), comments: HtmlPolicy::comments() }
+    }
+}
+
+/// Whether a link or image URL is relative or uses `http`, `https` or `mailto`. Checked after
+/// decoding, and ignoring the whitespace and control characters browsers skip, so
+/// `jav&#x61;script:` and `java\tscript:` are caught.
+pub fn is_safe_url(url: &str) -> bool {
+    let url: String = url.chars().filter(|c| !c.is_ascii_whitespace() && !c.is_control()).collect();
+    let url = url.to_ascii_lowercase();
+    match url.find([':', '/', '?', '#']) {
+        Some(index) if url[index..].starts_with(':') => matches!(&url[..index], "http" | "https" | "mailto"),
+        _ => true,
+    }
+}
+
+/// One piece of raw HTML
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HtmlToken {
+    /// Lowercase name, attributes with decoded values
+    Start { name: String, attributes: Vec<(String, String)>, self_closing: bool },
+    End { name: String },
+    /// Decoded text
+    Text(String),
+    /// Comments, doctypes and processing instructions
+    Ignored,
+}
+
+impl HtmlToken {
+    /// The first token of `html`
+    pub fn parse(html: &str) -> Self {
+        let Some(first) = split_html(html).first().copied() else {
+            return Self::Text(String::new());
+        };
+
+        if first.starts_with("<!") || first.starts_with("<?") {
+            return Self::Ignored;
+        }
+        if let Some(tag) = first.strip_prefix("</") {
+            let name = tag.trim_end_matches('>').trim().to_ascii_lowercase();
+            return Self::End { name };
+        }
+        if !is_tag_start(first) {
+            return Self::Text(decode_entities(first));
+        }
+
+        let inner = &first[1..first.len() - 1];
+        let self_closing = inner.ends_with('/');
+        let inner = inner.trim_end_matches('/');
+        let name_end = inner.find(|c: char| c.is_ascii_whitespace() || c == '/').unwrap_or(inner.len());
+        let name = inner[..name_end].to_ascii_lowercase();
+        Self::Start { name, attributes: parse_attributes(&inner[name_end..]), self_closing }
+    }
+}
+
+fn is_tag_start(html: &str) -> bool {
+    let mut chars = html.chars();
+    chars.next() == Some('<') && chars.next().is_some_and(|c| c.is_ascii_alphabetic()) && html.ends_with('>')
+}
+
+/// `html` cut into tags, comments and the text between them
+pub fn split_html(html: &str) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    let bytes = html.as_bytes();
+    while i < bytes.len() {
+        if bytes[i] != b'<' {
+            i += 1;
+            continue;
+        }
+        let rest = &html[i..];
+        let end = if rest.starts_with("<!--") {
+            rest.find("-->").map(|end| end + 3)
+        } else if rest.starts_with("</") || rest.starts_with("<!") || rest.starts_with("<?") || is_tag_start_char(rest) {
+            tag_end(rest)
+        } else {
+            None
+        };
+        match end {
+            Some(end) => {
+                if start < i {
+                    pieces.push(&html[start..i]);
+                }
+                pieces.push(&html[i..i + end]);
+                i += end;
+                start = i;
+            },
+            // A `<` that doesn't start a tag is text
+            None => i += 1,
+        }
+    }
+    if start < html.len() {
+        pieces.push(&html[start..]);
+    }
+    pieces
+}
+
+fn is_tag_start_char(html: &str) -> bool {
+    html[1..].starts_with(|c: char| c.is_ascii_alphabetic())
+}
+
+/// Length of the tag at the start of `html`, skipping `>` in quoted attribute values
+fn tag_end(html: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in html.char_indices().skip(1) {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), _) => {},
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '>') => return Some(i + 1),
+            (None, '<') => return None,
+            _ => {},
+        }
+    }
+    None
+}
+
+fn parse_attributes(html: &str) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+    let mut rest = html.trim_start();
+    while !rest.is_empty() {
+        let name_end = rest.find(|c: char| c.is_ascii_whitespace() || c == '=' || c == '/').unwrap_or(rest.len());
+        let name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+
+        let mut value = String::new();
+        if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start();
+            let (raw, remaining) = match after.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let end = after[1..].find(quote).map_or(after.len(), |end| end + 1);
+                    (&after[1..end], after.get(end + 1..).unwrap_or(""))
+                },
+                _ => {
+                    let end = after.find(|c: char| c.is_ascii_whitespace()).unwrap_or(after.len());
+                    (&after[..end], &after[end..])
+                },
+            };
+            value = decode_entities(raw);
+            rest = remaining;
+        } else if name.is_empty() {
+            // A stray `/`
+            rest = &rest[1.min(rest.len())..];
+        }
+
+        if !name.is_empty() {
+            attributes.push((name, value));
+        }
+        rest = rest.trim_start();
+    }
+    attributes
+}
+
+/// Text with character references like `&amp;`, `&#39;` and `&#x61;` replaced
+pub fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest.find(';').filter(|end| *end <= 10);
+        let character = end.and_then(|end| {
+            let entity = &rest[1..end];
+            match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                _ => {
+                    let number = entity.strip_prefix('#')?;
+                    let code = match number.strip_prefix(['x', 'X']) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => number.parse().ok()?,
+                    };
+                    char::from_u32(code)
+                },
+            }
+        });
+        match (character, end) {
+            (Some(character), Some(end)) => {
+                decoded.push(character);
+                rest = &rest[end + 1..];
+            },
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            },
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
@@ -0,0 +1,964 @@
+//! Translations: languages, message catalogs and a Fluent-style message format.
+//!
+//! Messages are plain text with placeables in braces:
+//!
+//! - `{ $name }` fills in an argument
+//! - `{ "{" }` is a literal brace
+//! - `{ $count -> [one] { $count } post *[other] { $count } posts }` selects a variant. Numbers
+//!   match an exact variant like `[0]` first, then their CLDR plural category (`zero`, `one`,
+//!   `two`, `few`, `many`, `other`) in the message's language. Strings, such as a gender,
+//!   match the variant with the same name. The variant marked `*` is used when nothing matches.
+//!
+//! Message ids are `namespace:key`; an id without a namespace is looked up in `common`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// Language used when no other is chosen, and for messages missing from a translation
+pub const DEFAULT_LANGUAGE: &str = "en";
+/// Namespace of message ids that don't name one
+pub const DEFAULT_NAMESPACE: &str = "common";
+
+/// Language definition
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Language {
+    pub code: String,        // ISO 639-1 code (e.g., "en", "es", "fr")
+    pub name: String,        // Display name (e.g., "English", "Español")
+    pub native_name: String, // Native name (e.g., "English", "Español")
+    pub direction: TextDirection,
+    pub active: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TextDirection {
+    #[default]
+    LeftToRight,
+    RightToLeft,
+}
+
+impl TextDirection {
+    /// Value of the HTML `dir` attribute
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::LeftToRight => "ltr",
+            Self::RightToLeft => "rtl",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "ltr" => Some(Self::LeftToRight),
+            "rtl" => Some(Self::RightToLeft),
+            _ => None,
+        }
+    }
+
+    /// Direction of the language `code`, left to right if it isn't one of `languages`
+    pub fn of(code: &str, languages: &[Language]) -> Self {
+        languages
+            .iter()
+            .find(|language| language.code == code)
+            .map_or(Self::LeftToRight, |language| language.direction)
+    }
+}
+
+/// Translation entry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Translation {
+    pub key: String,
+    pub language_code: String,
+    pub value: String,
+    pub namespace: String, // e.g., "common", "posts", "admin"
+}
+
+impl Translation {
+    pub fn new(namespace: &str, key: &str, language_code: &str, value: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            language_code: language_code.to_string(),
+            value: value.to_string(),
+            namespace: namespace.to_string(),
+        }
+    }
+
+    /// `namespace:key`
+    pub fn id(&self) -> String {
+        format!("{}:{}", self.namespace, self.key)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.namespace.trim().is_empty() || self.key.trim().is_empty() {
+            return Err("Namespace and key are required".to_string());
+        }
+        if self.namespace.contains(':') || self.key.contains(':') {
+            return Err("Namespaces and keys can't contain ':'".to_string());
+        }
+        if self.language_code.trim().is_empty() {
+            return Err("Language is required".to_string());
+        }
+        validate_message(&self.value).map_err(|e| format!("{}: {}", self.id(), e))
+    }
+}
+
+/// `path` under a `/:language` prefix, except for content in the default language (or with
+/// no language set), which lives at the unprefixed path
+pub fn localized_path(language: &str, default_language: &str, path: &str) -> String {
+    if language.is_empty() || language == default_language {
+        path.to_string()
+    } else {
+        format!("/{}{}", language, path)
+    }
+}
+
+/// Cookie holding the language the visitor picked
+pub const LANGUAGE_COOKIE: &str = "language";
+
+/// Language of the current request, as negotiated by the server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequestLocale {
+    /// Language the page is shown in
+    pub language: String,
+    /// Direction of `language`
+    pub direction: TextDirection,
+    /// Language the visitor prefers, ignoring the URL prefix; used for unprefixed pages
+    pub preferred: String,
+}
+
+impl RequestLocale {
+    /// Locale for a request to `path`: the active language in its URL prefix, or else the
+    /// one the visitor prefers (see [`negotiate_language`])
+    pub fn negotiate(path: &str, preference: Option<&str>, accept_language: Option<&str>, languages: &[Language], default_language: &str) -> Self {
+        let available: Vec<String> = languages
+            .iter()
+            .filter(|language| language.active)
+            .map(|language| language.code.clone())
+            .collect();
+        let preferred = negotiate_language(preference, accept_language, &available, default_language);
+        let language = url_language(path, &available).map(str::to_string).unwrap_or_else(|| preferred.clone());
+        let direction = TextDirection::of(&language, languages);
+        Self { language, direction, preferred }
+    }
+}
+
+/// Sets `lang` and `dir` on the `<html>` tag of a page as it is streamed, replacing the ones
+/// the page already has. Chunks are held back while the opening tag is incomplete.
+#[derive(Debug, Clone)]
+pub struct HtmlLocaleRewriter {
+    /// ` lang=".." dir=".."`
+    attributes: String,
+    /// The start of a tag that may be `<html`, waiting for the rest of it
+    pending: Vec<u8>,
+    done: bool,
+}
+
+impl HtmlLocaleRewriter {
+    pub fn new(locale: &RequestLocale) -> Self {
+        let language = locale.language.replace('&', "&amp;").replace('"', "&quot;");
+        Self {
+            attributes: format!(" lang=\"{}\" dir=\"{}\"", language, locale.direction.as_str()),
+            pending: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// The bytes to send for the next `chunk` of the page: nothing while waiting for the
+    /// rest of the `<html>` tag, the chunk itself once the tag has been rewritten
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<u8> {
+        if self.done {
+            return chunk.to_vec();
+        }
+        self.pending.extend_from_slice(chunk);
+        let buffer = std::mem::take(&mut self.pending);
+        match find_html_tag(&buffer) {
+            Ok(tag) => {
+                self.done = true;
+                let mut rewritten = buffer[..tag.start].to_vec();
+                rewritten.extend(with_locale_attributes(&buffer[tag.clone()], &self.attributes));
+                rewritten.extend_from_slice(&buffer[tag.end..]);
+                rewritten
+            }
+            Err(ready) => {
+                self.pending = buffer[ready..].to_vec();
+                buffer[..ready].to_vec()
+            }
+        }
+    }
+
+    /// Whatever is still held back when the page ends
+    pub fn finish(&mut self) -> Vec<u8> {
+        self.done = true;
+        std::mem::take(&mut self.pending)
+    }
+}
+
+/// Where the complete `<html ...>` tag in `html` is, or else how much of `html` comes before
+/// anything that could still become one
+fn find_html_tag(html: &[u8]) -> Result<std::ops::Range<usize>, usize> {
+    for (start, _) in html.iter().enumerate().filter(|(_, byte)| **byte == b'<') {
+        let name = &html[start + 1..html.len().min(start + 5)];
+        if !b"html"[..name.len()].eq_ignore_ascii_case(name) {
+            continue;
+        }
+        match html.get(start + 5) {
+            // `<htm`, `<html` at the end of the chunk
+            None => return Err(start),
+            Some(next) if !next.is_ascii_whitespace() && *next != b'>' && *next != b'/' => continue,
+            Some(_) => {}
+        }
+
+        let mut quote = None;
+        for (offset, byte) in html[start + 5..].iter().enumerate() {
+            match quote {
+                Some(open) if *byte == open => quote = None,
+                Some(_) => {}
+                None if *byte == b'"' || *byte == b'\'' => quote = Some(*byte),
+                None if *byte == b'>' => return Ok(start..start + 5 + offset + 1),
+                None => {}
+            }
+        }
+        return Err(start);
+    }
+    Err(html.len())
+}
+
+/// The `<html ...>` tag `tag` with its `lang` and `dir` replaced by `attributes`
+fn with_locale_attributes(tag: &[u8], attributes: &str) -> Vec<u8> {
+    let inner = &tag[5..tag.len() - 1];
+    let mut rewritten = b"<html".to_vec();
+    let mut i = 0;
+    while i < inner.len() {
+        if inner[i].is_ascii_whitespace() || inner[i] == b'/' || inner[i] == b'=' {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < inner.len() && !inner[i].is_ascii_whitespace() && !matches!(inner[i], b'=' | b'/') {
+            i += 1;
+        }
+        let name = &inner[start..i];
+
+        let mut j = i;
+        while j < inner.len() && inner[j].is_ascii_whitespace() {
+            j += 1;
+        }
+        if j < inner.len() && inner[j] == b'=' {
+            j += 1;
+            while j < inner.len() && inner[j].is_ascii_whitespace() {
+                j += 1;
+            }
+            match inner.get(j) {
+                Some(quote @ (b'"' | b'\'')) => {
+                    j += 1;
+                    while j < inner.len() && inner[j] != *quote {
+                        j += 1;
+                    }
+                    j = inner.len().min(j + 1);
+                }
+                _ => {
+                    while j < inner.len() && !inner[j].is_ascii_whitespace() {
+                        j += 1;
+                    }
+                }
+            }
+            i = j;
+        }
+
+        if !name.eq_ignore_ascii_case(b"lang") && !name.eq_ignore_ascii_case(b"dir") {
+            rewritten.push(b' ');
+            rewritten.extend_from_slice(&inner[start..i]);
+        }
+    }
+    rewritten.extend_from_slice(attributes.as_bytes());
+    rewritten.push(b'>');
+    rewritten
+}
+
+/// Language named by the first segment of `path`, if it is one of `available`
+pub fn url_language<'a>(path: &str, available: &'a [String]) -> Option<&'a str> {
+    let segment = path.trim_start_matches('/').split('/').next()?;
+    available.iter().find(|code| code.as_str() == segment).map(String::as_str)
+}
+
+/// Language tags of an `Accept-Language` header, most preferred first. Tags with `q=0`
+/// are left out.
+pub fn parse_accept_language(header: &str) -> Vec<String> {
+    let mut tags: Vec<(String, f32)> = header
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let tag = parts.next()?.trim().to_lowercase();
+            let quality = parts
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+        })
+        .collect();
+    // Stable, so equally preferred tags keep their order
+    tags.sort_by(|a, b| b.1.total_cmp(&a.1));
+    tags.into_iter().map(|(tag, _)| tag).collect()
+}
+
+/// The first of `requested` that one of `available` serves: an exact match, or else one
+/// with the same primary language, so `fr-CH` gets `fr` and `pt` gets `pt-BR`
+pub fn match_language<'a>(requested: &[String], available: &'a [String]) -> Option<&'a str> {
+    let primary = |tag: &str| tag.split(['-', '_']).next().unwrap_or_default().to_lowercase();
+    requested.iter().find_map(|tag| {
+        available
+            .iter()
+            .find(|code| code.eq_ignore_ascii_case(tag))
+            .or_else(|| available.iter().find(|code| primary(code) == primary(tag)))
+            .map(String::as_str)
+    })
+}
+
+/// Language for a visitor: their saved preference if it is still available, then the best
+/// match for `Accept-Language`, then the site's default
+pub fn negotiate_language(preference: Option<&str>, accept_language: Option<&str>, available: &[String], default_language: &str) -> String {
+    if let Some(code) = preference.and_then(|code| available.iter().find(|available| available.as_str() == code)) {
+        return code.clone();
+    }
+    accept_language
+        .and_then(|header| match_language(&parse_accept_language(header), available))
+        .unwrap_or(default_language)
+        .to_string()
+}
+
+/// Namespace and key of a message id; `key` alone is in the default namespace
+pub fn split_message_id(id: &str) -> (&str, &str) {
+    id.split_once(':').unwrap_or((DEFAULT_NAMESPACE, id))
+}
+
+/// Messages shipped with the CMS: (namespace, key, language, message). They are added to
+/// the database on startup without replacing edits, and used until translations load.
+pub const DEFAULT_TRANSLATIONS: &[(&str, &str, &str, &str)] = &[
+    ("common", "home", "en", "Home"),
+    ("common", "home", "es", "Inicio"),
+    ("common", "home", "fr", "Accueil"),
+    ("common", "blog", "en", "Blog"),
+    ("common", "blog", "es", "Blog"),
+    ("common", "blog", "fr", "Blog"),
+    ("common", "about", "en", "About"),
+    ("common", "about", "es", "Acerca de"),
+    ("common", "about", "fr", "À propos"),
+    ("common", "contact", "en", "Contact"),
+    ("common", "contact", "es", "Contacto"),
+    ("common", "contact", "fr", "Contact"),
+    ("common", "login", "en", "Login"),
+    ("common", "login", "es", "Iniciar sesión"),
+    ("common", "login", "fr", "Connexion"),
+    ("common", "register", "en", "Register"),
+    ("common", "register", "es", "Registrarse"),
+    ("common", "register", "fr", "S'inscrire"),
+    ("common", "admin", "en", "Admin"),
+    ("common", "admin", "es", "Administración"),
+    ("common", "admin", "fr", "Administration"),
+    ("common", "settings", "en", "Settings"),
+    ("common", "settings", "es", "Configuración"),
+    ("common", "settings", "fr", "Paramètres"),
+    ("common", "save", "en", "Save"),
+    ("common", "save", "es", "Guardar"),
+    ("common", "save", "fr", "Enregistrer"),
+    ("common", "cancel", "en", "Cancel"),
+    ("common", "cancel", "es", "Cancelar"),
+    ("common", "cancel", "fr", "Annuler"),
+    ("common", "search", "en", "Search"),
+    ("common", "search", "es", "Buscar"),
+    ("common", "search", "fr", "Rechercher"),
+    ("common", "back-home", "en", "← Back to Home"),
+    ("common", "back-home", "es", "← Volver al inicio"),
+    ("common", "back-home", "fr", "← Retour à l'accueil"),
+    ("common", "contents", "en", "Contents"),
+    ("common", "contents", "es", "Contenido"),
+    ("common", "contents", "fr", "Sommaire"),
+    ("search", "placeholder", "en", "Search posts"),
+    ("search", "placeholder", "es", "Buscar entradas"),
+    ("search", "placeholder", "fr", "Rechercher des articles"),
+    ("search", "no-results", "en", "No posts match \"{ $query }\"."),
+    ("search", "no-results", "es", "Ninguna entrada coincide con \"{ $query }\"."),
+    ("search", "no-results", "fr", "Aucun article ne correspond à « { $query } »."),
+    ("search", "result-count", "en", "{ $count -> [one] One result *[other] { $count } results } for \"{ $query }\""),
+    ("search", "result-count", "es", "{ $count -> [one] Un resultado *[other] { $count } resultados } para \"{ $query }\""),
+    ("search", "result-count", "fr", "{ $count -> [one] Un résultat *[other] { $count } résultats } pour « { $query } »"),
+    ("search", "searching", "en", "Searching..."),
+    ("search", "searching", "es", "Buscando..."),
+    ("search", "searching", "fr", "Recherche..."),
+    ("posts", "published-on", "en", "Published on { $date }"),
+    ("posts", "published-on", "es", "Publicado el { $date }"),
+    ("posts", "published-on", "fr", "Publié le { $date }"),
+];
+
+/// A value filled into a message
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessageArg {
+    String(String),
+    Number(f64),
+}
+
+impl std::fmt::Display for MessageArg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::String(value) => f.write_str(value),
+            Self::Number(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl From<&str> for MessageArg {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for MessageArg {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<&String> for MessageArg {
+    fn from(value: &String) -> Self {
+        Self::String(value.clone())
+    }
+}
+
+macro_rules! number_arg {
+    ($($t:ty),*) => {
+        $(impl From<$t> for MessageArg {
+            fn from(value: $t) -> Self {
+                Self::Number(value as f64)
+            }
+        })*
+    };
+}
+
+number_arg!(i32, i64, u32, u64, usize, f32, f64);
+
+/// CLDR plural category
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+impl PluralCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Zero => "zero",
+            Self::One => "one",
+            Self::Two => "two",
+            Self::Few => "few",
+            Self::Many => "many",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// Cardinal plural category of `n` in a language, following the CLDR plural rules.
+/// Languages without rules here use the English ones.
+pub fn plural_category(language: &str, n: f64) -> PluralCategory {
+    use PluralCategory::*;
+
+    // CLDR operands: i is the integer part, v the number of visible fraction digits,
+    // f the visible fraction digits as an integer
+    let n = n.abs();
+    let formatted = n.to_string();
+    let fraction = formatted.split_once('.').map(|(_, fraction)| fraction).unwrap_or("");
+    let i = n.trunc() as u64;
+    let v = fraction.len();
+    let f: u64 = fraction.parse().unwrap_or(0);
+    let (i10, i100) = (i % 10, i % 100);
+    let language = language.split(['-', '_']).next().unwrap_or(language).to_lowercase();
+
+    match language.as_str() {
+        "ja" | "zh" | "ko" | "vi" | "th" | "id" | "ms" | "lo" | "my" => Other,
+        "fr" => {
+            if i <= 1 {
+                One
+            } else if i != 0 && i.is_multiple_of(1_000_000) && v == 0 {
+                Many
+            } else {
+                Other
+            }
+        },
+        "es" | "it" | "ca" => {
+            let one = if language == "es" { n == 1.0 } else { i == 1 && v == 0 };
+            if one {
+                One
+            } else if i != 0 && i.is_multiple_of(1_000_000) && v == 0 {
+                Many
+            } else {
+                Other
+            }
+        },
+        "pt" => {
+            if i <= 1 {
+                One
+            } else if i != 0 && i.is_multiple_of(1_000_000) && v == 0 {
+                Many
+            } else {
+                Other
+            }
+        },
+        "ru" | "uk" | "be" => {
+            if v != 0 {
+                Other
+            } else if i10 == 1 && i100 != 11 {
+                One
+            } else if (2..=4).contains(&i10) && !(12..=14).contains(&i100) {
+                Few
+            } else {
+                Many
+            }
+        },
+        "pl" => {
+            if i == 1 && v == 0 {
+                One
+            } else if v == 0 && (2..=4).contains(&i10) && !(12..=14).contains(&i100) {
+                Few
+            } else if v == 0 {
+                Many
+            } else {
+                Other
+            }
+        },
+        "cs" | "sk" => {
+            if i == 1 && v == 0 {
+                One
+            } else if (2..=4).contains(&i) && v == 0 {
+                Few
+            } else if v != 0 {
+                Many
+            } else {
+                Other
+            }
+        },
+        "ar" => {
+            let n100 = if n.fract() == 0.0 { Some(i100) } else { None };
+            if n == 0.0 {
+                Zero
+            } else if n == 1.0 {
+                One
+            } else if n == 2.0 {
+                Two
+            } else if matches!(n100, Some(3..=10)) {
+                Few
+            } else if matches!(n100, Some(11..=99)) {
+                Many
+            } else {
+                Other
+            }
+        },
+        "he" => {
+            if (i == 1 && v == 0) || (i == 0 && v != 0) {
+                One
+            } else if i == 2 && v == 0 {
+                Two
+            } else {
+                Other
+            }
+        },
+        "tr" | "hu" | "el" | "bg" | "ka" | "az" => {
+            if n == 1.0 {
+                One
+            } else {
+                Other
+            }
+        },
+        "da" => {
+            if n == 1.0 || (f != 0 && i <= 1) {
+                One
+            } else {
+                Other
+            }
+        },
+        _ => {
+            if i == 1 && v == 0 {
+                One
+            } else {
+                Other
+            }
+        },
+    }
+}
+
+/// A parsed message
+#[derive(Debug, Clone, PartialEq)]
+enum Element {
+    Text(String),
+    Variable(String),
+    Select { selector: String, variants: Vec<(String, Vec<Element>)>, default: usize },
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn identifier(&mut self) -> Option<String> {
+        let mut name = String::new();
+        while let Some(c) = self.chars.next_if(|c| c.is_alphanumeric() || *c == '_' || *c == '-') {
+            name.push(c);
+        }
+        (!name.is_empty()).then_some(name)
+    }
+
+    /// Elements up to the end of the input, or inside a variant up to its closing point
+    fn pattern(&mut self, in_variant: bool) -> Option<Vec<Element>> {
+        let mut elements = Vec::new();
+        let mut text = String::new();
+
+        while let Some(&c) = self.chars.peek() {
+            match c {
+                '{' => {
+                    self.chars.next();
+                    if !text.is_empty() {
+                        elements.push(Element::Text(std::mem::take(&mut text)));
+                    }
+                    elements.push(self.placeable()?);
+                },
+                '}' if in_variant => break,
+                // A new variant starts at `[` or `*[` after whitespace
+                '[' | '*' if in_variant && text.ends_with(char::is_whitespace) => break,
+                '}' => return None,
+                c => {
+                    self.chars.next();
+                    text.push(c);
+                },
+            }
+        }
+
+        if in_variant {
+            text = text.trim_end().to_string();
+            if let Some(Element::Text(first)) = elements.first_mut() {
+                *first = first.trim_start().to_string();
+            } else {
+                text = text.trim_start().to_string();
+            }
+        }
+        if !text.is_empty() {
+            elements.push(Element::Text(text));
+        }
+        Some(elements)
+    }
+
+    /// What follows an opening brace, up to and including the closing one
+    fn placeable(&mut self) -> Option<Element> {
+        self.skip_whitespace();
+        let element = match self.chars.next()? {
+            '"' => {
+                let mut literal = String::new();
+                loop {
+                    match self.chars.next()? {
+                        '"' => break,
+                        '\\' => literal.push(self.chars.next()?),
+                        c => literal.push(c),
+                    }
+                }
+                Element::Text(literal)
+            },
+            '$' => {
+                let name = self.identifier()?;
+                self.skip_whitespace();
+                if self.chars.next_if_eq(&'-').is_some() {
+                    self.chars.next_if_eq(&'>')?;
+                    self.select(name)?
+                } else {
+                    Element::Variable(name)
+                }
+            },
+            _ => return None,
+        };
+
+        self.skip_whitespace();
+        self.chars.next_if_eq(&'}')?;
+        Some(element)
+    }
+
+    fn select(&mut self, selector: String) -> Option<Element> {
+        let mut variants = Vec::new();
+        let mut default = None;
+
+        loop {
+            self.skip_whitespace();
+            match self.chars.peek() {
+                Some('}') => break,
+                Some('*') => {
+                    self.chars.next();
+                    default = Some(variants.len());
+                },
+                _ => {},
+            }
+            self.chars.next_if_eq(&'[')?;
+            self.skip_whitespace();
+            let mut key = String::new();
+            while let Some(c) = self.chars.next_if(|c| *c != ']' && !c.is_whitespace()) {
+                key.push(c);
+            }
+            self.skip_whitespace();
+            self.chars.next_if_eq(&']')?;
+            variants.push((key, self.pattern(true)?));
+        }
+
+        // Fluent requires a default variant
+        Some(Element::Select { selector, variants, default: default? })
+    }
+}
+
+fn parse_message(message: &str) -> Option<Vec<Element>> {
+    Parser { chars: message.chars().peekable() }.pattern(false)
+}
+
+fn format_elements(elements: &[Element], language: &str, args: &[(&str, MessageArg)], out: &mut String) {
+    let arg = |name: &str| args.iter().find(|(arg, _)| *arg == name).map(|(_, value)| value);
+
+    for element in elements {
+        match element {
+            Element::Text(text) => out.push_str(text),
+            Element::Variable(name) => match arg(name) {
+                // Numbers get the language's separators, as Fluent's NUMBER does
+                Some(MessageArg::Number(n)) => out.push_str(&crate::Formatter::new(language, Default::default()).number(*n, 3)),
+                Some(value) => out.push_str(&value.to_string()),
+                None => out.push_str(&format!("{{${}}}", name)),
+            },
+            Element::Select { selector, variants, default } => {
+                let matches = |key: &str| variants.iter().position(|(variant, _)| variant == key);
+                let chosen = match arg(selector) {
+                    Some(MessageArg::Number(n)) => variants
+                        .iter()
+                        .position(|(variant, _)| variant.parse::<f64>().is_ok_and(|exact| exact == *n))
+                        .or_else(|| matches(plural_category(language, *n).as_str())),
+                    Some(MessageArg::String(value)) => matches(value),
+                    None => None,
+                };
+                format_elements(&variants[chosen.unwrap_or(*default)].1, language, args, out);
+            },
+        }
+    }
+}
+
+/// Format a message in `language` with named arguments. Messages that don't parse are
+/// returned as written, and missing arguments are shown as `{$name}`.
+pub fn format_message(message: &str, language: &str, args: &[(&str, MessageArg)]) -> String {
+    match parse_message(message) {
+        Some(elements) => {
+            let mut out = String::new();
+            format_elements(&elements, language, args, &mut out);
+            out
+        },
+        None => message.to_string(),
+    }
+}
+
+/// Check that a message parses, e.g. before saving it
+pub fn validate_message(message: &str) -> Result<(), String> {
+    parse_message(message)
+        .map(|_| ())
+        .ok_or_else(|| "Unbalanced braces, or a selector without a *[default] variant".to_string())
+}
+
+fn collect_arguments(elements: &[Element], names: &mut BTreeSet<String>) {
+    for element in elements {
+        match element {
+            Element::Text(_) => {},
+            Element::Variable(name) => {
+                names.insert(name.clone());
+            },
+            Element::Select { selector, variants, .. } => {
+                names.insert(selector.clone());
+                for (_, elements) in variants {
+                    collect_arguments(elements, names);
+                }
+            },
+        }
+    }
+}
+
+/// Names of the arguments a message uses, including selectors; `None` if it doesn't parse.
+/// A translation should use the same ones as its source.
+pub fn message_arguments(message: &str) -> Option<BTreeSet<String>> {
+    let elements = parse_message(message)?;
+    let mut names = BTreeSet::new();
+    collect_arguments(&elements, &mut names);
+    Some(names)
+}
+
+/// Messages for one language, falling back to the default language and then to the id
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Catalog {
+    pub language: String,
+    /// language -> `namespace:key` -> message
+    messages: HashMap<String, HashMap<String, String>>,
+}
+
+impl Catalog {
+    pub fn new(language: &str, translations: impl IntoIterator<Item = Translation>) -> Self {
+        let mut catalog = Self { language: language.to_string(), messages: HashMap::new() };
+        for translation in translations {
+            catalog.insert(translation);
+        }
+        catalog
+    }
+
+    /// The shipped messages in `language`
+    pub fn builtin(language: &str) -> Self {
+        Self::new(
+            language,
+            DEFAULT_TRANSLATIONS
+                .iter()
+                .filter(|(_, _, code, _)| *code == language || *code == DEFAULT_LANGUAGE)
+                .map(|(namespace, key, code, value)| Translation::new(namespace, key, code, value)),
+        )
+    }
+
+    pub fn insert(&mut self, translation: Translation) {
+        self.messages
+            .entry(translation.language_code.clone())
+            .or_default()
+            .insert(translation.id(), translation.value);
+    }
+
+    /// The message for `id` in the catalog's language or the default language
+    pub fn message(&self, id: &str) -> Option<&str> {
+        let (namespace, key) = split_message_id(id);
+        let id = format!("{}:{}", namespace, key);
+        [self.language.as_str(), DEFAULT_LANGUAGE]
+            .iter()
+            .find_map(|language| self.messages.get(*language)?.get(&id))
+            .map(String::as_str)
+    }
+
+    /// Format the message for `id`; unknown ids come back as the id itself
+    pub fn format(&self, id: &str, args: &[(&str, MessageArg)]) -> String {
+        match self.message(id) {
+            Some(message) => format_message(message, &self.language, args),
+            None => id.to_string(),
+        }
+    }
+}
+
+/// File formats translators exchange messages in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TranslationFileFormat {
+    /// gettext PO
+    Po,
+    /// XLIFF 2.0
+    Xliff,
+}
+
+impl TranslationFileFormat {
+    pub const ALL: [Self; 2] = [Self::Po, Self::Xliff];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Po => "po",
+            Self::Xliff => "xlf",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Po => "PO",
+            Self::Xliff => "XLIFF 2.0",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Po => "text/x-gettext-translation; charset=utf-8",
+            Self::Xliff => "application/xliff+xml; charset=utf-8",
+        }
+    }
+
+    /// Format of a file, from its extension
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        let extension = name.rsplit_once('.')?.1.to_lowercase();
+        match extension.as_str() {
+            "po" | "pot" => Some(Self::Po),
+            "xlf" | "xliff" => Some(Self::Xliff),
+            _ => None,
+        }
+    }
+
+    /// Download URL of one namespace in `language`
+    pub fn export_path(&self, language: &str, namespace: &str) -> String {
+        format!("/i18n/{}/{}.{}", language, namespace, self.extension())
+    }
+}
+
+/// A message from an imported file that would be added or changed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportedMessage {
+    pub namespace: String,
+    pub key: String,
+    pub value: String,
+    /// The stored message it replaces
+    pub previous: Option<String>,
+}
+
+/// Why a message from an imported file is left out
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ImportIssue {
+    /// The key doesn't exist in the source language
+    UnknownKey,
+    /// The message uses other arguments than its source
+    PlaceholderMismatch { source: Vec<String>, translation: Vec<String> },
+    /// The message doesn't parse
+    Invalid(String),
+}
+
+impl ImportIssue {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::UnknownKey => "Not in the source language".to_string(),
+            Self::PlaceholderMismatch { source, translation } => {
+                let list = |names: &[String]| match names.is_empty() {
+                    true => "none".to_string(),
+                    false => names.iter().map(|name| format!("${}", name)).collect::<Vec<_>>().join(", "),
+                };
+                format!("Placeholders {} don't match the source's {}", list(translation), list(source))
+            },
+            Self::Invalid(e) => e.clone(),
+        }
+    }
+}
+
+/// A message from an imported file with an [`ImportIssue`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlaggedMessage {
+    pub namespace: String,
+    pub key: String,
+    pub value: String,
+    pub issue: ImportIssue,
+}
+
+/// What importing a translation file would do to the stored messages of its language
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranslationImportPreview {
+    pub language: String,
+    pub format: TranslationFileFormat,
+    /// Namespaces the file covers; only these can lose messages
+    pub namespaces: Vec<String>,
+    pub added: Vec<ImportedMessage>,
+    pub changed: Vec<ImportedMessage>,
+    /// Stored messages the file has no translation for
+    pub removed: Vec<Translation>,
+    pub unchanged: usize,
+    /// Messages that won't be imported
+    pub flagged: Vec<FlaggedMessage>,
+}
+
+impl TranslationImportPreview {
+    /// Whether importing would change anything, with or without removing messages
+    pub fn has_changes(&self, remove_missing: bool) -> bool {
+        !self.added.is_empty() || !self.changed.is_empty() || (remove_missing && !self.removed.is_empty())
+    }
+}
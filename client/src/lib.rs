@@ -3,7 +3,15 @@
 pub mod types;
 pub mod time;
 pub mod api;
+pub mod i18n;
+pub mod format;
+pub mod front_matter;
+pub mod html;
 
 pub use types::*;
 pub use time::*;
-pub use api::*;
\ No newline at end of file
+pub use api::*;
+pub use i18n::*;
+pub use format::*;
+pub use front_matter::*;
+pub use html::*;
\ No newline at end of file
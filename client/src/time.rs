@@ -59,4 +59,96 @@ pub fn generate_id() -> String {
                 random1,
                 random2 & 0xffff)
     }
-}
\ No newline at end of file
+}
+/// Days since 1970-01-01 of a `YYYY-MM-DD` date
+pub fn date_to_days(date: &str) -> Option<i64> {
+    let mut parts = date.get(..10)?.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: i64 = parts.next()?.parse().ok()?;
+    let day: i64 = parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+
+    // Howard Hinnant's days_from_civil
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    Some(era * 146097 + day_of_era - 719468)
+}
+
+/// Number of days in `month` (1 to 12) of `year`, in the Gregorian calendar
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// `YYYY-MM-DD` date of a number of days since 1970-01-01
+pub fn days_to_date(days: i64) -> String {
+    // Howard Hinnant's civil_from_days
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Shift a `YYYY-MM-DD` date by a number of days
+pub fn add_days(date: &str, days: i64) -> Option<String> {
+    date_to_days(date).map(|start| days_to_date(start + days))
+}
+
+/// Every date from `start` to `end`, inclusive; empty if either is invalid or `end` is earlier
+pub fn date_range(start: &str, end: &str) -> Vec<String> {
+    match (date_to_days(start), date_to_days(end)) {
+        (Some(start), Some(end)) => (start..=end).map(days_to_date).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Canonical `YYYY-MM-DDTHH:MM:SSZ` form of a UTC timestamp, accepting what `datetime-local`
+/// inputs and JavaScript's `toISOString` produce: seconds and fractions are optional and the
+/// time zone, if any, must be `Z`. Timestamps in this form sort chronologically as text.
+pub fn normalize_timestamp(value: &str) -> Option<String> {
+    let value = value.trim().trim_end_matches('Z');
+    let (date, time) = value.split_once(['T', ' '])?;
+    date_to_days(date).filter(|_| date.len() == 10)?;
+
+    let time = time.split('.').next()?;
+    let mut parts = time.split(':');
+    let hour: u32 = parts.next()?.parse().ok()?;
+    let minute: u32 = parts.next()?.parse().ok()?;
+    let second: u32 = parts.next().map_or(Some(0), |second| second.parse().ok())?;
+    if parts.next().is_some() || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    Some(format!("{}T{:02}:{:02}:{:02}Z", date, hour, minute, second))
+}
+
+/// Seconds since 1970-01-01T00:00:00Z of a UTC timestamp (see [`normalize_timestamp`]) or of
+/// midnight UTC on a `YYYY-MM-DD` date
+pub fn timestamp_seconds(value: &str) -> Option<i64> {
+    let value = value.trim();
+    if value.len() == 10 {
+        return date_to_days(value).map(|days| days * 86_400);
+    }
+
+    let timestamp = normalize_timestamp(value)?;
+    let days = date_to_days(&timestamp)?;
+    let hour: i64 = timestamp.get(11..13)?.parse().ok()?;
+    let minute: i64 = timestamp.get(14..16)?.parse().ok()?;
+    let second: i64 = timestamp.get(17..19)?.parse().ok()?;
+    Some(days * 86_400 + hour * 3_600 + minute * 60 + second)
+}
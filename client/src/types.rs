@@ -3,7 +3,7 @@
 use serde::{Deserialize, Serialize};
 
 /// Post data structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Post {
     pub id: u32,
     pub slug: String,
@@ -13,6 +13,91 @@ pub struct Post {
     pub created_at: String,
     pub updated_at: String,
     pub published: bool,
+    /// Name of the theme layout template to render with; `None` uses the default
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Search result snippet; falls back to an excerpt of the content
+    #[serde(default)]
+    pub meta_description: Option<String>,
+    /// Comma-separated keywords; falls back to the site keywords
+    #[serde(default)]
+    pub meta_keywords: Option<String>,
+    /// Image shown in link previews
+    #[serde(default)]
+    pub cover_image: Option<String>,
+    /// Canonical URL when the post is syndicated from elsewhere; defaults to its own URL
+    #[serde(default)]
+    pub canonical_url: Option<String>,
+    /// Day (`YYYY-MM-DD`) a draft is planned to go out, shown on the content calendar
+    #[serde(default)]
+    pub target_date: Option<String>,
+    /// Embargo: hidden from readers until this time (UTC), even when published
+    #[serde(default)]
+    pub publish_at: Option<String>,
+    /// Hidden from readers from this time (UTC) on
+    #[serde(default)]
+    pub expire_at: Option<String>,
+    /// Language code of the content, e.g. `es`
+    #[serde(default)]
+    pub language: String,
+    /// Shared by a post and its translations into other languages
+    #[serde(default)]
+    pub translation_group: Option<String>,
+}
+
+impl Post {
+    /// Whether readers can see the post at `now` (`YYYY-MM-DDTHH:MM:SSZ`)
+    pub fn is_live_at(&self, now: &str) -> bool {
+        self.published && window_open(self.publish_at.as_deref(), self.expire_at.as_deref(), now)
+    }
+    
+    /// `/post/:slug`, or `/:language/post/:slug` for posts not in the site's default language
+    pub fn path(&self, default_language: &str) -> String {
+        crate::localized_path(&self.language, default_language, &format!("/post/{}", self.slug))
+    }
+}
+
+/// Site-wide SEO defaults, stored in the `settings` table under `seo.*` keys
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeoSettings {
+    /// Public URL of the site, used for canonical and Open Graph URLs
+    pub site_url: String,
+    pub site_title: String,
+    pub site_description: String,
+    pub keywords: String,
+    pub default_image: Option<String>,
+    pub author: Option<String>,
+    pub language: String,
+    pub robots: String,
+    /// Time zone dates and times are shown in; see [`crate::TimeZone`]
+    pub time_zone: String,
+}
+
+impl Default for SeoSettings {
+    fn default() -> Self {
+        Self {
+            site_url: "http://localhost:8080".to_string(),
+            site_title: "BananaBit CMS".to_string(),
+            site_description: "Modern, extension-based content management system".to_string(),
+            keywords: "cms, content management, rust, dioxus".to_string(),
+            default_image: None,
+            author: None,
+            language: "en".to_string(),
+            robots: "index,follow".to_string(),
+            time_zone: "UTC".to_string(),
+        }
+    }
+}
+
+impl SeoSettings {
+    /// Make a site-relative path absolute
+    pub fn absolute_url(&self, path: &str) -> String {
+        if path.starts_with("http://") || path.starts_with("https://") {
+            path.to_string()
+        } else {
+            format!("{}/{}", self.site_url.trim_end_matches('/'), path.trim_start_matches('/'))
+        }
+    }
 }
 
 /// User data structure
@@ -27,6 +112,75 @@ pub struct User {
     pub active: bool,
     pub email_verified: bool,
     pub verification_token: Option<String>,
+    /// Preferred color scheme; `None` uses the active theme's own palette
+    #[serde(default)]
+    pub color_scheme: Option<ColorScheme>,
+}
+
+/// Reader-selectable color scheme
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorScheme {
+    Light,
+    Dark,
+    /// Follow the operating system's `prefers-color-scheme`
+    System,
+}
+
+/// Cookie holding the color scheme of the current visitor
+pub const COLOR_SCHEME_COOKIE: &str = "color_scheme";
+
+/// Palette variables a theme defines in `--theme-light-*` and `--theme-dark-*` variants
+pub const COLOR_SCHEME_VARIABLES: [&str; 11] = [
+    "bg", "surface", "surface-hover", "nav-bg", "text", "text-muted",
+    "border", "accent", "accent-strong", "accent-soft", "on-accent",
+];
+
+impl ColorScheme {
+    pub const ALL: [ColorScheme; 3] = [ColorScheme::Light, ColorScheme::Dark, ColorScheme::System];
+    
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Light => "light",
+            Self::Dark => "dark",
+            Self::System => "system",
+        }
+    }
+    
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|scheme| scheme.as_str() == value)
+    }
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Light => "Light",
+            Self::Dark => "Dark",
+            Self::System => "System",
+        }
+    }
+    
+    /// CSS mapping the theme palette onto the light or dark variant, or both behind
+    /// `prefers-color-scheme` media queries for `System`
+    pub fn css(&self) -> String {
+        fn palette(variant: &str) -> String {
+            let mut css = format!(":root {{\n    color-scheme: {};\n", variant);
+            for name in COLOR_SCHEME_VARIABLES {
+                css.push_str(&format!("    --theme-{name}: var(--theme-{variant}-{name});\n"));
+            }
+            css.push_str("}\n");
+            css
+        }
+        
+        match self {
+            Self::Light => palette("light"),
+            Self::Dark => palette("dark"),
+            Self::System => format!(
+                "@media (prefers-color-scheme: light) {{\n{}}}\n@media (prefers-color-scheme: dark) {{\n{}}}\n",
+                palette("light"),
+                palette("dark"),
+            ),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -78,6 +232,438 @@ pub struct Theme {
     pub description: Option<String>,
     pub css_content: String,
     pub active: bool,
+    #[serde(default = "default_theme_version")]
+    pub version: String,
+    #[serde(default)]
+    pub variables: Vec<ThemeVariable>,
+    #[serde(default)]
+    pub templates: Vec<ThemeTemplate>,
+}
+
+fn default_theme_version() -> String {
+    "1.0.0".to_string()
+}
+
+impl Theme {
+    /// Full stylesheet served for this theme: the theme CSS followed by its design variables
+    pub fn stylesheet(&self) -> String {
+        if self.variables.is_empty() {
+            return self.css_content.clone();
+        }
+        
+        let mut css = self.css_content.clone();
+        css.push_str("\n\n/* Design variables */\n:root {\n");
+        for variable in &self.variables {
+            css.push_str(&format!("    {}: {};\n", variable.css_property(), variable.value));
+        }
+        css.push_str("}\n");
+        css
+    }
+    
+    /// Whether the theme defines both light and dark palettes for `ColorScheme`
+    pub fn supports_color_schemes(&self) -> bool {
+        COLOR_SCHEME_VARIABLES.iter().all(|name| {
+            self.css_content.contains(&format!("--theme-light-{name}:"))
+                && self.css_content.contains(&format!("--theme-dark-{name}:"))
+        })
+    }
+    
+    /// Stylesheet with the reader's color scheme applied on top, when the theme supports it
+    pub fn stylesheet_for(&self, scheme: Option<ColorScheme>) -> String {
+        let mut css = self.stylesheet();
+        if let Some(scheme) = scheme.filter(|_| self.supports_color_schemes()) {
+            css.push_str("\n/* Color scheme */\n");
+            css.push_str(&scheme.css());
+        }
+        css
+    }
+    
+    /// Find a layout template by name
+    pub fn template(&self, name: &str) -> Option<&ThemeTemplate> {
+        self.templates.iter().find(|template| template.name == name)
+    }
+}
+
+/// Named design variable exposed by a theme, e.g. an accent color or body font
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThemeVariable {
+    pub name: String,
+    pub label: String,
+    pub kind: ThemeVariableKind,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeVariableKind {
+    Color,
+    Font,
+    Size,
+}
+
+impl ThemeVariable {
+    /// CSS custom property the variable is written to
+    pub fn css_property(&self) -> String {
+        format!("--theme-{}", self.name)
+    }
+    
+    /// Check the name and value are safe to write into a stylesheet
+    pub fn validate(&self) -> Result<(), String> {
+        let valid_name = !self.name.is_empty()
+            && self.name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+        if !valid_name {
+            return Err(format!("Variable name '{}' may only contain a-z, 0-9 and '-'", self.name));
+        }
+        
+        let value = self.value.trim();
+        if value.is_empty() || value.contains([';', '{', '}', '<', '>', '\\']) {
+            return Err(format!("Variable '{}' has an invalid value", self.name));
+        }
+        
+        let valid_value = match self.kind {
+            ThemeVariableKind::Color => is_css_color(value),
+            ThemeVariableKind::Font => value.chars().all(|c| c.is_alphanumeric() || " ,'\"-_".contains(c)),
+            ThemeVariableKind::Size => value
+                .trim_end_matches(|c: char| c.is_ascii_alphabetic() || c == '%')
+                .parse::<f32>()
+                .is_ok(),
+        };
+        
+        if valid_value {
+            Ok(())
+        } else {
+            Err(format!("Variable '{}' is not a valid {:?} value: {}", self.name, self.kind, value))
+        }
+    }
+}
+
+/// Accepts hex colors and rgb()/rgba()/hsl()/hsla() functions
+fn is_css_color(value: &str) -> bool {
+    if let Some(hex) = value.strip_prefix('#') {
+        return matches!(hex.len(), 3 | 4 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit());
+    }
+    
+    ["rgb(", "rgba(", "hsl(", "hsla("].iter().any(|prefix| {
+        value
+            .strip_prefix(prefix)
+            .and_then(|rest| rest.strip_suffix(')'))
+            .is_some_and(|args| args.chars().all(|c| c.is_ascii_digit() || " ,.%/deg".contains(c)))
+    })
+}
+
+/// Named layout a page or post can be rendered with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThemeTemplate {
+    pub name: String,
+    pub label: String,
+    pub layout: TemplateLayout,
+}
+
+/// Page structures the views know how to render
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TemplateLayout {
+    /// Centered reading column
+    #[default]
+    Default,
+    /// Content spans the full page width
+    FullWidth,
+    /// Content with a sidebar next to it
+    Sidebar,
+    /// No navigation chrome, for landing pages
+    Landing,
+}
+
+impl TemplateLayout {
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            Self::Default => "layout-default",
+            Self::FullWidth => "layout-full-width",
+            Self::Sidebar => "layout-sidebar",
+            Self::Landing => "layout-landing",
+        }
+    }
+}
+
+/// A redirect from an old path, checked by the server before routing
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Redirect {
+    pub id: u32,
+    /// Request path matched exactly, e.g. `/post/old-slug`
+    pub source: String,
+    /// Path or absolute URL to send visitors to; empty for 410 Gone
+    pub target: String,
+    /// 301, 302 or 410
+    pub status: u16,
+    pub hits: u32,
+    pub created_at: String,
+}
+
+impl Redirect {
+    pub const STATUSES: [u16; 3] = [301, 302, 410];
+
+    /// Permanent redirect, as created when a slug changes
+    pub fn permanent(source: &str, target: &str) -> Self {
+        Self {
+            source: normalize_redirect_path(source),
+            target: target.trim().to_string(),
+            status: 301,
+            ..Default::default()
+        }
+    }
+
+    pub fn is_gone(&self) -> bool {
+        self.status == 410
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.source.starts_with('/') {
+            return Err(format!("Source \"{}\" must be a path starting with /", self.source));
+        }
+        if !Self::STATUSES.contains(&self.status) {
+            return Err(format!("Status {} is not one of 301, 302 or 410", self.status));
+        }
+        if !self.is_gone() {
+            if self.target.is_empty() {
+                return Err(format!("Redirect from {} has no target", self.source));
+            }
+            if normalize_redirect_path(&self.target) == self.source {
+                return Err(format!("{} redirects to itself", self.source));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Canonical form of a request path for redirect matching: leading slash,
+/// no query string or fragment, no trailing slash
+pub fn normalize_redirect_path(path: &str) -> String {
+    let path = path.trim().split(['?', '#']).next().unwrap_or_default().trim_end_matches('/');
+    if path.starts_with('/') || path.contains("://") {
+        path.to_string()
+    } else {
+        format!("/{}", path)
+    }
+}
+
+/// Static page, e.g. About or Contact
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Page {
+    pub id: u32,
+    pub slug: String,
+    pub title: String,
+    pub content: String,
+    pub author: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub published: bool,
+    pub template: String, // Template to use for rendering
+    #[serde(default)]
+    pub meta_description: Option<String>,
+    #[serde(default)]
+    pub meta_keywords: Option<String>,
+    #[serde(default)]
+    pub cover_image: Option<String>,
+    /// Embargo: hidden from readers until this time (UTC), even when published
+    #[serde(default)]
+    pub publish_at: Option<String>,
+    /// Hidden from readers from this time (UTC) on
+    #[serde(default)]
+    pub expire_at: Option<String>,
+    /// Language code of the content, e.g. `es`
+    #[serde(default)]
+    pub language: String,
+    /// Shared by a page and its translations into other languages
+    #[serde(default)]
+    pub translation_group: Option<String>,
+}
+
+impl Page {
+    /// Whether readers can see the page at `now` (`YYYY-MM-DDTHH:MM:SSZ`)
+    pub fn is_live_at(&self, now: &str) -> bool {
+        self.published && window_open(self.publish_at.as_deref(), self.expire_at.as_deref(), now)
+    }
+    
+    /// `/page/:slug`, or `/:language/page/:slug` for pages not in the site's default language
+    pub fn path(&self, default_language: &str) -> String {
+        crate::localized_path(&self.language, default_language, &format!("/page/{}", self.slug))
+    }
+}
+
+/// Whether `now` falls in the window from `publish_at` (inclusive) to `expire_at` (exclusive);
+/// missing ends are open. Timestamps compare as text in `YYYY-MM-DDTHH:MM:SSZ` form.
+pub fn window_open(publish_at: Option<&str>, expire_at: Option<&str>, now: &str) -> bool {
+    publish_at.is_none_or(|publish_at| publish_at <= now) && expire_at.is_none_or(|expire_at| expire_at > now)
+}
+
+/// Check an embargo and expiry pair and bring both to `YYYY-MM-DDTHH:MM:SSZ` form
+pub fn normalize_window(publish_at: Option<&str>, expire_at: Option<&str>) -> Result<(Option<String>, Option<String>), String> {
+    let normalize = |value: Option<&str>, name: &str| match value.map(str::trim).filter(|value| !value.is_empty()) {
+        None => Ok(None),
+        Some(value) => crate::time::normalize_timestamp(value)
+            .map(Some)
+            .ok_or_else(|| format!("{} \"{}\" is not a valid time", name, value)),
+    };
+    
+    let publish_at = normalize(publish_at, "Publish at")?;
+    let expire_at = normalize(expire_at, "Expire at")?;
+    if let (Some(publish_at), Some(expire_at)) = (&publish_at, &expire_at) {
+        if expire_at <= publish_at {
+            return Err("Content must expire after it is published".to_string());
+        }
+    }
+    Ok((publish_at, expire_at))
+}
+
+/// An action on a post, page or media file to be run by the server at `scheduled_at`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledContent {
+    pub id: u32,
+    pub content_type: ContentType,
+    pub content_id: u32,
+    pub scheduled_at: String, // ISO 8601 timestamp
+    pub action: ScheduledAction,
+    pub status: ScheduleStatus,
+    pub created_at: String,
+    pub created_by: u32,
+    /// For `Update`, the JSON of the post or page to save in place of the current one
+    #[serde(default)]
+    pub payload: Option<String>,
+    /// Why the job failed
+    #[serde(default)]
+    pub error: Option<String>,
+    /// When the job finished, successfully or not
+    #[serde(default)]
+    pub completed_at: Option<String>,
+}
+
+impl ScheduledContent {
+    /// A pending job for `content_id`, to run at `scheduled_at`
+    pub fn new(content_type: ContentType, content_id: u32, action: ScheduledAction, scheduled_at: &str) -> Self {
+        Self {
+            id: 0,
+            content_type,
+            content_id,
+            scheduled_at: scheduled_at.to_string(),
+            action,
+            status: ScheduleStatus::Pending,
+            created_at: String::new(),
+            created_by: 0,
+            payload: None,
+            error: None,
+            completed_at: None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if crate::time::normalize_timestamp(&self.scheduled_at).is_none() {
+            return Err(format!("\"{}\" is not a valid time", self.scheduled_at));
+        }
+        match (&self.content_type, &self.action) {
+            (ContentType::Media, ScheduledAction::Delete) => Ok(()),
+            (ContentType::Media, action) => Err(format!("Media files can't be scheduled to {}", action.label().to_lowercase())),
+            (_, ScheduledAction::Update) if self.payload.is_none() => Err("An update needs the new content".to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ContentType {
+    Post,
+    Page,
+    Media,
+}
+
+impl ContentType {
+    pub const ALL: [ContentType; 3] = [ContentType::Post, ContentType::Page, ContentType::Media];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Post => "post",
+            Self::Page => "page",
+            Self::Media => "media",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|content_type| content_type.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScheduledAction {
+    Publish,
+    Unpublish,
+    Delete,
+    Update,
+}
+
+impl ScheduledAction {
+    pub const ALL: [ScheduledAction; 4] = [
+        ScheduledAction::Publish,
+        ScheduledAction::Unpublish,
+        ScheduledAction::Delete,
+        ScheduledAction::Update,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Publish => "publish",
+            Self::Unpublish => "unpublish",
+            Self::Delete => "delete",
+            Self::Update => "update",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.as_str() == value)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Publish => "Publish",
+            Self::Unpublish => "Unpublish",
+            Self::Delete => "Delete",
+            Self::Update => "Update",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScheduleStatus {
+    Pending,
+    /// Claimed by a server instance and running
+    Processing,
+    Completed,
+    Failed,
+}
+
+impl ScheduleStatus {
+    pub const ALL: [ScheduleStatus; 4] = [
+        ScheduleStatus::Pending,
+        ScheduleStatus::Processing,
+        ScheduleStatus::Completed,
+        ScheduleStatus::Failed,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Processing => "processing",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|status| status.as_str() == value)
+    }
+
+    /// Whether the job has run, successfully or not
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed)
+    }
 }
 
 /// SEO metadata
@@ -90,13 +676,331 @@ pub struct SeoMetadata {
     pub og_image: Option<String>,
 }
 
-/// Analytics data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Analytics data. No cookies or IP addresses are stored; visitors are told apart by a
+/// salted hash whose salt changes every day, so they can't be followed across days.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct AnalyticsEvent {
     pub id: u32,
+    /// `pageview`, or `leave` when the visitor closes or navigates away from a page
     pub event_type: String,
     pub path: String,
-    pub user_agent: Option<String>,
-    pub ip_address: Option<String>,
+    /// Hash of the day's salt, the client IP and user agent
+    pub visitor_hash: String,
+    /// Host of the referring site, if it was another site
+    pub referrer: Option<String>,
+    /// `desktop`, `mobile` or `tablet`, from the user agent
+    #[serde(default)]
+    pub device: Option<String>,
     pub timestamp: String,
+}
+
+impl AnalyticsEvent {
+    pub const PAGE_VIEW: &'static str = "pageview";
+    pub const LEAVE: &'static str = "leave";
+}
+
+/// Traffic summary for one day, computed from stored analytics events
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DailyStats {
+    pub date: String,
+    pub total_views: u32,
+    pub unique_visitors: u32,
+    /// Seconds from a visitor's first event of the day to their last
+    pub avg_session_duration: f64,
+    /// Share of visitors who viewed a single page, from 0 to 1
+    pub bounce_rate: f64,
+    pub top_pages: Vec<(String, u32)>, // (url, views)
+    #[serde(default)]
+    pub top_referrers: Vec<(String, u32)>, // (host, views)
+    #[serde(default)]
+    pub devices: Vec<(String, u32)>, // (device, visitors)
+}
+
+impl DailyStats {
+    /// Entries kept in `top_pages` and `top_referrers`
+    pub const TOP_ENTRIES: usize = 10;
+
+    /// Summarize the events of `date` (`YYYY-MM-DD`); events from other days are ignored
+    pub fn from_events(date: &str, events: &[AnalyticsEvent]) -> Self {
+        use std::collections::HashMap;
+
+        // (first second of the day seen, last second seen, page views, device) per visitor
+        let mut visitors: HashMap<&str, (u32, u32, u32, Option<&str>)> = HashMap::new();
+        let mut pages: HashMap<String, u32> = HashMap::new();
+        let mut referrers: HashMap<String, u32> = HashMap::new();
+        let mut total_views = 0;
+
+        for event in events.iter().filter(|event| event.timestamp.starts_with(date)) {
+            let second = second_of_day(&event.timestamp);
+            let visitor = visitors.entry(event.visitor_hash.as_str()).or_insert((second, second, 0, None));
+            visitor.0 = visitor.0.min(second);
+            visitor.1 = visitor.1.max(second);
+
+            if event.event_type == AnalyticsEvent::PAGE_VIEW {
+                visitor.2 += 1;
+                visitor.3 = visitor.3.or(event.device.as_deref());
+                total_views += 1;
+                *pages.entry(event.path.clone()).or_default() += 1;
+                if let Some(referrer) = &event.referrer {
+                    *referrers.entry(referrer.clone()).or_default() += 1;
+                }
+            }
+        }
+
+        // Visitors who only sent `leave` events never had a counted page view
+        visitors.retain(|_, visitor| visitor.2 > 0);
+        let unique_visitors = visitors.len() as u32;
+
+        let (avg_session_duration, bounce_rate) = if visitors.is_empty() {
+            (0.0, 0.0)
+        } else {
+            let total_duration: u32 = visitors.values().map(|(first, last, _, _)| last - first).sum();
+            let bounces = visitors.values().filter(|visitor| visitor.2 == 1).count();
+            (
+                total_duration as f64 / visitors.len() as f64,
+                bounces as f64 / visitors.len() as f64,
+            )
+        };
+
+        let mut devices: HashMap<String, u32> = HashMap::new();
+        for (_, _, _, device) in visitors.values() {
+            *devices.entry(device.unwrap_or("unknown").to_string()).or_default() += 1;
+        }
+
+        Self {
+            date: date.to_string(),
+            total_views,
+            unique_visitors,
+            avg_session_duration,
+            bounce_rate,
+            top_pages: top_counts(pages, Self::TOP_ENTRIES),
+            top_referrers: top_counts(referrers, Self::TOP_ENTRIES),
+            devices: top_counts(devices, usize::MAX),
+        }
+    }
+}
+
+/// Page views and visitors in one hour, `hour` being `YYYY-MM-DDTHH`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HourlyStats {
+    pub hour: String,
+    pub views: u32,
+    pub unique_visitors: u32,
+}
+
+impl HourlyStats {
+    /// Per-hour counts for the hours of `date` that have page views
+    pub fn from_events(date: &str, events: &[AnalyticsEvent]) -> Vec<Self> {
+        use std::collections::{BTreeMap, HashSet};
+
+        let mut hours: BTreeMap<&str, (u32, HashSet<&str>)> = BTreeMap::new();
+        for event in events
+            .iter()
+            .filter(|event| event.timestamp.starts_with(date) && event.event_type == AnalyticsEvent::PAGE_VIEW)
+        {
+            let Some(hour) = event.timestamp.get(..13) else { continue };
+            let entry = hours.entry(hour).or_default();
+            entry.0 += 1;
+            entry.1.insert(event.visitor_hash.as_str());
+        }
+
+        hours
+            .into_iter()
+            .map(|(hour, (views, visitors))| Self {
+                hour: hour.to_string(),
+                views,
+                unique_visitors: visitors.len() as u32,
+            })
+            .collect()
+    }
+}
+
+/// Totals over several days of `DailyStats`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AnalyticsTotals {
+    pub views: u32,
+    /// Sum of daily unique visitors. Visitor hashes change every day, so the same person
+    /// on two days counts twice.
+    pub unique_visitors: u32,
+    pub avg_session_duration: f64,
+    pub bounce_rate: f64,
+}
+
+impl AnalyticsTotals {
+    pub fn from_days(days: &[DailyStats]) -> Self {
+        let views = days.iter().map(|day| day.total_views).sum();
+        let unique_visitors: u32 = days.iter().map(|day| day.unique_visitors).sum();
+
+        // Per-visitor averages, weighted by each day's visitors
+        let weighted = |value: fn(&DailyStats) -> f64| {
+            if unique_visitors == 0 {
+                return 0.0;
+            }
+            days.iter().map(|day| value(day) * day.unique_visitors as f64).sum::<f64>() / unique_visitors as f64
+        };
+
+        Self {
+            views,
+            unique_visitors,
+            avg_session_duration: weighted(|day| day.avg_session_duration),
+            bounce_rate: weighted(|day| day.bounce_rate),
+        }
+    }
+}
+
+/// Analytics for a date range, with the same-length period before it for comparison
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AnalyticsReport {
+    pub start: String,
+    pub end: String,
+    pub totals: AnalyticsTotals,
+    pub previous: AnalyticsTotals,
+    /// One entry per day in the range, including days without traffic
+    pub days: Vec<DailyStats>,
+    pub top_pages: Vec<(String, u32)>,
+    pub top_referrers: Vec<(String, u32)>,
+    pub devices: Vec<(String, u32)>,
+}
+
+impl AnalyticsReport {
+    /// Build a report from the days of the range and of the period before it
+    pub fn new(start: &str, end: &str, days: Vec<DailyStats>, previous_days: &[DailyStats]) -> Self {
+        use std::collections::HashMap;
+
+        let merge = |field: fn(&DailyStats) -> &Vec<(String, u32)>, limit: usize| {
+            let mut counts: HashMap<String, u32> = HashMap::new();
+            for (key, count) in days.iter().flat_map(|day| field(day).iter()) {
+                *counts.entry(key.clone()).or_default() += count;
+            }
+            top_counts(counts, limit)
+        };
+
+        Self {
+            start: start.to_string(),
+            end: end.to_string(),
+            totals: AnalyticsTotals::from_days(&days),
+            previous: AnalyticsTotals::from_days(previous_days),
+            top_pages: merge(|day| &day.top_pages, DailyStats::TOP_ENTRIES),
+            top_referrers: merge(|day| &day.top_referrers, DailyStats::TOP_ENTRIES),
+            devices: merge(|day| &day.devices, usize::MAX),
+            days,
+        }
+    }
+
+    /// One CSV row per day of the range
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("date,views,unique_visitors,avg_session_duration_seconds,bounce_rate\n");
+        for day in &self.days {
+            csv.push_str(&format!(
+                "{},{},{},{:.1},{:.3}\n",
+                day.date, day.total_views, day.unique_visitors, day.avg_session_duration, day.bounce_rate
+            ));
+        }
+        csv
+    }
+}
+
+/// A performance measurement, e.g. a Web Vital reported by a browser or a server render time
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Metric {
+    pub name: String,
+    pub value: f64,
+    pub timestamp: String,
+    /// `route` holds the route pattern the metric was measured on, e.g. `/post/:slug`
+    pub metadata: std::collections::HashMap<String, String>,
+}
+
+impl Metric {
+    /// Core Web Vitals reported by the browser beacon; CLS is unitless, the rest are milliseconds
+    pub const WEB_VITALS: [&'static str; 4] = ["LCP", "INP", "CLS", "TTFB"];
+    /// Server-side render time in milliseconds, recorded by `ba-server`
+    pub const SERVER_RENDER: &'static str = "server_render";
+
+    pub fn new(name: &str, value: f64, route: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            value,
+            metadata: [("route".to_string(), route.to_string())].into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    pub fn route(&self) -> &str {
+        self.metadata.get("route").map(String::as_str).unwrap_or("")
+    }
+
+    /// (good, poor) thresholds from web.dev; values up to `good` are good, above `poor` are poor
+    pub fn thresholds(name: &str) -> Option<(f64, f64)> {
+        match name {
+            "LCP" => Some((2500.0, 4000.0)),
+            "INP" => Some((200.0, 500.0)),
+            "CLS" => Some((0.1, 0.25)),
+            "TTFB" => Some((800.0, 1800.0)),
+            Self::SERVER_RENDER => Some((200.0, 600.0)),
+            _ => None,
+        }
+    }
+}
+
+/// Percentiles of one metric on one route
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetricSummary {
+    pub route: String,
+    pub name: String,
+    pub samples: u32,
+    pub p50: f64,
+    pub p75: f64,
+    pub p95: f64,
+}
+
+impl MetricSummary {
+    pub fn from_values(route: &str, name: &str, mut values: Vec<f64>) -> Self {
+        values.sort_by(|a, b| a.total_cmp(b));
+        Self {
+            route: route.to_string(),
+            name: name.to_string(),
+            samples: values.len() as u32,
+            p50: percentile(&values, 50.0),
+            p75: percentile(&values, 75.0),
+            p95: percentile(&values, 95.0),
+        }
+    }
+
+    /// `good`, `needs-improvement` or `poor`, judged on the 75th percentile
+    pub fn rating(&self) -> &'static str {
+        match Metric::thresholds(&self.name) {
+            Some((good, _)) if self.p75 <= good => "good",
+            Some((_, poor)) if self.p75 <= poor => "needs-improvement",
+            Some(_) => "poor",
+            None => "",
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted values; 0 when there are none
+pub fn percentile(sorted: &[f64], percent: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (percent / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Percentage change from `previous` to `current`, or `None` when there was nothing before
+pub fn percent_change(current: f64, previous: f64) -> Option<f64> {
+    (previous != 0.0).then(|| (current - previous) / previous * 100.0)
+}
+
+/// Highest counts first, ties by key
+fn top_counts(counts: std::collections::HashMap<String, u32>, limit: usize) -> Vec<(String, u32)> {
+    let mut counts: Vec<(String, u32)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts.truncate(limit);
+    counts
+}
+
+/// Seconds since midnight of an ISO 8601 timestamp (`YYYY-MM-DDTHH:MM:SS...`)
+fn second_of_day(timestamp: &str) -> u32 {
+    let part = |range: std::ops::Range<usize>| timestamp.get(range).and_then(|s| s.parse::<u32>().ok()).unwrap_or(0);
+    part(11..13) * 3600 + part(14..16) * 60 + part(17..19)
 }
\ No newline at end of file
@@ -0,0 +1,74 @@
+//! Dates, relative times, numbers and file sizes in a few languages and time zones.
+
+use client::{Formatter, TimeZone};
+
+fn formatter(language: &str, time_zone: &str) -> Formatter {
+    Formatter::new(language, TimeZone::parse(time_zone).unwrap())
+}
+
+#[test]
+fn dates_follow_the_language_and_time_zone() {
+    let timestamp = "2024-01-05T23:30:00Z";
+    assert_eq!(formatter("en", "UTC").datetime(timestamp), "January 5, 2024, 11:30\u{a0}PM");
+    assert_eq!(formatter("es", "Europe/Madrid").datetime(timestamp), "6 de enero de 2024, 00:30");
+    assert_eq!(formatter("fr", "UTC+05:30").date(timestamp), "6 janvier 2024");
+    assert_eq!(formatter("de", "America/New_York").datetime(timestamp), "5. Januar 2024, 18:30");
+    // Dates without a time are shown as they are, wherever the site is
+    assert_eq!(formatter("en", "Asia/Tokyo").date("2024-01-05"), "January 5, 2024");
+    assert_eq!(formatter("en", "UTC").date("not a date"), "not a date");
+}
+
+#[test]
+fn daylight_saving_time_is_applied() {
+    let paris = TimeZone::parse("Europe/Paris").unwrap();
+    let new_york = TimeZone::parse("America/New_York").unwrap();
+    let at = |timestamp| client::timestamp_seconds(timestamp).unwrap();
+
+    // Europe switches on 2024-03-31 and 2024-10-27 at 01:00 UTC
+    assert_eq!(paris.offset_at(at("2024-03-31T00:59:59Z")), 60);
+    assert_eq!(paris.offset_at(at("2024-03-31T01:00:00Z")), 120);
+    assert_eq!(paris.offset_at(at("2024-10-27T01:00:00Z")), 60);
+    // North America switches on 2024-03-10 and 2024-11-03 at 02:00 local time
+    assert_eq!(new_york.offset_at(at("2024-03-10T06:59:59Z")), -300);
+    assert_eq!(new_york.offset_at(at("2024-03-10T07:00:00Z")), -240);
+    assert_eq!(new_york.offset_at(at("2024-11-03T05:59:59Z")), -240);
+    assert_eq!(new_york.offset_at(at("2024-11-03T06:00:00Z")), -300);
+}
+
+#[test]
+fn relative_times_use_plural_forms() {
+    let now = "2024-05-10T12:00:00Z";
+    let en = formatter("en", "UTC");
+    assert_eq!(en.relative("2024-05-10T11:59:30Z", now), "just now");
+    assert_eq!(en.relative("2024-05-10T11:59:00Z", now), "1 minute ago");
+    assert_eq!(en.relative("2024-05-07T12:00:00Z", now), "3 days ago");
+    assert_eq!(en.relative("2024-05-10T14:00:00Z", now), "in 2 hours");
+    assert_eq!(formatter("es", "UTC").relative("2024-05-09T12:00:00Z", now), "hace 1 día");
+    assert_eq!(formatter("fr", "UTC").relative("2022-05-10T12:00:00Z", now), "il y a 2 ans");
+    assert_eq!(formatter("de", "UTC").relative("2024-04-19T12:00:00Z", now), "vor 3 Wochen");
+}
+
+#[test]
+fn numbers_and_file_sizes_use_local_separators() {
+    assert_eq!(formatter("en", "UTC").number(1234567.891, 2), "1,234,567.89");
+    assert_eq!(formatter("de", "UTC").number(1234.5, 2), "1.234,5");
+    assert_eq!(formatter("es", "UTC").number(1234.0, 0), "1234");
+    assert_eq!(formatter("es", "UTC").number(12345.0, 0), "12.345");
+    assert_eq!(formatter("fr", "UTC").number(-12345.6, 1), "-12\u{202f}345,6");
+
+    assert_eq!(formatter("en", "UTC").file_size(15432), "15.4 KB");
+    assert_eq!(formatter("en", "UTC").file_size(512), "512 B");
+    assert_eq!(formatter("en", "UTC").file_size(999_960), "1 MB");
+    assert_eq!(formatter("fr", "UTC").file_size(2_500_000), "2,5 Mo");
+}
+
+#[test]
+fn time_zones_parse_names_and_offsets() {
+    assert_eq!(TimeZone::parse("").unwrap(), TimeZone::utc());
+    assert_eq!(TimeZone::parse("UTC+0").unwrap(), TimeZone::utc());
+    assert_eq!(TimeZone::parse("+02:00").unwrap().name(), "UTC+02:00");
+    assert_eq!(TimeZone::parse("utc-3").unwrap().name(), "UTC-03:00");
+    assert_eq!(TimeZone::parse("europe/berlin").unwrap().name(), "Europe/Berlin");
+    assert!(TimeZone::parse("Mars/Olympus_Mons").is_none());
+    assert!(TimeZone::parse("+25:00").is_none());
+}
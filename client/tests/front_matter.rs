@@ -0,0 +1,58 @@
+//! YAML and TOML front matter, mapped onto posts, with errors on the right line.
+
+use client::{strip_front_matter, FrontMatter, Post};
+
+#[test]
+fn yaml_front_matter_maps_onto_a_post() {
+    let document = "---\ntitle: Hello, world\nslug: hello-world\ndate: 2024-05-01\ntags: [rust, cms]\ndescription: A first post\ndraft: true\ntemplate: wide\ncover: /uploads/hello.png\nlayout: ignored\n---\n# Hello\n";
+    let (front_matter, body) = FrontMatter::parse(document).unwrap();
+    assert_eq!(body, "# Hello\n");
+
+    let mut post = Post { published: true, ..Post::default() };
+    front_matter.unwrap().apply_to_post(&mut post);
+    assert_eq!(post.title, "Hello, world");
+    assert_eq!(post.slug, "hello-world");
+    assert_eq!(post.created_at, "2024-05-01T00:00:00Z");
+    assert_eq!(post.meta_keywords.as_deref(), Some("rust, cms"));
+    assert_eq!(post.meta_description.as_deref(), Some("A first post"));
+    assert!(!post.published);
+    assert_eq!(post.template.as_deref(), Some("wide"));
+    assert_eq!(post.cover_image.as_deref(), Some("/uploads/hello.png"));
+}
+
+#[test]
+fn toml_front_matter_with_native_dates() {
+    let document = "+++\ntitle = \"Hola\"\ndate = 2024-05-01T09:30:00Z\ntags = \"uno, dos\"\n+++\nCuerpo";
+    let (front_matter, body) = FrontMatter::parse(document).unwrap();
+    let front_matter = front_matter.unwrap();
+
+    assert_eq!(body, "Cuerpo");
+    assert_eq!(front_matter.title.as_deref(), Some("Hola"));
+    assert_eq!(front_matter.tags, ["uno", "dos"]);
+    let mut post = Post::default();
+    front_matter.apply_to_post(&mut post);
+    assert_eq!(post.created_at, "2024-05-01T09:30:00Z");
+}
+
+#[test]
+fn errors_point_at_the_offending_line() {
+    let line = |document: &str| FrontMatter::parse(document).unwrap_err().line;
+
+    // Wrong types, as reported by the parsers
+    assert_eq!(line("---\ntitle: Hello\ndraft: sometimes\n---\n"), 3);
+    assert_eq!(line("+++\ntitle = \"Hello\"\n\ndraft = \"no\"\n+++\n"), 4);
+    // Checked afterwards
+    assert_eq!(line("---\ntitle: Hello\n\nslug: Not A Slug\n---\n"), 4);
+    assert_eq!(line("+++\ndate = \"last tuesday\"\n+++\n"), 2);
+    // Never closed
+    assert_eq!(line("---\ntitle: Hello\n"), 1);
+}
+
+#[test]
+fn documents_without_front_matter_are_left_alone() {
+    let document = "# Title\n\n---\n\nAfter a rule";
+    assert_eq!(FrontMatter::parse(document).unwrap(), (None, document));
+    assert_eq!(strip_front_matter(document), document);
+    assert_eq!(strip_front_matter("---\ntitle: x\n---\nBody"), "Body");
+    assert_eq!(FrontMatter::parse("---\n---\nBody").unwrap(), (Some(FrontMatter::default()), "Body"));
+}
@@ -0,0 +1,115 @@
+//! CLDR plural categories and message formatting in a few languages.
+
+use client::{format_message, plural_category, MessageArg, PluralCategory};
+use PluralCategory::*;
+
+/// Check the plural category of each number in `cases`
+fn assert_categories(language: &str, cases: &[(f64, PluralCategory)]) {
+    for (n, category) in cases {
+        assert_eq!(plural_category(language, *n), *category, "{} in {}", n, language);
+    }
+}
+
+#[test]
+fn english_has_one_and_other() {
+    assert_categories("en", &[(1.0, One), (0.0, Other), (2.0, Other), (11.0, Other), (21.0, Other)]);
+    // One needs an integer (v = 0)
+    assert_categories("en", &[(1.5, Other), (0.1, Other)]);
+    // Languages without their own rules use these
+    assert_categories("xx", &[(1.0, One), (5.0, Other)]);
+}
+
+#[test]
+fn french_counts_zero_as_one_and_millions_as_many() {
+    assert_categories("fr", &[(0.0, One), (1.0, One), (1.5, One), (2.0, Other), (100.0, Other)]);
+    assert_categories("fr", &[(1_000_000.0, Many), (3_000_000.0, Many), (1_000_001.0, Other), (1_500_000.0, Other)]);
+    // Region subtags use the language's rules
+    assert_eq!(plural_category("fr-CA", 0.0), One);
+    assert_eq!(plural_category("fr_BE", 2_000_000.0), Many);
+}
+
+#[test]
+fn spanish_one_is_exactly_one() {
+    assert_categories("es", &[(1.0, One), (0.0, Other), (1.5, Other), (2.0, Other), (1_000_000.0, Many), (1_000_000.5, Other)]);
+}
+
+#[test]
+fn russian_follows_the_last_digits() {
+    assert_categories("ru", &[(1.0, One), (21.0, One), (101.0, One), (11.0, Many), (111.0, Many)]);
+    assert_categories("ru", &[(2.0, Few), (4.0, Few), (22.0, Few), (12.0, Many), (14.0, Many)]);
+    assert_categories("ru", &[(0.0, Many), (5.0, Many), (20.0, Many), (25.0, Many)]);
+    // Any visible fraction digits (v != 0)
+    assert_categories("ru", &[(1.5, Other), (2.25, Other)]);
+}
+
+#[test]
+fn polish_one_is_only_one() {
+    assert_categories("pl", &[(1.0, One), (21.0, Many), (0.0, Many), (5.0, Many), (12.0, Many)]);
+    assert_categories("pl", &[(2.0, Few), (3.0, Few), (22.0, Few), (104.0, Few), (112.0, Many)]);
+    assert_categories("pl", &[(1.5, Other), (0.5, Other)]);
+}
+
+#[test]
+fn arabic_has_all_six() {
+    assert_categories("ar", &[(0.0, Zero), (1.0, One), (2.0, Two)]);
+    assert_categories("ar", &[(3.0, Few), (10.0, Few), (103.0, Few), (1010.0, Few)]);
+    assert_categories("ar", &[(11.0, Many), (99.0, Many), (111.0, Many)]);
+    assert_categories("ar", &[(100.0, Other), (102.0, Other), (1.5, Other), (10.5, Other)]);
+}
+
+#[test]
+fn fraction_digits_are_read_from_the_number() {
+    // Danish: one when n = 1, or when there are fraction digits (f != 0) and i is 0 or 1
+    assert_categories("da", &[(1.0, One), (0.1, One), (1.25, One), (0.0, Other), (2.5, Other)]);
+    // Hebrew: one for 1, or 0 with fraction digits (i = 0, v != 0)
+    assert_categories("he", &[(1.0, One), (0.5, One), (1.5, Other), (2.0, Two)]);
+    // Negative numbers use their absolute value
+    assert_eq!(plural_category("ru", -21.0), One);
+}
+
+fn format(message: &str, language: &str, args: &[(&str, MessageArg)]) -> String {
+    format_message(message, language, args)
+}
+
+#[test]
+fn arguments_are_filled_in() {
+    assert_eq!(format("Hello, { $name }!", "en", &[("name", "Ada".into())]), "Hello, Ada!");
+    assert_eq!(format("{$a} and {$b}", "en", &[("b", "two".into()), ("a", "one".into())]), "one and two");
+    // Numbers get the language's separators
+    assert_eq!(format("{ $count } views", "en", &[("count", 12345.into())]), "12,345 views");
+    assert_eq!(format("{ $size } MB", "de", &[("size", 1.5.into())]), "1,5 MB");
+    // Missing arguments stay visible, and literal braces are quoted
+    assert_eq!(format("Hi { $name }", "en", &[]), "Hi {$name}");
+    assert_eq!(format("{ \"{\" }code{ \"}\" }", "en", &[]), "{code}");
+    // Messages that don't parse are shown as written
+    assert_eq!(format("Hi { $name", "en", &[("name", "Ada".into())]), "Hi { $name");
+}
+
+#[test]
+fn numbers_select_exact_variants_then_plural_categories() {
+    let message = "{ $count -> [0] No posts [one] One post *[other] { $count } posts }";
+    let posts = |count: i32| format(message, "en", &[("count", count.into())]);
+    assert_eq!(posts(0), "No posts");
+    assert_eq!(posts(1), "One post");
+    assert_eq!(posts(1000), "1,000 posts");
+
+    let message = "{ $count -> [one] { $count } статья [few] { $count } статьи *[many] { $count } статей }";
+    let articles = |count: i32| format(message, "ru", &[("count", count.into())]);
+    assert_eq!(articles(21), "21 статья");
+    assert_eq!(articles(3), "3 статьи");
+    assert_eq!(articles(11), "11 статей");
+
+    // A category without a variant falls back to the default
+    let message = "{ $count -> [one] un fichier *[other] { $count } fichiers }";
+    assert_eq!(format(message, "fr", &[("count", 0.into())]), "un fichier");
+    assert_eq!(format(message, "fr", &[("count", 1_000_000.into())]), "1\u{202f}000\u{202f}000 fichiers");
+}
+
+#[test]
+fn strings_select_the_variant_with_their_name() {
+    let message = "{ $gender -> [female] She *[other] They } replied to { $post }";
+    let reply = |gender: &str| format(message, "en", &[("gender", gender.into()), ("post", "Hello".into())]);
+    assert_eq!(reply("female"), "She replied to Hello");
+    assert_eq!(reply("unknown"), "They replied to Hello");
+    assert_eq!(format(message, "en", &[]), "They replied to {$post}");
+}
@@ -0,0 +1,114 @@
+//! Choosing a request's language, and setting it on the `<html>` tag of streamed pages.
+
+use client::{HtmlLocaleRewriter, Language, RequestLocale, TextDirection};
+
+fn language(code: &str, direction: TextDirection, active: bool) -> Language {
+    Language {
+        code: code.to_string(),
+        name: code.to_string(),
+        native_name: code.to_string(),
+        direction,
+        active,
+    }
+}
+
+fn languages() -> Vec<Language> {
+    vec![
+        language("en", TextDirection::LeftToRight, true),
+        language("fr", TextDirection::LeftToRight, true),
+        language("pt-BR", TextDirection::LeftToRight, true),
+        language("ar", TextDirection::RightToLeft, true),
+        language("de", TextDirection::LeftToRight, false),
+    ]
+}
+
+fn negotiate(path: &str, preference: Option<&str>, accept_language: Option<&str>) -> RequestLocale {
+    RequestLocale::negotiate(path, preference, accept_language, &languages(), "en")
+}
+
+#[test]
+fn the_url_prefix_wins() {
+    let locale = negotiate("/ar/post/hello", Some("fr"), Some("fr"));
+    assert_eq!(locale.language, "ar");
+    assert_eq!(locale.direction, TextDirection::RightToLeft);
+    // Unprefixed pages still use what the visitor asked for
+    assert_eq!(locale.preferred, "fr");
+
+    // Inactive languages and lookalike segments aren't prefixes
+    assert_eq!(negotiate("/de/post/hallo", None, None).language, "en");
+    assert_eq!(negotiate("/arabic", None, None).language, "en");
+}
+
+#[test]
+fn a_saved_preference_comes_before_the_browser() {
+    assert_eq!(negotiate("/", Some("fr"), Some("ar")).language, "fr");
+    // Languages the site no longer serves are ignored
+    assert_eq!(negotiate("/", Some("de"), Some("ar")).language, "ar");
+    assert_eq!(negotiate("/", None, None).language, "en");
+}
+
+#[test]
+fn accept_language_is_matched_by_quality_and_primary_language() {
+    assert_eq!(negotiate("/", None, Some("de;q=1, fr-CH;q=0.8, en;q=0.5")).language, "fr");
+    assert_eq!(negotiate("/", None, Some("pt")).language, "pt-BR");
+    assert_eq!(negotiate("/", None, Some("ar;q=0, en")).language, "en");
+    assert_eq!(negotiate("/", None, Some("ja, zh")).language, "en");
+}
+
+fn arabic() -> HtmlLocaleRewriter {
+    HtmlLocaleRewriter::new(&negotiate("/ar/", None, None))
+}
+
+/// The page as it leaves the rewriter, sent in `chunks`
+fn rewrite(chunks: &[&str]) -> String {
+    let mut rewriter = arabic();
+    let mut page = Vec::new();
+    for chunk in chunks {
+        page.extend(rewriter.push(chunk.as_bytes()));
+    }
+    page.extend(rewriter.finish());
+    String::from_utf8(page).unwrap()
+}
+
+#[test]
+fn existing_lang_and_dir_are_replaced() {
+    assert_eq!(
+        rewrite(&["<!DOCTYPE html><html lang=\"en\" class=\"dark\" DIR='ltr' data-x=\"a>b\"><head></head></html>"]),
+        "<!DOCTYPE html><html class=\"dark\" data-x=\"a>b\" lang=\"ar\" dir=\"rtl\"><head></head></html>",
+    );
+    assert_eq!(rewrite(&["<html><body>"]), "<html lang=\"ar\" dir=\"rtl\"><body>");
+    // Only the document's own tag
+    assert_eq!(
+        rewrite(&["<html>", "<p>&lt;html lang=\"en\"&gt; <htmlish></p>", "</html>"]),
+        "<html lang=\"ar\" dir=\"rtl\"><p>&lt;html lang=\"en\"&gt; <htmlish></p></html>",
+    );
+}
+
+#[test]
+fn a_tag_split_across_chunks_is_held_back() {
+    let mut rewriter = arabic();
+    assert_eq!(rewriter.push(b"<!DOCTYPE html><ht"), b"<!DOCTYPE html>");
+    assert_eq!(rewriter.push(b"ml lang=\"e"), b"");
+    assert_eq!(rewriter.push(b"n\" dir=\"ltr\"><head>"), b"<html lang=\"ar\" dir=\"rtl\"><head>");
+    assert_eq!(rewriter.push(b"<html lang=\"en\">"), b"<html lang=\"en\">");
+
+    for split in 1..30 {
+        let page = "<!DOCTYPE html><html lang=\"en\"><body>مرحبا</body></html>";
+        let (first, second) = page.as_bytes().split_at(split);
+        let mut rewriter = arabic();
+        let mut rewritten = rewriter.push(first);
+        rewritten.extend(rewriter.push(second));
+        rewritten.extend(rewriter.finish());
+        assert_eq!(
+            String::from_utf8(rewritten).unwrap(),
+            "<!DOCTYPE html><html lang=\"ar\" dir=\"rtl\"><body>مرحبا</body></html>",
+            "split at {}",
+            split,
+        );
+    }
+}
+
+#[test]
+fn pages_without_the_tag_pass_through() {
+    assert_eq!(rewrite(&["<p>no document</p>", "<h"]), "<p>no document</p><h");
+}
@@ -0,0 +1,46 @@
+//! Calendar dates and UTC timestamps as stored in the database.
+
+use client::time::{add_days, date_range, date_to_days, days_to_date, normalize_timestamp, timestamp_seconds};
+
+#[test]
+fn dates_count_days_from_the_epoch() {
+    assert_eq!(date_to_days("1970-01-01"), Some(0));
+    assert_eq!(date_to_days("1969-12-31"), Some(-1));
+    assert_eq!(date_to_days("2024-03-01"), Some(19_783));
+    // Only the date part of a timestamp is read
+    assert_eq!(date_to_days("2024-03-01T12:00:00Z"), Some(19_783));
+
+    // Round trips from 0001-01-01 to 9999-12-31
+    for days in [-719_162, -1, 0, 19_783, 2_932_896] {
+        assert_eq!(date_to_days(&days_to_date(days)), Some(days));
+    }
+}
+
+#[test]
+fn days_must_exist_in_their_month() {
+    for date in ["2024-01-31", "2024-04-30", "2024-02-29", "2000-02-29", "2023-02-28", "2023-12-31"] {
+        assert!(date_to_days(date).is_some(), "{}", date);
+    }
+    // 30-day months, February outside leap years, and century years that aren't leap years
+    for date in ["2024-04-31", "2024-06-31", "2024-09-31", "2024-11-31", "2023-02-29", "1900-02-29", "2100-02-29", "2024-02-30"] {
+        assert_eq!(date_to_days(date), None, "{}", date);
+    }
+    for date in ["2024-00-10", "2024-13-01", "2024-01-00", "2024-01-32", "2024-1-1", "yesterday"] {
+        assert_eq!(date_to_days(date), None, "{}", date);
+    }
+}
+
+#[test]
+fn invalid_dates_are_rejected_everywhere() {
+    assert_eq!(add_days("2024-02-28", 1).as_deref(), Some("2024-02-29"));
+    assert_eq!(add_days("2023-02-28", 1).as_deref(), Some("2023-03-01"));
+    assert_eq!(add_days("2023-02-31", 1), None);
+
+    assert_eq!(date_range("2024-02-28", "2024-03-01"), ["2024-02-28", "2024-02-29", "2024-03-01"]);
+    assert!(date_range("2024-02-28", "2024-02-30").is_empty());
+
+    assert_eq!(normalize_timestamp("2024-02-29T09:30").as_deref(), Some("2024-02-29T09:30:00Z"));
+    assert_eq!(normalize_timestamp("2023-02-29T09:30"), None);
+    assert_eq!(normalize_timestamp("2024-04-31T00:00:00Z"), None);
+    assert_eq!(timestamp_seconds("2024-06-31"), None);
+}
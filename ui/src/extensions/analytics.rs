@@ -1,57 +1,97 @@
 use dioxus::prelude::*;
 use super::{Extension, ExtensionRoute, ExtensionComponent};
-use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use client::time::{now_iso8601, today_date};
+use client::time::{add_days, today_date};
+use client::{percent_change, AnalyticsReport};
+use client::AnalyticsEvent;
+pub use client::{DailyStats, Metric, MetricSummary};
 
-/// Analytics metric
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Metric {
-    pub name: String,
-    pub value: f64,
-    pub timestamp: String,
-    pub metadata: HashMap<String, String>,
-}
+/// Sends a page view beacon on load and on every client-side navigation, a leave beacon
+/// when the page is hidden, and the Core Web Vitals (LCP, CLS, INP, TTFB) of the page load
+/// when it is first hidden. Does nothing when Do-Not-Track or Global Privacy Control is set,
+/// or on admin pages. No cookies or storage are used.
+const ANALYTICS_BEACON_SCRIPT: &str = r#"(function () {
+  if (window.__bananabitBeacon) return;
+  window.__bananabitBeacon = true;
+  if (navigator.doNotTrack === "1" || navigator.globalPrivacyControl) return;
+  var path = null;
+  var referrer = document.referrer || null;
+  function post(payload) {
+    if (payload.path.indexOf("/admin") === 0) return;
+    var body = JSON.stringify(payload);
+    if (navigator.sendBeacon) navigator.sendBeacon("/collect", body);
+    else fetch("/collect", { method: "POST", body: body, keepalive: true });
+  }
+  function send(event, p) {
+    post({ event: event, path: p, referrer: referrer });
+    referrer = null;
+  }
+  function view() {
+    if (location.pathname === path) return;
+    path = location.pathname;
+    send("pageview", path);
+  }
+  var pushState = history.pushState;
+  history.pushState = function () { pushState.apply(this, arguments); view(); };
+  addEventListener("popstate", view);
+  addEventListener("pagehide", function () { if (path) send("leave", path); });
+  view();
 
-/// Page view analytics
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AnalyticsPageView {
-    pub url: String,
-    pub title: String,
-    pub user_agent: String,
-    pub referrer: Option<String>,
-    pub timestamp: String,
-    pub duration: Option<u32>, // in seconds
-}
+  var landing = location.pathname;
+  var vitals = {};
+  var vitalsSent = false;
+  function observe(type, callback, options) {
+    try {
+      var observer = new PerformanceObserver(function (list) { list.getEntries().forEach(callback); });
+      observer.observe(Object.assign({ type: type, buffered: true }, options || {}));
+    } catch (e) {}
+  }
+  var navigation = performance.getEntriesByType ? performance.getEntriesByType("navigation")[0] : null;
+  if (navigation && navigation.responseStart > 0) vitals.TTFB = navigation.responseStart;
+  observe("largest-contentful-paint", function (entry) { vitals.LCP = entry.startTime; });
+  // CLS is the largest burst of shifts less than 1s apart, within a 5s window
+  var cls = 0, burst = 0, burstStart = 0, lastShift = 0;
+  observe("layout-shift", function (entry) {
+    if (entry.hadRecentInput) return;
+    if (entry.startTime - lastShift > 1000 || entry.startTime - burstStart > 5000) {
+      burst = 0;
+      burstStart = entry.startTime;
+    }
+    burst += entry.value;
+    lastShift = entry.startTime;
+    if (burst > cls) vitals.CLS = cls = burst;
+  });
+  // INP, approximated by the slowest interaction
+  observe("event", function (entry) {
+    if (entry.interactionId && entry.duration > (vitals.INP || 0)) vitals.INP = entry.duration;
+  }, { durationThreshold: 40 });
+  function sendVitals() {
+    if (vitalsSent || !Object.keys(vitals).length) return;
+    vitalsSent = true;
+    post({ event: "vitals", path: landing, metrics: vitals });
+  }
+  addEventListener("visibilitychange", function () { if (document.visibilityState === "hidden") sendVitals(); });
+  addEventListener("pagehide", sendVitals);
+})();"#;
 
 /// Performance analytics extension
 pub struct AnalyticsExtension {
     metrics: Vec<Metric>,
-    page_views: Vec<AnalyticsPageView>,
+    events: Vec<AnalyticsEvent>,
     daily_stats: HashMap<String, DailyStats>, // date -> stats
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DailyStats {
-    pub date: String,
-    pub total_views: u32,
-    pub unique_visitors: u32,
-    pub avg_session_duration: f64,
-    pub bounce_rate: f64,
-    pub top_pages: Vec<(String, u32)>, // (url, views)
-}
-
 impl AnalyticsExtension {
     pub fn new() -> Self {
         Self {
             metrics: Vec::new(),
-            page_views: Vec::new(),
+            events: Vec::new(),
             daily_stats: HashMap::new(),
         }
     }
     
-    pub fn track_page_view(&mut self, page_view: AnalyticsPageView) {
-        self.page_views.push(page_view);
+    pub fn track_event(&mut self, event: AnalyticsEvent) {
+        self.events.push(event);
     }
     
     pub fn record_metric(&mut self, metric: Metric) {
@@ -63,26 +103,7 @@ impl AnalyticsExtension {
     }
     
     pub fn generate_daily_stats(&mut self, date: &str) {
-        let views_today: Vec<&AnalyticsPageView> = self.page_views
-            .iter()
-            .filter(|view| view.timestamp.starts_with(date))
-            .collect();
-            
-        let total_views = views_today.len() as u32;
-        let unique_visitors = views_today.len() as u32; // Simplified
-        
-        let stats = DailyStats {
-            date: date.to_string(),
-            total_views,
-            unique_visitors,
-            avg_session_duration: 180.0, // Mock data
-            bounce_rate: 0.35,           // Mock data
-            top_pages: vec![
-                ("/".to_string(), total_views / 2),
-                ("/post/welcome-to-bananabit-cms".to_string(), total_views / 4),
-            ],
-        };
-        
+        let stats = DailyStats::from_events(date, &self.events);
         self.daily_stats.insert(date.to_string(), stats);
     }
 }
@@ -101,32 +122,7 @@ impl Extension for AnalyticsExtension {
     }
     
     fn init(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        // Add some sample data
-        let today = today_date();
-        
-        // Sample page views
-        for i in 0..50 {
-            self.track_page_view(AnalyticsPageView {
-                url: if i % 3 == 0 { "/" } else { "/post/welcome-to-bananabit-cms" }.to_string(),
-                title: if i % 3 == 0 { "Home" } else { "Welcome to BananaBit CMS" }.to_string(),
-                user_agent: "Mozilla/5.0 Browser".to_string(),
-                referrer: if i % 4 == 0 { Some("https://google.com".to_string()) } else { None },
-                timestamp: format!("{}T{:02}:00:00Z", today, (i % 24)),
-                duration: Some(120 + (i * 10) % 300),
-            });
-        }
-        
-        // Generate daily stats
-        self.generate_daily_stats(&today);
-        
-        // Sample metrics
-        self.record_metric(Metric {
-            name: "page_load_time".to_string(),
-            value: 1.2,
-            timestamp: now_iso8601(),
-            metadata: [("page".to_string(), "/".to_string())].iter().cloned().collect(),
-        });
-        
+        // Events are collected by the server's `/collect` beacon endpoint
         Ok(())
     }
     
@@ -154,145 +150,278 @@ impl Extension for AnalyticsExtension {
     }
 }
 
+/// Loads the first-party analytics beacon
+#[component]
+pub fn AnalyticsBeacon() -> Element {
+    rsx! {
+        document::Script { "{ANALYTICS_BEACON_SCRIPT}" }
+    }
+}
+
+/// "3:02" from seconds
+fn format_duration(seconds: f64) -> String {
+    let seconds = seconds.round() as u64;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// "↑ 12%" change against the previous period, and its CSS class. Rates (0 to 1) are
+/// compared in percentage points. `lower_is_better` flips the styling.
+fn change_label(current: f64, previous: f64, is_rate: bool, lower_is_better: bool) -> (String, &'static str) {
+    let (change, unit) = if is_rate {
+        ((current - previous) * 100.0, " pts")
+    } else {
+        match percent_change(current, previous) {
+            Some(change) => (change, "%"),
+            None if current > 0.0 => return ("↑ new".to_string(), "change-up"),
+            None => (0.0, "%"),
+        }
+    };
+    
+    if change.abs() < 0.5 {
+        return (format!("→ 0{}", unit), "change-none");
+    }
+    let arrow = if change > 0.0 { "↑" } else { "↓" };
+    let good = (change > 0.0) != lower_is_better;
+    (format!("{} {:.0}{}", arrow, change.abs(), unit), if good { "change-up" } else { "change-down" })
+}
+
 /// Analytics dashboard component
 #[component]
 pub fn AnalyticsDashboard() -> Element {
+    let today = today_date();
+    let mut start = use_signal(|| add_days(&today, -6).unwrap_or_else(|| today.clone()));
+    let mut end = use_signal(|| today.clone());
+    
+    let report = use_server_future(move || api::get_analytics_report(start(), end()))?;
+    let hourly = use_resource(move || async move {
+        if start() == end() { api::get_hourly_stats(end()).await.ok() } else { None }
+    });
+    
+    let mut preset = move |days: i64| {
+        let today = today_date();
+        start.set(add_days(&today, 1 - days).unwrap_or_else(|| today.clone()));
+        end.set(today);
+    };
+    
     rsx! {
         div {
-            h2 { "Performance Analytics" }
-            p { "Monitor your site's performance, traffic, and user engagement metrics." }
+            class: "analytics-dashboard",
+            h2 { "Analytics" }
+            p { "Cookieless first-party traffic. Visitors are counted per day and can't be followed across days." }
             
-            // Summary cards
             div {
-                div {
-                    h3 { "Today's Overview" }
-                    div {
-                        div {
-                            h4 { "47" }
-                            p { "Page Views" }
-                            span { "↑ 12%" }
-                        }
-                        div {
-                            h4 { "32" }
-                            p { "Unique Visitors" }
-                            span { "↑ 8%" }
-                        }
-                        div {
-                            h4 { "3:02" }
-                            p { "Avg. Session" }
-                            span { "↑ 5%" }
-                        }
-                        div {
-                            h4 { "35%" }
-                            p { "Bounce Rate" }
-                            span { "↓ 3%" }
-                        }
-                    }
+                class: "analytics-range",
+                label { "From" }
+                input {
+                    r#type: "date",
+                    value: "{start}",
+                    onchange: move |e| start.set(e.value()),
+                }
+                label { "to" }
+                input {
+                    r#type: "date",
+                    value: "{end}",
+                    onchange: move |e| end.set(e.value()),
+                }
+                button { onclick: move |_| preset(1), "Today" }
+                button { onclick: move |_| preset(7), "7 days" }
+                button { onclick: move |_| preset(30), "30 days" }
+                button { onclick: move |_| preset(90), "90 days" }
+                a {
+                    class: "button",
+                    href: "/analytics/export.csv?start={start}&end={end}",
+                    "Export CSV"
                 }
             }
             
-            // Charts section
-            div {
-                div {
-                    h3 { "Traffic Overview" }
-                    div {
-                        // Simplified chart representation
-                        div {
-                            div { "📊 Traffic chart would go here" }
-                            p { "Page views over the last 7 days" }
+            match &*report.read() {
+                None => rsx! { p { "Loading analytics..." } },
+                Some(Err(e)) => rsx! { p { class: "error", "Failed to load analytics: {e}" } },
+                Some(Ok(report)) => rsx! {
+                    AnalyticsSummary { report: report.clone() }
+                    
+                    if let Some(Some(hours)) = &*hourly.read() {
+                        TrafficChart {
+                            title: "Page views by hour".to_string(),
+                            bars: hours.iter().map(|hour| (hour.hour.get(11..13).unwrap_or_default().to_string(), hour.views)).collect::<Vec<_>>(),
+                        }
+                    } else {
+                        TrafficChart {
+                            title: "Page views by day".to_string(),
+                            bars: report.days.iter().map(|day| (day.date.get(5..).unwrap_or_default().to_string(), day.total_views)).collect::<Vec<_>>(),
                         }
                     }
-                }
-                
-                div {
-                    h3 { "Performance Metrics" }
+                    
                     div {
-                        div {
-                            h4 { "Page Load Time" }
-                            p { "1.2s average" }
-                            div { "█████░░░░░ Good" }
-                        }
-                        div {
-                            h4 { "Server Response" }
-                            p { "0.8s average" }
-                            div { "████████░░ Excellent" }
-                        }
-                        div {
-                            h4 { "Database Queries" }
-                            p { "12 avg per page" }
-                            div { "██████░░░░ Fair" }
-                        }
+                        class: "analytics-tables",
+                        CountTable { title: "Top Content".to_string(), label: "Page".to_string(), count_label: "Views".to_string(), rows: report.top_pages.clone() }
+                        CountTable { title: "Top Referrers".to_string(), label: "Site".to_string(), count_label: "Views".to_string(), rows: report.top_referrers.clone() }
+                        CountTable { title: "Devices".to_string(), label: "Device".to_string(), count_label: "Visitors".to_string(), rows: report.devices.clone() }
                     }
-                }
+                },
             }
             
-            // Top content section
-            div {
-                h3 { "Top Content" }
-                div {
-                    div {
-                        div {
-                            div { "Page" }
-                            div { "Views" }
-                            div { "Duration" }
-                            div { "Bounce Rate" }
-                        }
-                        
-                        div {
-                            div { "/" }
-                            div { "24" }
-                            div { "2:45" }
-                            div { "28%" }
-                        }
-                        
-                        div {
-                            div { "/post/welcome-to-bananabit-cms" }
-                            div { "18" }
-                            div { "4:12" }
-                            div { "22%" }
+            PerformanceTable { start: start(), end: end() }
+        }
+    }
+}
+
+/// Per-route percentiles of Web Vitals and server render times
+#[component]
+fn PerformanceTable(start: ReadOnlySignal<String>, end: ReadOnlySignal<String>) -> Element {
+    let summaries = use_server_future(move || api::get_metric_summaries(start(), end()))?;
+    
+    rsx! {
+        div {
+            class: "performance-metrics",
+            h3 { "Performance" }
+            p { "Core Web Vitals reported by visitors' browsers and server render times, per route. Ratings use the 75th percentile." }
+            
+            match &*summaries.read() {
+                Some(Ok(summaries)) if !summaries.is_empty() => rsx! {
+                    table {
+                        thead {
+                            tr {
+                                th { "Route" }
+                                th { "Metric" }
+                                th { "Samples" }
+                                th { "p50" }
+                                th { "p75" }
+                                th { "p95" }
+                            }
                         }
-                        
-                        div {
-                            div { "/admin" }
-                            div { "5" }
-                            div { "8:30" }
-                            div { "15%" }
+                        tbody {
+                            for summary in summaries.iter() {
+                                tr {
+                                    key: "{summary.route}-{summary.name}",
+                                    class: "metric-{summary.rating()}",
+                                    td { "{summary.route}" }
+                                    td { {metric_label(&summary.name)} }
+                                    td { "{summary.samples}" }
+                                    td { {format_metric(&summary.name, summary.p50)} }
+                                    td { {format_metric(&summary.name, summary.p75)} }
+                                    td { {format_metric(&summary.name, summary.p95)} }
+                                }
+                            }
                         }
                     }
+                },
+                Some(Err(e)) => rsx! { p { class: "error", "Failed to load performance metrics: {e}" } },
+                _ => rsx! { p { "No performance data for this period." } },
+            }
+        }
+    }
+}
+
+fn metric_label(name: &str) -> &str {
+    if name == Metric::SERVER_RENDER { "Server render" } else { name }
+}
+
+/// CLS is unitless; everything else is in milliseconds
+fn format_metric(name: &str, value: f64) -> String {
+    if name == "CLS" {
+        format!("{:.3}", value)
+    } else if value >= 1000.0 {
+        format!("{:.2} s", value / 1000.0)
+    } else {
+        format!("{:.0} ms", value)
+    }
+}
+
+/// Range totals with the change from the previous period
+#[component]
+fn AnalyticsSummary(report: AnalyticsReport) -> Element {
+    let current = &report.totals;
+    let previous = &report.previous;
+    let compare = |current: f64, previous_value: f64, is_rate: bool, lower_is_better: bool| {
+        if previous.views == 0 {
+            ("no data before".to_string(), "change-none")
+        } else {
+            change_label(current, previous_value, is_rate, lower_is_better)
+        }
+    };
+    let formatter = super::formatter();
+    let cards = [
+        ("Page Views", formatter.number(current.views as f64, 0), compare(current.views as f64, previous.views as f64, false, false)),
+        ("Unique Visitors", formatter.number(current.unique_visitors as f64, 0), compare(current.unique_visitors as f64, previous.unique_visitors as f64, false, false)),
+        ("Avg. Session", format_duration(current.avg_session_duration), compare(current.avg_session_duration, previous.avg_session_duration, false, false)),
+        ("Bounce Rate", format!("{:.0}%", current.bounce_rate * 100.0), compare(current.bounce_rate, previous.bounce_rate, true, true)),
+    ];
+    
+    rsx! {
+        div {
+            class: "quick-stats",
+            for (label, value, (change, class)) in cards {
+                div {
+                    class: "stat-box",
+                    h3 { "{value}" }
+                    p { "{label}" }
+                    span { class: "{class}", "{change}" }
                 }
             }
-            
-            // Real-time section
+        }
+    }
+}
+
+/// Bar chart of (label, value) pairs
+#[component]
+pub fn TrafficChart(title: String, bars: Vec<(String, u32)>) -> Element {
+    let max = bars.iter().map(|(_, value)| *value).max().unwrap_or(0).max(1);
+    let bars: Vec<(String, u32, f64)> = bars
+        .into_iter()
+        .map(|(label, value)| (label, value, value as f64 / max as f64 * 100.0))
+        .collect();
+    
+    rsx! {
+        div {
+            class: "traffic-chart",
+            h3 { "{title}" }
             div {
-                h3 { "Real-time Activity" }
-                div {
+                class: "traffic-bars",
+                for (label, value, height) in bars {
                     div {
-                        h4 { "Active Users" }
-                        p { "3 users online" }
-                    }
-                    
-                    div {
-                        h4 { "Recent Page Views" }
+                        class: "traffic-bar",
+                        title: "{label}: {value}",
                         div {
-                            div { "/ - 2 minutes ago" }
-                            div { "/post/welcome-to-bananabit-cms - 3 minutes ago" }
-                            div { "/ - 5 minutes ago" }
-                            div { "/admin/analytics - 8 minutes ago" }
+                            class: "traffic-bar-fill",
+                            style: "height: {height:.1}%",
                         }
+                        span { "{label}" }
                     }
                 }
             }
-            
-            // Export section
-            div {
-                h3 { "Export Data" }
-                div {
-                    button { "Export CSV" }
-                    button { "Export JSON" }
-                    button { "Generate Report" }
-                    button { "Schedule Email Reports" }
+        }
+    }
+}
+
+#[component]
+fn CountTable(title: String, label: String, count_label: String, rows: Vec<(String, u32)>) -> Element {
+    let formatter = super::formatter();
+
+    rsx! {
+        div {
+            h3 { "{title}" }
+            if rows.is_empty() {
+                p { "No data for this period." }
+            } else {
+                table {
+                    thead {
+                        tr {
+                            th { "{label}" }
+                            th { "{count_label}" }
+                        }
+                    }
+                    tbody {
+                        for (name, count) in rows {
+                            tr {
+                                key: "{name}",
+                                td { "{name}" }
+                                td { {formatter.number(count as f64, 0)} }
+                            }
+                        }
+                    }
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
@@ -41,6 +41,7 @@ impl AuthExtension {
             active: true,
             email_verified: false,
             verification_token: Some(verification_token.clone()),
+            color_scheme: None,
         };
         
         let user_id = user.id;
@@ -621,6 +622,14 @@ pub fn AdminDashboard() -> Element {
                 class: "admin-nav",
                 ul {
                     li { a { href: "/admin/posts", "Manage Posts" } }
+                    li { a { href: "/admin/seo", "SEO" } }
+                    li { a { href: "/admin/html", "Allowed HTML" } }
+                    li { a { href: "/admin/redirects", "Redirects" } }
+                    li { a { href: "/admin/analytics", "Analytics" } }
+                    li { a { href: "/admin/scheduling", "Scheduling" } }
+                    li { a { href: "/admin/calendar", "Calendar" } }
+                    li { a { href: "/admin/i18n", "Languages" } }
+                    li { a { href: "/admin/translations", "Translations" } }
                     li { a { href: "/admin/comments", "Manage Comments" } }
                     li { a { href: "/admin/users", "Manage Users" } }
                     li { a { href: "/admin/extensions", "Extensions" } }
@@ -129,6 +129,11 @@ impl Extension for CommentsExtension {
 
 #[component]
 pub fn CommentSection(post_id: u32) -> Element {
+    // Comments come from visitors, so they get the stricter policy
+    let html_settings = use_server_future(api::get_html_settings)?;
+    let html_policy = html_settings.read().as_ref().and_then(|result| result.as_ref().ok())
+        .map_or_else(client::HtmlPolicy::comments, |html| html.comments.clone());
+    
     rsx! {
         div {
             class: "comment-section",
@@ -147,7 +152,11 @@ pub fn CommentSection(post_id: u32) -> Element {
                     }
                     div {
                         class: "comment-content",
-                        p { "Great post! I love the extension architecture approach. It makes the CMS very flexible." }
+                        crate::Markdown {
+                            content: Some("Great post! I love the extension architecture approach. It makes the CMS very flexible.".to_string()),
+                            html_policy: Some(html_policy.clone()),
+                            shortcodes: false,
+                        }
                     }
                 }
                 
@@ -160,7 +169,11 @@ pub fn CommentSection(post_id: u32) -> Element {
                     }
                     div {
                         class: "comment-content",
-                        p { "I agree! Looking forward to seeing how this develops." }
+                        crate::Markdown {
+                            content: Some("I agree! Looking forward to seeing how this develops.".to_string()),
+                            html_policy: Some(html_policy.clone()),
+                            shortcodes: false,
+                        }
                     }
                 }
             }
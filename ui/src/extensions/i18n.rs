@@ -1,92 +1,18 @@
 use dioxus::prelude::*;
 use super::{Extension, ExtensionRoute, ExtensionComponent};
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-
-/// Language definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Language {
-    pub code: String,        // ISO 639-1 code (e.g., "en", "es", "fr")
-    pub name: String,        // Display name (e.g., "English", "Español")
-    pub native_name: String, // Native name (e.g., "English", "Español")
-    pub direction: TextDirection,
-    pub active: bool,
-}
+use std::collections::{BTreeMap, HashMap};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum TextDirection {
-    LeftToRight,
-    RightToLeft,
-}
+use client::{ContentType, Page, Post};
 
-/// Translation entry
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Translation {
-    pub key: String,
-    pub language_code: String,
-    pub value: String,
-    pub namespace: String, // e.g., "common", "posts", "admin"
-}
+pub use client::{split_message_id, Catalog, Formatter, Language, MessageArg, TextDirection, TimeZone, Translation, DEFAULT_LANGUAGE, DEFAULT_NAMESPACE};
 
-/// Multi-language support extension
-pub struct I18nExtension {
-    languages: HashMap<String, Language>,
-    translations: HashMap<String, HashMap<String, String>>, // lang_code -> (key -> value)
-    default_language: String,
-    current_language: String,
-}
+/// Multi-language support extension. Languages and messages are stored in the database;
+/// see [`client::i18n`] for the message format.
+pub struct I18nExtension;
 
 impl I18nExtension {
     pub fn new() -> Self {
-        Self {
-            languages: HashMap::new(),
-            translations: HashMap::new(),
-            default_language: "en".to_string(),
-            current_language: "en".to_string(),
-        }
-    }
-    
-    pub fn add_language(&mut self, language: Language) {
-        self.languages.insert(language.code.clone(), language);
-    }
-    
-    pub fn add_translation(&mut self, translation: Translation) {
-        let lang_map = self.translations
-            .entry(translation.language_code.clone())
-            .or_insert_with(HashMap::new);
-        lang_map.insert(translation.key.clone(), translation.value);
-    }
-    
-    pub fn get_translation(&self, key: &str, lang_code: Option<&str>) -> String {
-        let language = lang_code.unwrap_or(&self.current_language);
-        
-        if let Some(lang_map) = self.translations.get(language) {
-            if let Some(translation) = lang_map.get(key) {
-                return translation.clone();
-            }
-        }
-        
-        // Fallback to default language
-        if language != self.default_language {
-            if let Some(lang_map) = self.translations.get(&self.default_language) {
-                if let Some(translation) = lang_map.get(key) {
-                    return translation.clone();
-                }
-            }
-        }
-        
-        // Return key if no translation found
-        key.to_string()
-    }
-    
-    pub fn set_current_language(&mut self, lang_code: &str) {
-        if self.languages.contains_key(lang_code) {
-            self.current_language = lang_code.to_string();
-        }
-    }
-    
-    pub fn get_available_languages(&self) -> Vec<&Language> {
-        self.languages.values().filter(|lang| lang.active).collect()
+        Self
     }
 }
 
@@ -104,65 +30,7 @@ impl Extension for I18nExtension {
     }
     
     fn init(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        // Add default languages
-        self.add_language(Language {
-            code: "en".to_string(),
-            name: "English".to_string(),
-            native_name: "English".to_string(),
-            direction: TextDirection::LeftToRight,
-            active: true,
-        });
-        
-        self.add_language(Language {
-            code: "es".to_string(),
-            name: "Spanish".to_string(),
-            native_name: "Español".to_string(),
-            direction: TextDirection::LeftToRight,
-            active: true,
-        });
-        
-        self.add_language(Language {
-            code: "fr".to_string(),
-            name: "French".to_string(),
-            native_name: "Français".to_string(),
-            direction: TextDirection::LeftToRight,
-            active: true,
-        });
-        
-        // Add common translations
-        let common_translations = vec![
-            ("home", "en", "Home"),
-            ("home", "es", "Inicio"),
-            ("home", "fr", "Accueil"),
-            ("blog", "en", "Blog"),
-            ("blog", "es", "Blog"),
-            ("blog", "fr", "Blog"),
-            ("admin", "en", "Admin"),
-            ("admin", "es", "Administración"),
-            ("admin", "fr", "Administration"),
-            ("settings", "en", "Settings"),
-            ("settings", "es", "Configuración"),
-            ("settings", "fr", "Paramètres"),
-            ("save", "en", "Save"),
-            ("save", "es", "Guardar"),
-            ("save", "fr", "Enregistrer"),
-            ("cancel", "en", "Cancel"),
-            ("cancel", "es", "Cancelar"),
-            ("cancel", "fr", "Annuler"),
-            ("search", "en", "Search"),
-            ("search", "es", "Buscar"),
-            ("search", "fr", "Rechercher"),
-        ];
-        
-        for (key, lang, value) in common_translations {
-            self.add_translation(Translation {
-                key: key.to_string(),
-                language_code: lang.to_string(),
-                value: value.to_string(),
-                namespace: "common".to_string(),
-            });
-        }
-        
+        // Languages and shipped messages are seeded when the database is created
         Ok(())
     }
     
@@ -186,26 +54,736 @@ impl Extension for I18nExtension {
                 name: "LanguageSelector".to_string(),
                 description: "Language switcher component".to_string(),
             },
-            ExtensionComponent {
-                name: "TranslationEditor".to_string(),
-                description: "Edit translations for different languages".to_string(),
-            },
         ]
     }
 }
 
+
+/// Language the UI is shown in and its messages, provided by [`I18nProvider`]
+#[derive(Clone, Copy)]
+pub struct I18n {
+    /// Language picked by the visitor on this page, before the server knows about it
+    picked: Signal<Option<String>>,
+    default_language: Memo<String>,
+    language: Memo<String>,
+    languages: Memo<Vec<Language>>,
+    catalog: Memo<Catalog>,
+    /// The site's time zone, for dates and times
+    time_zone: Memo<TimeZone>,
+    /// Bumped after translations are edited so the catalog reloads
+    revision: Signal<u32>,
+}
+
+impl I18n {
+    pub fn language(&self) -> String {
+        self.language.read().clone()
+    }
+
+    /// Direction of the current language, for the page's `dir`
+    pub fn direction(&self) -> TextDirection {
+        self.direction_of(&self.language.read())
+    }
+
+    /// Direction of content in the language `code`
+    pub fn direction_of(&self, code: &str) -> TextDirection {
+        TextDirection::of(code, &self.languages.read())
+    }
+
+    /// The site's default language, used for content at unprefixed URLs
+    pub fn default_language(&self) -> String {
+        self.default_language.read().clone()
+    }
+
+    /// Switch to `code` and remember it for later visits. Pages under a language prefix
+    /// stay in that language.
+    pub fn set_language(&mut self, code: &str) {
+        self.picked.set(Some(code.to_string()));
+        let code = code.to_string();
+        spawn(async move {
+            // Already switched; if this fails the choice only lasts until the next page load
+            let _ = api::set_language_preference(Some(code)).await;
+        });
+    }
+
+    pub fn format(&self, id: &str, args: &[(&str, MessageArg)]) -> String {
+        self.catalog.read().format(id, args)
+    }
+
+    /// Dates, numbers and sizes in the current language and the site's time zone
+    pub fn formatter(&self) -> Formatter {
+        Formatter::new(&self.language.read(), self.time_zone.read().clone())
+    }
+
+    /// Reload messages, e.g. after saving a translation
+    pub fn reload(&mut self) {
+        self.revision += 1;
+    }
+}
+
+/// Loads the messages for the current language and provides [`I18n`] to everything inside.
+/// Resolved during SSR so server-rendered pages are already translated.
+///
+/// The language is the one in the URL prefix (`url_language`), then the one the visitor
+/// prefers, as negotiated by the server from their saved choice and browser.
+#[component]
+pub fn I18nProvider(url_language: ReadOnlySignal<Option<String>>, children: Element) -> Element {
+    let stored_default = use_server_future(api::get_default_language)?;
+    let locale = use_server_future(api::get_request_locale)?;
+    let stored_languages = use_server_future(api::get_languages)?;
+    let settings = use_server_future(api::get_seo_settings)?;
+    let picked = use_signal(|| None::<String>);
+    let revision = use_signal(|| 0u32);
+
+    let default_language = use_memo(move || match &*stored_default.read() {
+        Some(Ok(code)) => code.clone(),
+        _ => DEFAULT_LANGUAGE.to_string(),
+    });
+    let language = use_memo(move || {
+        let preferred = || match &*locale.read() {
+            Some(Ok(locale)) => locale.preferred.clone(),
+            _ => default_language.cloned(),
+        };
+        url_language().or_else(|| picked.cloned()).unwrap_or_else(preferred)
+    });
+    let languages = use_memo(move || match &*stored_languages.read() {
+        Some(Ok(languages)) => languages.clone(),
+        _ => Vec::new(),
+    });
+    let time_zone = use_memo(move || match &*settings.read() {
+        Some(Ok(settings)) => TimeZone::parse(&settings.time_zone).unwrap_or_default(),
+        _ => TimeZone::utc(),
+    });
+    let translations = use_server_future(move || {
+        revision();
+        api::get_translations(language())
+    })?;
+    let catalog = use_memo(move || match &*translations.read() {
+        Some(Ok(translations)) => Catalog::new(&language(), translations.iter().cloned()),
+        // Shipped messages until the stored ones arrive, or if they can't be loaded
+        _ => Catalog::builtin(&language()),
+    });
+
+    // The server sets `lang` and `dir` on the first page; keep them right as the visitor moves on
+    use_effect(move || {
+        let language = language();
+        let direction = TextDirection::of(&language, &languages.read());
+        document::eval(&format!(
+            "document.documentElement.lang = {:?}; document.documentElement.dir = {:?};",
+            language,
+            direction.as_str(),
+        ));
+    });
+
+    use_context_provider(|| I18n { picked, default_language, language, languages, catalog, time_zone, revision });
+
+    rsx! { {children} }
+}
+
+/// Provides [`I18n`] for `language` with the built-in messages, without asking the server.
+/// For rendering content outside the app, e.g. in tests.
+#[component]
+pub fn StaticI18nProvider(language: String, languages: Vec<Language>, children: Element) -> Element {
+    let picked = use_signal(|| None::<String>);
+    let revision = use_signal(|| 0u32);
+    let default_language = use_memo(|| DEFAULT_LANGUAGE.to_string());
+    let language = use_memo(move || language.clone());
+    let languages = use_memo(move || languages.clone());
+    let catalog = use_memo(move || Catalog::builtin(&language()));
+    let time_zone = use_memo(TimeZone::utc);
+
+    use_context_provider(|| I18n { picked, default_language, language, languages, catalog, time_zone, revision });
+
+    rsx! { {children} }
+}
+
+/// Format the message `id` (`namespace:key`, or `key` in the common namespace) in the
+/// current language. Components calling this re-render when the language changes.
+/// Usually called through [`t!`](crate::t).
+pub fn translate(id: &str, args: &[(&str, MessageArg)]) -> String {
+    match try_consume_context::<I18n>() {
+        Some(i18n) => i18n.format(id, args),
+        None => Catalog::builtin(DEFAULT_LANGUAGE).format(id, args),
+    }
+}
+
+/// Translate a message, with optional named arguments:
+///
+/// ```ignore
+/// t!("home")
+/// t!("search:result-count", count = posts.len(), query = &query)
+/// ```
+#[macro_export]
+macro_rules! t {
+    ($id:expr $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::extensions::i18n::translate(
+            $id,
+            &[$((stringify!($name), $crate::extensions::i18n::MessageArg::from($value))),*],
+        )
+    };
+}
+
+/// `lang` and `dir` attributes for content written in `language`; nothing when the
+/// content's language isn't known
+pub fn content_direction(language: &str) -> (Option<String>, Option<&'static str>) {
+    if language.is_empty() {
+        return (None, None);
+    }
+    let direction = match try_consume_context::<I18n>() {
+        Some(i18n) => i18n.direction_of(language),
+        None => TextDirection::default(),
+    };
+    (Some(language.to_string()), Some(direction.as_str()))
+}
+
+/// Formatter for the current language and the site's time zone; English and UTC outside
+/// [`I18nProvider`]
+pub fn formatter() -> Formatter {
+    match try_consume_context::<I18n>() {
+        Some(i18n) => i18n.formatter(),
+        None => Formatter::default(),
+    }
+}
+
+/// The site's default language, or the built-in one outside [`I18nProvider`]
+pub fn default_language() -> String {
+    match try_consume_context::<I18n>() {
+        Some(i18n) => i18n.default_language(),
+        None => DEFAULT_LANGUAGE.to_string(),
+    }
+}
+
+/// A post or page, as far as translation tracking is concerned
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranslatedContent {
+    pub content_type: ContentType,
+    pub id: u32,
+    pub title: String,
+    pub language: String,
+    pub translation_group: Option<String>,
+    pub published: bool,
+    pub updated_at: String,
+}
+
+impl From<&Post> for TranslatedContent {
+    fn from(post: &Post) -> Self {
+        Self {
+            content_type: ContentType::Post,
+            id: post.id,
+            title: post.title.clone(),
+            language: post.language.clone(),
+            translation_group: post.translation_group.clone(),
+            published: post.published,
+            updated_at: post.updated_at.clone(),
+        }
+    }
+}
+
+impl From<&Page> for TranslatedContent {
+    fn from(page: &Page) -> Self {
+        Self {
+            content_type: ContentType::Page,
+            id: page.id,
+            title: page.title.clone(),
+            language: page.language.clone(),
+            translation_group: page.translation_group.clone(),
+            published: page.published,
+            updated_at: page.updated_at.clone(),
+        }
+    }
+}
+
+/// How one language's version of some content compares to the source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranslationState {
+    /// The version the others are translated from
+    Source,
+    UpToDate,
+    /// The source was edited after this translation
+    Outdated,
+    /// Started but not published
+    Draft,
+    Missing,
+}
+
+impl TranslationState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Source => "Source",
+            Self::UpToDate => "Up to date",
+            Self::Outdated => "Outdated",
+            Self::Draft => "Draft",
+            Self::Missing => "Missing",
+        }
+    }
+
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            Self::Source => "translation-source",
+            Self::UpToDate => "translation-current",
+            Self::Outdated => "translation-outdated",
+            Self::Draft => "translation-draft",
+            Self::Missing => "translation-missing",
+        }
+    }
+}
+
+/// A source and where each language's translation of it stands
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranslationGroup {
+    pub source: TranslatedContent,
+    /// For each language: its state and its version of the content, if there is one
+    pub languages: Vec<(String, TranslationState, Option<TranslatedContent>)>,
+}
+
+/// Group content by translation group, with content in no group standing alone. The
+/// source of a group is its version in the default language, or else the oldest one.
+pub fn translation_groups(content: &[TranslatedContent], languages: &[String], default_language: &str) -> Vec<TranslationGroup> {
+    let mut groups: BTreeMap<(&str, String), Vec<&TranslatedContent>> = BTreeMap::new();
+    for item in content {
+        let group = item.translation_group.clone().unwrap_or_else(|| format!("#{}", item.id));
+        groups.entry((item.content_type.as_str(), group)).or_default().push(item);
+    }
+
+    let mut result: Vec<TranslationGroup> = groups
+        .into_values()
+        .filter_map(|members| {
+            let source = members
+                .iter()
+                .find(|item| item.language == default_language)
+                .or_else(|| members.iter().min_by_key(|item| item.id))?;
+            let languages = languages
+                .iter()
+                .map(|language| {
+                    let version = members.iter().find(|item| item.language == *language).map(|item| (*item).clone());
+                    let state = match &version {
+                        None => TranslationState::Missing,
+                        Some(item) if item.id == source.id => TranslationState::Source,
+                        Some(item) if !item.published => TranslationState::Draft,
+                        Some(item) if item.updated_at < source.updated_at => TranslationState::Outdated,
+                        Some(_) => TranslationState::UpToDate,
+                    };
+                    (language.clone(), state, version)
+                })
+                .collect();
+            Some(TranslationGroup { source: (*source).clone(), languages })
+        })
+        .collect();
+
+    result.sort_by(|a, b| (a.source.content_type.as_str(), &a.source.title).cmp(&(b.source.content_type.as_str(), &b.source.title)));
+    result
+}
+
+/// Links to the other languages a post or page is available in, and the matching
+/// `hreflang` alternates in the document head
+#[component]
+pub fn TranslationSwitcher(content_type: ContentType, group: String, language: String) -> Element {
+    // (language, path) of every version readers can see
+    let versions = use_server_future(move || {
+        let group = group.clone();
+        async move {
+            let settings = api::get_seo_settings().await?;
+            let paths = match content_type {
+                ContentType::Page => api::get_page_translations(group).await?
+                    .iter()
+                    .map(|page| (page.language.clone(), page.path(&settings.language)))
+                    .collect(),
+                _ => api::get_post_translations(group).await?
+                    .iter()
+                    .map(|post| (post.language.clone(), post.path(&settings.language)))
+                    .collect(),
+            };
+            Ok::<_, ServerFnError>((settings, paths))
+        }
+    })?;
+    let languages = use_server_future(api::get_languages)?;
+
+    let Some(Ok((settings, paths))) = versions.read().clone() else {
+        return rsx! {};
+    };
+    let paths: Vec<(String, String)> = paths;
+    if paths.len() < 2 {
+        return rsx! {};
+    }
+    let names: HashMap<String, String> = match &*languages.read() {
+        Some(Ok(list)) => list.iter().map(|language| (language.code.clone(), language.native_name.clone())).collect(),
+        _ => HashMap::new(),
+    };
+    let fallback = paths
+        .iter()
+        .find(|(code, _)| *code == settings.language)
+        .map(|(_, path)| settings.absolute_url(path));
+
+    rsx! {
+        for (code, path) in paths.iter() {
+            AlternateLink { key: "{code}", hreflang: code.clone(), href: settings.absolute_url(path) }
+        }
+        if let Some(href) = fallback {
+            AlternateLink { hreflang: "x-default", href }
+        }
+
+        nav {
+            class: "translation-switcher",
+            for (code, path) in paths.iter().filter(|(code, _)| *code != language) {
+                Link {
+                    key: "{code}",
+                    to: path.clone(),
+                    span { lang: "{code}", {names.get(code).cloned().unwrap_or_else(|| code.clone())} }
+                }
+            }
+        }
+    }
+}
+
+/// Start a translation of a post or page; returns the new draft's id
+pub async fn create_translation(content_type: ContentType, id: u32, language: String) -> Result<u32, ServerFnError> {
+    match content_type {
+        ContentType::Page => api::create_page_translation(id, language).await,
+        _ => api::create_post_translation(id, language).await,
+    }
+}
+
+/// Which translations of every post and page are missing or behind their source
+#[component]
+pub fn TranslationOverview() -> Element {
+    let mut posts = use_resource(api::get_all_posts);
+    let mut pages = use_resource(api::get_all_pages);
+    let languages = use_resource(api::get_languages);
+    let default_language = use_resource(api::get_default_language);
+    let mut needs_work_only = use_signal(|| false);
+    let mut message = use_signal(String::new);
+
+    let default_code = match &*default_language.read() {
+        Some(Ok(code)) => code.clone(),
+        _ => DEFAULT_LANGUAGE.to_string(),
+    };
+    let active: Vec<Language> = match &*languages.read() {
+        Some(Ok(list)) => list.iter().filter(|language| language.active).cloned().collect(),
+        _ => Vec::new(),
+    };
+    let codes: Vec<String> = active.iter().map(|language| language.code.clone()).collect();
+    let mut content: Vec<TranslatedContent> = Vec::new();
+    if let Some(Ok(list)) = &*posts.read() {
+        content.extend(list.iter().map(TranslatedContent::from));
+    }
+    if let Some(Ok(list)) = &*pages.read() {
+        content.extend(list.iter().map(TranslatedContent::from));
+    }
+    let groups: Vec<TranslationGroup> = translation_groups(&content, &codes, &default_code)
+        .into_iter()
+        .filter(|group| {
+            !needs_work_only()
+                || group.languages.iter().any(|(_, state, _)| matches!(state, TranslationState::Missing | TranslationState::Outdated | TranslationState::Draft))
+        })
+        .collect();
+
+    let translate = move |content_type: ContentType, id: u32, language: String| {
+        spawn(async move {
+            match create_translation(content_type, id, language.clone()).await {
+                Ok(new_id) if content_type == ContentType::Post => {
+                    dioxus::router::navigator().push(format!("/admin/posts/{}/edit", new_id));
+                },
+                Ok(_) => {
+                    message.set(format!("Started a {} draft of the page", language));
+                    pages.restart();
+                },
+                Err(e) => message.set(format!("Failed to start translation: {}", e)),
+            }
+            posts.restart();
+        });
+    };
+
+    rsx! {
+        div {
+            class: "translation-overview",
+            h2 { "Translations" }
+            p { "Each post and page next to its versions in the other active languages. A translation is outdated when its source was edited after it." }
+
+            if !message().is_empty() {
+                div { class: "status-message", p { "{message}" } }
+            }
+
+            label {
+                input {
+                    r#type: "checkbox",
+                    checked: needs_work_only(),
+                    onchange: move |e| needs_work_only.set(e.checked()),
+                }
+                " Only show content with missing, draft or outdated translations"
+            }
+
+            table {
+                class: "translation-table",
+                thead {
+                    tr {
+                        th { "Content" }
+                        for language in active.iter() {
+                            th { key: "{language.code}", "{language.name}" }
+                        }
+                    }
+                }
+                tbody {
+                    for group in groups {
+                        tr {
+                            key: "{group.source.content_type.as_str()}-{group.source.id}",
+                            td {
+                                "{group.source.title} "
+                                small { "({group.source.content_type.as_str()})" }
+                            }
+                            for (language, state, version) in group.languages.iter().cloned() {
+                                td {
+                                    key: "{language}",
+                                    class: state.css_class(),
+                                    match version {
+                                        Some(version) if version.content_type == ContentType::Post => rsx! {
+                                            a { href: "/admin/posts/{version.id}/edit", "{state.label()}" }
+                                        },
+                                        Some(_) => rsx! { "{state.label()}" },
+                                        None => {
+                                            let (content_type, id) = (group.source.content_type, group.source.id);
+                                            rsx! {
+                                                button {
+                                                    onclick: move |_| translate(content_type, id, language.clone()),
+                                                    "Translate"
+                                                }
+                                            }
+                                        },
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Where the translations of one post or page stand, with buttons to start missing ones
+#[component]
+pub fn TranslationPanel(content_type: ContentType, id: u32) -> Element {
+    let mut content = use_resource(move || async move {
+        Ok::<Vec<TranslatedContent>, ServerFnError>(match content_type {
+            ContentType::Page => api::get_all_pages().await?.iter().map(TranslatedContent::from).collect(),
+            _ => api::get_all_posts().await?.iter().map(TranslatedContent::from).collect(),
+        })
+    });
+    let languages = use_resource(api::get_languages);
+    let default_language = use_resource(api::get_default_language);
+    let mut message = use_signal(String::new);
+
+    let active: Vec<Language> = match &*languages.read() {
+        Some(Ok(list)) => list.iter().filter(|language| language.active).cloned().collect(),
+        _ => Vec::new(),
+    };
+    let codes: Vec<String> = active.iter().map(|language| language.code.clone()).collect();
+    let default_code = match &*default_language.read() {
+        Some(Ok(code)) => code.clone(),
+        _ => DEFAULT_LANGUAGE.to_string(),
+    };
+    let group = match &*content.read() {
+        Some(Ok(list)) => translation_groups(list, &codes, &default_code)
+            .into_iter()
+            .find(|group| group.languages.iter().any(|(_, _, version)| version.as_ref().is_some_and(|version| version.id == id))),
+        _ => None,
+    };
+
+    let Some(group) = group else {
+        return rsx! {};
+    };
+
+    rsx! {
+        div {
+            class: "translation-panel",
+            h3 { "Translations" }
+            if !message().is_empty() {
+                p { class: "status-message", "{message}" }
+            }
+            ul {
+                for (language, state, version) in group.languages.iter().cloned() {
+                    li {
+                        key: "{language}",
+                        class: state.css_class(),
+                        strong { "{language} " }
+                        match version {
+                            Some(version) if version.id == id => rsx! { "{state.label()} (this {content_type.as_str()})" },
+                            Some(version) if content_type == ContentType::Post => rsx! {
+                                a { href: "/admin/posts/{version.id}/edit", "{state.label()}: {version.title}" }
+                            },
+                            Some(version) => rsx! { "{state.label()}: {version.title}" },
+                            None => {
+                                let source = group.source.id;
+                                rsx! {
+                                    "{state.label()} "
+                                    button {
+                                        r#type: "button",
+                                        onclick: move |_| {
+                                            let language = language.clone();
+                                            spawn(async move {
+                                                match create_translation(content_type, source, language.clone()).await {
+                                                    Ok(new_id) if content_type == ContentType::Post => {
+                                                        dioxus::router::navigator().push(format!("/admin/posts/{}/edit", new_id));
+                                                    },
+                                                    Ok(_) => message.set(format!("Started a {} draft", language)),
+                                                    Err(e) => message.set(format!("Failed to start translation: {}", e)),
+                                                }
+                                                content.restart();
+                                            });
+                                        },
+                                        "Translate"
+                                    }
+                                }
+                            },
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// `<link rel="alternate" hreflang>` in the head. `document::Link` skips links to a URL that
+/// is already linked, such as the canonical URL, so this hands the props to the document directly.
+#[component]
+fn AlternateLink(hreflang: String, href: String) -> Element {
+    use_hook(|| {
+        let document = document::document();
+        if document.create_head_component() {
+            document.create_link(document::LinkProps::builder().rel("alternate".to_string()).hreflang(hreflang).href(href).build());
+        }
+    });
+
+    rsx! {}
+}
+
+/// Messages grouped by (namespace, key), then language
+fn translation_rows(translations: &[Translation]) -> BTreeMap<(String, String), HashMap<String, String>> {
+    let mut rows: BTreeMap<(String, String), HashMap<String, String>> = BTreeMap::new();
+    for translation in translations {
+        rows.entry((translation.namespace.clone(), translation.key.clone()))
+            .or_default()
+            .insert(translation.language_code.clone(), translation.value.clone());
+    }
+    rows
+}
+
 /// Language manager component
 #[component]
 pub fn LanguageManager() -> Element {
     let mut active_tab = use_signal(|| "languages".to_string());
-    
+    let mut languages = use_resource(api::get_languages);
+    let mut default_language = use_resource(api::get_default_language);
+    let mut translations = use_resource(api::get_all_translations);
+    let mut message = use_signal(String::new);
+    let mut i18n = try_use_context::<I18n>();
+
+    let mut new_language = use_signal(|| Language {
+        code: String::new(),
+        name: String::new(),
+        native_name: String::new(),
+        direction: TextDirection::LeftToRight,
+        active: true,
+    });
+    let mut namespace_filter = use_signal(String::new);
+    let mut search = use_signal(String::new);
+    // Edited messages not saved yet, by (namespace, key, language)
+    let mut drafts = use_signal(HashMap::<(String, String, String), String>::new);
+    let mut new_key = use_signal(|| Translation::new(DEFAULT_NAMESPACE, "", DEFAULT_LANGUAGE, ""));
+
+    let mut refresh = move || {
+        translations.restart();
+        if let Some(i18n) = i18n.as_mut() {
+            i18n.reload();
+        }
+    };
+
+    let save_language = move |language: Language| {
+        spawn(async move {
+            match api::save_language(language).await {
+                Ok(()) => languages.restart(),
+                Err(e) => message.set(format!("Failed to save language: {}", e)),
+            }
+        });
+    };
+
+    let on_add_language = move |evt: FormEvent| {
+        evt.prevent_default();
+        let language = new_language();
+        spawn(async move {
+            match api::save_language(language).await {
+                Ok(()) => {
+                    new_language.with_mut(|l| {
+                        l.code.clear();
+                        l.name.clear();
+                        l.native_name.clear();
+                    });
+                    message.set("Language saved".to_string());
+                    languages.restart();
+                },
+                Err(e) => message.set(format!("Failed to save language: {}", e)),
+            }
+        });
+    };
+
+    let on_add_key = move |evt: FormEvent| {
+        evt.prevent_default();
+        let translation = new_key();
+        spawn(async move {
+            match api::save_translation(translation).await {
+                Ok(()) => {
+                    new_key.with_mut(|t| {
+                        t.key.clear();
+                        t.value.clear();
+                    });
+                    message.set("Translation key added".to_string());
+                    refresh();
+                },
+                Err(e) => message.set(format!("Failed to add key: {}", e)),
+            }
+        });
+    };
+
+    let language_list = match &*languages.read() {
+        Some(Ok(list)) => list.clone(),
+        _ => Vec::new(),
+    };
+    let default_code = match &*default_language.read() {
+        Some(Ok(code)) => code.clone(),
+        _ => DEFAULT_LANGUAGE.to_string(),
+    };
+    let rows = match &*translations.read() {
+        Some(Ok(list)) => translation_rows(list),
+        _ => BTreeMap::new(),
+    };
+    let namespaces: Vec<String> = rows.keys().map(|(namespace, _)| namespace.clone()).collect::<std::collections::BTreeSet<_>>().into_iter().collect();
+    let active_languages: Vec<Language> = language_list.iter().filter(|l| l.active).cloned().collect();
+    let filter = namespace_filter();
+    let query = search().to_lowercase();
+    let visible_rows: Vec<((String, String), HashMap<String, String>)> = rows
+        .iter()
+        .filter(|((namespace, _), _)| filter.is_empty() || *namespace == filter)
+        .filter(|((namespace, key), values)| {
+            query.is_empty()
+                || format!("{}:{}", namespace, key).to_lowercase().contains(&query)
+                || values.values().any(|value| value.to_lowercase().contains(&query))
+        })
+        .map(|(id, values)| (id.clone(), values.clone()))
+        .collect();
+    let current_language = new_language();
+    let current_key = new_key();
+
     rsx! {
         div {
+            class: "language-manager",
             h2 { "Multi-language Support" }
             p { "Manage languages and translations for your CMS. Create a truly global experience." }
-            
+
+            if !message().is_empty() {
+                div { class: "status-message", p { "{message}" } }
+            }
+
             div {
                 div {
+                    class: "tab-buttons",
                     button {
                         onclick: move |_| active_tab.set("languages".to_string()),
                         "Languages"
@@ -219,161 +797,450 @@ pub fn LanguageManager() -> Element {
                         "Import/Export"
                     }
                 }
-                
+
                 div {
                     if active_tab() == "languages" {
                         div {
                             h3 { "Available Languages" }
-                            
+
                             div {
-                                div {
-                                    h4 { "🇺🇸 English" }
-                                    p { "English - Default Language" }
-                                    span { "ACTIVE" }
-                                    div {
-                                        button { "Edit" }
-                                        button { "Set as Default" }
+                                class: "language-list",
+                                for language in language_list.iter().cloned() {
+                                    {
+                                        let translated = rows.values().filter(|values| values.get(&language.code).is_some_and(|v| !v.is_empty())).count();
+                                        let percent = if rows.is_empty() { 100 } else { translated * 100 / rows.len() };
+                                        let is_default = language.code == default_code;
+                                        let code = language.code.clone();
+                                        let toggled = Language { active: !language.active, ..language.clone() };
+                                        rsx! {
+                                            div {
+                                                key: "{language.code}",
+                                                class: "language-card",
+                                                h4 { "{language.native_name} ({language.code})" }
+                                                p {
+                                                    "{language.name} - "
+                                                    if is_default { "Default Language" } else { "{percent}% translated" }
+                                                    if language.direction == TextDirection::RightToLeft { " - right to left" }
+                                                }
+                                                span {
+                                                    class: if language.active { "status-badge active" } else { "status-badge" },
+                                                    if language.active { "ACTIVE" } else { "INACTIVE" }
+                                                }
+                                                div {
+                                                    if !is_default {
+                                                        button {
+                                                            onclick: move |_| save_language(toggled.clone()),
+                                                            if language.active { "Deactivate" } else { "Activate" }
+                                                        }
+                                                    }
+                                                    if !is_default && language.active {
+                                                        button {
+                                                            onclick: move |_| {
+                                                                let code = code.clone();
+                                                                spawn(async move {
+                                                                    match api::set_default_language(code).await {
+                                                                        Ok(()) => default_language.restart(),
+                                                                        Err(e) => message.set(format!("Failed to set default language: {}", e)),
+                                                                    }
+                                                                });
+                                                            },
+                                                            "Set as Default"
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        }
                                     }
                                 }
-                                
-                                div {
-                                    h4 { "🇪🇸 Español" }
-                                    p { "Spanish - 95% translated" }
-                                    span { "ACTIVE" }
-                                    div {
-                                        button { "Edit" }
-                                        button { "Deactivate" }
-                                    }
+                            }
+
+                            form {
+                                class: "language-form",
+                                onsubmit: on_add_language,
+                                h4 { "Add Language" }
+                                input {
+                                    r#type: "text",
+                                    placeholder: "Code (e.g. de)",
+                                    value: "{current_language.code}",
+                                    oninput: move |e| new_language.with_mut(|l| l.code = e.value()),
                                 }
-                                
-                                div {
-                                    h4 { "🇫🇷 Français" }
-                                    p { "French - 78% translated" }
-                                    span { "ACTIVE" }
-                                    div {
-                                        button { "Edit" }
-                                        button { "Deactivate" }
-                                    }
+                                input {
+                                    r#type: "text",
+                                    placeholder: "Name (e.g. German)",
+                                    value: "{current_language.name}",
+                                    oninput: move |e| new_language.with_mut(|l| l.name = e.value()),
+                                }
+                                input {
+                                    r#type: "text",
+                                    placeholder: "Native name (e.g. Deutsch)",
+                                    value: "{current_language.native_name}",
+                                    oninput: move |e| new_language.with_mut(|l| l.native_name = e.value()),
                                 }
-                                
-                                div {
-                                    span { "+" }
-                                    p { "Add Language" }
+                                select {
+                                    value: "{current_language.direction.as_str()}",
+                                    onchange: move |e| new_language.with_mut(|l| l.direction = TextDirection::parse(&e.value()).unwrap_or(TextDirection::LeftToRight)),
+                                    option { value: "ltr", "Left to right" }
+                                    option { value: "rtl", "Right to left" }
                                 }
+                                button { r#type: "submit", "Add Language" }
                             }
                         }
                     }
-                    
+
                     if active_tab() == "translations" {
                         div {
                             h3 { "Translation Editor" }
-                            
+                            p {
+                                "Messages can fill in arguments with "
+                                code { "{{ $name }}" }
+                                " and choose plural forms with "
+                                code { "{{ $count -> [one] One post *[other] {{ $count }} posts }}" }
+                                "."
+                            }
+
                             div {
+                                class: "translation-filters",
                                 select {
-                                    option { "Common" }
-                                    option { "Posts" }
-                                    option { "Admin" }
-                                    option { "Navigation" }
+                                    value: "{filter}",
+                                    onchange: move |e| namespace_filter.set(e.value()),
+                                    option { value: "", "All namespaces" }
+                                    for namespace in namespaces.iter() {
+                                        option { key: "{namespace}", value: "{namespace}", "{namespace}" }
+                                    }
                                 }
-                                
+
                                 input {
                                     r#type: "text",
-                                    placeholder: "Search translations..."
+                                    placeholder: "Search translations...",
+                                    value: "{search}",
+                                    oninput: move |e| search.set(e.value()),
                                 }
                             }
-                            
-                            div {
-                                div {
-                                    div { "Key" }
-                                    div { "English" }
-                                    div { "Spanish" }
-                                    div { "French" }
-                                    div { "Actions" }
-                                }
-                                
-                                div {
-                                    div { "home" }
-                                    div { 
-                                        input { r#type: "text", value: "Home" }
-                                    }
-                                    div { 
-                                        input { r#type: "text", value: "Inicio" }
-                                    }
-                                    div { 
-                                        input { r#type: "text", value: "Accueil" }
-                                    }
-                                    div {
-                                        button { "Save" }
-                                        button { "Delete" }
+
+                            table {
+                                class: "translation-table",
+                                thead {
+                                    tr {
+                                        th { "Key" }
+                                        for language in active_languages.iter() {
+                                            th { key: "{language.code}", "{language.name}" }
+                                        }
+                                        th { "Actions" }
                                     }
                                 }
-                                
-                                div {
-                                    div { "blog" }
-                                    div { 
-                                        input { r#type: "text", value: "Blog" }
-                                    }
-                                    div { 
-                                        input { r#type: "text", value: "Blog" }
-                                    }
-                                    div { 
-                                        input { r#type: "text", value: "Blog" }
-                                    }
-                                    div {
-                                        button { "Save" }
-                                        button { "Delete" }
+                                tbody {
+                                    for ((namespace, key), values) in visible_rows {
+                                        tr {
+                                            key: "{namespace}:{key}",
+                                            td { code { "{namespace}:{key}" } }
+                                            for language in active_languages.iter() {
+                                                {
+                                                    let draft_id = (namespace.clone(), key.clone(), language.code.clone());
+                                                    let value = drafts.read().get(&draft_id).cloned()
+                                                        .or_else(|| values.get(&language.code).cloned())
+                                                        .unwrap_or_default();
+                                                    rsx! {
+                                                        td {
+                                                            key: "{language.code}",
+                                                            input {
+                                                                r#type: "text",
+                                                                class: if value.is_empty() { "missing" } else { "" },
+                                                                value: "{value}",
+                                                                oninput: move |e| { drafts.write().insert(draft_id.clone(), e.value()); },
+                                                            }
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                            td {
+                                                {
+                                                    let (save_namespace, save_key) = (namespace.clone(), key.clone());
+                                                    rsx! {
+                                                        button {
+                                                            onclick: move |_| {
+                                                                let changed: Vec<Translation> = drafts.read().iter()
+                                                                    .filter(|((n, k, _), _)| *n == save_namespace && *k == save_key)
+                                                                    .map(|((n, k, language), value)| Translation::new(n, k, language, value))
+                                                                    .collect();
+                                                                spawn(async move {
+                                                                    for translation in changed {
+                                                                        let draft_id = (translation.namespace.clone(), translation.key.clone(), translation.language_code.clone());
+                                                                        if let Err(e) = api::save_translation(translation).await {
+                                                                            message.set(format!("Failed to save translation: {}", e));
+                                                                            return;
+                                                                        }
+                                                                        drafts.write().remove(&draft_id);
+                                                                    }
+                                                                    message.set("Translations saved".to_string());
+                                                                    refresh();
+                                                                });
+                                                            },
+                                                            "Save"
+                                                        }
+                                                    }
+                                                }
+                                                button {
+                                                    onclick: move |_| {
+                                                        let (namespace, key) = (namespace.clone(), key.clone());
+                                                        spawn(async move {
+                                                            match api::delete_translation(namespace, key).await {
+                                                                Ok(()) => refresh(),
+                                                                Err(e) => message.set(format!("Failed to delete translation: {}", e)),
+                                                            }
+                                                        });
+                                                    },
+                                                    "Delete"
+                                                }
+                                            }
+                                        }
                                     }
                                 }
                             }
-                            
-                            div {
-                                button { "Add Translation Key" }
-                                button { "Bulk Import" }
-                                button { "Generate Missing Keys" }
+
+                            form {
+                                class: "translation-form",
+                                onsubmit: on_add_key,
+                                h4 { "Add Translation Key" }
+                                input {
+                                    r#type: "text",
+                                    placeholder: "Namespace",
+                                    value: "{current_key.namespace}",
+                                    oninput: move |e| new_key.with_mut(|t| t.namespace = e.value()),
+                                }
+                                input {
+                                    r#type: "text",
+                                    placeholder: "Key",
+                                    value: "{current_key.key}",
+                                    oninput: move |e| new_key.with_mut(|t| t.key = e.value()),
+                                }
+                                input {
+                                    r#type: "text",
+                                    placeholder: "Message in {DEFAULT_LANGUAGE}",
+                                    value: "{current_key.value}",
+                                    oninput: move |e| new_key.with_mut(|t| t.value = e.value()),
+                                }
+                                button { r#type: "submit", "Add" }
                             }
                         }
                     }
-                    
+
                     if active_tab() == "import" {
-                        div {
-                            h3 { "Import & Export Translations" }
-                            
-                            div {
-                                h4 { "Export Translations" }
-                                p { "Download translations in various formats for external editing." }
-                                
-                                div {
-                                    select {
-                                        option { "JSON" }
-                                        option { "CSV" }
-                                        option { "YAML" }
-                                        option { "PO/POT" }
+                        TranslationFiles {
+                            languages: language_list.clone(),
+                            namespaces: namespaces.clone(),
+                            default_language: default_code.clone(),
+                            on_imported: move |_| refresh(),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Exporting namespaces as PO or XLIFF for translators, and importing their files back
+/// after a preview of what would change
+#[component]
+fn TranslationFiles(languages: Vec<Language>, namespaces: Vec<String>, default_language: String, on_imported: EventHandler<()>) -> Element {
+    let mut export_language = use_signal(|| None::<String>);
+    let mut export_namespace = use_signal(|| DEFAULT_NAMESPACE.to_string());
+    // Name and contents of the uploaded file
+    let mut upload = use_signal(|| None::<(String, String)>);
+    let mut preview = use_signal(|| None::<client::TranslationImportPreview>);
+    let mut remove_missing = use_signal(|| false);
+    let mut message = use_signal(String::new);
+
+    let on_upload = move |evt: FormEvent| {
+        spawn(async move {
+            let Some(file_engine) = evt.files() else { return };
+            let Some(file_name) = file_engine.files().into_iter().next() else { return };
+            let Some(data) = file_engine.read_file_to_string(&file_name).await else {
+                message.set(format!("Could not read {}", file_name));
+                return;
+            };
+
+            preview.set(None);
+            match api::preview_translation_import(file_name.clone(), data.clone()).await {
+                Ok(result) => {
+                    message.set(String::new());
+                    upload.set(Some((file_name, data)));
+                    preview.set(Some(result));
+                },
+                Err(e) => message.set(format!("Failed to read {}: {}", file_name, e)),
+            }
+        });
+    };
+
+    let on_import = move |_| {
+        let Some((file_name, data)) = upload() else { return };
+        spawn(async move {
+            match api::import_translations(file_name, data, remove_missing()).await {
+                Ok(result) => {
+                    let removed = if remove_missing() { result.removed.len() } else { 0 };
+                    message.set(format!(
+                        "Imported {}: {} added, {} changed, {} removed, {} skipped",
+                        result.language,
+                        result.added.len(),
+                        result.changed.len(),
+                        removed,
+                        result.flagged.len(),
+                    ));
+                    upload.set(None);
+                    preview.set(None);
+                    on_imported.call(());
+                },
+                Err(e) => message.set(format!("Failed to import translations: {}", e)),
+            }
+        });
+    };
+
+    let language = export_language().unwrap_or_else(|| {
+        languages.iter().find(|language| language.code != default_language).map_or(default_language.clone(), |language| language.code.clone())
+    });
+    let namespace = export_namespace();
+
+    rsx! {
+        div {
+            class: "translation-files",
+            h3 { "Import & Export Translations" }
+
+            if !message().is_empty() {
+                div { class: "status-message", p { "{message}" } }
+            }
+
+            div {
+                h4 { "Export" }
+                p { "Download one namespace for a translator. Every message in {default_language} is included, with its translation where there is one." }
+
+                div {
+                    class: "translation-filters",
+                    select {
+                        value: "{language}",
+                        onchange: move |e| export_language.set(Some(e.value())),
+                        for language in languages.iter() {
+                            option { key: "{language.code}", value: "{language.code}", "{language.name}" }
+                        }
+                    }
+                    select {
+                        value: "{namespace}",
+                        onchange: move |e| export_namespace.set(e.value()),
+                        for namespace in namespaces.iter() {
+                            option { key: "{namespace}", value: "{namespace}", "{namespace}" }
+                        }
+                    }
+                    for format in client::TranslationFileFormat::ALL {
+                        a {
+                            key: "{format.extension()}",
+                            class: "btn",
+                            href: format.export_path(&language, &namespace),
+                            download: true,
+                            "Download {format.label()}"
+                        }
+                    }
+                }
+            }
+
+            div {
+                h4 { "Import" }
+                p { "Upload a translated PO or XLIFF 2.0 file. Nothing is saved until you confirm the preview." }
+
+                input {
+                    r#type: "file",
+                    accept: ".po,.pot,.xlf,.xliff",
+                    onchange: on_upload,
+                }
+            }
+
+            if let Some(result) = preview() {
+                div {
+                    class: "import-preview",
+                    h4 { "Preview: {result.format.label()} in {result.language}" }
+                    p {
+                        class: "import-summary",
+                        "{result.added.len()} added, {result.changed.len()} changed, {result.removed.len()} missing from the file, {result.unchanged} unchanged"
+                        if !result.flagged.is_empty() {
+                            ", {result.flagged.len()} flagged and skipped"
+                        }
+                    }
+
+                    if !result.flagged.is_empty() {
+                        h5 { "Flagged" }
+                        table {
+                            class: "translation-table import-flagged",
+                            tbody {
+                                for flagged in result.flagged.iter() {
+                                    tr {
+                                        key: "{flagged.namespace}:{flagged.key}",
+                                        td { code { "{flagged.namespace}:{flagged.key}" } }
+                                        td { "{flagged.value}" }
+                                        td { "{flagged.issue.describe()}" }
                                     }
-                                    
-                                    select {
-                                        option { "All Languages" }
-                                        option { "English" }
-                                        option { "Spanish" }
-                                        option { "French" }
+                                }
+                            }
+                        }
+                    }
+
+                    for (title, class, messages) in [("Added", "import-added", &result.added), ("Changed", "import-changed", &result.changed)] {
+                        if !messages.is_empty() {
+                            h5 { key: "{title}", "{title}" }
+                            table {
+                                class: "translation-table {class}",
+                                tbody {
+                                    for imported in messages.iter() {
+                                        tr {
+                                            key: "{imported.namespace}:{imported.key}",
+                                            td { code { "{imported.namespace}:{imported.key}" } }
+                                            if let Some(previous) = &imported.previous {
+                                                td { del { "{previous}" } }
+                                            }
+                                            td { "{imported.value}" }
+                                        }
                                     }
-                                    
-                                    button { "Export" }
                                 }
                             }
-                            
-                            div {
-                                h4 { "Import Translations" }
-                                p { "Upload translation files to bulk update your content." }
-                                
-                                div {
-                                    input {
-                                        r#type: "file",
-                                        accept: ".json,.csv,.yaml,.yml,.po,.pot"
+                        }
+                    }
+
+                    if !result.removed.is_empty() {
+                        h5 { "Missing from the file" }
+                        table {
+                            class: "translation-table import-removed",
+                            tbody {
+                                for removed in result.removed.iter() {
+                                    tr {
+                                        key: "{removed.id()}",
+                                        td { code { "{removed.id()}" } }
+                                        td { "{removed.value}" }
                                     }
-                                    button { "Upload" }
                                 }
                             }
                         }
+                        label {
+                            input {
+                                r#type: "checkbox",
+                                checked: remove_missing(),
+                                onchange: move |e| remove_missing.set(e.checked()),
+                            }
+                            " Delete these stored messages"
+                        }
+                    }
+
+                    div {
+                        class: "import-actions",
+                        button {
+                            class: "btn btn-primary",
+                            disabled: !result.has_changes(remove_missing()),
+                            onclick: on_import,
+                            "Import"
+                        }
+                        button {
+                            class: "btn btn-secondary",
+                            onclick: move |_| {
+                                upload.set(None);
+                                preview.set(None);
+                            },
+                            "Cancel"
+                        }
                     }
                 }
             }
@@ -384,52 +1251,56 @@ pub fn LanguageManager() -> Element {
 /// Language selector component
 #[component]
 pub fn LanguageSelector() -> Element {
-    let mut current_language = use_signal(|| "en".to_string());
+    let languages = use_server_future(api::get_languages)?;
     let mut show_dropdown = use_signal(|| false);
-    
+    let i18n = try_use_context::<I18n>();
+
+    let current = i18n.map(|i18n| i18n.language()).unwrap_or_else(|| DEFAULT_LANGUAGE.to_string());
+    let active: Vec<Language> = match &*languages.read() {
+        Some(Ok(list)) => list.iter().filter(|language| language.active).cloned().collect(),
+        _ => Vec::new(),
+    };
+    let label = active
+        .iter()
+        .find(|language| language.code == current)
+        .map(|language| language.native_name.clone())
+        .unwrap_or_else(|| current.clone());
+
+    // Nothing to choose between
+    if active.len() < 2 {
+        return rsx! {};
+    }
+
     rsx! {
         div {
+            class: "language-selector",
             button {
                 onclick: move |_| {
                     show_dropdown.set(!show_dropdown());
                 },
                 span { "🌍 " }
-                span { 
-                    match current_language().as_str() {
-                        "en" => "English",
-                        "es" => "Español", 
-                        "fr" => "Français",
-                        _ => "Unknown"
-                    }
-                }
+                span { "{label}" }
                 span { if show_dropdown() { "▲" } else { "▼" } }
             }
-            
+
             if show_dropdown() {
                 div {
-                    div {
-                        onclick: move |_| {
-                            current_language.set("en".to_string());
-                            show_dropdown.set(false);
-                        },
-                        "🇺🇸 English"
-                    }
-                    div {
-                        onclick: move |_| {
-                            current_language.set("es".to_string());
-                            show_dropdown.set(false);
-                        },
-                        "🇪🇸 Español"
-                    }
-                    div {
-                        onclick: move |_| {
-                            current_language.set("fr".to_string());
-                            show_dropdown.set(false);
-                        },
-                        "🇫🇷 Français"
+                    class: "language-selector-options",
+                    for language in active {
+                        div {
+                            key: "{language.code}",
+                            class: if language.code == current { "selected" } else { "" },
+                            onclick: move |_| {
+                                if let Some(mut i18n) = i18n {
+                                    i18n.set_language(&language.code);
+                                }
+                                show_dropdown.set(false);
+                            },
+                            "{language.native_name}"
+                        }
                     }
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
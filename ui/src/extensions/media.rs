@@ -12,7 +12,12 @@ pub struct ExtendedMediaFile {
 
 impl ExtendedMediaFile {
     pub fn from_media(media: MediaFile) -> Self {
-        let url = format!("/uploads/{}", media.filename);
+        Self::with_base_url(media, "/uploads")
+    }
+    
+    /// Build the serving URL under `base_url`; ba-server resolves it for whichever storage backend is configured
+    pub fn with_base_url(media: MediaFile, base_url: &str) -> Self {
+        let url = format!("{}/{}", base_url.trim_end_matches('/'), media.filename);
         Self { media, url }
     }
 }
@@ -21,7 +26,7 @@ impl ExtendedMediaFile {
 pub struct MediaExtension {
     media_files: HashMap<u32, ExtendedMediaFile>,
     next_id: u32,
-    upload_dir: String,
+    public_base_url: String,
 }
 
 impl MediaExtension {
@@ -29,10 +34,16 @@ impl MediaExtension {
         Self {
             media_files: HashMap::new(),
             next_id: 1,
-            upload_dir: "uploads".to_string(),
+            public_base_url: "/uploads".to_string(),
         }
     }
     
+    /// Serve media from a different URL prefix, e.g. a CDN in front of the storage bucket
+    pub fn with_public_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.public_base_url = base_url.into();
+        self
+    }
+    
     pub fn get_media_files(&self) -> Vec<&ExtendedMediaFile> {
         self.media_files.values().collect()
     }
@@ -43,7 +54,7 @@ impl MediaExtension {
     
     pub fn add_media_file(&mut self, mut media: MediaFile) -> u32 {
         media.id = self.next_id;
-        let extended = ExtendedMediaFile::from_media(media);
+        let extended = ExtendedMediaFile::with_base_url(media, &self.public_base_url);
         self.media_files.insert(self.next_id, extended);
         let id = self.next_id;
         self.next_id += 1;
@@ -78,9 +89,7 @@ impl Extension for MediaExtension {
     }
     
     fn init(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        // Create upload directory if it doesn't exist
-        std::fs::create_dir_all(&self.upload_dir)?;
-        
+        // Files themselves live in the server-side storage backend (see `api::storage`)
         // Add some sample media files for demo
         let sample_image = MediaFile {
             id: 0,
@@ -134,6 +143,8 @@ impl Extension for MediaExtension {
 /// Media library component for browsing uploaded files
 #[component]
 pub fn MediaLibrary() -> Element {
+    let formatter = super::formatter();
+
     rsx! {
         div {
             h2 { "Media Library" }
@@ -163,7 +174,7 @@ pub fn MediaLibrary() -> Element {
                     }
                     div {
                         h4 { "bananabit-logo.png" }
-                        p { "PNG Image • 15.4 KB" }
+                        p { "PNG Image • {formatter.file_size(15432)}" }
                         input {
                             r#type: "text",
                             placeholder: "Alt text...",
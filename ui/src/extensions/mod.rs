@@ -7,6 +7,8 @@ pub mod pages;
 pub mod media;
 pub mod themes;
 pub mod seo;
+pub mod seo_audit;
+pub mod redirects;
 pub mod scheduling;
 pub mod i18n;
 pub mod analytics;
@@ -18,12 +20,14 @@ pub use pages::*;
 pub use media::*;
 pub use themes::*;
 pub use seo::*;
+pub use seo_audit::*;
+pub use redirects::*;
 pub use scheduling::*;
 pub use i18n::*;
 pub use analytics::*;
 
 // Re-export types from client
-pub use client::{Post, User, UserRole, Session, Comment, MediaFile, Theme, SeoMetadata, AnalyticsEvent};
+pub use client::{Post, Redirect, User, UserRole, Session, Comment, MediaFile, Theme, SeoMetadata, AnalyticsEvent};
 
 /// Core trait that all extensions must implement
 pub trait Extension {
@@ -53,6 +57,11 @@ pub trait Extension {
     fn hooks(&self) -> ExtensionHooks {
         ExtensionHooks::default()
     }
+    
+    /// Get shortcodes that this extension adds to markdown
+    fn shortcodes(&self) -> Vec<ExtensionShortcode> {
+        Vec::new()
+    }
 }
 
 /// Route provided by an extension
@@ -70,6 +79,14 @@ pub struct ExtensionComponent {
     pub description: String,
 }
 
+/// Shortcode provided by an extension, e.g. `{{< map place="Lisbon" >}}` for `map`
+#[derive(Debug, Clone)]
+pub struct ExtensionShortcode {
+    pub name: String,
+    pub description: String,
+    pub handler: crate::ShortcodeHandler,
+}
+
 /// Hooks for system events
 #[derive(Debug, Default)]
 pub struct ExtensionHooks {
@@ -92,8 +109,11 @@ impl ExtensionManager {
         }
     }
     
-    /// Register a new extension
+    /// Register a new extension, along with its shortcodes
     pub fn register<T: Extension + 'static>(&mut self, extension: T) {
+        for shortcode in extension.shortcodes() {
+            crate::register_shortcode(&shortcode.name, shortcode.handler);
+        }
         let id = extension.id().to_string();
         self.extensions.insert(id, Box::new(extension));
     }
@@ -1,51 +1,15 @@
 use dioxus::prelude::*;
 use super::{Extension, ExtensionRoute, ExtensionComponent};
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use crate::t;
 
-/// Page data structure for static pages
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Page {
-    pub id: u32,
-    pub slug: String,
-    pub title: String,
-    pub content: String,
-    pub author: String,
-    pub created_at: String,
-    pub updated_at: String,
-    pub published: bool,
-    pub template: String, // Template to use for rendering
-}
+pub use client::Page;
 
-/// Pages extension - handles static pages
-pub struct PagesExtension {
-    pages: HashMap<u32, Page>,
-    slug_to_id: HashMap<String, u32>,
-}
+/// Pages extension - handles static pages, stored in the database
+pub struct PagesExtension;
 
 impl PagesExtension {
     pub fn new() -> Self {
-        Self {
-            pages: HashMap::new(),
-            slug_to_id: HashMap::new(),
-        }
-    }
-    
-    pub fn add_page(&mut self, page: Page) {
-        self.slug_to_id.insert(page.slug.clone(), page.id);
-        self.pages.insert(page.id, page);
-    }
-    
-    pub fn get_page_by_slug(&self, slug: &str) -> Option<&Page> {
-        let id = self.slug_to_id.get(slug)?;
-        self.pages.get(id)
-    }
-    
-    pub fn list_published_pages(&self) -> Vec<&Page> {
-        self.pages
-            .values()
-            .filter(|page| page.published)
-            .collect()
+        Self
     }
 }
 
@@ -63,90 +27,7 @@ impl Extension for PagesExtension {
     }
     
     fn init(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        // Create an About page
-        let about_page = Page {
-            id: 1,
-            slug: "about".to_string(),
-            title: "About BananaBit CMS".to_string(),
-            content: r#"# About BananaBit CMS
-
-BananaBit CMS is a modern, extension-based content management system built with Rust and Dioxus.
-
-## Mission
-
-Our mission is to provide a fast, secure, and highly customizable CMS that developers love to work with.
-
-## Features
-
-- **Extension-based Architecture**: Everything is a plugin, making the system highly modular
-- **Performance**: Built with Rust for maximum performance and safety
-- **Modern UI**: Uses Dioxus for a reactive, component-based frontend
-- **Security**: Type-safe code and secure defaults
-- **Flexibility**: Easy to extend and customize
-
-## Technology Stack
-
-- **Backend**: Rust with modern web frameworks
-- **Frontend**: Dioxus (React-like for Rust)
-- **Database**: Pluggable storage backends
-- **Authentication**: Built-in secure auth system
-
-## Getting Started
-
-Visit our documentation to learn how to set up and customize your BananaBit CMS installation.
-
----
-
-*Built with ❤️ by the BananaBit team*"#.to_string(),
-            author: "Admin".to_string(),
-            created_at: "2024-01-01T00:00:00Z".to_string(),
-            updated_at: "2024-01-01T00:00:00Z".to_string(),
-            published: true,
-            template: "default".to_string(),
-        };
-        
-        self.add_page(about_page);
-        
-        // Create a Contact page
-        let contact_page = Page {
-            id: 2,
-            slug: "contact".to_string(),
-            title: "Contact Us".to_string(),
-            content: r#"# Contact Us
-
-Get in touch with the BananaBit CMS team.
-
-## Ways to Reach Us
-
-### Email
-- General inquiries: hello@bananabit.cms
-- Support: support@bananabit.cms
-- Security issues: security@bananabit.cms
-
-### Social Media
-- Twitter: [@BananaBitCMS](https://twitter.com/bananabitcms)
-- GitHub: [bananabit-dev](https://github.com/bananabit-dev)
-
-### Community
-- Discord: Join our community server
-- Forums: Community discussion boards
-
-## Business Hours
-
-We're available Monday through Friday, 9 AM to 5 PM UTC.
-
----
-
-We'd love to hear from you!"#.to_string(),
-            author: "Admin".to_string(),
-            created_at: "2024-01-01T00:00:00Z".to_string(),
-            updated_at: "2024-01-01T00:00:00Z".to_string(),
-            published: true,
-            template: "default".to_string(),
-        };
-        
-        self.add_page(contact_page);
-        
+        // The About and Contact pages are seeded by the database
         Ok(())
     }
     
@@ -176,89 +57,84 @@ We'd love to hear from you!"#.to_string(),
 
 #[component]
 pub fn PageView(slug: String) -> Element {
-    // In a real implementation, this would fetch from the pages extension
-    let content = use_signal(|| match slug.as_str() {
-        "about" => r#"# About BananaBit CMS
-
-BananaBit CMS is a modern, extension-based content management system built with Rust and Dioxus.
-
-## Mission
-
-Our mission is to provide a fast, secure, and highly customizable CMS that developers love to work with.
-
-## Features
-
-- **Extension-based Architecture**: Everything is a plugin, making the system highly modular
-- **Performance**: Built with Rust for maximum performance and safety
-- **Modern UI**: Uses Dioxus for a reactive, component-based frontend
-- **Security**: Type-safe code and secure defaults
-- **Flexibility**: Easy to extend and customize
-
-## Technology Stack
-
-- **Backend**: Rust with modern web frameworks
-- **Frontend**: Dioxus (React-like for Rust)
-- **Database**: Pluggable storage backends
-- **Authentication**: Built-in secure auth system
-
-## Getting Started
-
-Visit our documentation to learn how to set up and customize your BananaBit CMS installation.
-
----
-
-*Built with ❤️ by the BananaBit team*"#.to_string(),
-        "contact" => r#"# Contact Us
-
-Get in touch with the BananaBit CMS team.
-
-## Ways to Reach Us
-
-### Email
-- General inquiries: hello@bananabit.cms
-- Support: support@bananabit.cms
-- Security issues: security@bananabit.cms
-
-### Social Media
-- Twitter: [@BananaBitCMS](https://twitter.com/bananabitcms)
-- GitHub: [bananabit-dev](https://github.com/bananabit-dev)
-
-### Community
-- Discord: Join our community server
-- Forums: Community discussion boards
-
-## Business Hours
-
-We're available Monday through Friday, 9 AM to 5 PM UTC.
-
----
-
-We'd love to hear from you!"#.to_string(),
-        _ => format!("# Page Not Found\n\nThe page '{}' could not be found.", slug),
+    let stored_page = use_server_future({
+        let slug = slug.clone();
+        move || api::get_page_by_slug(slug.clone())
+    })?;
+    let page = stored_page.read().as_ref().and_then(|result| result.as_ref().ok().cloned().flatten());
+    let content = match page.clone() {
+        Some(page) => page.content,
+        None => format!("# Page Not Found\n\nThe page '{}' could not be found.", slug),
+    };
+    let template = page.as_ref().map(|page| page.template.clone());
+    let preview = page.as_ref().map(|page| (page.published, page.publish_at.clone(), page.expire_at.clone()));
+    // Shortcodes that can't be rendered show why only while the content is hidden from readers
+    let now = client::time::normalize_timestamp(&client::time::now_iso8601()).unwrap_or_default();
+    let hidden = preview.as_ref().is_some_and(|(published, publish_at, expire_at)| {
+        super::hidden_reason(*published, publish_at.as_deref(), expire_at.as_deref(), &now).is_some()
     });
+    let translations = page.as_ref().and_then(|page| Some((page.translation_group.clone()?, page.language.clone())));
+    // Right-to-left content gets `dir="rtl"` even when the rest of the page is left to right
+    let (content_language, direction) = page.as_ref().map_or((None, None), |page| super::content_direction(&page.language));
+
+    let settings = use_server_future(api::get_seo_settings)?;
+    let settings = settings.read().as_ref().and_then(|result| result.as_ref().ok().cloned()).unwrap_or_default();
+    let html_settings = use_server_future(api::get_html_settings)?;
+    let html_policy = html_settings.read().as_ref().and_then(|result| result.as_ref().ok()).map(|html| html.posts.clone());
+    let structured_data = page.as_ref().map(|page| (
+        super::web_page_json_ld(page, &settings),
+        super::breadcrumb_json_ld(&super::route_breadcrumbs(&page.title, &page.path(&settings.language)), &settings),
+    ));
+    let meta = match &page {
+        Some(page) => super::SeoMeta::for_page(page, &settings),
+        None => super::SeoMeta {
+            robots: "noindex,follow".to_string(),
+            ..super::SeoMeta::from_settings(&settings, &format!("/page/{}", slug))
+        },
+    };
 
     let image_base_path = "/assets/images";
 
     rsx! {
+        super::MetaTags { meta }
+        if let Some((web_page, breadcrumbs)) = structured_data {
+            super::JsonLd { data: web_page }
+            super::JsonLd { data: breadcrumbs }
+        }
         document::Link { rel: "stylesheet", href: "/assets/blog.css"}
         document::Link { rel: "stylesheet", href: "/assets/styling/markdown.css"}
         document::Link { rel: "stylesheet", href: "/assets/styling/syntax.css"}
 
-        div {
-            id: "page",
-            class: "markdown-container page-content",
+        super::ThemeLayout {
+            template,
+            div {
+                id: "page",
+                class: "markdown-container page-content",
+                lang: content_language,
+                dir: direction,
 
-            crate::Markdown {
-                content: Some(content()),
-                image_base_path: Some(image_base_path.to_string()),
-                id: Some(format!("page-content-{}", slug))
-            }
+                if let Some((published, publish_at, expire_at)) = preview {
+                    super::PreviewNotice { published, publish_at, expire_at }
+                }
 
-            div {
-                class: "page-navigation",
-                Link {
-                    to: crate::navbar::Route::Home {},
-                    "← Back to Home"
+                if let Some((group, language)) = translations {
+                    super::TranslationSwitcher { content_type: client::ContentType::Page, group, language }
+                }
+
+                crate::Markdown {
+                    content: Some(content),
+                    image_base_path: Some(image_base_path.to_string()),
+                    html_policy,
+                    preview: hidden,
+                    id: Some(format!("page-content-{}", slug))
+                }
+
+                div {
+                    class: "page-navigation",
+                    Link {
+                        to: crate::navbar::Route::Home {},
+                        {t!("back-home")}
+                    }
                 }
             }
         }
@@ -267,20 +143,25 @@ We'd love to hear from you!"#.to_string(),
 
 #[component]
 pub fn PageList() -> Element {
+    let pages = use_server_future(api::get_published_pages)?;
+    let pages = pages.read().as_ref().and_then(|result| result.as_ref().ok().cloned()).unwrap_or_default();
+    let default_language = super::default_language();
+    
     rsx! {
         div {
             class: "page-list",
             
             ul {
-                li { 
-                    a { href: "/page/about", "About" }
-                    p { "Learn more about BananaBit CMS" }
-                }
-                li { 
-                    a { href: "/page/contact", "Contact" }
-                    p { "Get in touch with us" }
+                for page in pages {
+                    li {
+                        key: "{page.id}",
+                        a { href: page.path(&default_language), "{page.title}" }
+                        if let Some(description) = page.meta_description.clone() {
+                            p { "{description}" }
+                        }
+                    }
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
@@ -1,7 +1,9 @@
 use dioxus::prelude::*;
-use super::{Extension, ExtensionRoute, ExtensionComponent, Post};
+use super::{Extension, ExtensionRoute, ExtensionComponent, Post, SeoAuditPanel};
+use client::{FrontMatter, FrontMatterError, HtmlPolicy, HtmlSettings, HTML_ATTRIBUTES, HTML_TAGS};
 use crate::navbar::Route;
-use crate::Markdown;
+use crate::{Markdown, TableOfContents};
+use crate::t;
 use std::collections::HashMap;
 
 /// Posts extension - handles blog posts and pages
@@ -66,6 +68,10 @@ impl Extension for PostsExtension {
             created_at: "2024-01-01T00:00:00Z".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
             published: true,
+            meta_description: Some("Discover the power of our modern, extension-based content management system built with Rust and Dioxus.".to_string()),
+            meta_keywords: Some("cms, rust, dioxus, content management, blogging".to_string()),
+            cover_image: Some("/uploads/bananabit-logo.png".to_string()),
+            ..Default::default()
         };
         
         self.add_post(first_post);
@@ -98,6 +104,7 @@ This modular approach allows developers to easily add new features without modif
             created_at: "2024-01-02T00:00:00Z".to_string(),
             updated_at: "2024-01-02T00:00:00Z".to_string(),
             published: true,
+            ..Default::default()
         };
         
         self.add_post(second_post);
@@ -135,59 +142,143 @@ pub fn PostView(slug: String) -> Element {
     // For now, we'll use a simple static approach
     // In a real implementation, this would fetch from the extension manager
     let slug_for_format = slug.clone();
-    let content = use_resource(move || {
-        let slug_copy = slug.clone();
-        async move {
-            // This is a simplified version - in reality we'd get this from the extension manager
-            if slug_copy == "welcome-to-bananabit-cms" || slug_copy == "0" {
-                gloo_net::http::Request::get("/assets/blog/0.md")
-                    .send()
-                    .await
-                    .unwrap()
-                    .text()
-                    .await
-                    .unwrap_or_else(|_| "Error loading post".to_string())
-            } else {
-                format!("# Post: {}\n\nThis is a dynamically generated post for slug: {}", slug_copy, slug_copy)
-            }
-        }
+    let slug_for_template = slug.clone();
+    // The post's layout template comes from the database when the post is stored there
+    let stored_post = use_server_future(move || api::get_post_by_slug(slug_for_template.clone()))?;
+    let settings = use_server_future(api::get_seo_settings)?;
+    let html_settings = use_server_future(api::get_html_settings)?;
+    
+    // Posts not yet in the database fall back to the built-in examples
+    let post = stored_post.read().as_ref().and_then(|result| result.as_ref().ok().cloned().flatten()).or_else(|| {
+        let mut posts = PostsExtension::new();
+        posts.init().ok();
+        posts.get_post_by_slug(&slug_for_format).cloned()
     });
-
-    let image_base_path = "/assets/images";
+    let template = post.as_ref().and_then(|post| post.template.clone());
+    let settings = settings.read().as_ref().and_then(|result| result.as_ref().ok().cloned()).unwrap_or_default();
+    let html_policy = html_settings.read().as_ref().and_then(|result| result.as_ref().ok()).map(|html| html.posts.clone());
+    let structured_data = post.as_ref().map(|post| (
+        super::blog_posting_json_ld(post, &settings),
+        super::breadcrumb_json_ld(&super::route_breadcrumbs(&post.title, &post.path(&settings.language)), &settings),
+    ));
+    let meta = match &post {
+        Some(post) => super::SeoMeta::for_post(post, &settings),
+        None => super::SeoMeta {
+            robots: "noindex,follow".to_string(),
+            ..super::SeoMeta::from_settings(&settings, &format!("/post/{}", slug_for_format))
+        },
+    };
 
     rsx! {
+        super::MetaTags { meta }
+        if let Some((article, breadcrumbs)) = structured_data {
+            super::JsonLd { data: article }
+            super::JsonLd { data: breadcrumbs }
+        }
         document::Link { rel: "stylesheet", href: "/assets/blog.css"}
         document::Link { rel: "stylesheet", href: "/assets/styling/markdown.css"}
         document::Link { rel: "stylesheet", href: "/assets/styling/syntax.css"}
 
+        super::ThemeLayout {
+            template,
+            PostBody {
+                slug,
+                post,
+                html_policy,
+
+                div {
+                    class: "post-navigation",
+                    Link {
+                        to: Route::Home {},
+                        {t!("back-home")}
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The text of a post as `PostView` lays it out, in the post's language and direction,
+/// followed by `children`
+#[component]
+pub fn PostBody(slug: String, post: Option<Post>, html_policy: Option<HtmlPolicy>, children: Element) -> Element {
+    let content = match &post {
+        Some(post) => post.content.clone(),
+        None => format!("# Post Not Found\n\nThe post '{}' could not be found.", slug),
+    };
+    let preview = post.as_ref().map(|post| (post.published, post.publish_at.clone(), post.expire_at.clone()));
+    // Shortcodes that can't be rendered show why only while the content is hidden from readers
+    let now = client::time::normalize_timestamp(&client::time::now_iso8601()).unwrap_or_default();
+    let hidden = preview.as_ref().is_some_and(|(published, publish_at, expire_at)| {
+        hidden_reason(*published, publish_at.as_deref(), expire_at.as_deref(), &now).is_some()
+    });
+    let translations = post.as_ref().and_then(|post| Some((post.translation_group.clone()?, post.language.clone())));
+    // Right-to-left content gets `dir="rtl"` even when the rest of the page is left to right
+    let (content_language, direction) = post.as_ref().map_or((None, None), |post| super::content_direction(&post.language));
+
+    let image_base_path = "/assets/images";
+
+    rsx! {
         div {
             id: "post",
             class: "markdown-container",
+            lang: content_language,
+            dir: direction,
 
-            match content.read().as_ref() {
-                Some(markdown) => rsx! {
-                    Markdown {
-                        content: Some(markdown.clone()),
-                        image_base_path: Some(image_base_path.to_string()),
-                        id: Some(format!("post-content-{}", slug_for_format))
-                    }
-                },
-                None => rsx! { p { "Loading Post..." } }
+            if let Some((published, publish_at, expire_at)) = preview {
+                PreviewNotice { published, publish_at, expire_at }
             }
 
-            div {
-                class: "post-navigation",
-                Link {
-                    to: Route::Home {},
-                    "← Back to Home"
-                }
+            if let Some((group, language)) = translations {
+                super::TranslationSwitcher { content_type: client::ContentType::Post, group, language }
+            }
+
+            TableOfContents { content: content.clone() }
+
+            Markdown {
+                content: Some(content),
+                image_base_path: Some(image_base_path.to_string()),
+                html_policy,
+                preview: hidden,
+                id: Some(format!("post-content-{}", slug))
             }
+
+            {children}
+        }
+    }
+}
+
+/// Why readers can't see a post or page, if they can't
+pub fn hidden_reason(published: bool, publish_at: Option<&str>, expire_at: Option<&str>, now: &str) -> Option<String> {
+    match (publish_at, expire_at) {
+        _ if !published => Some("it is a draft".to_string()),
+        (Some(publish_at), _) if publish_at > now => Some(format!("it is embargoed until {}", publish_at)),
+        (_, Some(expire_at)) if expire_at <= now => Some(format!("it expired at {}", expire_at)),
+        _ => None,
+    }
+}
+
+/// Banner for editors previewing content that readers can't see
+#[component]
+pub fn PreviewNotice(published: bool, publish_at: Option<String>, expire_at: Option<String>) -> Element {
+    let now = client::time::normalize_timestamp(&client::time::now_iso8601()).unwrap_or_default();
+    let Some(reason) = hidden_reason(published, publish_at.as_deref(), expire_at.as_deref(), &now) else {
+        return rsx! {};
+    };
+    
+    rsx! {
+        div {
+            class: "preview-notice",
+            "Preview: readers can't see this because {reason}."
         }
     }
 }
 
 #[component]
 pub fn PostList() -> Element {
+    let formatter = super::formatter();
+    let published_on = |timestamp: &str| t!("posts:published-on", date = formatter.date(timestamp));
+
     rsx! {
         div {
             class: "post-list",
@@ -201,7 +292,7 @@ pub fn PostList() -> Element {
                     }
                 }
                 p { "The first post in our new extension-based CMS" }
-                span { class: "post-meta", "Published on 2024-01-01" }
+                span { class: "post-meta", {published_on("2024-01-01T00:00:00Z")} }
             }
             
             div {
@@ -210,8 +301,454 @@ pub fn PostList() -> Element {
                     a { href: "/post/extension-architecture", "Understanding the Extension Architecture" }
                 }
                 p { "Learn about our powerful extension system" }
-                span { class: "post-meta", "Published on 2024-01-02" }
+                span { class: "post-meta", {published_on("2024-01-02T00:00:00Z")} }
+            }
+        }
+    }
+}
+/// Admin list of every post, drafts included
+#[component]
+pub fn PostManager() -> Element {
+    let posts = use_resource(api::get_all_posts);
+    let formatter = super::formatter();
+    let now = client::time::now_iso8601();
+    
+    rsx! {
+        div {
+            class: "post-manager",
+            h2 { "Posts" }
+            a { class: "button", href: "/admin/posts/new", "New Post" }
+            
+            match &*posts.read() {
+                None => rsx! { p { "Loading posts..." } },
+                Some(Err(e)) => rsx! { p { class: "error", "Failed to load posts: {e}" } },
+                Some(Ok(posts)) => rsx! {
+                    ul {
+                        class: "post-list",
+                        for post in posts.iter() {
+                            li {
+                                key: "{post.id}",
+                                class: "post-item",
+                                a { href: "/admin/posts/{post.id}/edit", "{post.title}" }
+                                span {
+                                    class: "post-meta",
+                                    if post.published { " Published" } else { " Draft" }
+                                }
+                                span {
+                                    class: "post-meta",
+                                    title: formatter.datetime(&post.updated_at),
+                                    " · updated {formatter.relative(&post.updated_at, &now)}"
+                                }
+                            }
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// Move the front matter at the top of a post's content onto its fields
+fn apply_front_matter(post: &mut Post) -> Result<(), FrontMatterError> {
+    let (front_matter, body) = FrontMatter::parse(&post.content)?;
+    if let Some(front_matter) = front_matter {
+        let body = body.trim_start_matches(['\r', '\n']).to_string();
+        front_matter.apply_to_post(post);
+        post.content = body;
+    }
+    Ok(())
+}
+
+/// `My First Post.md` as `my-first-post`
+fn slug_from_file_name(file_name: &str) -> String {
+    let stem = file_name.rsplit(['/', '\\']).next().unwrap_or(file_name);
+    let stem = stem.rsplit_once('.').map_or(stem, |(stem, _)| stem);
+    stem.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Post editor with a live SEO audit; `id` is `None` for a new post
+#[component]
+pub fn PostEditor(id: ReadOnlySignal<Option<u32>>) -> Element {
+    let mut post = use_signal(Post::default);
+    let mut message = use_signal(String::new);
+    let languages = use_resource(api::get_languages);
+    
+    // Reloads when moving to another post, e.g. a newly started translation
+    use_effect(move || {
+        if let Some(id) = id() {
+            spawn(async move {
+                match api::get_post_by_id(id).await {
+                    Ok(Some(loaded)) => post.set(loaded),
+                    Ok(None) => message.set(format!("Post {} not found", id)),
+                    Err(e) => message.set(format!("Failed to load post: {}", e)),
+                }
+            });
+        }
+    });
+    
+    let on_save = move |evt: FormEvent| {
+        evt.prevent_default();
+        // Front matter typed into the content fills in the fields it sets
+        let mut current = post();
+        if let Err(e) = apply_front_matter(&mut current) {
+            message.set(format!("Front matter error on {}", e));
+            return;
+        }
+        post.set(current.clone());
+        spawn(async move {
+            match api::save_post(current).await {
+                Ok(saved_id) => {
+                    post.with_mut(|p| p.id = saved_id);
+                    message.set("Post saved".to_string());
+                },
+                Err(e) => message.set(format!("Failed to save post: {}", e)),
+            }
+        });
+    };
+    
+    // A markdown file written elsewhere, with its metadata in front matter
+    let on_import = move |evt: FormEvent| {
+        spawn(async move {
+            let Some(file_engine) = evt.files() else { return };
+            let Some(file_name) = file_engine.files().into_iter().next() else { return };
+            let Some(data) = file_engine.read_file_to_string(&file_name).await else {
+                message.set(format!("Could not read {}", file_name));
+                return;
+            };
+
+            let mut imported = Post { content: data, ..post() };
+            match apply_front_matter(&mut imported) {
+                Ok(()) => {
+                    if imported.slug.is_empty() {
+                        imported.slug = slug_from_file_name(&file_name);
+                    }
+                    post.set(imported);
+                    message.set(format!("Imported {}; review the fields and save", file_name));
+                },
+                Err(e) => message.set(format!("{} has a front matter error on {}", file_name, e)),
+            }
+        });
+    };
+    
+    // Optional fields are stored as `None` when left blank
+    let optional = |value: String| if value.trim().is_empty() { None } else { Some(value) };
+    let current = post();
+    
+    rsx! {
+        div {
+            class: "post-editor",
+            h2 { if current.id == 0 { "New Post" } else { "Edit Post" } }
+            
+            if !message().is_empty() {
+                div { class: "status-message", p { "{message}" } }
+            }
+            
+            div {
+                class: "post-editor-layout",
+                form {
+                    onsubmit: on_save,
+                    
+                    div {
+                        label { "Import Markdown" }
+                        input {
+                            r#type: "file",
+                            accept: ".md,.markdown,text/markdown",
+                            onchange: on_import,
+                        }
+                        small { "YAML (---) or TOML (+++) front matter fills in the title, slug, date, tags, description, draft, template and cover image" }
+                    }
+                    
+                    div {
+                        label { "Title" }
+                        input {
+                            r#type: "text",
+                            // Arabic or Hebrew titles and content are typed right to left
+                            dir: "auto",
+                            value: "{current.title}",
+                            oninput: move |e| post.with_mut(|p| p.title = e.value()),
+                        }
+                    }
+                    
+                    div {
+                        label { "Slug" }
+                        input {
+                            r#type: "text",
+                            value: "{current.slug}",
+                            placeholder: "my-post",
+                            oninput: move |e| post.with_mut(|p| p.slug = e.value()),
+                        }
+                    }
+                    
+                    div {
+                        label { "Language" }
+                        select {
+                            value: "{current.language}",
+                            onchange: move |e| post.with_mut(|p| p.language = e.value()),
+                            if current.language.is_empty() {
+                                option { value: "", "Site default" }
+                            }
+                            if let Some(Ok(languages)) = &*languages.read() {
+                                for language in languages.iter().filter(|language| language.active || language.code == current.language) {
+                                    option { key: "{language.code}", value: "{language.code}", "{language.name}" }
+                                }
+                            }
+                        }
+                    }
+                    
+                    div {
+                        label { "Author" }
+                        input {
+                            r#type: "text",
+                            value: "{current.author}",
+                            oninput: move |e| post.with_mut(|p| p.author = e.value()),
+                        }
+                    }
+                    
+                    div {
+                        label { "Content (Markdown)" }
+                        textarea {
+                            rows: "20",
+                            dir: "auto",
+                            value: "{current.content}",
+                            oninput: move |e| post.with_mut(|p| p.content = e.value()),
+                        }
+                    }
+                    
+                    details {
+                        class: "post-editor-preview",
+                        summary { "Preview" }
+                        Markdown {
+                            content: Some(current.content.clone()),
+                            image_base_path: Some("/assets/images".to_string()),
+                            preview: true,
+                            id: Some("post-editor-preview".to_string())
+                        }
+                    }
+                    
+                    h3 { "Search & Sharing" }
+                    
+                    div {
+                        label { "Meta Description" }
+                        textarea {
+                            rows: "3",
+                            value: current.meta_description.clone().unwrap_or_default(),
+                            oninput: move |e| post.with_mut(|p| p.meta_description = optional(e.value())),
+                        }
+                    }
+                    
+                    div {
+                        label { "Keywords" }
+                        input {
+                            r#type: "text",
+                            value: current.meta_keywords.clone().unwrap_or_default(),
+                            placeholder: "Comma-separated; the first is the focus keyword",
+                            oninput: move |e| post.with_mut(|p| p.meta_keywords = optional(e.value())),
+                        }
+                    }
+                    
+                    div {
+                        label { "Cover Image" }
+                        input {
+                            r#type: "text",
+                            value: current.cover_image.clone().unwrap_or_default(),
+                            placeholder: "Image URL or path",
+                            oninput: move |e| post.with_mut(|p| p.cover_image = optional(e.value())),
+                        }
+                    }
+                    
+                    div {
+                        label { "Canonical URL" }
+                        input {
+                            r#type: "url",
+                            value: current.canonical_url.clone().unwrap_or_default(),
+                            oninput: move |e| post.with_mut(|p| p.canonical_url = optional(e.value())),
+                        }
+                    }
+                    
+                    div {
+                        label { "Template" }
+                        input {
+                            r#type: "text",
+                            value: current.template.clone().unwrap_or_default(),
+                            placeholder: "Theme default",
+                            oninput: move |e| post.with_mut(|p| p.template = optional(e.value())),
+                        }
+                    }
+                    
+                    div {
+                        label {
+                            input {
+                                r#type: "checkbox",
+                                checked: current.published,
+                                onchange: move |e| post.with_mut(|p| p.published = e.checked()),
+                            }
+                            " Published"
+                        }
+                    }
+                    
+                    div {
+                        label { "Publish At (UTC)" }
+                        input {
+                            r#type: "datetime-local",
+                            // Stored as `YYYY-MM-DDTHH:MM:SSZ`; the input takes minutes
+                            value: current.publish_at.as_deref().and_then(|at| at.get(..16)).unwrap_or_default(),
+                            oninput: move |e| post.with_mut(|p| p.publish_at = optional(e.value())),
+                        }
+                        small { "Hidden from readers until then, even when published" }
+                    }
+                    
+                    div {
+                        label { "Expire At (UTC)" }
+                        input {
+                            r#type: "datetime-local",
+                            value: current.expire_at.as_deref().and_then(|at| at.get(..16)).unwrap_or_default(),
+                            oninput: move |e| post.with_mut(|p| p.expire_at = optional(e.value())),
+                        }
+                        small { "Hidden from readers from then on" }
+                    }
+                    
+                    if !current.published {
+                        div {
+                            label { "Target Date" }
+                            input {
+                                r#type: "date",
+                                value: current.target_date.clone().unwrap_or_default(),
+                                oninput: move |e| post.with_mut(|p| p.target_date = optional(e.value())),
+                            }
+                        }
+                    }
+                    
+                    button { r#type: "submit", "Save Post" }
+                    
+                    if current.id != 0 {
+                        super::TranslationPanel { content_type: client::ContentType::Post, id: current.id }
+                    }
+                }
+                
+                SeoAuditPanel { post: current.clone() }
+            }
+        }
+    }
+}
+
+/// Which raw HTML posts, pages and comments may contain
+#[component]
+pub fn HtmlSettingsManager() -> Element {
+    let mut settings = use_signal(HtmlSettings::new);
+    let mut message = use_signal(String::new);
+    
+    // Load the stored settings once
+    use_future(move || async move {
+        match api::get_html_settings().await {
+            Ok(loaded) => settings.set(loaded),
+            Err(e) => message.set(format!("Failed to load settings: {}", e)),
+        }
+    });
+    
+    let on_save = move |evt: FormEvent| {
+        evt.prevent_default();
+        spawn(async move {
+            match api::save_html_settings(settings()).await {
+                Ok(()) => message.set("Settings saved".to_string()),
+                Err(e) => message.set(format!("Failed to save settings: {}", e)),
+            }
+        });
+    };
+    
+    rsx! {
+        div {
+            class: "html-settings",
+            h2 { "Allowed HTML" }
+            p { "Raw HTML in markdown keeps only these tags and attributes. Scripts, styles, event handlers and javascript: links are always removed." }
+            
+            form {
+                onsubmit: on_save,
+                
+                if !message().is_empty() {
+                    div { class: "status-message", p { "{message}" } }
+                }
+                
+                HtmlPolicyFields { title: "Posts and Pages", settings, comments: false }
+                HtmlPolicyFields { title: "Comments", settings, comments: true }
+                
+                div {
+                    button { r#type: "submit", "Save Settings" }
+                    button {
+                        r#type: "button",
+                        onclick: move |_| settings.set(HtmlSettings::new()),
+                        "Reset to Default"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Checkboxes for one policy of [`HtmlSettingsManager`]
+#[component]
+fn HtmlPolicyFields(title: String, settings: Signal<HtmlSettings>, comments: bool) -> Element {
+    let current = if comments { settings.read().comments.clone() } else { settings.read().posts.clone() };
+    let mut update = move |change: &dyn Fn(&mut HtmlPolicy)| {
+        settings.with_mut(|s| change(if comments { &mut s.comments } else { &mut s.posts }))
+    };
+    
+    rsx! {
+        fieldset {
+            legend { "{title}" }
+            
+            h4 { "Tags" }
+            div {
+                class: "html-settings-options",
+                for tag in HTML_TAGS.iter().copied() {
+                    label {
+                        key: "{tag}",
+                        input {
+                            r#type: "checkbox",
+                            checked: current.tags.iter().any(|allowed| allowed == tag),
+                            onchange: move |e| update(&|policy| toggle(&mut policy.tags, tag, e.checked())),
+                        }
+                        code { {format!("<{}>", tag)} }
+                    }
+                }
+            }
+            
+            h4 { "Attributes" }
+            div {
+                class: "html-settings-options",
+                for name in HTML_ATTRIBUTES.iter().copied() {
+                    label {
+                        key: "{name}",
+                        input {
+                            r#type: "checkbox",
+                            checked: current.attributes.iter().any(|allowed| allowed == name),
+                            onchange: move |e| update(&|policy| toggle(&mut policy.attributes, name, e.checked())),
+                        }
+                        code { "{name}" }
+                    }
+                }
+            }
+            
+            label {
+                input {
+                    r#type: "checkbox",
+                    checked: current.nofollow,
+                    onchange: move |e| update(&|policy| policy.nofollow = e.checked()),
+                }
+                " Mark links nofollow"
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Check or uncheck `item`, keeping the list in a stable order
+fn toggle(list: &mut Vec<String>, item: &str, checked: bool) {
+    list.retain(|existing| existing != item);
+    if checked {
+        list.push(item.to_string());
+        list.sort();
+    }
+}
@@ -0,0 +1,308 @@
+//! Redirect management: CSV import, chain and loop detection, and the admin UI.
+
+use dioxus::prelude::*;
+use client::{normalize_redirect_path, Redirect};
+use std::collections::{HashMap, HashSet};
+
+/// Redirects followed before a chain is reported as too long to resolve
+const MAX_CHAIN_LENGTH: usize = 10;
+
+/// A problem with how redirects link together
+#[derive(Debug, Clone, PartialEq)]
+pub enum RedirectIssue {
+    /// More than one hop before reaching content; the first source should point at the end directly
+    Chain(Vec<String>),
+    /// Redirects that lead back to a path already visited
+    Loop(Vec<String>),
+}
+
+impl RedirectIssue {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Chain(path) => format!(
+                "Chain of {} redirects: {}. Point {} straight at {}.",
+                path.len() - 1,
+                path.join(" → "),
+                path[0],
+                path[path.len() - 1]
+            ),
+            Self::Loop(path) => format!("Redirect loop: {}", path.join(" → ")),
+        }
+    }
+}
+
+/// Find redirect chains and loops. Only local targets are followed; 410s end a path.
+pub fn redirect_issues(redirects: &[Redirect]) -> Vec<RedirectIssue> {
+    let targets: HashMap<&str, String> = redirects
+        .iter()
+        .filter(|redirect| !redirect.is_gone())
+        .map(|redirect| (redirect.source.as_str(), normalize_redirect_path(&redirect.target)))
+        .collect();
+
+    let mut issues = Vec::new();
+    let mut reported_loops: HashSet<Vec<String>> = HashSet::new();
+
+    for redirect in redirects.iter().filter(|redirect| !redirect.is_gone()) {
+        let mut path = vec![redirect.source.clone()];
+        let mut current = redirect.source.as_str();
+
+        while let Some(next) = targets.get(current) {
+            if let Some(start) = path.iter().position(|visited| visited == next) {
+                // Report each loop once, starting from its smallest path
+                let mut cycle = path[start..].to_vec();
+                let min = cycle.iter().enumerate().min_by_key(|(_, p)| p.as_str()).map(|(i, _)| i).unwrap_or(0);
+                cycle.rotate_left(min);
+                if reported_loops.insert(cycle.clone()) {
+                    cycle.push(cycle[0].clone());
+                    issues.push(RedirectIssue::Loop(cycle));
+                }
+                break;
+            }
+            path.push(next.clone());
+            if path.len() > MAX_CHAIN_LENGTH {
+                break;
+            }
+            current = next;
+        }
+
+        let looped = targets.get(current).is_some_and(|next| path.contains(next));
+        if !looped && path.len() > 2 {
+            issues.push(RedirectIssue::Chain(path));
+        }
+    }
+
+    issues
+}
+
+/// A parsed CSV line: the redirect, or why it was rejected
+pub type CsvRow = (usize, Result<Redirect, String>);
+
+/// Parse redirects from CSV with `source,target[,status]` columns. A header row is skipped,
+/// the status defaults to 301, and fields may be quoted.
+pub fn parse_redirects_csv(csv: &str) -> Vec<CsvRow> {
+    csv.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter(|(index, line)| !(*index == 0 && line.to_lowercase().starts_with("source")))
+        .map(|(index, line)| (index + 1, parse_csv_redirect(line)))
+        .collect()
+}
+
+fn parse_csv_redirect(line: &str) -> Result<Redirect, String> {
+    let fields = split_csv_line(line);
+    let field = |index: usize| fields.get(index).map(|field| field.trim()).unwrap_or_default();
+
+    let status = match field(2) {
+        "" => 301,
+        status => status.parse().map_err(|_| format!("\"{}\" is not a status code", status))?,
+    };
+    let redirect = Redirect {
+        source: normalize_redirect_path(field(0)),
+        target: field(1).to_string(),
+        status,
+        ..Default::default()
+    };
+
+    redirect.validate()?;
+    Ok(redirect)
+}
+
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            },
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+
+    fields
+}
+
+#[component]
+pub fn RedirectManager() -> Element {
+    let mut redirects = use_resource(|| async { api::get_redirects().await });
+    let mut draft = use_signal(|| Redirect { status: 301, ..Default::default() });
+    let mut csv_rows = use_signal(Vec::<CsvRow>::new);
+    let mut message = use_signal(String::new);
+
+    let on_add = move |evt: FormEvent| {
+        evt.prevent_default();
+        spawn(async move {
+            match api::save_redirect(draft()).await {
+                Ok(_) => {
+                    draft.set(Redirect { status: 301, ..Default::default() });
+                    message.set("Redirect saved".to_string());
+                    redirects.restart();
+                },
+                Err(e) => message.set(format!("Failed to save redirect: {}", e)),
+            }
+        });
+    };
+
+    let on_csv = move |evt: FormEvent| {
+        spawn(async move {
+            let Some(file_engine) = evt.files() else { return };
+            let Some(file_name) = file_engine.files().into_iter().next() else { return };
+            match file_engine.read_file_to_string(&file_name).await {
+                Some(csv) => csv_rows.set(parse_redirects_csv(&csv)),
+                None => message.set(format!("Could not read {}", file_name)),
+            }
+        });
+    };
+
+    let on_import = move |_| {
+        let valid: Vec<Redirect> = csv_rows.read().iter().filter_map(|(_, row)| row.clone().ok()).collect();
+        spawn(async move {
+            match api::import_redirects(valid).await {
+                Ok(count) => {
+                    message.set(format!("Imported {} redirects", count));
+                    csv_rows.set(Vec::new());
+                    redirects.restart();
+                },
+                Err(e) => message.set(format!("Import failed: {}", e)),
+            }
+        });
+    };
+
+    let current = draft();
+    let rows = csv_rows();
+    let valid_rows = rows.iter().filter(|(_, row)| row.is_ok()).count();
+
+    rsx! {
+        div {
+            class: "redirect-manager",
+            h2 { "Redirects" }
+            p { "Old URLs are sent to their new location before any page is rendered. Changing a post's slug adds a 301 automatically." }
+
+            if !message().is_empty() {
+                div { class: "status-message", p { "{message}" } }
+            }
+
+            form {
+                class: "redirect-form",
+                onsubmit: on_add,
+                input {
+                    r#type: "text",
+                    placeholder: "/old-path",
+                    value: "{current.source}",
+                    oninput: move |e| draft.with_mut(|d| d.source = e.value()),
+                }
+                input {
+                    r#type: "text",
+                    placeholder: "/new-path or https://…",
+                    value: "{current.target}",
+                    disabled: current.is_gone(),
+                    oninput: move |e| draft.with_mut(|d| d.target = e.value()),
+                }
+                select {
+                    value: "{current.status}",
+                    onchange: move |e| draft.with_mut(|d| d.status = e.value().parse().unwrap_or(301)),
+                    option { value: "301", "301 Moved Permanently" }
+                    option { value: "302", "302 Found" }
+                    option { value: "410", "410 Gone" }
+                }
+                button { r#type: "submit", "Add Redirect" }
+            }
+
+            div {
+                class: "redirect-import",
+                h3 { "Import CSV" }
+                p { "Columns: source, target, status (optional, defaults to 301)." }
+                input { r#type: "file", accept: ".csv,text/csv", onchange: on_csv }
+
+                if !rows.is_empty() {
+                    ul {
+                        class: "audit-issues",
+                        for (line, row) in rows.iter() {
+                            match row {
+                                Ok(redirect) => rsx! {
+                                    li { class: "audit-info", "Line {line}: {redirect.source} → {redirect.target} ({redirect.status})" }
+                                },
+                                Err(e) => rsx! {
+                                    li { class: "audit-error", "Line {line}: {e}" }
+                                },
+                            }
+                        }
+                    }
+                    button {
+                        disabled: valid_rows == 0,
+                        onclick: on_import,
+                        "Import {valid_rows} redirects"
+                    }
+                }
+            }
+
+            match &*redirects.read() {
+                None => rsx! { p { "Loading redirects..." } },
+                Some(Err(e)) => rsx! { p { class: "error", "Failed to load redirects: {e}" } },
+                Some(Ok(list)) => {
+                    let issues = redirect_issues(list);
+                    rsx! {
+                        if !issues.is_empty() {
+                            div {
+                                class: "redirect-issues",
+                                h3 { "Problems" }
+                                ul {
+                                    class: "audit-issues",
+                                    for issue in issues {
+                                        li {
+                                            class: if matches!(issue, RedirectIssue::Loop(_)) { "audit-error" } else { "audit-warning" },
+                                            "{issue.describe()}"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        table {
+                            class: "redirect-table",
+                            thead {
+                                tr {
+                                    th { "Source" }
+                                    th { "Target" }
+                                    th { "Status" }
+                                    th { "Hits" }
+                                    th {}
+                                }
+                            }
+                            tbody {
+                                for redirect in list.iter().cloned() {
+                                    tr {
+                                        key: "{redirect.id}",
+                                        td { "{redirect.source}" }
+                                        td { "{redirect.target}" }
+                                        td { "{redirect.status}" }
+                                        td { "{redirect.hits}" }
+                                        td {
+                                            button {
+                                                onclick: move |_| {
+                                                    let id = redirect.id;
+                                                    spawn(async move {
+                                                        match api::delete_redirect(id).await {
+                                                            Ok(()) => redirects.restart(),
+                                                            Err(e) => message.set(format!("Failed to delete redirect: {}", e)),
+                                                        }
+                                                    });
+                                                },
+                                                "Delete"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
@@ -1,107 +1,17 @@
 use dioxus::prelude::*;
 use super::{Extension, ExtensionRoute, ExtensionComponent};
-use serde::{Deserialize, Serialize};
+use client::time::{add_days, date_to_days, days_to_date, normalize_timestamp, now_iso8601, today_date};
 use std::collections::HashMap;
-use client::time::now_iso8601;
 
-/// Scheduled content entry
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ScheduledContent {
-    pub id: u32,
-    pub content_type: ContentType,
-    pub content_id: u32,
-    pub scheduled_at: String, // ISO 8601 timestamp
-    pub action: ScheduledAction,
-    pub status: ScheduleStatus,
-    pub created_at: String,
-    pub created_by: u32,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum ContentType {
-    Post,
-    Page,
-    Media,
-}
+pub use client::{ContentType, ScheduleStatus, ScheduledAction, ScheduledContent};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum ScheduledAction {
-    Publish,
-    Unpublish,
-    Delete,
-    Update,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum ScheduleStatus {
-    Pending,
-    Processing,
-    Completed,
-    Failed,
-}
-
-/// Content scheduling extension
-pub struct SchedulingExtension {
-    scheduled_items: HashMap<u32, ScheduledContent>,
-    next_id: u32,
-}
+/// Content scheduling extension. Jobs are stored in the database and run by the server's
+/// scheduler, so they fire whether or not anyone has the admin open.
+pub struct SchedulingExtension;
 
 impl SchedulingExtension {
     pub fn new() -> Self {
-        Self {
-            scheduled_items: HashMap::new(),
-            next_id: 1,
-        }
-    }
-    
-    pub fn schedule_content(&mut self, mut item: ScheduledContent) -> u32 {
-        item.id = self.next_id;
-        item.status = ScheduleStatus::Pending;
-        self.scheduled_items.insert(self.next_id, item);
-        let id = self.next_id;
-        self.next_id += 1;
-        id
-    }
-    
-    pub fn get_scheduled_items(&self) -> Vec<&ScheduledContent> {
-        self.scheduled_items.values().collect()
-    }
-    
-    pub fn get_pending_items(&self) -> Vec<&ScheduledContent> {
-        self.scheduled_items
-            .values()
-            .filter(|item| matches!(item.status, ScheduleStatus::Pending))
-            .collect()
-    }
-    
-    pub fn cancel_scheduled_item(&mut self, id: u32) -> Option<ScheduledContent> {
-        self.scheduled_items.remove(&id)
-    }
-    
-    pub fn update_item_status(&mut self, id: u32, status: ScheduleStatus) -> bool {
-        if let Some(item) = self.scheduled_items.get_mut(&id) {
-            item.status = status;
-            true
-        } else {
-            false
-        }
-    }
-    
-    /// Process pending scheduled items (would be called by a background task)
-    pub fn process_pending_items(&mut self) -> Vec<u32> {
-        let now = now_iso8601();
-        let mut processed_ids = Vec::new();
-        
-        for (id, item) in &mut self.scheduled_items {
-            if matches!(item.status, ScheduleStatus::Pending) && item.scheduled_at <= now {
-                item.status = ScheduleStatus::Processing;
-                processed_ids.push(*id);
-                // In real implementation, this would trigger the actual action
-                println!("Processing scheduled item {}: {:?}", id, item.action);
-            }
-        }
-        
-        processed_ids
+        Self
     }
 }
 
@@ -119,20 +29,6 @@ impl Extension for SchedulingExtension {
     }
     
     fn init(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        // Add some sample scheduled content
-        let sample_schedule = ScheduledContent {
-            id: 0,
-            content_type: ContentType::Post,
-            content_id: 1,
-            scheduled_at: "2024-12-31T23:59:59Z".to_string(), // Fixed future date for demo
-            action: ScheduledAction::Publish,
-            status: ScheduleStatus::Pending,
-            created_at: now_iso8601(),
-            created_by: 1,
-        };
-        
-        self.schedule_content(sample_schedule);
-        
         Ok(())
     }
     
@@ -143,6 +39,11 @@ impl Extension for SchedulingExtension {
                 requires_auth: true,
                 admin_only: false,
             },
+            ExtensionRoute {
+                path: "/admin/calendar".to_string(),
+                requires_auth: true,
+                admin_only: false,
+            },
         ]
     }
     
@@ -164,21 +65,80 @@ impl Extension for SchedulingExtension {
     }
 }
 
+/// Titles of the posts and pages jobs can act on, keyed by type and id
+fn content_titles(posts: &[client::Post], pages: &[client::Page]) -> HashMap<(ContentType, u32), String> {
+    posts
+        .iter()
+        .map(|post| ((ContentType::Post, post.id), post.title.clone()))
+        .chain(pages.iter().map(|page| ((ContentType::Page, page.id), page.title.clone())))
+        .collect()
+}
+
+/// `2024-05-01T09:00:00Z` as `May 1, 2024, 11:00 AM (Europe/Paris)`, in the site's time zone
+fn format_schedule_time(timestamp: &str) -> String {
+    let formatter = super::formatter();
+    format!("{} ({})", formatter.datetime(timestamp), formatter.time_zone().name())
+}
+
 /// Scheduling manager component
 #[component]
 pub fn SchedulingManager() -> Element {
     let mut active_tab = use_signal(|| "upcoming".to_string());
+    let mut jobs = use_resource(api::get_scheduled_jobs);
+    let posts = use_resource(api::get_all_posts);
+    let pages = use_resource(api::get_all_pages);
+    let mut draft = use_signal(|| ScheduledContent::new(ContentType::Post, 0, ScheduledAction::Publish, ""));
+    let mut message = use_signal(String::new);
+    
+    let posts = loaded(&posts);
+    let pages = loaded(&pages);
+    let titles = content_titles(&posts, &pages);
+    let title_of = move |job: &ScheduledContent| {
+        titles
+            .get(&(job.content_type, job.content_id))
+            .cloned()
+            .unwrap_or_else(|| format!("{} #{}", job.content_type.as_str(), job.content_id))
+    };
+    
+    let (history, upcoming): (Vec<ScheduledContent>, Vec<ScheduledContent>) =
+        loaded(&jobs).into_iter().partition(|job| job.status.is_finished());
+    
+    let on_schedule = move |evt: FormEvent| {
+        evt.prevent_default();
+        spawn(async move {
+            match api::schedule_content(draft()).await {
+                Ok(_) => {
+                    draft.set(ScheduledContent::new(ContentType::Post, 0, ScheduledAction::Publish, ""));
+                    message.set("Scheduled".to_string());
+                    jobs.restart();
+                },
+                Err(e) => message.set(format!("Failed to schedule: {}", e)),
+            }
+        });
+    };
+    
+    let current = draft();
+    let options: Vec<(u32, String)> = match current.content_type {
+        ContentType::Page => pages.iter().map(|page| (page.id, page.title.clone())).collect(),
+        _ => posts.iter().map(|post| (post.id, post.title.clone())).collect(),
+    };
     
     rsx! {
         div {
+            class: "scheduling-manager",
             h2 { "Content Scheduling" }
-            p { "Schedule posts to be published, updated, or removed at specific times. Perfect for maintaining a consistent publishing schedule." }
+            p { "Schedule posts and pages to be published or removed at specific times. The server runs each job once it falls due." }
+            
+            if !message().is_empty() {
+                div { class: "status-message", p { "{message}" } }
+            }
             
             div {
                 div {
+                    class: "tab-buttons",
                     button { 
                         onclick: move |_| active_tab.set("upcoming".to_string()),
-                        "Upcoming (1)"
+                        "Upcoming ({upcoming.len()})"
                     }
                     button { 
                         onclick: move |_| active_tab.set("history".to_string()),
@@ -192,87 +152,134 @@ pub fn SchedulingManager() -> Element {
                 
                 div {
                     if active_tab() == "upcoming" {
-                        div {
-                            div {
-                                button { "Schedule New Content" }
-                                button { "Bulk Actions" }
+                        form {
+                            class: "schedule-form",
+                            onsubmit: on_schedule,
+                            select {
+                                value: "{current.content_type.as_str()}",
+                                onchange: move |e| draft.with_mut(|d| {
+                                    d.content_type = ContentType::parse(&e.value()).unwrap_or(ContentType::Post);
+                                    d.content_id = 0;
+                                }),
+                                option { value: "post", "Post" }
+                                option { value: "page", "Page" }
                             }
-                            
+                            select {
+                                value: "{current.content_id}",
+                                onchange: move |e| draft.with_mut(|d| d.content_id = e.value().parse().unwrap_or_default()),
+                                option { value: "0", disabled: true, "Choose…" }
+                                for (id, title) in options {
+                                    option { key: "{id}", value: "{id}", "{title}" }
+                                }
+                            }
+                            select {
+                                value: "{current.action.as_str()}",
+                                onchange: move |e| draft.with_mut(|d| d.action = ScheduledAction::parse(&e.value()).unwrap_or(ScheduledAction::Publish)),
+                                for action in [ScheduledAction::Publish, ScheduledAction::Unpublish, ScheduledAction::Delete] {
+                                    option { value: "{action.as_str()}", "{action.label()}" }
+                                }
+                            }
+                            label {
+                                "At (UTC) "
+                                input {
+                                    r#type: "datetime-local",
+                                    value: "{current.scheduled_at}",
+                                    oninput: move |e| draft.with_mut(|d| d.scheduled_at = e.value()),
+                                }
+                            }
+                            button {
+                                r#type: "submit",
+                                disabled: current.content_id == 0 || normalize_timestamp(&current.scheduled_at).is_none(),
+                                "Schedule"
+                            }
+                        }
+                        
+                        if upcoming.is_empty() {
                             div {
-                                div {
-                                    div {
-                                        h4 { "📝 Publish: Extension Architecture Deep Dive" }
-                                        span { "PENDING" }
-                                    }
-                                    div {
-                                        div {
-                                            span { "Scheduled:" }
-                                            span { "Tomorrow at 9:00 AM" }
-                                        }
-                                        div {
-                                            span { "Action:" }
-                                            span { "Publish Post" }
+                                class: "empty-state",
+                                div { "📅" }
+                                h3 { "No scheduled content" }
+                                p { "Schedule posts to be published automatically at the perfect time." }
+                            }
+                        }
+                        
+                        ul {
+                            class: "scheduled-jobs",
+                            for job in upcoming {
+                                li {
+                                    key: "{job.id}",
+                                    class: "scheduled-job",
+                                    h4 { "{job.action.label()}: {title_of(&job)}" }
+                                    span { class: "job-status job-{job.status.as_str()}", "{job.status.as_str()}" }
+                                    span { "{format_schedule_time(&job.scheduled_at)}" }
+                                    if job.status == ScheduleStatus::Pending {
+                                        button {
+                                            onclick: {
+                                                let job = job.clone();
+                                                move |_| {
+                                                    let job = ScheduledContent { scheduled_at: now_iso8601(), ..job.clone() };
+                                                    spawn(async move {
+                                                        match api::schedule_content(job).await {
+                                                            Ok(_) => message.set("Runs within a minute".to_string()),
+                                                            Err(e) => message.set(format!("Failed to reschedule: {}", e)),
+                                                        }
+                                                        jobs.restart();
+                                                    });
+                                                }
+                                            },
+                                            "Run Now"
                                         }
-                                        div {
-                                            span { "Created by:" }
-                                            span { "Admin" }
+                                        button {
+                                            onclick: move |_| {
+                                                let id = job.id;
+                                                spawn(async move {
+                                                    if let Err(e) = api::cancel_scheduled_job(id).await {
+                                                        message.set(format!("Failed to cancel: {}", e));
+                                                    }
+                                                    jobs.restart();
+                                                });
+                                            },
+                                            "Cancel"
                                         }
                                     }
-                                    div {
-                                        button { "Edit" }
-                                        button { "Run Now" }
-                                        button { "Cancel" }
-                                    }
-                                }
-                                
-                                div {
-                                    div { "📅" }
-                                    h3 { "No other scheduled content" }
-                                    p { "Schedule posts to be published automatically at the perfect time." }
-                                    button { "Schedule Content" }
                                 }
                             }
                         }
                     }
                     
                     if active_tab() == "history" {
-                        div {
-                            div {
-                                select {
-                                    option { "All Actions" }
-                                    option { "Published" }
-                                    option { "Unpublished" }
-                                    option { "Deleted" }
-                                }
-                                input {
-                                    r#type: "date",
-                                    placeholder: "From date"
-                                }
-                                input {
-                                    r#type: "date",
-                                    placeholder: "To date"
-                                }
-                            }
-                            
-                            div {
-                                div {
-                                    div {
-                                        h4 { "✅ Published: Welcome to BananaBit CMS" }
-                                        span { "COMPLETED" }
-                                    }
-                                    div {
-                                        span { "Completed 2 days ago" }
+                        if history.is_empty() {
+                            p { "No scheduled jobs have run yet." }
+                        }
+                        ul {
+                            class: "scheduled-jobs",
+                            for job in history.into_iter().rev() {
+                                li {
+                                    key: "{job.id}",
+                                    class: "scheduled-job",
+                                    h4 {
+                                        if job.status == ScheduleStatus::Completed { "✅ " } else { "❌ " }
+                                        "{job.action.label()}: {title_of(&job)}"
                                     }
-                                }
-                                
-                                div {
-                                    div {
-                                        h4 { "❌ Failed: Update Homepage Content" }
-                                        span { "FAILED" }
+                                    span { class: "job-status job-{job.status.as_str()}", "{job.status.as_str()}" }
+                                    span { "{format_schedule_time(job.completed_at.as_deref().unwrap_or(&job.scheduled_at))}" }
+                                    if let Some(error) = &job.error {
+                                        span { class: "error", "Error: {error}" }
                                     }
-                                    div {
-                                        span { "Failed 1 week ago" }
-                                        span { "Error: Content not found" }
+                                    if job.status == ScheduleStatus::Failed {
+                                        button {
+                                            onclick: move |_| {
+                                                let id = job.id;
+                                                spawn(async move {
+                                                    match api::retry_scheduled_job(id).await {
+                                                        Ok(()) => message.set("Runs within a minute".to_string()),
+                                                        Err(e) => message.set(format!("Failed to retry: {}", e)),
+                                                    }
+                                                    jobs.restart();
+                                                });
+                                            },
+                                            "Retry"
+                                        }
                                     }
                                 }
                             }
@@ -290,52 +297,359 @@ pub fn SchedulingManager() -> Element {
     }
 }
 
-/// Calendar view for scheduled content
+/// What a calendar entry stands for
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalendarEntryKind {
+    /// Content that went live on the entry's day
+    Published,
+    /// A job from the scheduler
+    Scheduled(ScheduledContent),
+    /// A draft planned for the entry's day
+    Draft,
+}
+
+/// One item on the content calendar
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEntry {
+    /// `YYYY-MM-DD`
+    pub date: String,
+    pub title: String,
+    pub content_type: ContentType,
+    pub content_id: u32,
+    pub author: Option<String>,
+    pub kind: CalendarEntryKind,
+}
+
+impl CalendarEntry {
+    pub fn key(&self) -> String {
+        match &self.kind {
+            CalendarEntryKind::Scheduled(job) => format!("job-{}", job.id),
+            _ => format!("{}-{}", self.content_type.as_str(), self.content_id),
+        }
+    }
+    
+    pub fn label(&self) -> String {
+        match &self.kind {
+            CalendarEntryKind::Published => self.title.clone(),
+            CalendarEntryKind::Scheduled(job) => format!("{}: {}", job.action.label(), self.title),
+            CalendarEntryKind::Draft => format!("Draft: {}", self.title),
+        }
+    }
+    
+    pub fn css_class(&self) -> &'static str {
+        match &self.kind {
+            CalendarEntryKind::Published => "calendar-entry calendar-published",
+            CalendarEntryKind::Scheduled(job) => match (job.status, job.action) {
+                (ScheduleStatus::Failed, _) => "calendar-entry calendar-failed",
+                (_, ScheduledAction::Publish) => "calendar-entry calendar-publish",
+                (_, ScheduledAction::Unpublish) => "calendar-entry calendar-unpublish",
+                (_, ScheduledAction::Delete) => "calendar-entry calendar-delete",
+                (_, ScheduledAction::Update) => "calendar-entry calendar-update",
+            },
+            CalendarEntryKind::Draft => "calendar-entry calendar-draft",
+        }
+    }
+    
+    /// Pending jobs and planned drafts can be dragged to another day; what already
+    /// happened can't
+    pub fn can_reschedule(&self) -> bool {
+        match &self.kind {
+            CalendarEntryKind::Scheduled(job) => job.status == ScheduleStatus::Pending,
+            CalendarEntryKind::Draft => true,
+            CalendarEntryKind::Published => false,
+        }
+    }
+}
+
+/// Calendar entries for published posts, pages and media, scheduled jobs and drafts with
+/// a target date
+pub fn calendar_entries(
+    posts: &[client::Post],
+    pages: &[client::Page],
+    media: &[client::MediaFile],
+    jobs: &[ScheduledContent],
+) -> Vec<CalendarEntry> {
+    let date_of = |timestamp: &str| timestamp.get(..10).unwrap_or_default().to_string();
+    let mut entries = Vec::new();
+    
+    for post in posts {
+        let (date, kind) = match (&post.target_date, post.published) {
+            (_, true) => (date_of(post.publish_at.as_deref().unwrap_or(&post.created_at)), CalendarEntryKind::Published),
+            (Some(target_date), false) => (target_date.clone(), CalendarEntryKind::Draft),
+            (None, false) => continue,
+        };
+        entries.push(CalendarEntry {
+            date,
+            title: post.title.clone(),
+            content_type: ContentType::Post,
+            content_id: post.id,
+            author: Some(post.author.clone()),
+            kind,
+        });
+    }
+    
+    entries.extend(pages.iter().filter(|page| page.published).map(|page| CalendarEntry {
+        date: date_of(page.publish_at.as_deref().unwrap_or(&page.created_at)),
+        title: page.title.clone(),
+        content_type: ContentType::Page,
+        content_id: page.id,
+        author: Some(page.author.clone()),
+        kind: CalendarEntryKind::Published,
+    }));
+    
+    entries.extend(media.iter().map(|file| CalendarEntry {
+        date: date_of(&file.uploaded_at),
+        title: file.original_name.clone(),
+        content_type: ContentType::Media,
+        content_id: file.id,
+        author: None,
+        kind: CalendarEntryKind::Published,
+    }));
+    
+    for job in jobs {
+        let (title, author) = match job.content_type {
+            ContentType::Post => posts.iter().find(|post| post.id == job.content_id).map(|post| (post.title.clone(), Some(post.author.clone()))),
+            ContentType::Page => pages.iter().find(|page| page.id == job.content_id).map(|page| (page.title.clone(), Some(page.author.clone()))),
+            ContentType::Media => media.iter().find(|file| file.id == job.content_id).map(|file| (file.original_name.clone(), None)),
+        }
+        .unwrap_or_else(|| (format!("{} #{}", job.content_type.as_str(), job.content_id), None));
+        
+        entries.push(CalendarEntry {
+            date: date_of(&job.scheduled_at),
+            title,
+            content_type: job.content_type,
+            content_id: job.content_id,
+            author,
+            kind: CalendarEntryKind::Scheduled(job.clone()),
+        });
+    }
+    
+    entries
+}
+
+/// Month or week layout of the content calendar
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CalendarView {
+    Month,
+    Week,
+}
+
+const MONTH_NAMES: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December",
+];
+
+/// Day of the week of a `YYYY-MM-DD` date, 0 for Sunday
+fn weekday(days: i64) -> i64 {
+    // 1970-01-01 was a Thursday
+    (days + 4).rem_euclid(7)
+}
+
+/// First day of the month `months` away from the month of `date`
+fn shift_month(date: &str, months: i64) -> Option<String> {
+    let year: i64 = date.get(..4)?.parse().ok()?;
+    let month: i64 = date.get(5..7)?.parse().ok()?;
+    let index = year * 12 + (month - 1) + months;
+    Some(format!("{:04}-{:02}-01", index.div_euclid(12), index.rem_euclid(12) + 1))
+}
+
+/// Days shown for `anchor`, in whole weeks from Sunday: the weeks covering its month,
+/// or the week containing it
+pub fn calendar_days(view: CalendarView, anchor: &str) -> Vec<String> {
+    let (first, last) = match view {
+        CalendarView::Month => {
+            let (Some(first), Some(next)) = (shift_month(anchor, 0), shift_month(anchor, 1)) else {
+                return Vec::new();
+            };
+            match (date_to_days(&first), date_to_days(&next)) {
+                (Some(first), Some(next)) => (first, next - 1),
+                _ => return Vec::new(),
+            }
+        },
+        CalendarView::Week => match date_to_days(anchor) {
+            Some(day) => (day, day),
+            None => return Vec::new(),
+        },
+    };
+    
+    let start = first - weekday(first);
+    let end = last + 6 - weekday(last);
+    (start..=end).map(days_to_date).collect()
+}
+
+/// The anchor one month or week before (`step` -1) or after (`step` 1)
+pub fn shift_calendar(view: CalendarView, anchor: &str, step: i64) -> String {
+    match view {
+        CalendarView::Month => shift_month(anchor, step),
+        CalendarView::Week => add_days(anchor, step * 7),
+    }
+    .unwrap_or_else(|| anchor.to_string())
+}
+
+fn calendar_title(view: CalendarView, anchor: &str) -> String {
+    match view {
+        CalendarView::Month => {
+            let month: usize = anchor.get(5..7).and_then(|month| month.parse().ok()).unwrap_or(1);
+            format!("{} {}", MONTH_NAMES[(month - 1).min(11)], anchor.get(..4).unwrap_or_default())
+        },
+        CalendarView::Week => match calendar_days(view, anchor).first() {
+            Some(first) => format!("Week of {}", first),
+            None => anchor.to_string(),
+        },
+    }
+}
+
+/// What a list resource has loaded; empty while loading or after an error
+fn loaded<T: Clone + 'static>(resource: &Resource<Result<Vec<T>, ServerFnError>>) -> Vec<T> {
+    resource.read().as_ref().and_then(|result| result.as_ref().ok().cloned()).unwrap_or_default()
+}
+
+/// Calendar of published, scheduled and planned content. Pending jobs and planned drafts
+/// are rescheduled by dragging them to another day.
 #[component]
 pub fn SchedulingCalendar() -> Element {
+    let mut posts = use_resource(api::get_all_posts);
+    let pages = use_resource(api::get_all_pages);
+    let media = use_resource(api::get_media_files);
+    let mut jobs = use_resource(api::get_scheduled_jobs);
+    let mut view = use_signal(|| CalendarView::Month);
+    let mut anchor = use_signal(today_date);
+    let mut author_filter = use_signal(String::new);
+    let mut type_filter = use_signal(String::new);
+    let mut dragging = use_signal(|| None::<CalendarEntry>);
+    let mut message = use_signal(String::new);
+    
+    let entries = calendar_entries(&loaded(&posts), &loaded(&pages), &loaded(&media), &loaded(&jobs));
+    
+    let mut authors: Vec<String> = entries.iter().filter_map(|entry| entry.author.clone()).collect();
+    authors.sort();
+    authors.dedup();
+    
+    let entries: Vec<CalendarEntry> = entries
+        .into_iter()
+        .filter(|entry| author_filter().is_empty() || entry.author.as_deref() == Some(author_filter().as_str()))
+        .filter(|entry| type_filter().is_empty() || entry.content_type.as_str() == type_filter())
+        .collect();
+    
+    let mut reschedule = move |date: String| {
+        let Some(entry) = dragging.take() else { return };
+        if entry.date == date {
+            return;
+        }
+        spawn(async move {
+            let result = match entry.kind {
+                CalendarEntryKind::Scheduled(job) => {
+                    // Keep the time of day, move the date
+                    let scheduled_at = format!("{}{}", date, job.scheduled_at.get(10..).unwrap_or("T00:00:00Z"));
+                    api::schedule_content(ScheduledContent { scheduled_at, ..job }).await.map(|_| jobs.restart())
+                },
+                CalendarEntryKind::Draft => api::set_post_target_date(entry.content_id, Some(date.clone()))
+                    .await
+                    .map(|_| posts.restart()),
+                CalendarEntryKind::Published => return,
+            };
+            match result {
+                Ok(()) => message.set(format!("Moved \"{}\" to {}", entry.title, date)),
+                Err(e) => message.set(format!("Failed to reschedule \"{}\": {}", entry.title, e)),
+            }
+        });
+    };
+    
+    let days = calendar_days(view(), &anchor());
+    let current_month = anchor().get(..7).unwrap_or_default().to_string();
+    let today = today_date();
+    
     rsx! {
         div {
+            class: "content-calendar",
+            
             div {
-                button { "‹ Previous" }
-                h3 { "January 2024" }
-                button { "Next ›" }
+                class: "calendar-toolbar",
+                button { onclick: move |_| anchor.set(shift_calendar(view(), &anchor(), -1)), "‹ Previous" }
+                h3 { "{calendar_title(view(), &anchor())}" }
+                button { onclick: move |_| anchor.set(shift_calendar(view(), &anchor(), 1)), "Next ›" }
+                button { onclick: move |_| anchor.set(today_date()), "Today" }
+                select {
+                    value: if view() == CalendarView::Month { "month" } else { "week" },
+                    onchange: move |e| view.set(if e.value() == "week" { CalendarView::Week } else { CalendarView::Month }),
+                    option { value: "month", "Month" }
+                    option { value: "week", "Week" }
+                }
+                select {
+                    value: "{author_filter}",
+                    onchange: move |e| author_filter.set(e.value()),
+                    option { value: "", "All authors" }
+                    for author in authors {
+                        option { key: "{author}", value: "{author}", "{author}" }
+                    }
+                }
+                select {
+                    value: "{type_filter}",
+                    onchange: move |e| type_filter.set(e.value()),
+                    option { value: "", "All content" }
+                    option { value: "post", "Posts" }
+                    option { value: "page", "Pages" }
+                    option { value: "media", "Media" }
+                }
+            }
+            
+            if !message().is_empty() {
+                div { class: "status-message", p { "{message}" } }
             }
             
             div {
-                // Calendar days header
-                div { "Sun" }
-                div { "Mon" }
-                div { "Tue" }
-                div { "Wed" }
-                div { "Thu" }
-                div { "Fri" }
-                div { "Sat" }
+                class: if view() == CalendarView::Week { "calendar-grid calendar-week" } else { "calendar-grid" },
+                for name in ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"] {
+                    div { class: "calendar-weekday", "{name}" }
+                }
                 
-                // Calendar days (simplified)
-                for day in 1..32 {
-                    div { 
-                        span { "{day}" }
-                        if day == 15 {
-                            div { "📝" }
+                for day in days {
+                    div {
+                        key: "{day}",
+                        class: match (day == today, view() == CalendarView::Month && !day.starts_with(&current_month)) {
+                            (true, _) => "calendar-day calendar-today",
+                            (false, true) => "calendar-day calendar-other-month",
+                            (false, false) => "calendar-day",
+                        },
+                        ondragover: move |e| e.prevent_default(),
+                        ondrop: {
+                            let day = day.clone();
+                            move |e: DragEvent| {
+                                e.prevent_default();
+                                reschedule(day.clone());
+                            }
+                        },
+                        span { class: "calendar-date", "{day.get(8..).unwrap_or_default()}" }
+                        for entry in entries.iter().filter(|entry| entry.date == day).cloned() {
+                            div {
+                                key: "{entry.key()}",
+                                class: entry.css_class(),
+                                title: entry.label(),
+                                draggable: entry.can_reschedule(),
+                                ondragstart: {
+                                    let entry = entry.clone();
+                                    move |_| dragging.set(Some(entry.clone()))
+                                },
+                                ondragend: move |_| dragging.set(None),
+                                if entry.content_type == ContentType::Post {
+                                    a { href: "/admin/posts/{entry.content_id}/edit", "{entry.label()}" }
+                                } else {
+                                    "{entry.label()}"
+                                }
+                            }
                         }
                     }
                 }
             }
             
             div {
-                div {
-                    span { }
-                    span { "Publish" }
-                }
-                div {
-                    span { }
-                    span { "Unpublish" }
-                }
-                div {
-                    span { }
-                    span { "Delete" }
-                }
+                class: "calendar-legend",
+                span { class: "calendar-entry calendar-published", "Published" }
+                span { class: "calendar-entry calendar-publish", "Publish" }
+                span { class: "calendar-entry calendar-unpublish", "Unpublish" }
+                span { class: "calendar-entry calendar-delete", "Delete" }
+                span { class: "calendar-entry calendar-draft", "Draft" }
             }
         }
     }
-}
\ No newline at end of file
+}
@@ -1,8 +1,8 @@
 use dioxus::prelude::*;
-use super::{Extension, ExtensionRoute, ExtensionComponent};
+use super::{Extension, ExtensionRoute, ExtensionComponent, Page, Post, SeoAuditReport};
+use client::{SeoSettings, TimeZone};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use client::time::today_date;
 
 /// SEO metadata structure
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -17,6 +17,8 @@ pub struct SeoMeta {
     pub robots: String, // "index,follow", "noindex,nofollow", etc.
     pub lang: String,
     pub author: Option<String>,
+    /// Open Graph object type: "website" or "article"
+    pub og_type: String,
 }
 
 impl Default for SeoMeta {
@@ -32,10 +34,114 @@ impl Default for SeoMeta {
             robots: "index,follow".to_string(),
             lang: "en".to_string(),
             author: None,
+            og_type: "website".to_string(),
         }
     }
 }
 
+/// Longest description search engines reliably show in full
+const DESCRIPTION_LENGTH: usize = 160;
+
+impl SeoMeta {
+    /// Site-wide defaults for the page at `path`
+    pub fn from_settings(settings: &SeoSettings, path: &str) -> Self {
+        Self {
+            title: settings.site_title.clone(),
+            description: settings.site_description.clone(),
+            keywords: settings.keywords.clone(),
+            og_title: None,
+            og_description: None,
+            og_image: settings.default_image.as_deref().map(|image| settings.absolute_url(image)),
+            canonical_url: Some(settings.absolute_url(path)),
+            robots: settings.robots.clone(),
+            lang: settings.language.clone(),
+            author: settings.author.clone(),
+            og_type: "website".to_string(),
+        }
+    }
+    
+    /// Metadata from a post's own fields, falling back to the site defaults
+    pub fn for_post(post: &Post, settings: &SeoSettings) -> Self {
+        let defaults = Self::from_settings(settings, &post.path(&settings.language));
+        
+        Self {
+            title: format!("{} | {}", post.title, settings.site_title),
+            description: non_empty(&post.meta_description)
+                .or_else(|| excerpt(&post.content, DESCRIPTION_LENGTH))
+                .unwrap_or(defaults.description),
+            keywords: non_empty(&post.meta_keywords).unwrap_or(defaults.keywords),
+            og_title: Some(post.title.clone()),
+            og_description: None,
+            og_image: non_empty(&post.cover_image).map(|image| settings.absolute_url(&image)).or(defaults.og_image),
+            canonical_url: non_empty(&post.canonical_url).or(defaults.canonical_url),
+            robots: defaults.robots,
+            lang: Some(post.language.clone()).filter(|language| !language.is_empty()).unwrap_or(defaults.lang),
+            author: Some(post.author.clone()).filter(|author| !author.is_empty()).or(defaults.author),
+            og_type: "article".to_string(),
+        }
+    }
+    
+    /// Metadata from a static page's own fields, falling back to the site defaults
+    pub fn for_page(page: &Page, settings: &SeoSettings) -> Self {
+        let defaults = Self::from_settings(settings, &page.path(&settings.language));
+        
+        Self {
+            title: format!("{} | {}", page.title, settings.site_title),
+            description: non_empty(&page.meta_description)
+                .or_else(|| excerpt(&page.content, DESCRIPTION_LENGTH))
+                .unwrap_or(defaults.description),
+            keywords: non_empty(&page.meta_keywords).unwrap_or(defaults.keywords),
+            og_title: Some(page.title.clone()),
+            og_description: None,
+            og_image: non_empty(&page.cover_image).map(|image| settings.absolute_url(&image)).or(defaults.og_image),
+            canonical_url: defaults.canonical_url,
+            robots: defaults.robots,
+            lang: Some(page.language.clone()).filter(|language| !language.is_empty()).unwrap_or(defaults.lang),
+            author: Some(page.author.clone()).filter(|author| !author.is_empty()).or(defaults.author),
+            og_type: "website".to_string(),
+        }
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value.as_ref().map(|value| value.trim()).filter(|value| !value.is_empty()).map(str::to_string)
+}
+
+/// Plain-text start of the first paragraphs of a markdown document, cut at a word boundary
+pub fn excerpt(markdown: &str, max_chars: usize) -> Option<String> {
+    use pulldown_cmark::{Event, Parser, Tag};
+    
+    let mut text = String::new();
+    let mut in_paragraph = false;
+    for event in Parser::new(client::strip_front_matter(markdown)) {
+        match event {
+            Event::Start(Tag::Paragraph) => in_paragraph = true,
+            Event::End(Tag::Paragraph) => {
+                in_paragraph = false;
+                text.push(' ');
+            },
+            Event::Text(t) | Event::Code(t) if in_paragraph => text.push_str(&t),
+            Event::SoftBreak | Event::HardBreak if in_paragraph => text.push(' '),
+            _ => {},
+        }
+        if text.chars().count() > max_chars {
+            break;
+        }
+    }
+    
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return None;
+    }
+    if text.chars().count() <= max_chars {
+        return Some(text);
+    }
+    
+    let cut: String = text.chars().take(max_chars - 1).collect();
+    let cut = cut.rsplit_once(' ').map_or(cut.as_str(), |(head, _)| head);
+    Some(format!("{}…", cut.trim_end_matches(|c: char| c.is_ascii_punctuation())))
+}
+
 /// Sitemap entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SitemapEntry {
@@ -78,33 +184,16 @@ impl SeoExtension {
     }
     
     pub fn generate_sitemap_xml(&self) -> String {
-        let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>
-<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
-"#);
-        
-        for entry in &self.sitemap_entries {
-            xml.push_str(&format!(
-                r#"  <url>
-    <loc>{}</loc>
-    <lastmod>{}</lastmod>
-    <changefreq>{}</changefreq>
-    <priority>{:.1}</priority>
-  </url>
-"#,
-                entry.url, entry.last_modified, entry.change_frequency, entry.priority
-            ));
-        }
-        
-        xml.push_str("</urlset>");
-        xml
+        sitemap_xml(&self.sitemap_entries)
     }
     
-    pub fn generate_robots_txt(&self) -> String {
+    /// robots.txt pointing crawlers at the sitemap under `base_url`
+    pub fn generate_robots_txt(&self, base_url: &str) -> String {
         format!(
             r#"User-agent: *
 Allow: /
 
-Sitemap: /sitemap.xml
+Sitemap: {}/sitemap.xml
 
 # Common crawl patterns
 Disallow: /admin/
@@ -113,11 +202,243 @@ Disallow: /*.json$
 Disallow: /uploads/private/
 
 # Generated by BananaBit CMS
-"#
+"#,
+            base_url.trim_end_matches('/')
         )
     }
 }
 
+/// One step of a breadcrumb trail
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breadcrumb {
+    pub name: String,
+    pub path: String,
+}
+
+impl Breadcrumb {
+    pub fn new(name: impl Into<String>, path: impl Into<String>) -> Self {
+        Self { name: name.into(), path: path.into() }
+    }
+}
+
+/// Breadcrumb trail for a route: the home page, then each level below it
+pub fn route_breadcrumbs(title: &str, path: &str) -> Vec<Breadcrumb> {
+    let mut crumbs = vec![Breadcrumb::new("Home", "/")];
+    if path != "/" {
+        crumbs.push(Breadcrumb::new(title, path));
+    }
+    crumbs
+}
+
+/// Search page URL template advertised by the `WebSite` SearchAction
+pub const SEARCH_URL_TEMPLATE: &str = "/search?q={search_term_string}";
+
+/// schema.org `BlogPosting` for a post
+pub fn blog_posting_json_ld(post: &Post, settings: &SeoSettings) -> serde_json::Value {
+    let meta = SeoMeta::for_post(post, settings);
+    let url = settings.absolute_url(&post.path(&settings.language));
+    
+    let mut data = serde_json::json!({
+        "@context": "https://schema.org",
+        "@type": "BlogPosting",
+        "headline": post.title,
+        "description": meta.description,
+        "datePublished": post.created_at,
+        "dateModified": post.updated_at,
+        "author": { "@type": "Person", "name": post.author },
+        "publisher": organization_json_ld(settings),
+        "url": url,
+        "mainEntityOfPage": { "@type": "WebPage", "@id": meta.canonical_url.unwrap_or(url) },
+        "inLanguage": meta.lang,
+    });
+    if let Some(keywords) = non_empty(&post.meta_keywords) {
+        data["keywords"] = keywords.into();
+    }
+    if let Some(image) = meta.og_image {
+        data["image"] = image.into();
+    }
+    data
+}
+
+/// schema.org `WebPage` for a static page
+pub fn web_page_json_ld(page: &Page, settings: &SeoSettings) -> serde_json::Value {
+    let meta = SeoMeta::for_page(page, settings);
+    
+    serde_json::json!({
+        "@context": "https://schema.org",
+        "@type": "WebPage",
+        "name": page.title,
+        "description": meta.description,
+        "url": settings.absolute_url(&page.path(&settings.language)),
+        "datePublished": page.created_at,
+        "dateModified": page.updated_at,
+        "inLanguage": meta.lang,
+        "isPartOf": { "@type": "WebSite", "name": settings.site_title, "url": settings.absolute_url("/") },
+    })
+}
+
+/// schema.org `BreadcrumbList`
+pub fn breadcrumb_json_ld(crumbs: &[Breadcrumb], settings: &SeoSettings) -> serde_json::Value {
+    let items: Vec<serde_json::Value> = crumbs
+        .iter()
+        .enumerate()
+        .map(|(index, crumb)| serde_json::json!({
+            "@type": "ListItem",
+            "position": index + 1,
+            "name": crumb.name,
+            "item": settings.absolute_url(&crumb.path),
+        }))
+        .collect();
+    
+    serde_json::json!({
+        "@context": "https://schema.org",
+        "@type": "BreadcrumbList",
+        "itemListElement": items,
+    })
+}
+
+fn organization_json_ld(settings: &SeoSettings) -> serde_json::Value {
+    let mut organization = serde_json::json!({
+        "@type": "Organization",
+        "name": settings.site_title,
+        "url": settings.absolute_url("/"),
+    });
+    if let Some(logo) = &settings.default_image {
+        organization["logo"] = settings.absolute_url(logo).into();
+    }
+    organization
+}
+
+/// Site-wide schema.org `Organization` and `WebSite` with a search action
+pub fn site_json_ld(settings: &SeoSettings) -> serde_json::Value {
+    let site_url = settings.absolute_url("/");
+    let mut organization = organization_json_ld(settings);
+    organization["@id"] = format!("{}#organization", site_url).into();
+    
+    serde_json::json!({
+        "@context": "https://schema.org",
+        "@graph": [
+            organization,
+            {
+                "@type": "WebSite",
+                "@id": format!("{}#website", site_url),
+                "name": settings.site_title,
+                "description": settings.site_description,
+                "url": site_url,
+                "inLanguage": settings.language,
+                "publisher": { "@id": format!("{}#organization", site_url) },
+                "potentialAction": {
+                    "@type": "SearchAction",
+                    "target": {
+                        "@type": "EntryPoint",
+                        "urlTemplate": settings.absolute_url(SEARCH_URL_TEMPLATE),
+                    },
+                    "query-input": "required name=search_term_string",
+                },
+            },
+        ],
+    })
+}
+
+/// Serialize JSON-LD for a `<script>` element.
+///
+/// `<`, `>` and `&` are written as unicode escapes so strings like `</script>` or
+/// `<!--` can't end the element early; U+2028/U+2029 are escaped for older JS parsers.
+pub fn json_ld_script(data: &serde_json::Value) -> String {
+    let json = data.to_string();
+    let mut escaped = String::with_capacity(json.len());
+    for c in json.chars() {
+        match c {
+            '<' => escaped.push_str("\\u003c"),
+            '>' => escaped.push_str("\\u003e"),
+            '&' => escaped.push_str("\\u0026"),
+            '\u{2028}' => escaped.push_str("\\u2028"),
+            '\u{2029}' => escaped.push_str("\\u2029"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Most URLs a single sitemap file may list; larger sites need a sitemap index
+pub const MAX_SITEMAP_URLS: usize = 50_000;
+
+/// Escape text for use in XML content and attribute values
+pub fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// `<urlset>` document for up to `MAX_SITEMAP_URLS` entries
+pub fn sitemap_xml(entries: &[SitemapEntry]) -> String {
+    let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+"#);
+    
+    for entry in entries {
+        xml.push_str(&format!(
+            r#"  <url>
+    <loc>{}</loc>
+    <lastmod>{}</lastmod>
+    <changefreq>{}</changefreq>
+    <priority>{:.1}</priority>
+  </url>
+"#,
+            xml_escape(&entry.url), xml_escape(&entry.last_modified), xml_escape(&entry.change_frequency), entry.priority
+        ));
+    }
+    
+    xml.push_str("</urlset>");
+    xml
+}
+
+/// Sitemap files of a site with more than `MAX_SITEMAP_URLS` URLs, as `(url, lastmod)` pairs
+/// for `sitemap_index_xml`: `{base_url}/sitemap/{n}.xml`, numbered from 1
+pub fn sitemap_files(entries: &[SitemapEntry], base_url: &str) -> Vec<(String, Option<String>)> {
+    entries
+        .chunks(MAX_SITEMAP_URLS)
+        .enumerate()
+        .map(|(index, chunk)| {
+            let last_modified = chunk.iter().map(|entry| entry.last_modified.clone()).max();
+            (format!("{}/sitemap/{}.xml", base_url, index + 1), last_modified)
+        })
+        .collect()
+}
+
+/// Entries listed by `/sitemap/{number}.xml`, if there is such a file
+pub fn sitemap_file_entries(entries: &[SitemapEntry], number: usize) -> Option<&[SitemapEntry]> {
+    entries.chunks(MAX_SITEMAP_URLS).nth(number.checked_sub(1)?)
+}
+
+/// `<sitemapindex>` document listing sitemap files as `(url, lastmod)` pairs
+pub fn sitemap_index_xml(sitemaps: &[(String, Option<String>)]) -> String {
+    let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>
+<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+"#);
+    
+    for (url, last_modified) in sitemaps {
+        xml.push_str("  <sitemap>\n");
+        xml.push_str(&format!("    <loc>{}</loc>\n", xml_escape(url)));
+        if let Some(last_modified) = last_modified {
+            xml.push_str(&format!("    <lastmod>{}</lastmod>\n", xml_escape(last_modified)));
+        }
+        xml.push_str("  </sitemap>\n");
+    }
+    
+    xml.push_str("</sitemapindex>");
+    xml
+}
+
 impl Extension for SeoExtension {
     fn id(&self) -> &'static str {
         "core.seo"
@@ -132,36 +453,10 @@ impl Extension for SeoExtension {
     }
     
     fn init(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        // Add default sitemap entries
-        self.add_sitemap_entry(SitemapEntry {
-            url: "https://bananabit.dev/".to_string(),
-            last_modified: today_date(),
-            change_frequency: "daily".to_string(),
-            priority: 1.0,
-        });
-        
-        self.add_sitemap_entry(SitemapEntry {
-            url: "https://bananabit.dev/post/welcome-to-bananabit-cms".to_string(),
-            last_modified: today_date(),
-            change_frequency: "monthly".to_string(),
-            priority: 0.8,
-        });
-        
-        // Set page-specific meta
-        let post_meta = SeoMeta {
-            title: "Welcome to BananaBit CMS".to_string(),
-            description: "Discover the power of our modern, extension-based content management system built with Rust and Dioxus.".to_string(),
-            keywords: "cms, rust, dioxus, content management, blogging".to_string(),
-            og_title: Some("Welcome to BananaBit CMS".to_string()),
-            og_description: Some("Modern, extension-based content management system".to_string()),
-            og_image: Some("/uploads/bananabit-logo.png".to_string()),
-            canonical_url: Some("https://bananabit.dev/post/welcome-to-bananabit-cms".to_string()),
-            robots: "index,follow".to_string(),
-            lang: "en".to_string(),
-            author: Some("BananaBit Team".to_string()),
-        };
+        // Sitemap entries come from the database; ba-server builds /sitemap.xml on request
         
-        self.set_page_meta("/post/welcome-to-bananabit-cms", post_meta);
+        // Per-page metadata is built from each post's and page's own fields by the views;
+        // `set_page_meta` remains for extensions that need to override a URL
         
         Ok(())
     }
@@ -196,6 +491,10 @@ impl Extension for SeoExtension {
                 name: "MetaTags".to_string(),
                 description: "Render meta tags for pages".to_string(),
             },
+            ExtensionComponent {
+                name: "JsonLd".to_string(),
+                description: "Render schema.org structured data".to_string(),
+            },
             ExtensionComponent {
                 name: "SeoAnalytics".to_string(),
                 description: "SEO performance analytics".to_string(),
@@ -207,6 +506,29 @@ impl Extension for SeoExtension {
 /// SEO manager component for admin
 #[component]
 pub fn SeoManager() -> Element {
+    let mut settings = use_signal(SeoSettings::default);
+    let mut message = use_signal(String::new);
+    
+    // Load the stored settings once
+    use_future(move || async move {
+        match api::get_seo_settings().await {
+            Ok(loaded) => settings.set(loaded),
+            Err(e) => message.set(format!("Failed to load settings: {}", e)),
+        }
+    });
+    
+    let on_save = move |evt: FormEvent| {
+        evt.prevent_default();
+        spawn(async move {
+            match api::save_seo_settings(settings()).await {
+                Ok(()) => message.set("Settings saved".to_string()),
+                Err(e) => message.set(format!("Failed to save settings: {}", e)),
+            }
+        });
+    };
+    
+    let current = settings();
+    
     rsx! {
         div {
             h2 { "SEO & Analytics" }
@@ -220,17 +542,35 @@ pub fn SeoManager() -> Element {
                     button { "Analytics" }
                 }
                 
-                div {
+                form {
+                    onsubmit: on_save,
+                    
+                    if !message().is_empty() {
+                        div { class: "status-message", p { "{message}" } }
+                    }
+                    
                     div {
                         h3 { "Global SEO Settings" }
+                        p { "Used for every page, and as the fallback when a post or page leaves a field empty." }
                         
                         div {
+                            div {
+                                label { "Site URL" }
+                                input {
+                                    r#type: "url",
+                                    value: "{current.site_url}",
+                                    placeholder: "https://example.com",
+                                    oninput: move |e| settings.with_mut(|s| s.site_url = e.value()),
+                                }
+                            }
+                            
                             div {
                                 label { "Site Title" }
                                 input {
                                     r#type: "text",
-                                    value: "BananaBit CMS",
-                                    placeholder: "Your site title"
+                                    value: "{current.site_title}",
+                                    placeholder: "Your site title",
+                                    oninput: move |e| settings.with_mut(|s| s.site_title = e.value()),
                                 }
                             }
                             
@@ -239,7 +579,8 @@ pub fn SeoManager() -> Element {
                                 textarea {
                                     placeholder: "Brief description of your site",
                                     rows: "3",
-                                    "Modern, extension-based content management system built with Rust and Dioxus."
+                                    value: "{current.site_description}",
+                                    oninput: move |e| settings.with_mut(|s| s.site_description = e.value()),
                                 }
                             }
                             
@@ -247,8 +588,9 @@ pub fn SeoManager() -> Element {
                                 label { "Keywords" }
                                 input {
                                     r#type: "text",
-                                    value: "cms, rust, dioxus, content management",
-                                    placeholder: "Comma-separated keywords"
+                                    value: "{current.keywords}",
+                                    placeholder: "Comma-separated keywords",
+                                    oninput: move |e| settings.with_mut(|s| s.keywords = e.value()),
                                 }
                             }
                             
@@ -256,6 +598,8 @@ pub fn SeoManager() -> Element {
                                 div {
                                     label { "Default Language" }
                                     select {
+                                        value: "{current.language}",
+                                        onchange: move |e| settings.with_mut(|s| s.language = e.value()),
                                         option { value: "en", "English" }
                                         option { value: "es", "Spanish" }
                                         option { value: "fr", "French" }
@@ -266,12 +610,29 @@ pub fn SeoManager() -> Element {
                                 div {
                                     label { "Robots Default" }
                                     select {
+                                        value: "{current.robots}",
+                                        onchange: move |e| settings.with_mut(|s| s.robots = e.value()),
                                         option { value: "index,follow", "Index, Follow" }
                                         option { value: "noindex,nofollow", "No Index, No Follow" }
                                         option { value: "index,nofollow", "Index, No Follow" }
                                         option { value: "noindex,follow", "No Index, Follow" }
                                     }
                                 }
+
+                                div {
+                                    label { "Time Zone" }
+                                    select {
+                                        value: "{current.time_zone}",
+                                        onchange: move |e| settings.with_mut(|s| s.time_zone = e.value()),
+                                        // A fixed offset saved from elsewhere stays selectable
+                                        if !TimeZone::names().any(|name| name == current.time_zone) {
+                                            option { value: "{current.time_zone}", "{current.time_zone}" }
+                                        }
+                                        for name in TimeZone::names() {
+                                            option { value: "{name}", "{name}" }
+                                        }
+                                    }
+                                }
                             }
                         }
                         
@@ -279,13 +640,14 @@ pub fn SeoManager() -> Element {
                         div {
                             div {
                                 label { "Default OG Image" }
-                                div {
-                                    input {
-                                        r#type: "text",
-                                        value: "/uploads/bananabit-logo.png",
-                                        placeholder: "Image URL or path"
-                                    }
-                                    button { "Browse" }
+                                input {
+                                    r#type: "text",
+                                    value: current.default_image.clone().unwrap_or_default(),
+                                    placeholder: "Image URL or path",
+                                    oninput: move |e| settings.with_mut(|s| {
+                                        let value = e.value();
+                                        s.default_image = if value.trim().is_empty() { None } else { Some(value) };
+                                    }),
                                 }
                             }
                             
@@ -293,15 +655,26 @@ pub fn SeoManager() -> Element {
                                 label { "Site Author" }
                                 input {
                                     r#type: "text",
-                                    value: "BananaBit Team",
-                                    placeholder: "Default author name"
+                                    value: current.author.clone().unwrap_or_default(),
+                                    placeholder: "Default author name",
+                                    oninput: move |e| settings.with_mut(|s| {
+                                        let value = e.value();
+                                        s.author = if value.trim().is_empty() { None } else { Some(value) };
+                                    }),
                                 }
                             }
                         }
                         
                         div {
-                            button { "Save Settings" }
-                            button { "Reset to Default" }
+                            button { r#type: "submit", "Save Settings" }
+                            button {
+                                r#type: "button",
+                                onclick: move |_| {
+                                    let site_url = settings.read().site_url.clone();
+                                    settings.set(SeoSettings { site_url, ..SeoSettings::default() });
+                                },
+                                "Reset to Default"
+                            }
                         }
                     }
                 }
@@ -327,55 +700,81 @@ pub fn SeoManager() -> Element {
                         p { "Configure search engine crawler behavior" }
                         button { "Edit Robots.txt" }
                     }
-                    
-                    div {
-                        h4 { "🔍 SEO Audit" }
-                        p { "Analyze your site for SEO best practices" }
-                        button { "Run Audit" }
-                    }
                 }
             }
+            
+            SeoAuditReport {}
         }
     }
 }
 
-/// Meta tags component to render in document head
+/// Renders JSON-LD structured data into the document head
+#[component]
+pub fn JsonLd(data: serde_json::Value) -> Element {
+    let script = json_ld_script(&data);
+    
+    rsx! {
+        document::Script { r#type: "application/ld+json", "{script}" }
+    }
+}
+
+/// Site-wide `Organization` and `WebSite` structured data, rendered once by `App`
+#[component]
+pub fn SiteStructuredData() -> Element {
+    let settings = use_server_future(api::get_seo_settings)?;
+    let settings = settings.read().as_ref().and_then(|result| result.as_ref().ok().cloned()).unwrap_or_default();
+    
+    rsx! {
+        JsonLd { data: site_json_ld(&settings) }
+    }
+}
+
+/// Meta tags component to render in document head.
+///
+/// Uses the `document` elements so the tags are hoisted into `<head>`, including in
+/// server-rendered HTML.
 #[component]
 pub fn MetaTags(meta: SeoMeta) -> Element {
-    let og_title = meta.og_title.as_ref().unwrap_or(&meta.title);
-    let og_description = meta.og_description.as_ref().unwrap_or(&meta.description);
-    let author = meta.author.as_deref().unwrap_or("");
+    let og_title = meta.og_title.clone().unwrap_or_else(|| meta.title.clone());
+    let og_description = meta.og_description.clone().unwrap_or_else(|| meta.description.clone());
     
     rsx! {
         // Basic meta tags
-        title { "{meta.title}" }
-        meta { name: "description", content: "{meta.description}" }
-        meta { name: "keywords", content: "{meta.keywords}" }
-        meta { name: "author", content: "{author}" }
-        meta { name: "robots", content: "{meta.robots}" }
-        meta { name: "language", content: "{meta.lang}" }
+        document::Title { "{meta.title}" }
+        document::Meta { name: "description", content: "{meta.description}" }
+        document::Meta { name: "keywords", content: "{meta.keywords}" }
+        if let Some(author) = meta.author.clone() {
+            document::Meta { name: "author", content: "{author}" }
+        }
+        document::Meta { name: "robots", content: "{meta.robots}" }
+        document::Meta { name: "language", content: "{meta.lang}" }
         
         // Open Graph tags
-        meta { property: "og:title", content: "{og_title}" }
-        meta { property: "og:description", content: "{og_description}" }
-        meta { property: "og:type", content: "website" }
-        
-        if let Some(ref og_image) = meta.og_image {
-            meta { property: "og:image", content: "{og_image}" }
+        document::Meta { property: "og:title", content: "{og_title}" }
+        document::Meta { property: "og:description", content: "{og_description}" }
+        document::Meta { property: "og:type", content: "{meta.og_type}" }
+        if let Some(canonical) = meta.canonical_url.clone() {
+            document::Meta { property: "og:url", content: "{canonical}" }
+        }
+        if let Some(og_image) = meta.og_image.clone() {
+            document::Meta { property: "og:image", content: "{og_image}" }
         }
         
         // Twitter Card tags
-        meta { name: "twitter:card", content: "summary_large_image" }
-        meta { name: "twitter:title", content: "{og_title}" }
-        meta { name: "twitter:description", content: "{og_description}" }
+        document::Meta {
+            name: "twitter:card",
+            content: if meta.og_image.is_some() { "summary_large_image" } else { "summary" },
+        }
+        document::Meta { name: "twitter:title", content: "{og_title}" }
+        document::Meta { name: "twitter:description", content: "{og_description}" }
         
         // Canonical URL
-        if let Some(ref canonical) = meta.canonical_url {
-            link { rel: "canonical", href: "{canonical}" }
+        if let Some(canonical) = meta.canonical_url.clone() {
+            document::Link { rel: "canonical", href: "{canonical}" }
         }
         
         // Additional SEO tags
-        meta { name: "viewport", content: "width=device-width, initial-scale=1.0" }
-        meta { name: "format-detection", content: "telephone=no" }
+        document::Meta { name: "viewport", content: "width=device-width, initial-scale=1.0" }
+        document::Meta { name: "format-detection", content: "telephone=no" }
     }
-}
\ No newline at end of file
+}
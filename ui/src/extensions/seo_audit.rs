@@ -0,0 +1,380 @@
+//! SEO content audit: checks a post's markdown and metadata and scores it.
+
+use dioxus::prelude::*;
+use super::{excerpt, Page, Post};
+use pulldown_cmark::{Event, HeadingLevel, Parser, Tag};
+use std::collections::{HashMap, HashSet};
+
+/// Recommended title length range, in characters
+const TITLE_LENGTH: (usize, usize) = (30, 60);
+/// Recommended meta description length range, in characters
+const DESCRIPTION_LENGTH: (usize, usize) = (70, 160);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AuditSeverity {
+    /// Hurts how the post is indexed or shown in results
+    Error,
+    /// Worth fixing
+    Warning,
+    /// Suggestion only; doesn't affect the score
+    Info,
+}
+
+impl AuditSeverity {
+    fn penalty(&self) -> u32 {
+        match self {
+            Self::Error => 20,
+            Self::Warning => 10,
+            Self::Info => 0,
+        }
+    }
+
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            Self::Error => "audit-error",
+            Self::Warning => "audit-warning",
+            Self::Info => "audit-info",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditIssue {
+    pub severity: AuditSeverity,
+    pub message: String,
+}
+
+/// Result of auditing one post
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeoAudit {
+    /// 0 to 100
+    pub score: u32,
+    pub issues: Vec<AuditIssue>,
+}
+
+impl SeoAudit {
+    pub fn grade(&self) -> &'static str {
+        match self.score {
+            90..=100 => "good",
+            60..=89 => "needs-work",
+            _ => "poor",
+        }
+    }
+}
+
+/// What the analyzer needs to know about the rest of the site
+#[derive(Debug, Clone, Default)]
+pub struct AuditContext {
+    post_slugs: HashSet<String>,
+    page_slugs: HashSet<String>,
+    /// Meta description -> ids of the posts using it
+    descriptions: HashMap<String, Vec<u32>>,
+    titles: HashMap<u32, String>,
+}
+
+impl AuditContext {
+    pub fn new<'a>(posts: impl IntoIterator<Item = &'a Post>, page_slugs: impl IntoIterator<Item = String>) -> Self {
+        let mut context = Self {
+            page_slugs: page_slugs.into_iter().collect(),
+            ..Self::default()
+        };
+
+        for post in posts {
+            context.post_slugs.insert(post.slug.clone());
+            context.titles.insert(post.id, post.title.clone());
+            if let Some(description) = normalized_description(post) {
+                context.descriptions.entry(description).or_default().push(post.id);
+            }
+        }
+
+        context
+    }
+}
+
+fn normalized_description(post: &Post) -> Option<String> {
+    post.meta_description
+        .as_deref()
+        .map(|description| description.trim().to_lowercase())
+        .filter(|description| !description.is_empty())
+}
+
+/// Audit a post against SEO best practices
+pub fn audit_post(post: &Post, context: &AuditContext) -> SeoAudit {
+    let mut issues = Vec::new();
+    let mut issue = |severity: AuditSeverity, message: String| issues.push(AuditIssue { severity, message });
+
+    // Title
+    let title_length = post.title.trim().chars().count();
+    if title_length == 0 {
+        issue(AuditSeverity::Error, "The post has no title.".to_string());
+    } else if title_length < TITLE_LENGTH.0 {
+        issue(AuditSeverity::Warning, format!(
+            "Title is {} characters; aim for {}–{} so it is descriptive in search results.",
+            title_length, TITLE_LENGTH.0, TITLE_LENGTH.1
+        ));
+    } else if title_length > TITLE_LENGTH.1 {
+        issue(AuditSeverity::Warning, format!(
+            "Title is {} characters; search engines cut titles after about {}.",
+            title_length, TITLE_LENGTH.1
+        ));
+    }
+
+    // Meta description
+    match normalized_description(post) {
+        None => issue(AuditSeverity::Warning, format!(
+            "No meta description; an excerpt of the content is used instead{}.",
+            excerpt(&post.content, 60).map(|e| format!(" (\"{}\")", e)).unwrap_or_default()
+        )),
+        Some(description) => {
+            let length = description.chars().count();
+            if length < DESCRIPTION_LENGTH.0 || length > DESCRIPTION_LENGTH.1 {
+                issue(AuditSeverity::Warning, format!(
+                    "Meta description is {} characters; aim for {}–{}.",
+                    length, DESCRIPTION_LENGTH.0, DESCRIPTION_LENGTH.1
+                ));
+            }
+
+            let duplicates: Vec<&str> = context
+                .descriptions
+                .get(&description)
+                .into_iter()
+                .flatten()
+                .filter(|id| **id != post.id)
+                .filter_map(|id| context.titles.get(id).map(String::as_str))
+                .collect();
+            if !duplicates.is_empty() {
+                issue(AuditSeverity::Error, format!(
+                    "Meta description is also used by: {}.",
+                    duplicates.join(", ")
+                ));
+            }
+        },
+    }
+
+    // Markdown structure
+    let mut previous_level: Option<u32> = None;
+    let mut h1_count = 0;
+    let mut image: Option<(String, String)> = None;
+    for event in Parser::new(&post.content) {
+        match event {
+            Event::Start(Tag::Heading(level, ..)) => {
+                let level = heading_level(level);
+                if level == 1 {
+                    h1_count += 1;
+                }
+                if let Some(previous) = previous_level {
+                    if level > previous + 1 {
+                        issue(AuditSeverity::Warning, format!(
+                            "Heading jumps from H{} to H{}; don't skip heading levels.",
+                            previous, level
+                        ));
+                    }
+                }
+                previous_level = Some(level);
+            },
+            Event::Start(Tag::Image(_, url, _)) => image = Some((url.to_string(), String::new())),
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, alt)) = image.as_mut() {
+                    alt.push_str(&text);
+                }
+            },
+            Event::End(Tag::Image(..)) => {
+                if let Some((url, alt)) = image.take() {
+                    if alt.trim().is_empty() {
+                        issue(AuditSeverity::Warning, format!("Image {} has no alt text.", url));
+                    }
+                }
+            },
+            Event::Start(Tag::Link(_, url, _)) => {
+                if let Some(message) = broken_internal_link(&url, context) {
+                    issue(AuditSeverity::Error, message);
+                }
+            },
+            _ => {},
+        }
+    }
+    if h1_count > 1 {
+        issue(AuditSeverity::Warning, format!("Content has {} H1 headings; use one at most.", h1_count));
+    }
+
+    // Focus keyword: the first of the post's keywords
+    let keyword = post
+        .meta_keywords
+        .as_deref()
+        .and_then(|keywords| keywords.split(',').map(str::trim).find(|keyword| !keyword.is_empty()));
+    match keyword {
+        None => issue(AuditSeverity::Info, "Add keywords; the first one is checked as the focus keyword.".to_string()),
+        Some(keyword) => {
+            let keyword_lower = keyword.to_lowercase();
+            let contains = |text: &str| text.to_lowercase().contains(&keyword_lower);
+            if !contains(&post.title) {
+                issue(AuditSeverity::Warning, format!("Focus keyword \"{}\" is not in the title.", keyword));
+            }
+            if !post.meta_description.as_deref().is_some_and(contains) {
+                issue(AuditSeverity::Warning, format!("Focus keyword \"{}\" is not in the meta description.", keyword));
+            }
+            if !excerpt(&post.content, 300).is_some_and(|intro| contains(&intro)) {
+                issue(AuditSeverity::Warning, format!("Focus keyword \"{}\" is not in the opening paragraph.", keyword));
+            }
+        },
+    }
+
+    issues.sort_by_key(|issue| issue.severity);
+    let penalty: u32 = issues.iter().map(|issue| issue.severity.penalty()).sum();
+
+    SeoAudit {
+        score: 100u32.saturating_sub(penalty),
+        issues,
+    }
+}
+
+fn heading_level(level: HeadingLevel) -> u32 {
+    match level {
+        HeadingLevel::H1 => 1,
+        HeadingLevel::H2 => 2,
+        HeadingLevel::H3 => 3,
+        HeadingLevel::H4 => 4,
+        HeadingLevel::H5 => 5,
+        HeadingLevel::H6 => 6,
+    }
+}
+
+/// Links to `/post/{slug}` or `/page/{slug}` must point at something that exists
+fn broken_internal_link(url: &str, context: &AuditContext) -> Option<String> {
+    let path = url.split(['?', '#']).next().unwrap_or_default().trim_end_matches('/');
+
+    let (known, slug) = if let Some(slug) = path.strip_prefix("/post/") {
+        (&context.post_slugs, slug)
+    } else if let Some(slug) = path.strip_prefix("/page/") {
+        (&context.page_slugs, slug)
+    } else {
+        return None;
+    };
+
+    (!known.contains(slug)).then(|| format!("Link to {} points at an unknown slug \"{}\".", url, slug))
+}
+
+/// Audit every post, worst score first
+pub fn audit_site(posts: &[Post], page_slugs: impl IntoIterator<Item = String>) -> Vec<(Post, SeoAudit)> {
+    let context = AuditContext::new(posts, page_slugs);
+    let mut report: Vec<(Post, SeoAudit)> = posts
+        .iter()
+        .map(|post| (post.clone(), audit_post(post, &context)))
+        .collect();
+    report.sort_by_key(|(post, audit)| (audit.score, post.id));
+    report
+}
+
+/// Slugs of the published pages, for link checks; empty until they have loaded
+fn published_page_slugs(pages: &Option<Result<Vec<Page>, ServerFnError>>) -> Vec<String> {
+    match pages {
+        Some(Ok(pages)) => pages.iter().map(|page| page.slug.clone()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Score and warnings for a post being edited; re-runs on every change
+#[component]
+pub fn SeoAuditPanel(post: Post) -> Element {
+    let others = use_resource(api::get_all_posts);
+    let pages = use_resource(api::get_published_pages);
+    
+    let audit = {
+        let others = others.read();
+        // Audit against the stored posts, with this post's unsaved edits in place of its stored copy
+        let mut posts: Vec<Post> = match &*others {
+            Some(Ok(posts)) => posts.iter().filter(|other| other.id != post.id).cloned().collect(),
+            _ => Vec::new(),
+        };
+        posts.push(post.clone());
+        audit_post(&post, &AuditContext::new(&posts, published_page_slugs(&pages.read())))
+    };
+    
+    rsx! {
+        div {
+            class: "seo-audit-panel",
+            h3 { "SEO Audit" }
+            AuditScore { audit: audit.clone() }
+            AuditIssueList { issues: audit.issues }
+        }
+    }
+}
+
+/// Every post's audit, worst first, for `/admin/seo`
+#[component]
+pub fn SeoAuditReport() -> Element {
+    let posts = use_resource(api::get_all_posts);
+    let pages = use_resource(api::get_published_pages);
+    
+    rsx! {
+        div {
+            class: "seo-audit-report",
+            h3 { "🔍 SEO Audit" }
+            p { "Checks titles, meta descriptions, image alt text, heading order, internal links and focus keywords." }
+            
+            match &*posts.read() {
+                None => rsx! { p { "Auditing posts..." } },
+                Some(Err(e)) => rsx! { p { class: "error", "Failed to load posts: {e}" } },
+                Some(Ok(posts)) if posts.is_empty() => rsx! { p { "No posts to audit yet." } },
+                Some(Ok(posts)) => {
+                    let report = audit_site(posts, published_page_slugs(&pages.read()));
+                    let average = report.iter().map(|(_, audit)| audit.score).sum::<u32>() / report.len() as u32;
+                    
+                    rsx! {
+                        p { class: "audit-summary", "Average score {average} across {report.len()} posts" }
+                        table {
+                            thead {
+                                tr {
+                                    th { "Score" }
+                                    th { "Post" }
+                                    th { "Issues" }
+                                }
+                            }
+                            tbody {
+                                for (post, audit) in report {
+                                    tr {
+                                        key: "{post.id}",
+                                        td { AuditScore { audit: audit.clone() } }
+                                        td {
+                                            a { href: "/admin/posts/{post.id}/edit", "{post.title}" }
+                                            if !post.published {
+                                                span { class: "post-meta", " (draft)" }
+                                            }
+                                        }
+                                        td { AuditIssueList { issues: audit.issues } }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+#[component]
+fn AuditScore(audit: SeoAudit) -> Element {
+    rsx! {
+        span {
+            class: "audit-score audit-{audit.grade()}",
+            "{audit.score}/100"
+        }
+    }
+}
+
+#[component]
+fn AuditIssueList(issues: Vec<AuditIssue>) -> Element {
+    if issues.is_empty() {
+        return rsx! { p { class: "audit-ok", "No issues found." } };
+    }
+    
+    rsx! {
+        ul {
+            class: "audit-issues",
+            for issue in issues {
+                li { class: issue.severity.css_class(), "{issue.message}" }
+            }
+        }
+    }
+}
@@ -1,23 +1,19 @@
 use dioxus::prelude::*;
-use super::{Extension, ExtensionRoute, ExtensionComponent};
-use serde::{Deserialize, Serialize};
+use super::{Extension, ExtensionRoute, ExtensionComponent, Theme};
+use api::{
+    activate_theme, delete_theme, get_active_theme, get_active_theme_stylesheet, get_color_scheme, get_themes,
+    import_theme, save_theme, set_color_scheme,
+};
+use client::{ColorScheme, TemplateLayout, ThemeVariableKind};
 use std::collections::HashMap;
 
-/// Theme data structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Theme {
-    pub id: u32,
-    pub name: String,
-    pub description: String,
-    pub css_content: String,
-    pub active: bool,
-}
-
 /// Theme management extension
+///
+/// Themes are persisted in the `themes` table; this extension holds a cached copy
+/// loaded with `api::get_themes`.
 pub struct ThemeExtension {
     themes: HashMap<u32, Theme>,
     active_theme_id: Option<u32>,
-    next_id: u32,
 }
 
 impl ThemeExtension {
@@ -25,54 +21,23 @@ impl ThemeExtension {
         Self {
             themes: HashMap::new(),
             active_theme_id: None,
-            next_id: 1,
-        }
-    }
-    
-    pub fn get_themes(&self) -> Vec<&Theme> {
-        self.themes.values().collect()
-    }
-    
-    pub fn get_active_theme(&self) -> Option<&Theme> {
-        if let Some(id) = self.active_theme_id {
-            self.themes.get(&id)
-        } else {
-            None
         }
     }
     
-    pub fn add_theme(&mut self, mut theme: Theme) -> u32 {
-        theme.id = self.next_id;
-        self.themes.insert(self.next_id, theme);
-        let id = self.next_id;
-        self.next_id += 1;
-        id
+    /// Replace the cached themes with those loaded from the database
+    pub fn set_themes(&mut self, themes: Vec<Theme>) {
+        self.active_theme_id = themes.iter().find(|theme| theme.active).map(|theme| theme.id);
+        self.themes = themes.into_iter().map(|theme| (theme.id, theme)).collect();
     }
     
-    pub fn activate_theme(&mut self, theme_id: u32) -> bool {
-        if self.themes.contains_key(&theme_id) {
-            // Deactivate current theme
-            if let Some(current_id) = self.active_theme_id {
-                if let Some(current_theme) = self.themes.get_mut(&current_id) {
-                    current_theme.active = false;
-                }
-            }
-            
-            // Activate new theme
-            if let Some(new_theme) = self.themes.get_mut(&theme_id) {
-                new_theme.active = true;
-                self.active_theme_id = Some(theme_id);
-                return true;
-            }
-        }
-        false
+    pub fn get_themes(&self) -> Vec<&Theme> {
+        let mut themes: Vec<&Theme> = self.themes.values().collect();
+        themes.sort_by_key(|theme| theme.id);
+        themes
     }
     
-    pub fn delete_theme(&mut self, theme_id: u32) -> Option<Theme> {
-        if Some(theme_id) == self.active_theme_id {
-            self.active_theme_id = None;
-        }
-        self.themes.remove(&theme_id)
+    pub fn get_active_theme(&self) -> Option<&Theme> {
+        self.themes.get(&self.active_theme_id?)
     }
 }
 
@@ -90,40 +55,7 @@ impl Extension for ThemeExtension {
     }
     
     fn init(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        // Add default dark theme
-        let dark_theme = Theme {
-            id: 0,
-            name: "Dark Professional".to_string(),
-            description: "Professional dark theme with blue accents".to_string(),
-            css_content: "/* Dark theme CSS variables would go here */".to_string(),
-            active: true,
-        };
-        
-        let theme_id = self.add_theme(dark_theme);
-        self.activate_theme(theme_id);
-        
-        // Add light theme
-        let light_theme = Theme {
-            id: 0,
-            name: "Light Professional".to_string(),
-            description: "Clean light theme with subtle shadows".to_string(),
-            css_content: "/* Light theme CSS variables would go here */".to_string(),
-            active: false,
-        };
-        
-        self.add_theme(light_theme);
-        
-        // Add colorful theme
-        let colorful_theme = Theme {
-            id: 0,
-            name: "Vibrant Colors".to_string(),
-            description: "Bright and colorful theme for creative sites".to_string(),
-            css_content: "/* Vibrant theme CSS variables would go here */".to_string(),
-            active: false,
-        };
-        
-        self.add_theme(colorful_theme);
-        
+        // Themes are seeded and persisted by the database; nothing to set up in memory
         Ok(())
     }
     
@@ -143,70 +75,242 @@ impl Extension for ThemeExtension {
                 name: "ThemeManager".to_string(),
                 description: "Manage and switch between themes".to_string(),
             },
+            ExtensionComponent {
+                name: "ThemeLayout".to_string(),
+                description: "Wraps page content in the layout of a theme template".to_string(),
+            },
             ExtensionComponent {
                 name: "ThemeSelector".to_string(),
-                description: "Quick theme switcher component".to_string(),
+                description: "Light, dark or system color scheme switcher for readers".to_string(),
             },
         ]
     }
 }
 
+/// Bumped whenever the active theme changes so `ThemeStylesheet` refetches its URL
+#[derive(Clone, Copy)]
+pub struct ThemeRevision(pub Signal<u32>);
+
+/// Links the active theme's stylesheet into the document head.
+///
+/// The URL carries a content hash, so browsers cache it until the theme is edited or
+/// another theme is activated. Resolved during SSR so the first paint is already themed.
+#[component]
+pub fn ThemeStylesheet() -> Element {
+    let revision = try_use_context::<ThemeRevision>();
+    let stylesheet = use_server_future(move || {
+        // Subscribe to theme changes made in the admin
+        if let Some(ThemeRevision(revision)) = revision {
+            revision();
+        }
+        get_active_theme_stylesheet()
+    })?;
+    
+    let href = stylesheet.read().as_ref().and_then(|result| result.as_ref().ok().cloned().flatten());
+    
+    rsx! {
+        if let Some(href) = href {
+            document::Link { rel: "stylesheet", href: "{href}" }
+        }
+    }
+}
+
+/// Wraps content in the layout of one of the active theme's templates.
+///
+/// Unknown template names, and themes without templates, fall back to the default layout.
+#[component]
+pub fn ThemeLayout(template: Option<String>, children: Element) -> Element {
+    let theme = use_server_future(get_active_theme)?;
+    
+    let layout = match (&*theme.read(), template) {
+        (Some(Ok(Some(theme))), Some(name)) => theme.template(&name).map(|t| t.layout).unwrap_or_default(),
+        _ => TemplateLayout::default(),
+    };
+    
+    rsx! {
+        div {
+            class: "theme-layout {layout.css_class()}",
+            div { class: "layout-main", {children} }
+            if layout == TemplateLayout::Sidebar {
+                aside {
+                    class: "layout-aside",
+                    super::PageList {}
+                }
+            }
+        }
+    }
+}
+
 /// Theme manager component for admin
 #[component]
 pub fn ThemeManager() -> Element {
-    let mut active_theme = use_signal(|| "Dark Professional".to_string());
+    let mut themes = use_resource(|| async { get_themes().await });
+    let mut editing = use_signal(|| None::<Theme>);
+    let mut message = use_signal(String::new);
+    let revision = try_use_context::<ThemeRevision>();
+    
+    let refresh_stylesheet = move || {
+        if let Some(ThemeRevision(mut revision)) = revision {
+            revision += 1;
+        }
+    };
+    
+    let on_activate = move |id: u32| {
+        spawn(async move {
+            match activate_theme(id).await {
+                Ok(()) => {
+                    message.set("Theme activated".to_string());
+                    themes.restart();
+                    refresh_stylesheet();
+                },
+                Err(e) => message.set(format!("Failed to activate theme: {}", e)),
+            }
+        });
+    };
+    
+    let on_delete = move |id: u32| {
+        spawn(async move {
+            match delete_theme(id).await {
+                Ok(()) => {
+                    message.set("Theme deleted".to_string());
+                    themes.restart();
+                },
+                Err(e) => message.set(format!("Failed to delete theme: {}", e)),
+            }
+        });
+    };
+    
+    let on_import = move |evt: FormEvent| {
+        spawn(async move {
+            let Some(file_engine) = evt.files() else { return };
+            let Some(file_name) = file_engine.files().into_iter().next() else { return };
+            let Some(data) = file_engine.read_file(&file_name).await else {
+                message.set(format!("Could not read {}", file_name));
+                return;
+            };
+            
+            match import_theme(data).await {
+                Ok(theme) => {
+                    message.set(format!("Imported theme \"{}\" {}", theme.name, theme.version));
+                    themes.restart();
+                    if theme.active {
+                        refresh_stylesheet();
+                    }
+                },
+                Err(e) => message.set(format!("Failed to import theme: {}", e)),
+            }
+        });
+    };
+    
+    let on_save = move |evt: FormEvent| {
+        evt.prevent_default();
+        let Some(theme) = editing() else { return };
+        
+        spawn(async move {
+            let was_active = theme.active;
+            match save_theme(theme.clone()).await {
+                Ok(id) => {
+                    message.set(format!("Saved theme \"{}\"", theme.name));
+                    editing.set(Some(Theme { id, ..theme }));
+                    themes.restart();
+                    if was_active {
+                        refresh_stylesheet();
+                    }
+                },
+                Err(e) => message.set(format!("Failed to save theme: {}", e)),
+            }
+        });
+    };
+    
+    let theme_list = match &*themes.read() {
+        Some(Ok(list)) => list.clone(),
+        Some(Err(e)) => {
+            return rsx! {
+                div {
+                    class: "theme-manager",
+                    h2 { "Theme Management" }
+                    div { class: "error-message", p { "Failed to load themes: {e}" } }
+                }
+            };
+        },
+        None => Vec::new(),
+    };
+    let active = theme_list.iter().find(|theme| theme.active).cloned();
     
     rsx! {
         div {
+            class: "theme-manager",
             h2 { "Theme Management" }
-            p { "Customize the appearance of your CMS with different themes. Changes apply immediately." }
+            p { "Customize the appearance of your CMS with different themes. Changes apply on the next page load for visitors." }
+            
+            if !message().is_empty() {
+                div { class: "status-message", p { "{message}" } }
+            }
             
             div {
                 h3 { "Current Theme" }
-                div {
+                if let Some(active) = active {
                     div {
-                        h4 { "{active_theme}" }
-                        span { "ACTIVE" }
+                        class: "theme-card active",
+                        div {
+                            h4 { "{active.name}" }
+                            span { "ACTIVE" }
+                        }
+                        div { {active.description.clone().unwrap_or_default()} }
                     }
-                    div { "Professional dark theme with blue accents" }
+                } else {
+                    p { "No theme is active; only the base stylesheet is applied." }
                 }
             }
             
             div {
                 h3 { "Available Themes" }
-                div {
+                for theme in theme_list {
                     div {
-                        onclick: move |_| {
-                            active_theme.set("Dark Professional".to_string());
-                        },
+                        key: "{theme.id}",
+                        class: if theme.active { "theme-card active" } else { "theme-card" },
                         div {
-                            h4 { "Dark Professional" }
-                            span { "ACTIVE" }
+                            h4 { "{theme.name}" }
+                            span { class: "theme-version", "v{theme.version}" }
+                            if theme.active {
+                                span { "ACTIVE" }
+                            }
                         }
-                        div { "Professional dark theme with blue accents" }
-                        button { "Activate" }
-                    }
-                    
-                    div {
-                        onclick: move |_| {
-                            active_theme.set("Light Professional".to_string());
-                        },
-                        div {
-                            h4 { "Light Professional" }
+                        div { {theme.description.clone().unwrap_or_default()} }
+                        if !theme.templates.is_empty() {
+                            div {
+                                class: "theme-templates",
+                                "Templates: "
+                                {theme.templates.iter().map(|t| t.label.clone()).collect::<Vec<_>>().join(", ")}
+                            }
                         }
-                        div { "Clean light theme with subtle shadows" }
-                        button { "Activate" }
-                    }
-                    
-                    div {
-                        onclick: move |_| {
-                            active_theme.set("Vibrant Colors".to_string());
-                        },
                         div {
-                            h4 { "Vibrant Colors" }
+                            if !theme.active {
+                                button {
+                                    onclick: move |_| on_activate(theme.id),
+                                    "Activate"
+                                }
+                            }
+                            button {
+                                onclick: {
+                                    let theme = theme.clone();
+                                    move |_| editing.set(Some(theme.clone()))
+                                },
+                                "Edit"
+                            }
+                            a {
+                                class: "btn",
+                                href: "/theme/{theme.id}/export.zip",
+                                download: "",
+                                "Export"
+                            }
+                            if !theme.active {
+                                button {
+                                    onclick: move |_| on_delete(theme.id),
+                                    "Delete"
+                                }
+                            }
                         }
-                        div { "Bright and colorful theme for creative sites" }
-                        button { "Activate" }
                     }
                 }
             }
@@ -214,56 +318,190 @@ pub fn ThemeManager() -> Element {
             div {
                 h3 { "Theme Actions" }
                 div {
-                    button { "Create Custom Theme" }
-                    button { "Import Theme" }
-                    button { "Export Current Theme" }
+                    button {
+                        onclick: move |_| {
+                            editing.set(Some(Theme {
+                                id: 0,
+                                name: "Custom Theme".to_string(),
+                                description: None,
+                                css_content: ":root {\n}\n".to_string(),
+                                active: false,
+                                version: "1.0.0".to_string(),
+                                variables: Vec::new(),
+                                templates: Vec::new(),
+                            }));
+                        },
+                        "Create Custom Theme"
+                    }
+                    label {
+                        class: "btn",
+                        r#for: "theme-import",
+                        "Import Theme Package"
+                    }
+                    input {
+                        r#type: "file",
+                        id: "theme-import",
+                        accept: ".zip,application/zip",
+                        style: "display: none;",
+                        onchange: on_import,
+                    }
+                }
+            }
+            
+            if let Some(theme) = editing() {
+                form {
+                    class: "theme-editor",
+                    onsubmit: on_save,
+                    h3 { if theme.id == 0 { "New Theme" } else { "Edit Theme" } }
+                    
+                    div {
+                        class: "form-group",
+                        label { r#for: "theme-name", "Name:" }
+                        input {
+                            r#type: "text",
+                            id: "theme-name",
+                            value: "{theme.name}",
+                            oninput: move |e| editing.with_mut(|t| if let Some(t) = t { t.name = e.value() }),
+                            required: true
+                        }
+                    }
+                    
+                    div {
+                        class: "form-group",
+                        label { r#for: "theme-description", "Description:" }
+                        input {
+                            r#type: "text",
+                            id: "theme-description",
+                            value: theme.description.clone().unwrap_or_default(),
+                            oninput: move |e| editing.with_mut(|t| if let Some(t) = t {
+                                let value = e.value();
+                                t.description = if value.trim().is_empty() { None } else { Some(value) };
+                            }),
+                        }
+                    }
+                    
+                    div {
+                        class: "form-group",
+                        label { r#for: "theme-version", "Version:" }
+                        input {
+                            r#type: "text",
+                            id: "theme-version",
+                            value: "{theme.version}",
+                            oninput: move |e| editing.with_mut(|t| if let Some(t) = t { t.version = e.value() }),
+                            required: true
+                        }
+                    }
+                    
+                    if !theme.variables.is_empty() {
+                        fieldset {
+                            class: "theme-variables",
+                            legend { "Design Variables" }
+                            for (index, variable) in theme.variables.iter().cloned().enumerate() {
+                                div {
+                                    key: "{variable.name}",
+                                    class: "form-group",
+                                    label { r#for: "theme-var-{variable.name}", "{variable.label}" }
+                                    input {
+                                        r#type: if variable.kind == ThemeVariableKind::Color && variable.value.starts_with('#') { "color" } else { "text" },
+                                        id: "theme-var-{variable.name}",
+                                        value: "{variable.value}",
+                                        oninput: move |e| editing.with_mut(|t| if let Some(t) = t { t.variables[index].value = e.value() }),
+                                    }
+                                    code { "{variable.css_property()}" }
+                                    if let Err(e) = variable.validate() {
+                                        span { class: "error-message", "{e}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    
+                    div {
+                        class: "form-group",
+                        label { r#for: "theme-css", "CSS:" }
+                        textarea {
+                            id: "theme-css",
+                            class: "theme-css-editor",
+                            rows: "24",
+                            spellcheck: "false",
+                            value: "{theme.css_content}",
+                            oninput: move |e| editing.with_mut(|t| if let Some(t) = t { t.css_content = e.value() }),
+                        }
+                    }
+                    
+                    div {
+                        button { r#type: "submit", "Save Theme" }
+                        button {
+                            r#type: "button",
+                            onclick: move |_| editing.set(None),
+                            "Close"
+                        }
+                    }
                 }
             }
         }
     }
 }
 
-/// Quick theme selector component
+/// Reader's light / dark / system color scheme switcher.
+///
+/// The choice is saved to a cookie (and the profile of a logged-in user) and applied
+/// through the theme stylesheet URL, so server-rendered pages already use it.
 #[component]
 pub fn ThemeSelector() -> Element {
-    let mut current_theme = use_signal(|| "Dark Professional".to_string());
+    let current = use_server_future(get_color_scheme)?;
+    let mut selected = use_signal(|| None::<Option<ColorScheme>>);
     let mut show_dropdown = use_signal(|| false);
+    let revision = try_use_context::<ThemeRevision>();
+    
+    let scheme = selected().unwrap_or_else(|| current.read().as_ref().and_then(|result| result.as_ref().ok().copied().flatten()));
+    let label = scheme.map_or("Theme default", |scheme| scheme.label());
+    
+    let mut choose = move |scheme: Option<ColorScheme>| {
+        show_dropdown.set(false);
+        selected.set(Some(scheme));
+        spawn(async move {
+            if set_color_scheme(scheme).await.is_ok() {
+                if let Some(ThemeRevision(mut revision)) = revision {
+                    revision += 1;
+                }
+            }
+        });
+    };
     
     rsx! {
         div {
+            class: "theme-selector",
             button {
                 onclick: move |_| {
                     show_dropdown.set(!show_dropdown());
                 },
-                span { "🎨 {current_theme}" }
+                span { "🎨 {label}" }
                 span { if show_dropdown() { "▲" } else { "▼" } }
             }
             
             if show_dropdown() {
                 div {
-                    div {
-                        onclick: move |_| {
-                            current_theme.set("Dark Professional".to_string());
-                            show_dropdown.set(false);
-                        },
-                        "🌙 Dark Professional"
-                    }
-                    div {
-                        onclick: move |_| {
-                            current_theme.set("Light Professional".to_string());
-                            show_dropdown.set(false);
-                        },
-                        "☀️ Light Professional"
+                    class: "theme-selector-options",
+                    for option in ColorScheme::ALL {
+                        div {
+                            key: "{option.as_str()}",
+                            class: if scheme == Some(option) { "selected" } else { "" },
+                            onclick: move |_| choose(Some(option)),
+                            {match option {
+                                ColorScheme::Light => "☀️ Light",
+                                ColorScheme::Dark => "🌙 Dark",
+                                ColorScheme::System => "💻 System",
+                            }}
+                        }
                     }
                     div {
-                        onclick: move |_| {
-                            current_theme.set("Vibrant Colors".to_string());
-                            show_dropdown.set(false);
-                        },
-                        "🌈 Vibrant Colors"
+                        class: if scheme.is_none() { "selected" } else { "" },
+                        onclick: move |_| choose(None),
+                        "🎨 Theme default"
                     }
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
@@ -3,11 +3,14 @@
 mod views;
 
 mod navbar;
-pub use navbar::Route;
+pub use navbar::{route_pattern, Route};
 pub use navbar::App;
 
 mod markdown;
-pub use markdown::Markdown;
+pub use markdown::{slugify, table_of_contents, FrontMatter, FrontMatterError, Markdown, TableOfContents, TocEntry};
+
+mod shortcodes;
+pub use shortcodes::{register_shortcode, shortcode_names, Shortcode, ShortcodeHandler};
 
 pub mod extensions;
 pub use extensions::*;
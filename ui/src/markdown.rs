@@ -1,11 +1,19 @@
 use std::sync::OnceLock;
 use dioxus::prelude::*;
-use pulldown_cmark::{Options, Parser, Tag, Event};
+use pulldown_cmark::{CowStr, Options, Parser, Tag, Event};
 use syntect::highlighting::ThemeSet;
 use syntect::parsing::SyntaxSet;
 use std::time;
 use std::fs;
 use std::path::Path;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use crate::t;
+
+use client::{HtmlPolicy, HtmlToken, SanitizedTag};
+use crate::shortcodes::{expand_shortcodes, placeholder_index, render_shortcode, Shortcode};
+
+pub use client::{FrontMatter, FrontMatterError};
 
 /// Component for rendering markdown content safely.
 /// 
@@ -18,6 +26,10 @@ use std::path::Path;
 /// - Image handling with optional base paths
 /// - External link detection and special handling
 /// - Safe rendering without using dangerous_inner_html
+/// - Raw HTML is kept only as far as `html_policy` allows; posts' defaults otherwise
+/// - Shortcodes like `{{< gallery folder="trip" >}}`, unless `shortcodes` is off; ones
+///   that can't be rendered show an error when `preview` is on and nothing otherwise
+/// - YAML or TOML front matter is left out; read it with [`FrontMatter::parse`]
 /// 
 /// # Example
 /// 
@@ -42,6 +54,9 @@ use std::path::Path;
 pub fn Markdown(
     content: Option<String>,
     #[props(optional)] image_base_path: Option<String>,
+    #[props(optional)] html_policy: Option<HtmlPolicy>,
+    #[props(default = true)] shortcodes: bool,
+    #[props(default)] preview: bool,
     #[props(optional)] id: Option<String>,
     #[props(optional)] file_path: Option<String>,
 ) -> Element {
@@ -62,13 +77,13 @@ pub fn Markdown(
         content_str.clone()
     };
     
-    let options = Options::all();
-    let parser = Parser::new_ext(&final_content, options);
-    
-    let mut events = Vec::new();
-    for event in parser {
-        events.push(event);
-    }
+    let (markdown, shortcodes) = prepare(&final_content, shortcodes);
+    let events = parse_events(&markdown);
+    let context = RenderContext {
+        shortcodes,
+        preview,
+        ..RenderContext::new(&events, image_base_path, html_policy.unwrap_or_default())
+    };
     
     // Use the provided ID or generate a simple one based on current timestamp
     let markdown_id = id.unwrap_or_else(|| {
@@ -82,7 +97,302 @@ pub fn Markdown(
         div {
             class: "markdown-container",
             id: markdown_id,
-            {render_markdown_events(events, image_base_path)}
+            {render_markdown_events(events.clone(), &context)}
+            {render_footnotes(&events, &context)}
+        }
+    }
+}
+
+/// Markdown as [`Markdown`] parses it: front matter left out and, if `shortcodes` is on,
+/// shortcodes swapped for placeholder tags
+fn prepare(markdown: &str, shortcodes: bool) -> (String, Vec<Shortcode>) {
+    let markdown = client::strip_front_matter(markdown);
+    match shortcodes {
+        true => expand_shortcodes(markdown),
+        false => (markdown.to_string(), Vec::new()),
+    }
+}
+
+/// Parse prepared markdown. Raw HTML comes one tag or piece of text per event, so tags can
+/// be matched up like markdown's.
+fn parse_events(markdown: &str) -> Vec<Event<'_>> {
+    Parser::new_ext(markdown, Options::all())
+        .flat_map(|event| match event {
+            Event::Html(html) => client::split_html(&html)
+                .into_iter()
+                .map(|piece| Event::Html(CowStr::from(piece.to_string())))
+                .collect(),
+            event => vec![event],
+        })
+        .collect()
+}
+
+/// A heading of a markdown document, as listed in its table of contents
+#[derive(Debug, Clone, PartialEq)]
+pub struct TocEntry {
+    /// 1 for `#` through 6 for `######`
+    pub level: u8,
+    pub text: String,
+    /// `id` of the rendered heading
+    pub id: String,
+}
+
+/// The headings of a markdown document with the ids [`Markdown`] gives them
+pub fn table_of_contents(markdown: &str) -> Vec<TocEntry> {
+    let (markdown, _) = prepare(markdown, true);
+    outline(&parse_events(&markdown))
+}
+
+/// Headings in document order, footnotes left out. Ids are the `{#id}` written after the
+/// heading, or a slug of its text made unique with `-1`, `-2`...
+fn outline(events: &[Event]) -> Vec<TocEntry> {
+    let mut headings = Vec::new();
+    let mut current: Option<(u8, Option<String>, String)> = None;
+    let mut in_footnote = 0;
+    for event in events {
+        match event {
+            Event::Start(Tag::FootnoteDefinition(_)) => in_footnote += 1,
+            Event::End(Tag::FootnoteDefinition(_)) => in_footnote -= 1,
+            Event::Start(Tag::Heading(level, id, _)) if in_footnote == 0 => {
+                current = Some((*level as u8, id.map(str::to_string), String::new()));
+            },
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, _, heading)) = &mut current {
+                    heading.push_str(text);
+                }
+            },
+            Event::End(Tag::Heading(..)) => headings.extend(current.take()),
+            _ => {},
+        }
+    }
+
+    // Written ids are kept even if repeated; generated ones avoid them
+    let mut used: HashSet<String> = headings.iter().filter_map(|(_, id, _)| id.clone()).collect();
+    headings
+        .into_iter()
+        .map(|(level, id, text)| {
+            let id = id.unwrap_or_else(|| {
+                let base = slugify(&text);
+                let id = (1..)
+                    .map(|n| format!("{}-{}", base, n))
+                    .find(|id| !used.contains(id))
+                    .filter(|_| used.contains(&base))
+                    .unwrap_or(base);
+                used.insert(id.clone());
+                id
+            });
+            TocEntry { level, text: text.trim().to_string(), id }
+        })
+        .collect()
+}
+
+/// `What's new in 2.0?` as `whats-new-in-20`. Letters outside ASCII are kept, so headings
+/// in other scripts still get readable ids.
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.trim().chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if (c.is_whitespace() || c == '-' || c == '_') && !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    match slug.trim_end_matches('-') {
+        "" => "section".to_string(),
+        slug => slug.to_string(),
+    }
+}
+
+fn footnote_id(label: &str) -> String {
+    format!("fn-{}", slugify(label))
+}
+
+/// `id` of the `n`th reference (from 1) to a footnote
+fn footnote_reference_id(label: &str, n: usize) -> String {
+    match n {
+        1 => format!("fnref-{}", slugify(label)),
+        n => format!("fnref-{}-{}", slugify(label), n),
+    }
+}
+
+/// Document-wide state while rendering, as heading ids and footnote numbers depend on
+/// everything before them
+struct RenderContext {
+    image_base_path: Option<String>,
+    html_policy: HtmlPolicy,
+    /// By placeholder index
+    shortcodes: Vec<Shortcode>,
+    preview: bool,
+    heading_ids: Vec<String>,
+    next_heading: Cell<usize>,
+    /// Footnote label -> number, in order of first reference; unreferenced footnotes last
+    footnote_numbers: HashMap<String, usize>,
+    /// References to each footnote in the document
+    footnote_references: HashMap<String, usize>,
+    /// References to each footnote rendered so far
+    footnote_references_rendered: RefCell<HashMap<String, usize>>,
+}
+
+impl RenderContext {
+    fn new(events: &[Event], image_base_path: Option<String>, html_policy: HtmlPolicy) -> Self {
+        let mut footnote_numbers = HashMap::new();
+        let mut footnote_references = HashMap::new();
+        let mut definitions = Vec::new();
+        for event in events {
+            match event {
+                Event::FootnoteReference(label) => *footnote_references.entry(label.to_string()).or_insert(0) += 1,
+                Event::Start(Tag::FootnoteDefinition(label)) => definitions.push(label.to_string()),
+                _ => {},
+            }
+        }
+        let referenced = events.iter().filter_map(|event| match event {
+            Event::FootnoteReference(label) => Some(label.to_string()),
+            _ => None,
+        });
+        for label in referenced.chain(definitions.iter().cloned()) {
+            let next = footnote_numbers.len() + 1;
+            footnote_numbers.entry(label).or_insert(next);
+        }
+        // Only footnotes with a definition are linked
+        footnote_numbers.retain(|label, _| definitions.contains(label));
+        let mut numbers: Vec<_> = footnote_numbers.values_mut().collect();
+        numbers.sort();
+        for (number, value) in numbers.into_iter().enumerate() {
+            *value = number + 1;
+        }
+
+        Self {
+            image_base_path,
+            html_policy,
+            shortcodes: Vec::new(),
+            preview: false,
+            heading_ids: outline(events).into_iter().map(|entry| entry.id).collect(),
+            next_heading: Cell::new(0),
+            footnote_numbers,
+            footnote_references,
+            footnote_references_rendered: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn next_heading_id(&self) -> Option<String> {
+        let index = self.next_heading.get();
+        self.next_heading.set(index + 1);
+        self.heading_ids.get(index).cloned()
+    }
+
+    /// Number of the footnote `label` and the `id` of this reference to it; `None` if the
+    /// footnote isn't defined
+    fn footnote_reference(&self, label: &str) -> Option<(usize, String)> {
+        let number = *self.footnote_numbers.get(label)?;
+        let mut rendered = self.footnote_references_rendered.borrow_mut();
+        let n = rendered.entry(label.to_string()).or_insert(0);
+        *n += 1;
+        Some((number, footnote_reference_id(label, *n)))
+    }
+}
+
+/// The footnote definitions of a document as a numbered list after it, each linking back
+/// to the references to it
+fn render_footnotes(events: &[Event], context: &RenderContext) -> Element {
+    let mut definitions = Vec::new();
+    let mut i = 0;
+    while i < events.len() {
+        match &events[i] {
+            Event::Start(tag @ Tag::FootnoteDefinition(label)) => {
+                let (content, new_index) = collect_until_end_with_index(events, i, tag.clone());
+                if let Some(number) = context.footnote_numbers.get(label.as_ref()) {
+                    definitions.push((*number, label.to_string(), content));
+                }
+                i = new_index;
+            },
+            _ => i += 1,
+        }
+    }
+    if definitions.is_empty() {
+        return rsx! {};
+    }
+    definitions.sort_by_key(|(number, _, _)| *number);
+
+    rsx! {
+        section {
+            class: "markdown-footnote",
+            role: "doc-endnotes",
+            ol {
+                for (number, label, content) in definitions {
+                    li {
+                        key: "{label}",
+                        id: footnote_id(&label),
+                        value: "{number}",
+                        dir: "auto",
+                        {render_markdown_events(content, context)}
+                        for n in 1..=context.footnote_references.get(&label).copied().unwrap_or(0) {
+                            a {
+                                class: "markdown-footnote-backref",
+                                href: "#{footnote_reference_id(&label, n)}",
+                                role: "doc-backlink",
+                                aria_label: "Back to reference {n}",
+                                if n == 1 { "↩" } else { "↩{n}" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Linked, nested list of the headings of a long markdown document. Nothing is shown
+/// with fewer than `min_headings` entries. `depth` is how many heading levels are listed,
+/// from the highest one used; a lone `#` title at the start isn't listed.
+#[component]
+pub fn TableOfContents(
+    content: String,
+    #[props(default = 3)] depth: u8,
+    #[props(default = 3)] min_headings: usize,
+) -> Element {
+    let mut entries = table_of_contents(&content);
+    let is_title = |entry: &TocEntry| entry.level == 1;
+    if entries.first().is_some_and(is_title) && entries.iter().filter(|entry| is_title(entry)).count() == 1 {
+        entries.remove(0);
+    }
+    let top = entries.iter().map(|entry| entry.level).min().unwrap_or(1);
+    entries.retain(|entry| entry.level < top + depth);
+    if entries.len() < min_headings.max(1) {
+        return rsx! {};
+    }
+
+    rsx! {
+        nav {
+            class: "table-of-contents",
+            aria_label: t!("contents"),
+            h2 { class: "table-of-contents-title", {t!("contents")} }
+            {toc_list(&entries)}
+        }
+    }
+}
+
+/// Entries as an ordered list, each followed by the deeper entries under it
+fn toc_list(entries: &[TocEntry]) -> Element {
+    let mut items = Vec::new();
+    let mut i = 0;
+    while i < entries.len() {
+        let level = entries[i].level;
+        let end = entries[i + 1..].iter().position(|entry| entry.level <= level).map_or(entries.len(), |p| i + 1 + p);
+        items.push((entries[i].clone(), &entries[i + 1..end]));
+        i = end;
+    }
+
+    rsx! {
+        ol {
+            for (entry, children) in items {
+                li {
+                    key: "{entry.id}",
+                    a { href: "#{entry.id}", dir: "auto", "{entry.text}" }
+                    if !children.is_empty() {
+                        {toc_list(children)}
+                    }
+                }
+            }
         }
     }
 }
@@ -297,7 +607,7 @@ fn add_line_numbers_elements(code: &str) -> Vec<Element> {
 }
 
 /// Render markdown events to Dioxus elements
-fn render_markdown_events<'a>(events: Vec<Event<'a>>, image_base_path: Option<String>) -> impl Iterator<Item = Element> {
+fn render_markdown_events<'a>(events: Vec<Event<'a>>, context: &RenderContext) -> impl Iterator<Item = Element> {
     let mut elements = Vec::new();
     let mut current_text = String::new();
     let mut list_stack = Vec::new();
@@ -322,30 +632,38 @@ fn render_markdown_events<'a>(events: Vec<Event<'a>>, image_base_path: Option<St
                 
                 // Handle opening tags
                 match tag {
+                    // Blocks take their direction from their own text, so an English
+                    // paragraph in an Arabic post still reads left to right
                     Tag::Paragraph => {
                         // Collect all events until the matching End(Paragraph)
                         let (content, new_index) = collect_until_end_with_index(events_slice, i, Tag::Paragraph);
-                        elements.push(rsx! { p { class: "markdown-paragraph", {render_markdown_events(content, image_base_path.clone())} } });
+                        elements.push(rsx! { p { class: "markdown-paragraph", dir: "auto", {render_markdown_events(content, context)} } });
                         i = new_index;
                     },
                     Tag::Heading(level, _, _) => {
                         let class = format!("markdown-heading-{}", *level as u8);
+                        // Headings inside footnotes come after the outline and get no id
+                        let id = context.next_heading_id();
+                        let anchor = id.clone().map(|id| rsx! {
+                            a { class: "markdown-heading-anchor", href: "#{id}", aria_label: "Link to this section", "#" }
+                        });
                         // Collect all events until the matching End(Heading)
-                        let (content, new_index) = collect_until_end_with_index(events_slice, i, Tag::Heading(*level, None, Vec::new()));
+                        let (content, new_index) = collect_until_end_with_index(events_slice, i, tag.clone());
+                        let content = render_markdown_events(content, context);
                         match level {
-                            pulldown_cmark::HeadingLevel::H1 => elements.push(rsx! { h1 { class: class, {render_markdown_events(content, image_base_path.clone())} } }),
-                            pulldown_cmark::HeadingLevel::H2 => elements.push(rsx! { h2 { class: class, {render_markdown_events(content, image_base_path.clone())} } }),
-                            pulldown_cmark::HeadingLevel::H3 => elements.push(rsx! { h3 { class: class, {render_markdown_events(content, image_base_path.clone())} } }),
-                            pulldown_cmark::HeadingLevel::H4 => elements.push(rsx! { h4 { class: class, {render_markdown_events(content, image_base_path.clone())} } }),
-                            pulldown_cmark::HeadingLevel::H5 => elements.push(rsx! { h5 { class: class, {render_markdown_events(content, image_base_path.clone())} } }),
-                            pulldown_cmark::HeadingLevel::H6 => elements.push(rsx! { h6 { class: class, {render_markdown_events(content, image_base_path.clone())} } }),
+                            pulldown_cmark::HeadingLevel::H1 => elements.push(rsx! { h1 { class: class, id: id, dir: "auto", {content} {anchor} } }),
+                            pulldown_cmark::HeadingLevel::H2 => elements.push(rsx! { h2 { class: class, id: id, dir: "auto", {content} {anchor} } }),
+                            pulldown_cmark::HeadingLevel::H3 => elements.push(rsx! { h3 { class: class, id: id, dir: "auto", {content} {anchor} } }),
+                            pulldown_cmark::HeadingLevel::H4 => elements.push(rsx! { h4 { class: class, id: id, dir: "auto", {content} {anchor} } }),
+                            pulldown_cmark::HeadingLevel::H5 => elements.push(rsx! { h5 { class: class, id: id, dir: "auto", {content} {anchor} } }),
+                            pulldown_cmark::HeadingLevel::H6 => elements.push(rsx! { h6 { class: class, id: id, dir: "auto", {content} {anchor} } }),
                         }
                         i = new_index;
                     },
                     Tag::BlockQuote => {
                         // Collect all events until the matching End(BlockQuote)
                         let (content, new_index) = collect_until_end_with_index(events_slice, i, Tag::BlockQuote);
-                        elements.push(rsx! { blockquote { class: "markdown-blockquote", {render_markdown_events(content, image_base_path.clone())} } });
+                        elements.push(rsx! { blockquote { class: "markdown-blockquote", dir: "auto", {render_markdown_events(content, context)} } });
                         i = new_index;
                     },
                     Tag::CodeBlock(kind) => {
@@ -382,6 +700,8 @@ fn render_markdown_events<'a>(events: Vec<Event<'a>>, image_base_path: Option<St
                             elements.push(rsx! {
                                 div {
                                     class: "markdown-code-block language-{language} {scroll_class}",
+                                    // Code reads left to right on right-to-left pages too
+                                    dir: "ltr",
                                     pre {
                                         code {
                                             class: "syntax-highlighted line-numbers",
@@ -424,6 +744,7 @@ fn render_markdown_events<'a>(events: Vec<Event<'a>>, image_base_path: Option<St
                             elements.push(rsx! {
                                 div {
                                     class: "markdown-code-block {scroll_class}",
+                                    dir: "ltr",
                                     pre {
                                         code {
                                             class: "line-numbers",
@@ -466,14 +787,14 @@ fn render_markdown_events<'a>(events: Vec<Event<'a>>, image_base_path: Option<St
                                 // Collect all events until the matching End(List)
                                 let (content, new_index) = collect_until_end_with_index(events_slice, i, Tag::List(*first_item_number));
                                 let list_class = if is_task_list { "markdown-list markdown-task-list" } else { "markdown-list" };
-                                elements.push(rsx! { ol { class: list_class, start: "{number}", {render_markdown_events(content, image_base_path.clone())} } });
+                                elements.push(rsx! { ol { class: list_class, start: "{number}", {render_markdown_events(content, context)} } });
                                 i = new_index;
                             },
                             None => {
                                 // Collect all events until the matching End(List)
                                 let (content, new_index) = collect_until_end_with_index(events_slice, i, Tag::List(*first_item_number));
                                 let list_class = if is_task_list { "markdown-list markdown-task-list" } else { "markdown-list" };
-                                elements.push(rsx! { ul { class: list_class, {render_markdown_events(content, image_base_path.clone())} } });
+                                elements.push(rsx! { ul { class: list_class, {render_markdown_events(content, context)} } });
                                 i = new_index;
                             }
                         }
@@ -522,7 +843,7 @@ fn render_markdown_events<'a>(events: Vec<Event<'a>>, image_base_path: Option<St
                                                 }
                                                 span {
                                                     class: "markdown-task-text",
-                                                    {render_markdown_events(content, image_base_path.clone())}
+                                                    {render_markdown_events(content, context)}
                                                 }
                                             }
                                         } 
@@ -535,32 +856,33 @@ fn render_markdown_events<'a>(events: Vec<Event<'a>>, image_base_path: Option<St
                             // Regular list item
                             // Collect all events until the matching End(Item)
                             let (content, new_index) = collect_until_end_with_index(events_slice, i, Tag::Item);
-                            elements.push(rsx! { li { class: "markdown-list-item", {render_markdown_events(content, image_base_path.clone())} } });
+                            elements.push(rsx! { li { class: "markdown-list-item", dir: "auto", {render_markdown_events(content, context)} } });
                             i = new_index;
                         }
                     },
                     Tag::FootnoteDefinition(_) => {
-                        // Skip footnote definitions for now
-                        i += 1;
+                        // Rendered together after the document by `render_footnotes`
+                        let (_, new_index) = collect_until_end_with_index(events_slice, i, tag.clone());
+                        i = new_index;
                     },
                     Tag::Table(alignments) => {
                         // Collect all events until the matching End(Table)
                         let (content, new_index) = collect_until_end_with_index(events_slice, i, Tag::Table(alignments.clone()));
-                        elements.push(rsx! { table { class: "markdown-table", {render_markdown_events(content, image_base_path.clone())} } });
+                        elements.push(rsx! { table { class: "markdown-table", {render_markdown_events(content, context)} } });
                         i = new_index;
                     },
                     Tag::TableHead => {
                         // in_table_head = true; // Note: This assignment is overwritten before use
                         // Collect all events until the matching End(TableHead)
                         let (content, new_index) = collect_until_end_with_index(events_slice, i, Tag::TableHead);
-                        elements.push(rsx! { thead { {render_markdown_events(content, image_base_path.clone())} } });
+                        elements.push(rsx! { thead { {render_markdown_events(content, context)} } });
                         i = new_index;
                         in_table_head = false;
                     },
                     Tag::TableRow => {
                         // Collect all events until the matching End(TableRow)
                         let (content, new_index) = collect_until_end_with_index(events_slice, i, Tag::TableRow);
-                        elements.push(rsx! { tr { {render_markdown_events(content, image_base_path.clone())} } });
+                        elements.push(rsx! { tr { {render_markdown_events(content, context)} } });
                         i = new_index;
                     },
                     Tag::TableCell => {
@@ -569,31 +891,31 @@ fn render_markdown_events<'a>(events: Vec<Event<'a>>, image_base_path: Option<St
                         
                         // Collect all events until the matching End(TableCell)
                         let (content, new_index) = collect_until_end_with_index(events_slice, i, Tag::TableCell);
-                        let cell_content = render_markdown_events(content, image_base_path.clone());
+                        let cell_content = render_markdown_events(content, context);
                         
                         if cell_type == "th" {
-                            elements.push(rsx! { th { class: "markdown-table-header", {cell_content} } });
+                            elements.push(rsx! { th { class: "markdown-table-header", dir: "auto", {cell_content} } });
                         } else {
-                            elements.push(rsx! { td { class: "markdown-table-cell", {cell_content} } });
+                            elements.push(rsx! { td { class: "markdown-table-cell", dir: "auto", {cell_content} } });
                         }
                         i = new_index;
                     },
                     Tag::Emphasis => {
                         // Collect all events until the matching End(Emphasis)
                         let (content, new_index) = collect_until_end_with_index(events_slice, i, Tag::Emphasis);
-                        elements.push(rsx! { em { class: "markdown-emphasis", {render_markdown_events(content, image_base_path.clone())} } });
+                        elements.push(rsx! { em { class: "markdown-emphasis", {render_markdown_events(content, context)} } });
                         i = new_index;
                     },
                     Tag::Strong => {
                         // Collect all events until the matching End(Strong)
                         let (content, new_index) = collect_until_end_with_index(events_slice, i, Tag::Strong);
-                        elements.push(rsx! { strong { class: "markdown-strong", {render_markdown_events(content, image_base_path.clone())} } });
+                        elements.push(rsx! { strong { class: "markdown-strong", {render_markdown_events(content, context)} } });
                         i = new_index;
                     },
                     Tag::Strikethrough => {
                         // Collect all events until the matching End(Strikethrough)
                         let (content, new_index) = collect_until_end_with_index(events_slice, i, Tag::Strikethrough);
-                        elements.push(rsx! { del { class: "markdown-strikethrough", {render_markdown_events(content, image_base_path.clone())} } });
+                        elements.push(rsx! { del { class: "markdown-strikethrough", {render_markdown_events(content, context)} } });
                         i = new_index;
                     },
                     Tag::Link(link_type, url, title) => {
@@ -608,21 +930,25 @@ fn render_markdown_events<'a>(events: Vec<Event<'a>>, image_base_path: Option<St
                         // Collect the content of the link before advancing the index
                         let (content, new_index) = collect_until_end_with_index(events_slice, i, Tag::Link(*link_type, url.clone(), title.clone()));
                         
-                        let link = if url_str.starts_with("http://") || url_str.starts_with("https://") {
+                        let link = if !client::is_safe_url(&url_str) {
+                            // `javascript:` and the like: the text without the link
+                            rsx! { {render_markdown_events(content, context)} }
+                        } else if url_str.starts_with("http://") || url_str.starts_with("https://") {
+                            let rel = if context.html_policy.nofollow { "nofollow ugc noopener noreferrer" } else { "noopener noreferrer" };
                             rsx! { a {
                                 class: link_class,
                                 href: url_str,
                                 title: title_str,
                                 target: "_blank",
-                                rel: "noopener noreferrer",
-                                {render_markdown_events(content, image_base_path.clone())}
+                                rel: rel,
+                                {render_markdown_events(content, context)}
                             }}
                         } else {
                             rsx! { a {
                                 class: link_class,
                                 href: url_str,
                                 title: title_str,
-                                {render_markdown_events(content, image_base_path.clone())}
+                                {render_markdown_events(content, context)}
                             }}
                         };
                         
@@ -634,7 +960,7 @@ fn render_markdown_events<'a>(events: Vec<Event<'a>>, image_base_path: Option<St
                         let title_str = title.to_string();
                         
                         // Handle image base path if provided
-                        if let Some(base) = &image_base_path {
+                        if let Some(base) = &context.image_base_path {
                             if !url_str.starts_with("http://") && !url_str.starts_with("https://") {
                                 // For relative paths, prepend the base path
                                 if url_str.starts_with('/') {
@@ -648,22 +974,24 @@ fn render_markdown_events<'a>(events: Vec<Event<'a>>, image_base_path: Option<St
                         let alt_text = collect_text_until_end(events_slice, Tag::Image(*link_type, url.clone(), title.clone()));
                         let alt_text_clone = alt_text.clone();
                         
-                        elements.push(rsx! {
-                            figure {
-                                class: "markdown-image-container",
-                                img {
-                                    class: "markdown-image",
-                                    src: "{url_str}",
-                                    alt: "{alt_text_clone}",
-                                    title: "{title_str}",
-                                    loading: "lazy",
-                                }
-                                figcaption {
-                                    class: "markdown-image-caption",
-                                    {alt_text}
+                        if client::is_safe_url(&url_str) {
+                            elements.push(rsx! {
+                                figure {
+                                    class: "markdown-image-container",
+                                    img {
+                                        class: "markdown-image",
+                                        src: "{url_str}",
+                                        alt: "{alt_text_clone}",
+                                        title: "{title_str}",
+                                        loading: "lazy",
+                                    }
+                                    figcaption {
+                                        class: "markdown-image-caption",
+                                        {alt_text}
+                                    }
                                 }
-                            }
-                        });
+                            });
+                        }
                         
                         // Skip past the end tag
                         let (_, new_index) = collect_until_end_with_index(events_slice, i, Tag::Image(*link_type, url.clone(), title.clone()));
@@ -689,12 +1017,60 @@ fn render_markdown_events<'a>(events: Vec<Event<'a>>, image_base_path: Option<St
                     current_text.clear();
                 }
                 
-                elements.push(rsx! { code { class: "markdown-inline-code", {code.to_string()} } });
+                elements.push(rsx! { code { class: "markdown-inline-code", dir: "ltr", {code.to_string()} } });
                 i += 1;
             },
-            Event::Html(html) => {
-                current_text.push_str(&html);
-                i += 1;
+            Event::Html(html) => match HtmlToken::parse(html) {
+                HtmlToken::Start { name, attributes, self_closing } => {
+                    if !current_text.is_empty() {
+                        elements.push(rsx! { span { {current_text.clone()} } });
+                        current_text.clear();
+                    }
+                    
+                    let end = if client::VOID_TAGS.contains(&name.as_str()) || self_closing {
+                        Some(i)
+                    } else {
+                        matching_html_end(events_slice, i, &name)
+                    };
+                    let shortcode = placeholder_index(&name).and_then(|index| context.shortcodes.get(index));
+                    let sanitized = context.html_policy.sanitize(&name, &attributes);
+                    match end {
+                        // Dropped along with everything up to its end tag or, if it is never
+                        // closed, the rest of the raw HTML it starts
+                        _ if client::DROPPED_TAGS.contains(&name.as_str()) => {
+                            i = match end {
+                                Some(end) => end + 1,
+                                None => events_slice[i..]
+                                    .iter()
+                                    .position(|event| !matches!(event, Event::Html(_)))
+                                    .map_or(events_slice.len(), |length| i + length),
+                            };
+                        },
+                        Some(end) => {
+                            let children = render_markdown_events(events_slice[i + 1..end.max(i + 1)].to_vec(), context);
+                            match (shortcode, sanitized) {
+                                (Some(shortcode), _) => elements.push(render_shortcode(shortcode, rsx! { {children} }, context.preview)),
+                                (None, Some(tag)) => elements.push(html_element(&tag, rsx! { {children} })),
+                                // Not allowed, but its content is
+                                (None, None) => elements.extend(children),
+                            }
+                            i = end + 1;
+                        },
+                        // Never closed: the tag is dropped and its content rendered as it comes
+                        None => i += 1,
+                    }
+                },
+                HtmlToken::Text(text) => {
+                    // Line breaks between block tags are layout, spaces between inline ones aren't
+                    if !text.trim().is_empty() {
+                        current_text.push_str(&text);
+                    } else if !text.is_empty() && !text.contains('\n') {
+                        current_text.push(' ');
+                    }
+                    i += 1;
+                },
+                // Stray end tags and comments
+                HtmlToken::End { .. } | HtmlToken::Ignored => i += 1,
             },
             Event::FootnoteReference(reference) => {
                 if !current_text.is_empty() {
@@ -702,7 +1078,14 @@ fn render_markdown_events<'a>(events: Vec<Event<'a>>, image_base_path: Option<St
                     current_text.clear();
                 }
                 
-                elements.push(rsx! { sup { class: "markdown-footnote-ref", {format!("[{}]", reference)} } });
+                let footnote = match context.footnote_reference(reference) {
+                    Some((number, id)) => rsx! {
+                        a { id: id, href: "#{footnote_id(reference)}", role: "doc-noteref", "{number}" }
+                    },
+                    // No definition to link to
+                    None => rsx! { {format!("[{}]", reference)} },
+                };
+                elements.push(rsx! { sup { class: "markdown-footnote-ref", {footnote} } });
                 i += 1;
             },
             Event::SoftBreak => {
@@ -754,6 +1137,56 @@ fn render_markdown_events<'a>(events: Vec<Event<'a>>, image_base_path: Option<St
     elements.into_iter()
 }
 
+/// Index of the end tag closing the raw HTML start tag at `start`, among the same
+/// markdown block's events
+fn matching_html_end(events: &[Event], start: usize, name: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, event) in events.iter().enumerate().skip(start) {
+        let Event::Html(html) = event else { continue };
+        match HtmlToken::parse(html) {
+            HtmlToken::Start { name: tag, self_closing: false, .. } if tag == name => depth += 1,
+            HtmlToken::End { name: tag } if tag == name => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            },
+            _ => {},
+        }
+    }
+    None
+}
+
+/// A raw HTML tag that passed the policy, as the element it names
+fn html_element(tag: &SanitizedTag, children: Element) -> Element {
+    let attributes: Vec<Attribute> = tag
+        .attributes
+        .iter()
+        .map(|(name, value)| match *name {
+            // Present means on, whatever the value; Dioxus reads an empty one as off
+            "open" | "reversed" => Attribute::new(name, true, None, false),
+            _ => Attribute::new(name, value.clone(), None, false),
+        })
+        .collect();
+
+    macro_rules! elements {
+        (void: $($void:ident)*; $($name:ident)*) => {
+            match tag.name {
+                $(stringify!($void) => rsx! { $void { ..attributes } },)*
+                $(stringify!($name) => rsx! { $name { ..attributes, {children} } },)*
+                _ => children,
+            }
+        };
+    }
+
+    elements!(
+        void: br hr img wbr;
+        a abbr b blockquote caption cite code dd del details dfn div dl dt em figcaption figure
+        h1 h2 h3 h4 h5 h6 i ins kbd li mark ol p pre q s samp small span strong sub summary sup
+        table tbody td tfoot th thead time tr u ul var
+    )
+}
+
 /// Helper function to collect events until a matching end tag, returning the collected events and the new index
 fn collect_until_end_with_index<'a>(events: &[Event<'a>], start_index: usize, start_tag: Tag<'a>) -> (Vec<Event<'a>>, usize) {
     let mut collected = Vec::new();
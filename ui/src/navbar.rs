@@ -1,7 +1,7 @@
 use dioxus::prelude::*;
-use crate::views::{Home,Blog};
-use crate::extensions::{PostView, PageView, LoginPage, RegisterPage, EmailVerificationPage};
-use api::is_first_user;
+use crate::views::{Home,Blog,Search};
+use crate::t;
+use crate::extensions::{PostView, PageView, LoginPage, RegisterPage, EmailVerificationPage, ThemeManager, ThemeRevision, PostManager, PostEditor, SeoManager, HtmlSettingsManager, RedirectManager, AnalyticsDashboard, SchedulingManager, SchedulingCalendar, AnalyticsBeacon, I18nProvider, LanguageManager, LanguageSelector, TranslationOverview, ThemeSelector, ThemeStylesheet, SiteStructuredData};
 
 #[derive(Debug, Clone, Routable, PartialEq)]
 #[rustfmt::skip]
@@ -15,6 +15,8 @@ pub enum Route {
     PostRoute { slug: String },
     #[route("/page/:slug")]
     PageRoute { slug: String },
+    #[route("/search?:q")]
+    Search { q: String },
     #[route("/login")]
     LoginRoute {},
     #[route("/register")]
@@ -23,6 +25,78 @@ pub enum Route {
     VerifyEmailRoute {},
     #[route("/admin")]
     AdminRoute {},
+    #[route("/admin/themes")]
+    AdminThemesRoute {},
+    #[route("/admin/posts")]
+    AdminPostsRoute {},
+    #[route("/admin/posts/new")]
+    AdminNewPostRoute {},
+    #[route("/admin/posts/:id/edit")]
+    AdminEditPostRoute { id: u32 },
+    #[route("/admin/seo")]
+    AdminSeoRoute {},
+    #[route("/admin/html")]
+    AdminHtmlRoute {},
+    #[route("/admin/redirects")]
+    AdminRedirectsRoute {},
+    #[route("/admin/analytics")]
+    AdminAnalyticsRoute {},
+    #[route("/admin/scheduling")]
+    AdminSchedulingRoute {},
+    #[route("/admin/calendar")]
+    AdminCalendarRoute {},
+    #[route("/admin/i18n")]
+    AdminI18nRoute {},
+    #[route("/admin/translations")]
+    AdminTranslationsRoute {},
+    #[route("/:lang/post/:slug")]
+    LocalizedPostRoute { lang: String, slug: String },
+    #[route("/:lang/page/:slug")]
+    LocalizedPageRoute { lang: String, slug: String },
+}
+
+impl Route {
+    /// The route's path pattern, e.g. `/post/:slug`, for grouping per-route metrics
+    pub fn pattern(&self) -> &'static str {
+        match self {
+            Route::Home {} => "/",
+            Route::Blog { .. } => "/blog/:id",
+            Route::PostRoute { .. } => "/post/:slug",
+            Route::PageRoute { .. } => "/page/:slug",
+            Route::Search { .. } => "/search",
+            Route::LoginRoute {} => "/login",
+            Route::RegisterRoute {} => "/register",
+            Route::VerifyEmailRoute {} => "/verify-email",
+            Route::AdminRoute {} => "/admin",
+            Route::AdminThemesRoute {} => "/admin/themes",
+            Route::AdminPostsRoute {} => "/admin/posts",
+            Route::AdminNewPostRoute {} => "/admin/posts/new",
+            Route::AdminEditPostRoute { .. } => "/admin/posts/:id/edit",
+            Route::AdminSeoRoute {} => "/admin/seo",
+            Route::AdminHtmlRoute {} => "/admin/html",
+            Route::AdminRedirectsRoute {} => "/admin/redirects",
+            Route::AdminAnalyticsRoute {} => "/admin/analytics",
+            Route::AdminSchedulingRoute {} => "/admin/scheduling",
+            Route::AdminCalendarRoute {} => "/admin/calendar",
+            Route::AdminI18nRoute {} => "/admin/i18n",
+            Route::AdminTranslationsRoute {} => "/admin/translations",
+            Route::LocalizedPostRoute { .. } => "/:lang/post/:slug",
+            Route::LocalizedPageRoute { .. } => "/:lang/page/:slug",
+        }
+    }
+
+    /// Language named by the URL prefix of a localized route
+    pub fn language(&self) -> Option<String> {
+        match self {
+            Route::LocalizedPostRoute { lang, .. } | Route::LocalizedPageRoute { lang, .. } => Some(lang.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Route pattern of a request path; paths no route matches are grouped as `(unmatched)`
+pub fn route_pattern(path: &str) -> &'static str {
+    path.parse::<Route>().map(|route| route.pattern()).unwrap_or("(unmatched)")
 }
 
 // Route components
@@ -36,6 +110,19 @@ fn PageRoute(slug: String) -> Element {
     rsx! { PageView { slug } }
 }
 
+// Posts and pages not in the default language. Slugs are unique across languages, and the
+// server answers a prefix that doesn't match the content's language with a 301 to its
+// canonical URL, so the slug alone finds the content.
+#[component]
+fn LocalizedPostRoute(lang: String, slug: String) -> Element {
+    rsx! { PostView { slug } }
+}
+
+#[component]
+fn LocalizedPageRoute(lang: String, slug: String) -> Element {
+    rsx! { PageView { slug } }
+}
+
 #[component]
 fn LoginRoute() -> Element {
     rsx! { LoginPage {} }
@@ -54,7 +141,7 @@ fn VerifyEmailRoute() -> Element {
 #[component]
 fn AdminRoute() -> Element {
     // Check if user is authenticated and has admin privileges
-    let auth_state = use_signal(|| None::<client::Session>);
+    let _auth_state = use_signal(|| None::<client::Session>);
     
     use_effect(move || {
         spawn(async move {
@@ -86,9 +173,90 @@ fn AdminRoute() -> Element {
     }
 }
 
-/// Shared navbar component.
+#[component]
+fn AdminThemesRoute() -> Element {
+    rsx! { ThemeManager {} }
+}
+
+#[component]
+fn AdminPostsRoute() -> Element {
+    rsx! { PostManager {} }
+}
+
+#[component]
+fn AdminNewPostRoute() -> Element {
+    rsx! { PostEditor { id: None } }
+}
+
+#[component]
+fn AdminEditPostRoute(id: u32) -> Element {
+    rsx! { PostEditor { id: Some(id) } }
+}
+
+#[component]
+fn AdminSeoRoute() -> Element {
+    rsx! { SeoManager {} }
+}
+
+#[component]
+fn AdminHtmlRoute() -> Element {
+    rsx! { HtmlSettingsManager {} }
+}
+
+#[component]
+fn AdminRedirectsRoute() -> Element {
+    rsx! { RedirectManager {} }
+}
+
+#[component]
+fn AdminAnalyticsRoute() -> Element {
+    rsx! { AnalyticsDashboard {} }
+}
+
+#[component]
+fn AdminSchedulingRoute() -> Element {
+    rsx! { SchedulingManager {} }
+}
+
+#[component]
+fn AdminCalendarRoute() -> Element {
+    rsx! {
+        div {
+            class: "calendar-page",
+            h2 { "Content Calendar" }
+            SchedulingCalendar {}
+        }
+    }
+}
+
+#[component]
+fn AdminI18nRoute() -> Element {
+    rsx! { LanguageManager {} }
+}
+
+#[component]
+fn AdminTranslationsRoute() -> Element {
+    rsx! { TranslationOverview {} }
+}
+
+/// Layout shared by every route: the navbar above the page, in the page's language.
 #[component]
 pub fn Navbar() -> Element {
+    let route = use_route::<Route>();
+
+    rsx! {
+        // Translated messages for everything below, in the language of the URL prefix if
+        // the page has one
+        I18nProvider {
+            url_language: route.language(),
+            NavigationBar {}
+            Outlet::<Route> {}
+        }
+    }
+}
+
+#[component]
+fn NavigationBar() -> Element {
     rsx! {
         div {
             id: "navbar",
@@ -104,39 +272,39 @@ pub fn Navbar() -> Element {
                 class: "nav-links",
                 Link {
                     to: Route::Home {},
-                    "Home"
+                    {t!("home")}
                 }
                 Link {
                     to: Route::Blog { id: 0},
-                    "Blog"
+                    {t!("blog")}
                 }
                 Link {
                     to: Route::PageRoute { slug: "about".to_string() },
-                    "About"
+                    {t!("about")}
                 }
                 Link {
                     to: Route::PageRoute { slug: "contact".to_string() },
-                    "Contact"
+                    {t!("contact")}
                 }
             }
             div {
                 class: "nav-auth",
+                LanguageSelector {}
+                ThemeSelector {}
                 Link {
                     to: Route::LoginRoute {},
-                    "Login"
+                    {t!("login")}
                 }
                 Link {
                     to: Route::RegisterRoute {},
-                    "Register"
+                    {t!("register")}
                 }
                 Link {
                     to: Route::AdminRoute {},
-                    "Admin"
+                    {t!("admin")}
                 }
             }
         }
-
-        Outlet::<Route> {}
     }
 }
 
@@ -145,12 +313,19 @@ pub fn Navbar() -> Element {
 #[component]
 pub fn App() -> Element {
     // Build cool things 
+    use_context_provider(|| ThemeRevision(Signal::new(0)));
 
     rsx! {
         // Global app resources
         document::Link { rel: "icon", href: "/assets/favicon.ico" }
         document::Link { rel: "stylesheet", href: "/assets/main.css" }
         document::Link { rel: "stylesheet", href: "/assets/components.css" }
+        // Active theme goes last so it overrides the base styles
+        ThemeStylesheet {}
+        // Organization and WebSite structured data for every page
+        SiteStructuredData {}
+        // Cookieless page view counting
+        AnalyticsBeacon {}
 
         Router::<Route> {}
     }
@@ -0,0 +1,424 @@
+//! Shortcodes: embeds plain markdown can't express, written as
+//! `{{< gallery folder="trip" >}}`, or around markdown as
+//! `{{< callout type="warning" >}} ... {{< /callout >}}`.
+//!
+//! They are swapped for placeholder tags before markdown is parsed, so they can sit in a
+//! paragraph or on a line of their own, and rendered by the handler registered under
+//! their name. `{{</* gallery */>}}` writes the shortcode itself instead.
+
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+use dioxus::prelude::*;
+use client::{HtmlToken, MediaFile};
+use crate::extensions::I18n;
+
+/// A shortcode as written in markdown
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shortcode {
+    /// Lowercase, e.g. `gallery`
+    pub name: String,
+    /// `key="value"` pairs in order, values decoded
+    pub attributes: Vec<(String, String)>,
+    /// Written around content with a closing `{{< /name >}}`
+    pub paired: bool,
+}
+
+impl Shortcode {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.attributes.iter().find(|(name, _)| name == key).map(|(_, value)| value.as_str())
+    }
+
+    fn require(&self, key: &str) -> Result<&str, String> {
+        self.get(key).filter(|value| !value.trim().is_empty()).ok_or_else(|| format!("{} is required", key))
+    }
+}
+
+/// Renders a shortcode, given what it wraps (empty unless [`Shortcode::paired`]). An `Err`
+/// says what's wrong with how it was written, which editors see in previews.
+pub type ShortcodeHandler = fn(&Shortcode, Element) -> Result<Element, String>;
+
+fn registry() -> &'static RwLock<HashMap<String, ShortcodeHandler>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, ShortcodeHandler>>> = OnceLock::new();
+
+    REGISTRY.get_or_init(|| {
+        let built_in: [(&str, ShortcodeHandler); 5] = [
+            ("callout", callout),
+            ("gallery", gallery),
+            ("recent-posts", recent_posts),
+            ("video", video),
+            ("gist", gist),
+        ];
+        RwLock::new(built_in.into_iter().map(|(name, handler)| (name.to_string(), handler)).collect())
+    })
+}
+
+/// Add a shortcode, or replace the one with that name
+pub fn register_shortcode(name: &str, handler: ShortcodeHandler) {
+    registry().write().unwrap_or_else(|e| e.into_inner()).insert(name.to_ascii_lowercase(), handler);
+}
+
+/// Names of the registered shortcodes, sorted
+pub fn shortcode_names() -> Vec<String> {
+    let mut names: Vec<String> = registry().read().unwrap_or_else(|e| e.into_inner()).keys().cloned().collect();
+    names.sort();
+    names
+}
+
+fn shortcode_handler(name: &str) -> Option<ShortcodeHandler> {
+    registry().read().unwrap_or_else(|e| e.into_inner()).get(name).copied()
+}
+
+/// Prefix of the placeholder tags shortcodes are swapped for, followed by their index
+const PLACEHOLDER: &str = "x-shortcode-";
+
+/// The shortcode a placeholder tag stands for
+pub(crate) fn placeholder_index(tag: &str) -> Option<usize> {
+    tag.strip_prefix(PLACEHOLDER)?.parse().ok()
+}
+
+/// A shortcode through its handler. When it can't be rendered, previews say why and
+/// published pages leave it out.
+pub(crate) fn render_shortcode(shortcode: &Shortcode, children: Element, preview: bool) -> Element {
+    let result = match shortcode_handler(&shortcode.name) {
+        Some(handler) => handler(shortcode, children),
+        None => Err("unknown shortcode".to_string()),
+    };
+
+    match result {
+        Ok(element) => element,
+        Err(message) if preview => {
+            let message = format!("{{{{< {} >}}}}: {}", shortcode.name, message);
+            rsx! { span { class: "shortcode-error", role: "alert", "{message}" } }
+        },
+        Err(_) => rsx! {},
+    }
+}
+
+enum Piece {
+    Text(String),
+    Open { shortcode: Shortcode, self_closing: bool, alone: bool },
+    Close { name: String, alone: bool },
+}
+
+/// `markdown` with its shortcodes swapped for placeholder tags, and the shortcodes by
+/// placeholder index. Shortcodes in code are left as they are.
+pub(crate) fn expand_shortcodes(markdown: &str) -> (String, Vec<Shortcode>) {
+    if !markdown.contains("{{<") {
+        return (markdown.to_string(), Vec::new());
+    }
+
+    let mut pieces = Vec::new();
+    let mut fence: Option<&str> = None;
+    for line in markdown.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            pieces.push(Piece::Text(line.to_string()));
+            continue;
+        }
+        if let Some(marker) = ["```", "~~~"].into_iter().find(|marker| trimmed.starts_with(marker)) {
+            fence = Some(marker);
+            pieces.push(Piece::Text(line.to_string()));
+            continue;
+        }
+        split_line(line, &mut pieces);
+    }
+
+    // Opening shortcodes with a matching close wrap what's between them
+    let mut closes: HashMap<usize, usize> = HashMap::new();
+    for (open, piece) in pieces.iter().enumerate() {
+        let Piece::Open { shortcode, self_closing: false, .. } = piece else { continue };
+        let mut depth = 0;
+        for (index, piece) in pieces.iter().enumerate().skip(open + 1) {
+            match piece {
+                Piece::Open { shortcode: inner, self_closing: false, .. } if inner.name == shortcode.name => depth += 1,
+                Piece::Close { name, .. } if *name == shortcode.name && depth > 0 => depth -= 1,
+                Piece::Close { name, .. } if *name == shortcode.name => {
+                    if !closes.values().any(|matched| *matched == index) {
+                        closes.insert(open, index);
+                    }
+                    break;
+                },
+                _ => {},
+            }
+        }
+    }
+
+    let mut expanded = String::with_capacity(markdown.len());
+    let mut shortcodes = Vec::new();
+    let mut open_indices: HashMap<usize, usize> = HashMap::new();
+    // A shortcode on a line of its own stands apart as a block, so markdown inside a
+    // paired one is still parsed
+    let push_tag = |expanded: &mut String, tag: String, alone: bool| match alone {
+        true => expanded.push_str(&format!("\n{}\n\n", tag)),
+        false => expanded.push_str(&tag),
+    };
+    for (index, piece) in pieces.into_iter().enumerate() {
+        match piece {
+            Piece::Text(text) => expanded.push_str(&text),
+            Piece::Open { mut shortcode, alone, .. } => {
+                let n = shortcodes.len();
+                shortcode.paired = closes.contains_key(&index);
+                let tag = match shortcode.paired {
+                    true => format!("<{}{}>", PLACEHOLDER, n),
+                    false => format!("<{}{} />", PLACEHOLDER, n),
+                };
+                if let Some(close) = closes.get(&index) {
+                    open_indices.insert(*close, n);
+                }
+                shortcodes.push(shortcode);
+                push_tag(&mut expanded, tag, alone);
+            },
+            // Closes without an opening shortcode are dropped
+            Piece::Close { alone, .. } => {
+                if let Some(n) = open_indices.get(&index) {
+                    push_tag(&mut expanded, format!("</{}{}>", PLACEHOLDER, n), alone);
+                }
+            },
+        }
+    }
+
+    (expanded, shortcodes)
+}
+
+/// Cut a line into text and shortcodes, skipping inline code
+fn split_line(line: &str, pieces: &mut Vec<Piece>) {
+    let mut text = String::new();
+    let mut rest = line;
+    while !rest.is_empty() {
+        if rest.starts_with('`') {
+            let ticks = rest.len() - rest.trim_start_matches('`').len();
+            let code = &rest[..ticks];
+            let close = rest[ticks..].find(code).map(|end| ticks + end + ticks);
+            let end = close.unwrap_or(ticks);
+            text.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+
+        if rest.starts_with("{{<") {
+            if let Some(end) = rest.find(">}}") {
+                let written = &rest[..end + 3];
+                let inner = rest[3..end].trim();
+                let alone = line.trim() == written;
+                if let Some(escaped) = inner.strip_prefix("/*").and_then(|inner| inner.strip_suffix("*/")) {
+                    text.push_str(&format!("{{{{< {} >}}}}", escaped.trim()));
+                } else if let Some(piece) = parse_shortcode(inner, alone) {
+                    if !text.is_empty() {
+                        pieces.push(Piece::Text(std::mem::take(&mut text)));
+                    }
+                    pieces.push(piece);
+                } else {
+                    text.push_str(written);
+                }
+                rest = &rest[end + 3..];
+                // The line break after a standalone shortcode is part of its block
+                if alone {
+                    rest = rest.trim_start_matches(['\r', '\n']);
+                }
+                continue;
+            }
+        }
+
+        let next = rest.char_indices().skip(1).find(|(_, c)| *c == '`' || *c == '{').map_or(rest.len(), |(i, _)| i);
+        text.push_str(&rest[..next]);
+        rest = &rest[next..];
+    }
+    if !text.is_empty() {
+        pieces.push(Piece::Text(text));
+    }
+}
+
+/// What's between `{{<` and `>}}`, read like an HTML tag
+fn parse_shortcode(inner: &str, alone: bool) -> Option<Piece> {
+    if let Some(name) = inner.strip_prefix('/') {
+        return Some(Piece::Close { name: name.trim().to_ascii_lowercase(), alone });
+    }
+    match HtmlToken::parse(&format!("<{}>", inner)) {
+        HtmlToken::Start { name, attributes, self_closing } => Some(Piece::Open {
+            shortcode: Shortcode { name, attributes, paired: false },
+            self_closing,
+            alone,
+        }),
+        _ => None,
+    }
+}
+
+const CALLOUT_TYPES: &[&str] = &["note", "tip", "warning", "danger"];
+
+/// `{{< callout type="warning" title="Careful" >}} ... {{< /callout >}}`
+fn callout(shortcode: &Shortcode, children: Element) -> Result<Element, String> {
+    let kind = shortcode.get("type").unwrap_or("note");
+    if !CALLOUT_TYPES.contains(&kind) {
+        return Err(format!("type must be one of {}, not \"{}\"", CALLOUT_TYPES.join(", "), kind));
+    }
+    let class = format!("shortcode-callout shortcode-callout-{}", kind);
+    let title = shortcode.get("title").map(str::to_string);
+
+    Ok(rsx! {
+        aside {
+            class: class,
+            role: "note",
+            if let Some(title) = title {
+                p { class: "shortcode-callout-title", "{title}" }
+            }
+            {children}
+        }
+    })
+}
+
+/// `{{< gallery folder="trip" columns="3" >}}` for the images uploaded from a folder, or
+/// `ids="4,2,9"` for chosen ones in that order
+fn gallery(shortcode: &Shortcode, _: Element) -> Result<Element, String> {
+    let folder = shortcode.get("folder").map(|folder| folder.trim_matches('/').to_string());
+    let ids = match shortcode.get("ids") {
+        Some(ids) => ids
+            .split(',')
+            .map(|id| id.trim().parse::<u32>().map_err(|_| format!("\"{}\" in ids is not a media id", id.trim())))
+            .collect::<Result<Vec<_>, _>>()?,
+        None => Vec::new(),
+    };
+    if folder.is_none() && ids.is_empty() {
+        return Err("folder or ids is required".to_string());
+    }
+    let columns = match shortcode.get("columns") {
+        Some(columns) => columns.parse::<u8>().ok().filter(|columns| (1..=6).contains(columns))
+            .ok_or_else(|| format!("columns must be 1 to 6, not \"{}\"", columns))?,
+        None => 3,
+    };
+
+    Ok(rsx! { ShortcodeGallery { folder, ids, columns } })
+}
+
+#[component]
+fn ShortcodeGallery(folder: Option<String>, ids: Vec<u32>, columns: u8) -> Element {
+    let images = use_server_future(move || gallery_images(folder.clone(), ids.clone()))?;
+    let images = images.read().as_ref().and_then(|result| result.as_ref().ok().cloned()).unwrap_or_default();
+
+    rsx! {
+        div {
+            class: "shortcode-gallery",
+            style: "--gallery-columns: {columns}",
+            for (image, url) in images {
+                figure {
+                    key: "{image.id}",
+                    img {
+                        src: "{url}",
+                        alt: image.alt_text.clone().unwrap_or_default(),
+                        loading: "lazy",
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Images from the media library with the URLs to load them from
+async fn gallery_images(folder: Option<String>, ids: Vec<u32>) -> Result<Vec<(MediaFile, String)>, ServerFnError> {
+    let mut images = Vec::new();
+    for file in api::get_media_files().await? {
+        let included = match &folder {
+            Some(folder) => file.original_name.starts_with(&format!("{}/", folder)),
+            None => ids.contains(&file.id),
+        };
+        if included && file.mime_type.starts_with("image/") {
+            if let Some(url) = api::get_media_url(file.id).await? {
+                images.push((file, url));
+            }
+        }
+    }
+    if folder.is_none() {
+        images.sort_by_key(|(file, _)| ids.iter().position(|id| *id == file.id));
+    }
+    Ok(images)
+}
+
+/// `{{< recent-posts count="5" >}}`
+fn recent_posts(shortcode: &Shortcode, _: Element) -> Result<Element, String> {
+    let count = match shortcode.get("count") {
+        Some(count) => count.parse::<usize>().ok().filter(|count| (1..=20).contains(count))
+            .ok_or_else(|| format!("count must be 1 to 20, not \"{}\"", count))?,
+        None => 5,
+    };
+
+    Ok(rsx! { ShortcodeRecentPosts { count } })
+}
+
+#[component]
+fn ShortcodeRecentPosts(count: usize) -> Element {
+    let posts = use_server_future(api::get_posts)?;
+    let default_language = try_use_context::<I18n>().map_or_else(|| "en".to_string(), |i18n| i18n.default_language());
+    let mut posts = posts.read().as_ref().and_then(|result| result.as_ref().ok().cloned()).unwrap_or_default();
+    posts.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    posts.truncate(count);
+
+    rsx! {
+        ul {
+            class: "shortcode-recent-posts",
+            for post in posts {
+                li {
+                    key: "{post.id}",
+                    a { href: post.path(&default_language), "{post.title}" }
+                }
+            }
+        }
+    }
+}
+
+/// `{{< video youtube="dQw4w9WgXcQ" >}}`, `vimeo="76979871"` or `src="/media/clip.mp4"`,
+/// with an optional `title`
+fn video(shortcode: &Shortcode, _: Element) -> Result<Element, String> {
+    let title = shortcode.get("title").unwrap_or("Video").to_string();
+    let embed = if let Some(id) = shortcode.get("youtube") {
+        format!("https://www.youtube-nocookie.com/embed/{}", embed_id("youtube", id)?)
+    } else if let Some(id) = shortcode.get("vimeo") {
+        format!("https://player.vimeo.com/video/{}", embed_id("vimeo", id)?)
+    } else if let Some(src) = shortcode.get("src") {
+        if !client::is_safe_url(src) {
+            return Err(format!("\"{}\" is not a link that can be embedded", src));
+        }
+        let src = src.to_string();
+        return Ok(rsx! { video { class: "shortcode-video", src: src, title: title, controls: true, preload: "metadata" } });
+    } else {
+        return Err("youtube, vimeo or src is required".to_string());
+    };
+
+    Ok(rsx! {
+        div {
+            class: "shortcode-embed",
+            iframe {
+                src: embed,
+                title: title,
+                allow: "encrypted-media; picture-in-picture; fullscreen",
+                allowfullscreen: true,
+            }
+        }
+    })
+}
+
+/// `{{< gist user="octocat" id="6cad326836d38bd3a7ae" >}}`
+fn gist(shortcode: &Shortcode, _: Element) -> Result<Element, String> {
+    let user = embed_id("user", shortcode.require("user")?)?;
+    let id = embed_id("id", shortcode.require("id")?)?;
+    let url = format!("https://gist.github.com/{}/{}", user, id);
+    // GitHub serves gists as a page of their own for embedding, where scripts can't run here
+    let embed = format!("{}.pibb", url);
+
+    Ok(rsx! {
+        figure {
+            class: "shortcode-gist",
+            iframe { src: embed, title: "Gist {id} by {user}" }
+            figcaption { a { href: url, "View on GitHub" } }
+        }
+    })
+}
+
+/// An id that's safe to put in an embed URL
+fn embed_id<'a>(key: &str, id: &'a str) -> Result<&'a str, String> {
+    match !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        true => Ok(id),
+        false => Err(format!("\"{}\" is not a valid {}", id, key)),
+    }
+}
@@ -0,0 +1,68 @@
+use dioxus::prelude::*;
+use crate::extensions::{excerpt, MetaTags, SeoMeta};
+use crate::t;
+
+#[component]
+pub fn Search(q: String) -> Element {
+    let query = q.clone();
+    let results = use_server_future(move || api::search_posts(query.clone()))?;
+    let settings = use_server_future(api::get_seo_settings)?;
+    let settings = settings.read().as_ref().and_then(|result| result.as_ref().ok().cloned()).unwrap_or_default();
+
+    // Result pages shouldn't be indexed themselves
+    let meta = SeoMeta {
+        title: format!("Search: {} | {}", q, settings.site_title),
+        robots: "noindex,follow".to_string(),
+        ..SeoMeta::from_settings(&settings, "/search")
+    };
+
+    rsx! {
+        MetaTags { meta }
+
+        div {
+            class: "search-page",
+            h1 { {t!("search")} }
+
+            form {
+                action: "/search",
+                method: "get",
+                input {
+                    r#type: "search",
+                    name: "q",
+                    value: "{q}",
+                    placeholder: t!("search:placeholder")
+                }
+                button { r#type: "submit", {t!("search")} }
+            }
+
+            match results.read().as_ref() {
+                Some(Ok(posts)) if posts.is_empty() && !q.trim().is_empty() => rsx! {
+                    p { {t!("search:no-results", query = &q)} }
+                },
+                Some(Ok(posts)) => rsx! {
+                    if !q.trim().is_empty() {
+                        p { class: "search-summary", {t!("search:result-count", count = posts.len(), query = &q)} }
+                    }
+                    div {
+                        class: "post-list",
+                        for post in posts.iter() {
+                            div {
+                                key: "{post.id}",
+                                class: "post-item",
+                                h3 {
+                                    Link {
+                                        to: post.path(&settings.language),
+                                        "{post.title}"
+                                    }
+                                }
+                                p { {excerpt(&post.content, 200).unwrap_or_default()} }
+                            }
+                        }
+                    }
+                },
+                Some(Err(e)) => rsx! { p { class: "error-message", "Search failed: {e}" } },
+                None => rsx! { p { {t!("search:searching")} } },
+            }
+        }
+    }
+}
@@ -0,0 +1,18 @@
+//! Helpers shared by the UI tests.
+
+use std::path::PathBuf;
+
+/// Compare `actual` with the snapshot `file` in `tests/snapshots`, or write it when
+/// `UPDATE_SNAPSHOTS` is set
+pub fn assert_snapshot(file: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots").join(file);
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, format!("{}\n", actual)).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("missing snapshot {}; run with UPDATE_SNAPSHOTS=1", path.display()));
+    assert_eq!(expected.trim_end(), actual, "snapshot {} changed", file);
+}
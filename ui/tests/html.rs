@@ -0,0 +1,76 @@
+//! Raw HTML in markdown, kept only as far as the allow-list goes.
+
+use client::HtmlPolicy;
+use dioxus::prelude::*;
+use ui::Markdown;
+
+fn render(content: &'static str, policy: HtmlPolicy) -> String {
+    #[component]
+    fn Document(content: &'static str, policy: HtmlPolicy) -> Element {
+        rsx! { Markdown { content: Some(content.to_string()), html_policy: Some(policy), id: Some("doc".to_string()) } }
+    }
+
+    dioxus_ssr::render_element(rsx! { Document { content, policy } })
+}
+
+#[test]
+fn allowed_tags_become_elements() {
+    let html = render(
+        "Press <kbd title=\"keys\">Ctrl</kbd>+<kbd>C</kbd>.\n\n<details open>\n<summary>More</summary>\n\nHidden **text**\n\n</details>\n",
+        HtmlPolicy::posts(),
+    );
+    assert!(html.contains(r#"<kbd title="keys"><span>Ctrl</span></kbd>"#), "{}", html);
+    assert!(html.contains("<details open=true>"), "{}", html);
+    assert!(html.contains("<summary><span>More</span></summary>"), "{}", html);
+    assert!(html.contains(r#"<strong class="markdown-strong"><span>text</span></strong></p></details>"#), "{}", html);
+}
+
+#[test]
+fn scripts_handlers_and_javascript_urls_are_removed() {
+    let html = render(
+        "<script>alert(1)</script>\n\n<p onclick=\"alert(2)\" style=\"color:red\">Hi <a href=\" jav&#x61;script:alert(3)\">there</a> <img src=x onerror=alert(4)></p>\n\n[link](javascript:alert(5))\n",
+        HtmlPolicy::posts(),
+    );
+    for bad in ["alert", "onclick", "onerror", "style", "<script", "javascript"] {
+        assert!(!html.contains(bad), "{} in {}", bad, html);
+    }
+    assert!(html.contains(r#"<p><span>Hi </span><a><span>there</span></a><span> </span><img src="x"/></p>"#), "{}", html);
+}
+
+#[test]
+fn unclosed_dropped_tags_only_take_their_own_html() {
+    let html = render(
+        "<iframe src=\"https://example.com\">\n\nStill **here**.\n\n<svg><circle r=\"1\">\n\nA <textarea>note and the end.\n",
+        HtmlPolicy::posts(),
+    );
+    for bad in ["iframe", "svg", "circle", "textarea"] {
+        assert!(!html.contains(bad), "{} in {}", bad, html);
+    }
+    assert!(html.contains(r#"<span>Still </span><strong class="markdown-strong"><span>here</span></strong>"#), "{}", html);
+    assert!(html.contains("<span>note and the end.</span>"), "{}", html);
+}
+
+#[test]
+fn comments_are_stricter_than_posts() {
+    let content = "<div class=\"box\"><a href=\"https://example.com\">site</a> <img src=\"/cat.png\"></div>";
+    let post = render(content, HtmlPolicy::posts());
+    assert!(post.contains(r#"<div class="box">"#), "{}", post);
+    assert!(post.contains("<img"), "{}", post);
+
+    let comment = render(content, HtmlPolicy::comments());
+    assert!(!comment.contains("<div class=\"box\">") && !comment.contains("<img"), "{}", comment);
+    assert!(comment.contains(r#"<a href="https://example.com" rel="nofollow ugc noopener"><span>site</span></a>"#), "{}", comment);
+}
+
+#[test]
+fn policies_only_allow_known_tags() {
+    let mut policy = HtmlPolicy::comments();
+    assert_eq!(policy.validate(), Ok(()));
+    policy.tags.push("script".to_string());
+    assert!(policy.validate().is_err());
+
+    assert!(client::is_safe_url("/post/hello"));
+    assert!(client::is_safe_url("https://example.com/a:b"));
+    assert!(!client::is_safe_url("java\tscript:alert(1)"));
+    assert!(!client::is_safe_url("data:text/html,hi"));
+}
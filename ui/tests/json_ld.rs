@@ -0,0 +1,97 @@
+//! Snapshot tests for the JSON-LD structured data emitted by the SEO extension.
+//!
+//! Snapshots live in `tests/snapshots`. After an intentional change, regenerate them with
+//! `UPDATE_SNAPSHOTS=1 cargo test -p ui --test json_ld` and review the diff.
+
+mod common;
+
+use client::{Post, SeoSettings};
+use common::assert_snapshot;
+use ui::extensions::{
+    blog_posting_json_ld, breadcrumb_json_ld, json_ld_script, route_breadcrumbs, site_json_ld, web_page_json_ld, Page,
+};
+
+fn settings() -> SeoSettings {
+    SeoSettings {
+        site_url: "https://example.com/".to_string(),
+        site_title: "Example & Co".to_string(),
+        site_description: "Notes on <Rust> and the web".to_string(),
+        default_image: Some("/uploads/logo.png".to_string()),
+        ..SeoSettings::default()
+    }
+}
+
+fn post() -> Post {
+    Post {
+        id: 7,
+        slug: "closing-tags".to_string(),
+        title: "Why </script> needs escaping".to_string(),
+        content: "Inline JSON can end a script element early.\n\nThis post explains how.".to_string(),
+        author: "Ada \"Countess\" Lovelace".to_string(),
+        created_at: "2024-03-01T09:30:00Z".to_string(),
+        updated_at: "2024-03-05T17:00:00Z".to_string(),
+        published: true,
+        meta_keywords: Some("json-ld, seo".to_string()),
+        cover_image: Some("/uploads/cover.png".to_string()),
+        ..Default::default()
+    }
+}
+
+fn page() -> Page {
+    Page {
+        id: 1,
+        slug: "about".to_string(),
+        title: "About <us>\u{2028}".to_string(),
+        content: "We write software.\u{2028}Mostly Rust.".to_string(),
+        author: "Admin".to_string(),
+        created_at: "2024-01-01T00:00:00Z".to_string(),
+        updated_at: "2024-02-01T00:00:00Z".to_string(),
+        published: true,
+        template: "default".to_string(),
+        meta_description: None,
+        meta_keywords: None,
+        cover_image: None,
+        ..Default::default()
+    }
+}
+
+/// The script body must never contain markup and must still parse back to the same data
+fn assert_script_safe(data: &serde_json::Value) -> String {
+    let script = json_ld_script(data);
+    for forbidden in ['<', '>', '&', '\u{2028}', '\u{2029}'] {
+        assert!(!script.contains(forbidden), "unescaped {:?} in {}", forbidden, script);
+    }
+    assert_eq!(&serde_json::from_str::<serde_json::Value>(&script).unwrap(), data);
+    script
+}
+
+#[test]
+fn blog_posting() {
+    let data = blog_posting_json_ld(&post(), &settings());
+    assert_snapshot("blog_posting.json", &assert_script_safe(&data));
+}
+
+#[test]
+fn web_page() {
+    let data = web_page_json_ld(&page(), &settings());
+    assert_snapshot("web_page.json", &assert_script_safe(&data));
+}
+
+#[test]
+fn breadcrumbs() {
+    let post = post();
+    let data = breadcrumb_json_ld(&route_breadcrumbs(&post.title, &format!("/post/{}", post.slug)), &settings());
+    assert_snapshot("breadcrumbs.json", &assert_script_safe(&data));
+}
+
+#[test]
+fn home_page_breadcrumbs_have_a_single_item() {
+    let data = breadcrumb_json_ld(&route_breadcrumbs("Home", "/"), &settings());
+    assert_eq!(data["itemListElement"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn site() {
+    let data = site_json_ld(&settings());
+    assert_snapshot("site.json", &assert_script_safe(&data));
+}
@@ -0,0 +1,81 @@
+//! Heading ids, footnotes and the table of contents built from the same parse.
+
+use dioxus::prelude::*;
+use ui::{table_of_contents, Markdown, TableOfContents};
+
+const GUIDE: &str = "# Guide
+
+## Setup
+
+Install it first.[^install] Then again.[^install]
+
+### Setup
+
+## What's new in 2.0? {#new}
+
+See the notes.[^notes]
+
+## Setup
+
+[^notes]: Release notes.
+[^install]: Needs `cargo`.
+";
+
+fn render(content: &'static str) -> String {
+    fn markdown(content: &'static str) -> Element {
+        rsx! { Markdown { content: Some(content.to_string()), id: Some("doc".to_string()) } }
+    }
+
+    dioxus_ssr::render_element(markdown(content))
+}
+
+#[test]
+fn headings_get_unique_ids() {
+    let ids: Vec<_> = table_of_contents(GUIDE).into_iter().map(|entry| entry.id).collect();
+    assert_eq!(ids, ["guide", "setup", "setup-1", "new", "setup-2"]);
+
+    let html = render(GUIDE);
+    assert!(html.contains(r##"<h2 class="markdown-heading-2" id="setup-2" dir="auto">"##), "{}", html);
+    assert!(html.contains(r##"<a class="markdown-heading-anchor" href="#new" aria-label="Link to this section">#</a>"##), "{}", html);
+}
+
+#[test]
+fn footnotes_link_both_ways() {
+    let html = render(GUIDE);
+
+    // Numbered by first reference, whatever the order of the definitions
+    assert!(html.contains(r##"<a id="fnref-install" href="#fn-install" role="doc-noteref">1</a>"##), "{}", html);
+    assert!(html.contains(r##"<a id="fnref-install-2" href="#fn-install" role="doc-noteref">1</a>"##), "{}", html);
+    assert!(html.contains(r##"<a id="fnref-notes" href="#fn-notes" role="doc-noteref">2</a>"##), "{}", html);
+
+    let footnotes = &html[html.find(r#"class="markdown-footnote""#).expect("no footnotes section")..];
+    assert!(footnotes.find(r#"id="fn-install""#) < footnotes.find(r#"id="fn-notes""#), "{}", footnotes);
+    assert!(footnotes.contains(r##"href="#fnref-install""##) && footnotes.contains(r##"href="#fnref-install-2""##), "{}", footnotes);
+    // Definitions only show up at the end
+    assert_eq!(html.matches("Release notes.").count(), 1);
+}
+
+#[test]
+fn table_of_contents_nests_to_the_configured_depth() {
+    fn toc(depth: u8) -> Element {
+        rsx! { TableOfContents { content: GUIDE.to_string(), depth } }
+    }
+
+    let html = dioxus_ssr::render_element(toc(2));
+    // The `# Guide` title is left out and `### Setup` nests under the first `## Setup`
+    assert!(!html.contains("#guide"), "{}", html);
+    assert!(html.contains(r##"<a href="#setup" dir="auto">Setup</a><ol><li><a href="#setup-1" dir="auto">Setup</a>"##), "{}", html);
+    assert!(html.contains(r##"<a href="#new" dir="auto">What’s new in 2.0?</a>"##), "{}", html);
+
+    let html = dioxus_ssr::render_element(toc(1));
+    assert!(!html.contains("#setup-1"), "{}", html);
+}
+
+#[test]
+fn short_documents_get_no_table_of_contents() {
+    fn toc() -> Element {
+        rsx! { TableOfContents { content: "# Title\n\n## Only section\n".to_string() } }
+    }
+
+    assert_eq!(dioxus_ssr::render_element(toc()), "");
+}
@@ -0,0 +1,99 @@
+//! Redirect chains and loops, and redirects imported from CSV.
+
+use client::Redirect;
+use ui::extensions::{parse_redirects_csv, redirect_issues, RedirectIssue};
+
+fn redirect(source: &str, target: &str, status: u16) -> Redirect {
+    Redirect { source: source.to_string(), target: target.to_string(), status, ..Redirect::default() }
+}
+
+fn paths(paths: &[&str]) -> Vec<String> {
+    paths.iter().map(|path| path.to_string()).collect()
+}
+
+#[test]
+fn chains_are_reported_from_their_start() {
+    let redirects = [
+        redirect("/a", "/b", 301),
+        redirect("/b", "/c/", 302),
+        redirect("/c", "https://example.com/c", 301),
+        redirect("/direct", "/c", 301),
+    ];
+    let issues = redirect_issues(&redirects);
+
+    assert_eq!(
+        issues,
+        [
+            RedirectIssue::Chain(paths(&["/a", "/b", "/c", "https://example.com/c"])),
+            RedirectIssue::Chain(paths(&["/b", "/c", "https://example.com/c"])),
+            RedirectIssue::Chain(paths(&["/direct", "/c", "https://example.com/c"])),
+        ]
+    );
+    assert_eq!(
+        issues[1].describe(),
+        "Chain of 2 redirects: /b → /c → https://example.com/c. Point /b straight at https://example.com/c."
+    );
+}
+
+#[test]
+fn loops_are_reported_once() {
+    let redirects = [
+        redirect("/y", "/z", 301),
+        redirect("/x", "/y", 301),
+        redirect("/z", "/x?from=z", 301),
+        redirect("/into-loop", "/x", 301),
+        redirect("/self", "/self/", 301),
+    ];
+    let issues = redirect_issues(&redirects);
+
+    assert_eq!(
+        issues,
+        [
+            RedirectIssue::Loop(paths(&["/x", "/y", "/z", "/x"])),
+            RedirectIssue::Loop(paths(&["/self", "/self"])),
+        ]
+    );
+    assert_eq!(issues[0].describe(), "Redirect loop: /x → /y → /z → /x");
+}
+
+#[test]
+fn gone_redirects_end_a_path() {
+    let redirects = [redirect("/a", "/b", 301), redirect("/b", "", 410), redirect("/c", "/a", 301)];
+    assert_eq!(redirect_issues(&redirects), [RedirectIssue::Chain(paths(&["/c", "/a", "/b"]))]);
+}
+
+#[test]
+fn csv_rows_are_parsed_and_checked() {
+    let csv = "Source,Target,Status\n\
+               /old/,/new\n\
+               \n\
+               \"/with,comma\", \"/say \"\"hi\"\"\" ,302\n\
+               /gone,,410\n\
+               relative,/x\n\
+               /bad,/x,999\n\
+               /bad,/x,moved\n\
+               /self,/self/\n\
+               /empty\n";
+    let rows = parse_redirects_csv(csv);
+
+    let lines: Vec<usize> = rows.iter().map(|(line, _)| *line).collect();
+    assert_eq!(lines, [2, 4, 5, 6, 7, 8, 9, 10]);
+
+    let ok: Vec<(&str, &str, u16)> = rows
+        .iter()
+        .filter_map(|(_, row)| row.as_ref().ok())
+        .map(|redirect| (redirect.source.as_str(), redirect.target.as_str(), redirect.status))
+        .collect();
+    assert_eq!(ok, [("/old", "/new", 301), ("/with,comma", "/say \"hi\"", 302), ("/gone", "", 410), ("/relative", "/x", 301)]);
+
+    let errors: Vec<&str> = rows.iter().filter_map(|(_, row)| row.as_ref().err()).map(String::as_str).collect();
+    assert_eq!(
+        errors,
+        [
+            "Status 999 is not one of 301, 302 or 410",
+            "\"moved\" is not a status code",
+            "/self redirects to itself",
+            "Redirect from /empty has no target",
+        ]
+    );
+}
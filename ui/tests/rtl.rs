@@ -0,0 +1,118 @@
+//! Snapshot tests for right-to-left pages: an Arabic post renders with `dir="rtl"` while
+//! code stays left to right and mixed-direction blocks pick their own direction.
+//!
+//! Snapshots live in `tests/snapshots`. After an intentional change, regenerate them with
+//! `UPDATE_SNAPSHOTS=1 cargo test -p ui --test rtl` and review the diff.
+
+mod common;
+
+use client::{HtmlLocaleRewriter, Language, Post, RequestLocale, TextDirection};
+use common::assert_snapshot;
+use dioxus::prelude::*;
+use ui::extensions::{content_direction, PostBody, StaticI18nProvider};
+
+const ARABIC_POST: &str = "# مرحبا بالعالم
+
+هذه مقالة عن لغة Rust و `cargo build` في سطر واحد.
+
+This paragraph is written in English.
+
+- عنصر أول
+- second item
+
+```rust
+fn main() {
+    println!(\"مرحبا\");
+}
+```
+";
+
+fn languages() -> Vec<Language> {
+    [("en", TextDirection::LeftToRight), ("ar", TextDirection::RightToLeft)]
+        .into_iter()
+        .map(|(code, direction)| Language {
+            code: code.to_string(),
+            name: code.to_string(),
+            native_name: code.to_string(),
+            direction,
+            active: true,
+        })
+        .collect()
+}
+
+/// `PostView`'s body for a post written in `language`, on a site shown in Arabic
+fn render_post(language: &str) -> String {
+    #[component]
+    fn ArabicSite(post: Post) -> Element {
+        rsx! {
+            StaticI18nProvider {
+                language: "ar",
+                languages: languages(),
+                PostBody { slug: post.slug.clone(), post: Some(post) }
+            }
+        }
+    }
+
+    let post = Post {
+        slug: "rtl".to_string(),
+        title: "مرحبا بالعالم".to_string(),
+        content: ARABIC_POST.to_string(),
+        language: language.to_string(),
+        published: true,
+        ..Post::default()
+    };
+    dioxus_ssr::render_element(rsx! { ArabicSite { post } })
+}
+
+#[test]
+fn rtl_page() {
+    let html = render_post("ar");
+    assert!(html.starts_with(r#"<div id="post" class="markdown-container" lang="ar" dir="rtl">"#), "{}", html);
+    assert_snapshot("rtl_page.html", &html);
+}
+
+#[test]
+fn code_stays_left_to_right() {
+    let html = render_post("ar");
+    assert!(html.contains(r#"<div class="markdown-code-block language-rust no-scroll" dir="ltr">"#), "{}", html);
+    assert!(html.contains(r#"<code class="markdown-inline-code" dir="ltr">cargo build</code>"#), "{}", html);
+}
+
+#[test]
+fn blocks_follow_their_own_text() {
+    let html = render_post("ar");
+    assert_eq!(html.matches(r#"<p class="markdown-paragraph" dir="auto">"#).count(), 2);
+    assert!(html.contains(r#"<h1 class="markdown-heading-1" id="مرحبا-بالعالم" dir="auto">"#), "{}", html);
+}
+
+#[test]
+fn content_keeps_its_own_direction_on_an_rtl_site() {
+    let html = render_post("en");
+    assert!(html.starts_with(r#"<div id="post" class="markdown-container" lang="en" dir="ltr">"#), "{}", html);
+}
+
+#[test]
+fn unknown_content_language_sets_no_direction() {
+    assert_eq!(content_direction(""), (None, None));
+    let html = render_post("");
+    assert!(html.starts_with(r#"<div id="post" class="markdown-container">"#), "{}", html);
+}
+
+#[test]
+fn arabic_pages_get_dir_on_the_document() {
+    let locale = RequestLocale::negotiate("/ar/post/rtl", None, Some("en"), &languages(), "en");
+    let mut rewriter = HtmlLocaleRewriter::new(&locale);
+    let body = render_post("ar");
+    let page = format!("<!DOCTYPE html><html lang=\"en\"><head></head><body>{}</body></html>", body);
+
+    // Streamed in small chunks, as the server sends it
+    let mut rewritten = Vec::new();
+    for chunk in page.as_bytes().chunks(7) {
+        rewritten.extend(rewriter.push(chunk));
+    }
+    rewritten.extend(rewriter.finish());
+    let rewritten = String::from_utf8(rewritten).unwrap();
+
+    assert!(rewritten.starts_with(r#"<!DOCTYPE html><html lang="ar" dir="rtl"><head>"#), "{}", rewritten);
+    assert!(rewritten.ends_with(&format!("<body>{}</body></html>", body)));
+}
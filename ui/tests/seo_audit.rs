@@ -0,0 +1,194 @@
+//! Each rule of the SEO content audit, on a post that passes it and one that doesn't.
+
+use client::Post;
+use ui::extensions::{audit_post, audit_site, AuditContext, AuditSeverity, SeoAudit};
+
+const DESCRIPTION: &str = "How Rust services answer thousands of requests a second, and what it takes to keep them fast.";
+const CONTENT: &str = "Rust makes fast services approachable for small teams.
+
+## Measuring
+
+See [the setup](/page/about) and [the last post](/post/older-post#results).
+
+![Latency chart](/uploads/latency.png)
+
+### Results
+
+More text.
+";
+
+/// A post every rule passes
+fn post() -> Post {
+    Post {
+        id: 1,
+        slug: "fast-rust-services".to_string(),
+        title: "Building fast web services in Rust".to_string(),
+        content: CONTENT.to_string(),
+        meta_description: Some(DESCRIPTION.to_string()),
+        meta_keywords: Some("rust, web services".to_string()),
+        ..Post::default()
+    }
+}
+
+fn older_post() -> Post {
+    Post {
+        id: 2,
+        slug: "older-post".to_string(),
+        title: "An older post about something else".to_string(),
+        meta_description: Some("A different description of a different post, long enough to pass the check.".to_string()),
+        ..Post::default()
+    }
+}
+
+fn audit(post: &Post) -> SeoAudit {
+    let context = AuditContext::new([post, &older_post()], ["about".to_string()]);
+    audit_post(post, &context)
+}
+
+/// The issues found in `post`, as (severity, message)
+fn issues(post: &Post) -> Vec<(AuditSeverity, String)> {
+    audit(post).issues.into_iter().map(|issue| (issue.severity, issue.message)).collect()
+}
+
+/// Whether auditing `post` reports an issue of `severity` whose message contains `text`
+fn reports(post: &Post, severity: AuditSeverity, text: &str) -> bool {
+    issues(post).iter().any(|(found, message)| *found == severity && message.contains(text))
+}
+
+#[test]
+fn a_good_post_has_no_issues() {
+    let audit = audit(&post());
+    assert_eq!(audit.issues, vec![], "{:?}", audit.issues);
+    assert_eq!(audit.score, 100);
+    assert_eq!(audit.grade(), "good");
+}
+
+#[test]
+fn titles_must_exist_and_fit_results() {
+    let missing = Post { title: "  ".to_string(), ..post() };
+    assert!(reports(&missing, AuditSeverity::Error, "no title"));
+
+    let short = Post { title: "Rust services".to_string(), ..post() };
+    assert!(reports(&short, AuditSeverity::Warning, "Title is 13 characters; aim for 30–60"));
+
+    let long = Post { title: format!("Rust {}", "very ".repeat(12)), ..post() };
+    assert!(reports(&long, AuditSeverity::Warning, "search engines cut titles after about 60"));
+
+    // Both ends of the range pass
+    for length in [30, 60] {
+        let title = format!("Rust{}", "x".repeat(length - 4));
+        assert!(!reports(&Post { title, ..post() }, AuditSeverity::Warning, "Title is"), "{}", length);
+    }
+}
+
+#[test]
+fn meta_descriptions_must_exist_and_fit_results() {
+    let missing = Post { meta_description: Some(" ".to_string()), ..post() };
+    assert!(reports(&missing, AuditSeverity::Warning, "No meta description; an excerpt of the content is used instead (\"Rust makes"));
+
+    let short = Post { meta_description: Some("Rust is fast.".to_string()), ..post() };
+    assert!(reports(&short, AuditSeverity::Warning, "Meta description is 13 characters; aim for 70–160"));
+
+    let long = Post { meta_description: Some(format!("Rust {}", "x".repeat(160))), ..post() };
+    assert!(reports(&long, AuditSeverity::Warning, "Meta description is 165 characters"));
+}
+
+#[test]
+fn meta_descriptions_must_be_unique() {
+    let first = post();
+    // Compared ignoring case and surrounding space
+    let copy = Post {
+        id: 3,
+        title: "A copied post".to_string(),
+        meta_description: Some(format!("  {}  ", DESCRIPTION.to_uppercase())),
+        ..post()
+    };
+    let context = AuditContext::new([&first, &copy, &older_post()], ["about".to_string()]);
+
+    let audit = audit_post(&first, &context);
+    assert!(audit.issues.iter().any(|issue| {
+        issue.severity == AuditSeverity::Error && issue.message == "Meta description is also used by: A copied post."
+    }), "{:?}", audit.issues);
+    assert!(!issues(&first).iter().any(|(_, message)| message.contains("also used by")));
+}
+
+#[test]
+fn heading_levels_must_not_be_skipped() {
+    let skipped = Post { content: "Rust\n\n## Part\n\n#### Detail\n".to_string(), ..post() };
+    assert!(reports(&skipped, AuditSeverity::Warning, "Heading jumps from H2 to H4"));
+
+    // Going back up any number of levels is fine
+    let nested = Post { content: "Rust\n\n## Part\n\n### Detail\n\n## Next part\n".to_string(), ..post() };
+    assert!(!reports(&nested, AuditSeverity::Warning, "Heading jumps"));
+}
+
+#[test]
+fn only_one_h1_is_allowed() {
+    let two = Post { content: "# Rust\n\ntext\n\n# Again\n".to_string(), ..post() };
+    assert!(reports(&two, AuditSeverity::Warning, "Content has 2 H1 headings"));
+
+    let one = Post { content: "# Rust\n\ntext\n".to_string(), ..post() };
+    assert!(!reports(&one, AuditSeverity::Warning, "H1 headings"));
+}
+
+#[test]
+fn images_need_alt_text() {
+    let missing = Post { content: "Rust\n\n![](/uploads/a.png) ![ ](/uploads/b.png)\n".to_string(), ..post() };
+    assert!(reports(&missing, AuditSeverity::Warning, "Image /uploads/a.png has no alt text"));
+    assert!(reports(&missing, AuditSeverity::Warning, "Image /uploads/b.png has no alt text"));
+
+    // Formatted alt text counts
+    let formatted = Post { content: "Rust\n\n![the `cargo` *output*](/uploads/a.png)\n".to_string(), ..post() };
+    assert!(!reports(&formatted, AuditSeverity::Warning, "alt text"));
+}
+
+#[test]
+fn internal_links_must_point_at_known_content() {
+    let broken = Post {
+        content: "Rust\n\n[gone](/post/missing) and [also gone](/page/missing/?a=1)\n".to_string(),
+        ..post()
+    };
+    assert!(reports(&broken, AuditSeverity::Error, "Link to /post/missing points at an unknown slug \"missing\""));
+    assert!(reports(&broken, AuditSeverity::Error, "Link to /page/missing/?a=1 points at an unknown slug \"missing\""));
+
+    // Other links aren't checked
+    let external = Post { content: "Rust\n\n[out](https://example.com/post/missing) [home](/)\n".to_string(), ..post() };
+    assert!(!reports(&external, AuditSeverity::Error, "unknown slug"));
+}
+
+#[test]
+fn the_focus_keyword_is_used_throughout() {
+    let none = Post { meta_keywords: Some(" , ".to_string()), ..post() };
+    assert!(reports(&none, AuditSeverity::Info, "Add keywords"));
+    // Suggestions don't cost points
+    assert_eq!(audit(&none).score, 100);
+
+    let unused = Post { meta_keywords: Some("tokio, rust".to_string()), ..post() };
+    assert!(reports(&unused, AuditSeverity::Warning, "Focus keyword \"tokio\" is not in the title"));
+    assert!(reports(&unused, AuditSeverity::Warning, "Focus keyword \"tokio\" is not in the meta description"));
+    assert!(reports(&unused, AuditSeverity::Warning, "Focus keyword \"tokio\" is not in the opening paragraph"));
+
+    // Matched ignoring case
+    let shouted = Post { meta_keywords: Some("RUST".to_string()), ..post() };
+    assert_eq!(issues(&shouted), vec![]);
+}
+
+#[test]
+fn scores_drop_with_each_issue() {
+    // Two warnings and an error
+    let post = Post {
+        title: "Rust".to_string(),
+        content: "Rust\n\n![](/a.png)\n\n[x](/post/missing)\n".to_string(),
+        ..post()
+    };
+    let audit = audit(&post);
+    assert_eq!(audit.score, 60);
+    assert_eq!(audit.grade(), "needs-work");
+    // Worst first
+    assert_eq!(audit.issues[0].severity, AuditSeverity::Error);
+
+    let report = audit_site(&[older_post(), post.clone(), self::post()], ["about".to_string()]);
+    let slugs: Vec<&str> = report.iter().map(|(post, _)| post.slug.as_str()).collect();
+    assert_eq!(slugs.first(), Some(&"fast-rust-services"));
+    assert_eq!(report.first().unwrap().1.score, 60);
+}
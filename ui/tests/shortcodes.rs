@@ -0,0 +1,59 @@
+//! Shortcodes in markdown, rendered by the handlers registered for them.
+
+use dioxus::prelude::*;
+use ui::{register_shortcode, Markdown, Shortcode};
+
+fn render(content: &'static str, preview: bool) -> String {
+    #[component]
+    fn Document(content: &'static str, preview: bool) -> Element {
+        rsx! { Markdown { content: Some(content.to_string()), preview, id: Some("doc".to_string()) } }
+    }
+
+    dioxus_ssr::render_element(rsx! { Document { content, preview } })
+}
+
+#[test]
+fn paired_shortcodes_wrap_markdown() {
+    let html = render(
+        "Intro\n\n{{< callout type=\"warning\" title=\"Careful\" >}}\nBack up **first**.\n{{< /callout >}}\n\nAfter\n",
+        false,
+    );
+    assert!(html.contains(r#"<aside class="shortcode-callout shortcode-callout-warning" role="note"><p class="shortcode-callout-title">Careful</p><p class="markdown-paragraph" dir="auto"><span>Back up </span><strong class="markdown-strong"><span>first</span></strong><span>.</span></p></aside>"#), "{}", html);
+    assert!(html.contains("<span>After</span>"), "{}", html);
+}
+
+#[test]
+fn shortcodes_in_code_and_escaped_ones_are_written_out() {
+    let html = render(
+        "Write `{{< video youtube=\"abc\" >}}` or {{</* gist user=\"a\" id=\"b\" */>}}.\n\n```\n{{< callout >}}\n```\n",
+        false,
+    );
+    assert!(!html.contains("iframe") && !html.contains("<aside"), "{}", html);
+    assert!(html.contains(r#"<code class="markdown-inline-code" dir="ltr">{{&#60; video youtube=&#34;abc&#34; &#62;}}</code>"#), "{}", html);
+    assert!(html.contains("{{&#60; gist user=“a” id=“b” &#62;}}"), "{}", html);
+    assert!(html.contains(r#"<span class="syntax-text">{{&#60; callout &#62;}}</span>"#), "{}", html);
+}
+
+#[test]
+fn broken_shortcodes_only_show_in_preview() {
+    let content = "Before {{< nonexistent >}} and {{< video youtube=\"bad id!\" >}} after";
+    let published = render(content, false);
+    assert!(!published.contains("shortcode-error") && !published.contains("nonexistent"), "{}", published);
+
+    let preview = render(content, true);
+    assert!(preview.contains(r#"<span class="shortcode-error" role="alert">{{&#60; nonexistent &#62;}}: unknown shortcode</span>"#), "{}", preview);
+    assert!(preview.contains("is not a valid youtube"), "{}", preview);
+}
+
+#[test]
+fn registered_handlers_render_inline() {
+    fn shout(shortcode: &Shortcode, _: Element) -> Result<Element, String> {
+        let text = shortcode.get("text").ok_or("text is required")?.to_uppercase();
+        Ok(rsx! { strong { class: "shout", "{text}" } })
+    }
+    register_shortcode("Shout", shout);
+
+    let html = render("Say {{< shout text=\"hi\" >}} and {{< video youtube=\"dQw4w9WgXcQ\" >}}", false);
+    assert!(html.contains(r#"<span>Say </span><strong class="shout">HI</strong>"#), "{}", html);
+    assert!(html.contains(r#"src="https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ""#), "{}", html);
+}
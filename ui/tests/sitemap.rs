@@ -0,0 +1,69 @@
+//! Sitemap and sitemap index XML, and splitting large sites across sitemap files.
+
+use ui::extensions::{
+    sitemap_file_entries, sitemap_files, sitemap_index_xml, sitemap_xml, xml_escape, SitemapEntry, MAX_SITEMAP_URLS,
+};
+
+fn entry(url: &str, last_modified: &str) -> SitemapEntry {
+    SitemapEntry {
+        url: url.to_string(),
+        last_modified: last_modified.to_string(),
+        change_frequency: "weekly".to_string(),
+        priority: 0.8,
+    }
+}
+
+#[test]
+fn markup_characters_are_escaped() {
+    assert_eq!(xml_escape(r#"a&b<c>"d"'e'"#), "a&amp;b&lt;c&gt;&quot;d&quot;&apos;e&apos;");
+    assert_eq!(xml_escape("/post/plain-slug"), "/post/plain-slug");
+    // Already escaped text is escaped again
+    assert_eq!(xml_escape("&amp;"), "&amp;amp;");
+}
+
+#[test]
+fn sitemaps_escape_urls() {
+    let xml = sitemap_xml(&[entry("https://example.com/post/fish&chips<3", "2024-05-01")]);
+    assert!(xml.contains("<loc>https://example.com/post/fish&amp;chips&lt;3</loc>"), "{}", xml);
+    assert!(xml.contains("<lastmod>2024-05-01</lastmod>"), "{}", xml);
+    assert!(xml.contains("<priority>0.8</priority>"), "{}", xml);
+    assert!(!xml.contains("fish&chips"), "{}", xml);
+
+    let index = sitemap_index_xml(&[("https://example.com/sitemap/1.xml?a=1&b=<2>".to_string(), None)]);
+    assert!(index.contains("<loc>https://example.com/sitemap/1.xml?a=1&amp;b=&lt;2&gt;</loc>"), "{}", index);
+    assert!(!index.contains("<lastmod>"), "{}", index);
+}
+
+#[test]
+fn large_sites_are_split_across_numbered_files() {
+    let entries: Vec<SitemapEntry> = (0..MAX_SITEMAP_URLS + 2)
+        .map(|n| entry(&format!("https://example.com/post/{}", n), if n == 3 { "2024-06-01" } else { "2024-01-01" }))
+        .collect();
+
+    let files = sitemap_files(&entries, "https://example.com");
+    assert_eq!(
+        files,
+        vec![
+            ("https://example.com/sitemap/1.xml".to_string(), Some("2024-06-01".to_string())),
+            ("https://example.com/sitemap/2.xml".to_string(), Some("2024-01-01".to_string())),
+        ]
+    );
+
+    assert_eq!(sitemap_file_entries(&entries, 1).map(<[_]>::len), Some(MAX_SITEMAP_URLS));
+    let last = sitemap_file_entries(&entries, 2).unwrap();
+    assert_eq!(last.len(), 2);
+    assert_eq!(last[0].url, format!("https://example.com/post/{}", MAX_SITEMAP_URLS));
+    assert!(sitemap_file_entries(&entries, 0).is_none());
+    assert!(sitemap_file_entries(&entries, 3).is_none());
+
+    let index = sitemap_index_xml(&files);
+    assert_eq!(index.matches("<sitemap>").count(), 2);
+    assert!(index.contains("<loc>https://example.com/sitemap/2.xml</loc>"), "{}", index);
+}
+
+#[test]
+fn small_sites_fit_in_one_file() {
+    let entries = vec![entry("https://example.com/", "2024-01-01")];
+    assert_eq!(sitemap_files(&entries, "https://example.com").len(), 1);
+    assert_eq!(sitemap_file_entries(&entries, 1).map(<[_]>::len), Some(1));
+}